@@ -1,7 +1,8 @@
 """
 Custom exceptions for Tlacuilo backend.
 
-All exceptions inherit from TlacuiloError for easy catching.
+All exceptions inherit from TlacuiloError for easy catching. Each class has
+a machine-readable `code` that utils.report_error() passes to the Rust bridge.
 """
 
 from __future__ import annotations
@@ -10,6 +11,8 @@ from __future__ import annotations
 class TlacuiloError(Exception):
     """Base exception for all Tlacuilo backend errors."""
 
+    code = "error"
+
     def __init__(self, message: str, details: str | None = None) -> None:
         self.message = message
         self.details = details
@@ -24,6 +27,8 @@ class TlacuiloError(Exception):
 class FileNotFoundError(TlacuiloError):
     """Raised when a required file does not exist."""
 
+    code = "file_not_found"
+
     def __init__(self, path: str) -> None:
         super().__init__("File not found", path)
         self.path = path
@@ -32,6 +37,8 @@ class FileNotFoundError(TlacuiloError):
 class InvalidFileTypeError(TlacuiloError):
     """Raised when a file has an unsupported or invalid type."""
 
+    code = "invalid_file_type"
+
     def __init__(self, path: str, expected: list[str] | None = None) -> None:
         details = path
         if expected:
@@ -44,6 +51,8 @@ class InvalidFileTypeError(TlacuiloError):
 class ConversionError(TlacuiloError):
     """Raised when a file conversion fails."""
 
+    code = "conversion_failed"
+
     def __init__(self, source: str, target: str, reason: str | None = None) -> None:
         details = f"{source} -> {target}"
         if reason:
@@ -57,6 +66,8 @@ class ConversionError(TlacuiloError):
 class DependencyMissingError(TlacuiloError):
     """Raised when a required system dependency is not available."""
 
+    code = "missing_dependency"
+
     def __init__(self, dependency: str, install_hint: str | None = None) -> None:
         details = dependency
         if install_hint:
@@ -69,6 +80,8 @@ class DependencyMissingError(TlacuiloError):
 class CompressionError(TlacuiloError):
     """Raised when PDF compression fails."""
 
+    code = "compression_failed"
+
     def __init__(self, path: str, reason: str | None = None) -> None:
         details = path
         if reason:
@@ -81,6 +94,8 @@ class CompressionError(TlacuiloError):
 class TimeoutError(TlacuiloError):
     """Raised when an operation exceeds its timeout."""
 
+    code = "timeout"
+
     def __init__(self, operation: str, timeout_seconds: int) -> None:
         super().__init__("Operation timed out", f"{operation} after {timeout_seconds}s")
         self.operation = operation
@@ -90,6 +105,8 @@ class TimeoutError(TlacuiloError):
 class CorruptedFileError(TlacuiloError):
     """Raised when a file is corrupted or malformed."""
 
+    code = "corrupted_file"
+
     def __init__(self, path: str, reason: str | None = None) -> None:
         details = path
         if reason:
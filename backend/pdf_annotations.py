@@ -15,6 +15,7 @@ from __future__ import annotations
 import argparse
 import json
 import re
+import shutil
 import sys
 import xml.etree.ElementTree as ET
 from datetime import datetime
@@ -24,6 +25,16 @@ from uuid import uuid4
 
 import fitz  # PyMuPDF
 
+try:
+    from pdf_sign import validate_signatures
+except ImportError:
+    from .pdf_sign import validate_signatures
+
+try:
+    from utils import report_error
+except ImportError:
+    from .utils import report_error
+
 
 # Map our annotation types to PyMuPDF types
 ANNOT_TYPE_MAP = {
@@ -53,6 +64,13 @@ ANNOT_TYPE_REVERSE = {
     fitz.PDF_ANNOT_LINE: "line",  # Could be line or arrow, check line ends
 }
 
+# FreeText font families and the PDF base-14 fonts used for them
+FREETEXT_FONTS = {
+    "sans": "helv",
+    "serif": "tiro",
+    "mono": "cour",
+}
+
 # Arrow head styles for line annotations
 ARROW_STYLE_MAP = {
     "none": fitz.PDF_ANNOT_LE_NONE,
@@ -93,9 +111,9 @@ def parse_da_string(doc: fitz.Document, annot: fitz.Annot) -> dict:
     - Tf = text font operator
     - 0 0 0 rg = RGB color (black)
 
-    Returns dict with 'color' (tuple) and 'fontsize' (float) keys.
+    Returns dict with 'color' (tuple), 'fontsize' (float) and 'fontname' (str) keys.
     """
-    result = {"color": None, "fontsize": None}
+    result = {"color": None, "fontsize": None, "fontname": None}
 
     try:
         xref = annot.xref
@@ -112,9 +130,10 @@ def parse_da_string(doc: fitz.Document, annot: fitz.Annot) -> dict:
 
             # Parse font size: /FontName SIZE Tf
             # Examples: /Helv 12 Tf, /TiRo 10.5 Tf
-            fontsize_match = re.search(r'/\w+\s+([\d.]+)\s+Tf', da_content)
+            fontsize_match = re.search(r'/(\w+)\s+([\d.]+)\s+Tf', da_content)
             if fontsize_match:
-                result["fontsize"] = float(fontsize_match.group(1))
+                result["fontname"] = fontsize_match.group(1)
+                result["fontsize"] = float(fontsize_match.group(2))
 
             # Parse RGB color (r g b rg)
             rgb_match = re.search(r'([\d.]+)\s+([\d.]+)\s+([\d.]+)\s+rg', da_content)
@@ -136,6 +155,26 @@ def parse_da_string(doc: fitz.Document, annot: fitz.Annot) -> dict:
     return result
 
 
+def appearance_stroke_color(doc: fitz.Document, annot: fitz.Annot) -> tuple[float, ...] | None:
+    """
+    First stroke color ("r g b RG") used in the annotation's normal appearance.
+
+    FreeText border colors aren't stored in a dedicated key, only drawn
+    into the appearance stream.
+    """
+    try:
+        kind, value = doc.xref_get_key(annot.xref, "AP/N")
+        if kind != "xref":
+            return None
+        stream = doc.xref_stream(int(value.split()[0])) or b""
+        match = re.search(rb'([\d.]+)\s+([\d.]+)\s+([\d.]+)\s+RG', stream)
+        if match:
+            return tuple(float(v) for v in match.groups())
+    except Exception:
+        pass
+    return None
+
+
 def normalized_to_pdf_rect(
     norm_rect: dict[str, float],
     page_width: float,
@@ -172,14 +211,64 @@ def rect_to_quad(rect: fitz.Rect) -> fitz.Quad:
     return rect.quad
 
 
+def normalized_to_quads(
+    quad_points: list[list[float]],
+    page_width: float,
+    page_height: float,
+) -> list[fitz.Quad]:
+    """
+    Convert normalized quadPoints ([x1, y1, ..., x4, y4] per line, ordered
+    upper-left, upper-right, lower-left, lower-right) to PDF quads.
+    """
+    quads = []
+    for qp in quad_points:
+        points = [
+            fitz.Point(qp[i] * page_width, qp[i + 1] * page_height)
+            for i in range(0, 8, 2)
+        ]
+        quads.append(fitz.Quad(*points))
+    return quads
+
+
+def vertices_to_quad_points(
+    vertices: list,
+    page_width: float,
+    page_height: float,
+) -> list[list[float]]:
+    """Group text markup vertices (4 points per line) into normalized quadPoints."""
+    if vertices and not isinstance(vertices[0], (tuple, list)):
+        vertices = [(vertices[i], vertices[i + 1]) for i in range(0, len(vertices), 2)]
+    quad_points = []
+    for i in range(0, len(vertices) - 3, 4):
+        quad = []
+        for x, y in vertices[i:i + 4]:
+            quad.extend([x / page_width, y / page_height])
+        quad_points.append(quad)
+    return quad_points
+
+
+def _valid_signatures(path: Path) -> set[str] | None:
+    """Names of the signatures that currently validate, or None without pyHanko."""
+    validation = validate_signatures(path)
+    if not validation["available"]:
+        return None
+    return {sig["name"] for sig in validation["signatures"] if sig["valid"]}
+
+
 def embed_annotations(
     input_path: Path,
     annotations_json: str,
     output_path: Path,
+    incremental: bool = False,
 ) -> dict[str, Any]:
     """
     Embed annotations from JSON into a PDF.
 
+    With `incremental`, the original bytes are kept and the changes appended as
+    an incremental update, so existing digital signatures stay valid. Only
+    annotations being re-embedded (matched by id) are replaced in that mode;
+    anything else already in the document is left untouched.
+
     annotations_json format:
     {
       "1": [
@@ -188,29 +277,69 @@ def embed_annotations(
           "type": "highlight|underline|strikethrough|comment",
           "page": 1,
           "rect": {"x": 0.1, "y": 0.2, "width": 0.3, "height": 0.05},
+          "quadPoints": [[x1, y1, x2, y2, x3, y3, x4, y4], ...],  (optional, markup only)
           "color": "#FFFF00",
           "opacity": 0.5,
           "text": "optional comment text",
+          "paths": [{"points": [{"x": 0.1, "y": 0.2}, ...], "strokeWidth": 0.003}],  (ink)
+          "fontsize": 12, "fontFamily": "sans|serif|mono",  (freetext)
+          "fill": {"enabled": true, "color": "#FFFFFF"}, "borderColor": "#000000",
+          "strokeWidth": 0.002,  (freetext border, shapes)
           "createdAt": "...",
           "modifiedAt": "..."
         }
       ]
     }
 
-    Returns stats about embedded annotations.
+    Returns stats about embedded annotations. In incremental mode,
+    `signature_check` is "checked" (with `signatures_invalidated` listing the
+    signatures the save broke) or "unavailable" when pyHanko isn't installed.
     """
-    doc = fitz.open(str(input_path))
-    annotations = json.loads(annotations_json)
+    input_resolved = Path(input_path).resolve()
+    output_resolved = Path(output_path).resolve()
+    same_file = input_resolved == output_resolved
 
+    annotations = json.loads(annotations_json)
     stats = {"total": 0, "by_type": {}, "errors": [], "removed": 0}
 
-    # First, remove all existing annotations of supported types to avoid duplicates
+    if incremental:
+        stats["signatures_invalidated"] = []
+        signed_before = _valid_signatures(input_path)
+        stats["signature_check"] = "unavailable" if signed_before is None else "checked"
+        # An incremental update has to be appended to a copy of the original
+        if not same_file:
+            shutil.copyfile(input_path, output_path)
+        doc = fitz.open(str(output_path))
+    else:
+        doc = fitz.open(str(input_path))
+
+    incoming_ids = {
+        annot_data.get("id")
+        for page_annots in annotations.values()
+        for annot_data in page_annots
+    }
+
+    # First, remove existing annotations of supported types to avoid duplicates
     supported_types = set(ANNOT_TYPE_MAP.values())
     for page in doc:
         annots_to_delete = []
+        replaced_seq = set()
         for annot in page.annots():
-            if annot.type[0] in supported_types:
-                annots_to_delete.append(annot)
+            if annot.type[0] not in supported_types:
+                continue
+            if incremental and annot.info.get("subject") not in incoming_ids:
+                continue
+            annots_to_delete.append(annot)
+            content = annot.info.get("content", "")
+            if content.startswith("SEQ:"):
+                replaced_seq.add(content[len("SEQ:"):])
+        # Sequence numbers have a FreeText companion (subject "SEQ_TEXT:N") that
+        # doesn't carry the id; drop it with its marker or re-embedding duplicates it
+        if incremental and replaced_seq:
+            for annot in page.annots():
+                subject = annot.info.get("subject", "")
+                if subject.startswith("SEQ_TEXT:") and subject[len("SEQ_TEXT:"):] in replaced_seq:
+                    annots_to_delete.append(annot)
         for annot in annots_to_delete:
             page.delete_annot(annot)
             stats["removed"] += 1
@@ -250,19 +379,30 @@ def embed_annotations(
                     annot = page.add_text_annot(point, text or "")
                     annot.set_colors(stroke=color_rgb)
                 elif annot_type == "freetext":
-                    # FreeText annotation (typewriter - text directly on page)
-                    # fill_color=None for transparent background (no yellow box)
+                    # FreeText annotation (typewriter / text box directly on page)
+                    # Without fill the background is transparent (no yellow box)
+                    fill_data = annot_data.get("fill") or {}
+                    fill_color = hex_to_rgb(fill_data.get("color", "#FFFFFF")) if fill_data.get("enabled") else None
+                    border_hex = annot_data.get("borderColor")
+                    border_width = annot_data.get("strokeWidth", 0) * page_width if border_hex else 0
                     annot = page.add_freetext_annot(
                         pdf_rect,
                         text or "",
-                        fontsize=12,
-                        fontname="helv",
+                        fontsize=annot_data.get("fontsize") or 12,
+                        fontname=FREETEXT_FONTS.get(annot_data.get("fontFamily"), "helv"),
                         text_color=color_rgb,
-                        fill_color=None,  # Transparent background
+                        fill_color=fill_color,
+                        border_color=hex_to_rgb(border_hex) if border_width else None,
                     )
+                    if annot:
+                        annot.set_border(width=border_width)
                 elif annot_type in ("highlight", "underline", "strikethrough"):
+                    quad_points = annot_data.get("quadPoints")
+                    if quad_points:
+                        # One quad per line so multi-line selections follow the text
+                        quad = normalized_to_quads(quad_points, page_width, page_height)
                     # For underline/strikethrough, normalize height for consistent line thickness
-                    if annot_type in ("underline", "strikethrough"):
+                    elif annot_type in ("underline", "strikethrough"):
                         # Standard text line height (12pt) for consistent rendering
                         standard_height = 12.0
                         if pdf_rect.height > standard_height * 1.5:
@@ -451,18 +591,18 @@ def embed_annotations(
             except Exception as e:
                 stats["errors"].append(f"Failed to add annotation: {e}")
 
-    # Save: use incremental save when saving to same file
-    input_resolved = Path(input_path).resolve()
-    output_resolved = Path(output_path).resolve()
-
-    if input_resolved == output_resolved:
-        # Same file: must use incremental save
+    # Save: incremental when requested, and always when saving to the same file
+    if incremental or same_file:
+        # Append to the existing bytes
         doc.save(str(output_path), incremental=True, encryption=fitz.PDF_ENCRYPT_KEEP)
     else:
         # Different file: can optimize
         doc.save(str(output_path), garbage=4, deflate=True)
     doc.close()
 
+    if incremental and signed_before is not None:
+        stats["signatures_invalidated"] = sorted(signed_before - (_valid_signatures(output_path) or set()))
+
     return stats
 
 
@@ -502,7 +642,9 @@ def read_annotations(input_path: Path) -> dict[str, list[dict[str, Any]]]:
 
             # For text markup annotations, use vertices to get the actual rect
             # because annot.rect includes visual padding that grows on each save
+            quad_points = None
             if our_type in ("highlight", "underline", "strikethrough") and annot.vertices:
+                quad_points = vertices_to_quad_points(annot.vertices, page_width, page_height)
                 vertices = annot.vertices
                 # Handle both tuple format [(x,y), ...] and flat format [x,y,x,y,...]
                 if vertices and isinstance(vertices[0], tuple):
@@ -525,6 +667,8 @@ def read_annotations(input_path: Path) -> dict[str, list[dict[str, Any]]]:
 
             # Get colors and fontsize - FreeText needs special handling
             fontsize = None
+            font_family = None
+            border_color = None
             fill_data = None
             stroke_width = None
             line_style = None
@@ -549,6 +693,21 @@ def read_annotations(input_path: Path) -> dict[str, list[dict[str, Any]]]:
                 else:
                     color_hex = "#000000"  # Default black for freetext
                 fontsize = da_info["fontsize"] or 12  # Default 12pt if not found
+                font_family = next(
+                    (family for family, name in FREETEXT_FONTS.items()
+                     if (da_info["fontname"] or "").lower() == name),
+                    "sans",
+                )
+                # Text box styling: FreeText keeps its background in /C, which
+                # PyMuPDF reports as the "stroke" color
+                background = annot.colors.get("stroke")
+                if background:
+                    fill_data = {"enabled": True, "color": rgb_to_hex(background), "opacity": 1.0}
+                border = annot.border or {}
+                sw = border.get("width", 0) if isinstance(border, dict) else 0
+                if sw and sw > 0:
+                    stroke_width = sw / page_width
+                    border_color = rgb_to_hex(appearance_stroke_color(doc, annot) or (0, 0, 0))
                 # For freetext, get text content from the annotation itself
                 text = annot.get_text() or info.get("content", "") or ""
             elif our_type == "ink":
@@ -685,6 +844,10 @@ def read_annotations(input_path: Path) -> dict[str, list[dict[str, Any]]]:
             # Add optional fields
             if fontsize is not None:
                 annot_data["fontsize"] = fontsize
+            if font_family is not None:
+                annot_data["fontFamily"] = font_family
+            if border_color is not None:
+                annot_data["borderColor"] = border_color
             if paths is not None:
                 annot_data["paths"] = paths
             if fill_data is not None:
@@ -701,6 +864,8 @@ def read_annotations(input_path: Path) -> dict[str, list[dict[str, Any]]]:
                 annot_data["startPoint"] = start_point
             if end_point is not None:
                 annot_data["endPoint"] = end_point
+            if quad_points:
+                annot_data["quadPoints"] = quad_points
             if seq_num is not None:
                 annot_data["sequenceNumber"] = seq_num
                 # Clear the "SEQ:" text since it's just metadata
@@ -918,6 +1083,8 @@ def _build_parser() -> argparse.ArgumentParser:
     embed_p.add_argument("--input", required=True, help="Input PDF path")
     embed_p.add_argument("--annotations", required=True, help="Annotations JSON file or string")
     embed_p.add_argument("--output", required=True, help="Output PDF path")
+    embed_p.add_argument("--incremental", action="store_true",
+                         help="Append changes as an incremental update (keeps signatures valid)")
 
     read_p = sub.add_parser("read", help="Read annotations from PDF")
     read_p.add_argument("--input", required=True, help="Input PDF path")
@@ -952,6 +1119,7 @@ def _main(argv: list[str]) -> int:
                 Path(args.input),
                 annot_input,
                 Path(args.output),
+                incremental=args.incremental,
             )
             print(json.dumps(stats))
 
@@ -975,7 +1143,8 @@ def _main(argv: list[str]) -> int:
             parser.error("Unknown command")
 
     except Exception as exc:
-        print(json.dumps({"error": str(exc)}), file=sys.stderr)
+        report_error(exc)
+        print(f"Error: {exc}", file=sys.stderr)
         return 1
 
     return 0
@@ -1,11 +1,16 @@
 """
-PDF attachments: list, extract, and get info about embedded files.
+PDF attachments: list, extract, add, delete, and get info about embedded files.
 
 CLI usage (dev):
   python pdf_attachments.py list --input document.pdf
   python pdf_attachments.py extract --input document.pdf --name "file.txt" --output /tmp/file.txt
   python pdf_attachments.py extract-all --input document.pdf --output-dir /tmp/attachments
   python pdf_attachments.py preview --input document.pdf --name "file.txt"
+  python pdf_attachments.py scan --input document.pdf
+  python pdf_attachments.py add --input document.pdf --output out.pdf --file data.xlsx --description "Q3 figures"
+  python pdf_attachments.py delete --input document.pdf --output out.pdf --name "file.txt"
+  python pdf_attachments.py delete-all --input document.pdf --output out.pdf
+  python pdf_attachments.py add-many --input document.pdf --output out.pdf --files-json '[{"file_path": "a.csv"}]'
 """
 
 from __future__ import annotations
@@ -13,12 +18,18 @@ from __future__ import annotations
 import argparse
 import base64
 import json
+import os
 import sys
 from pathlib import Path
 from typing import Any
 
 import fitz  # PyMuPDF
 
+try:
+    from utils import report_error
+except ImportError:
+    from .utils import report_error
+
 
 def list_attachments(input_path: Path) -> list[dict[str, Any]]:
     """List all embedded files in a PDF with their metadata."""
@@ -235,6 +246,238 @@ def extract_all_attachments(input_path: Path, output_dir: Path) -> list[dict[str
     return results
 
 
+# Magic byte signatures -> detected type (checked in order, first match wins)
+MAGIC_SIGNATURES = [
+    (b"MZ", "exe"),
+    (b"\x7fELF", "elf"),
+    (b"\xfe\xed\xfa\xce", "macho"),
+    (b"\xfe\xed\xfa\xcf", "macho"),
+    (b"\xce\xfa\xed\xfe", "macho"),
+    (b"\xcf\xfa\xed\xfe", "macho"),
+    (b"\xca\xfe\xba\xbe", "macho"),
+    (b"#!", "script"),
+    (b"%PDF", "pdf"),
+    (b"PK\x03\x04", "zip"),
+    (b"Rar!\x1a\x07", "rar"),
+    (b"7z\xbc\xaf\x27\x1c", "7z"),
+    (b"\x1f\x8b", "gzip"),
+    (b"\xd0\xcf\x11\xe0\xa1\xb1\x1a\xe1", "ole"),
+    (b"{\\rtf", "rtf"),
+    (b"\x89PNG", "png"),
+    (b"\xff\xd8\xff", "jpeg"),
+    (b"GIF8", "gif"),
+]
+
+EXECUTABLE_TYPES = {"exe", "elf", "macho"}
+ARCHIVE_TYPES = {"zip", "rar", "7z", "gzip"}
+
+# Extensions that run code when opened, whatever their content
+SCRIPT_EXTENSIONS = {
+    "js", "jse", "vbs", "vbe", "wsf", "wsh", "hta", "ps1", "psm1", "bat", "cmd",
+    "sh", "bash", "py", "pl", "rb", "scr", "com", "lnk", "jar", "msi", "reg",
+}
+
+# Office formats that can carry macros
+MACRO_EXTENSIONS = {"docm", "xlsm", "pptm", "dotm", "xltm", "xlam", "ppsm"}
+
+# Extension -> the detected types its content may legitimately have
+EXPECTED_TYPES = {
+    "exe": {"exe"}, "dll": {"exe"}, "sys": {"exe"}, "scr": {"exe"}, "com": {"exe"},
+    "pdf": {"pdf"},
+    "png": {"png"}, "jpg": {"jpeg"}, "jpeg": {"jpeg"}, "gif": {"gif"},
+    "zip": {"zip"}, "docx": {"zip"}, "xlsx": {"zip"}, "pptx": {"zip"},
+    "odt": {"zip"}, "ods": {"zip"}, "odp": {"zip"}, "epub": {"zip"}, "jar": {"zip"},
+    "docm": {"zip"}, "xlsm": {"zip"}, "pptm": {"zip"},
+    "rar": {"rar"}, "7z": {"7z"}, "gz": {"gzip"}, "tgz": {"gzip"},
+    "doc": {"ole"}, "xls": {"ole"}, "ppt": {"ole"}, "msi": {"ole"}, "msg": {"ole"},
+    "rtf": {"rtf"},
+    "txt": {"text"}, "csv": {"text"}, "md": {"text"}, "log": {"text"},
+    "json": {"text"}, "xml": {"text"}, "html": {"text"}, "htm": {"text"},
+}
+
+
+def _save(doc: fitz.Document, input_path: Path, output_path: Path) -> None:
+    """Save to output_path, going through a temp file when overwriting the input."""
+    if output_path.resolve() == input_path.resolve():
+        tmp_path = output_path.with_name(output_path.name + ".tmp")
+        doc.save(str(tmp_path), garbage=1, deflate=True)
+        doc.close()
+        os.replace(tmp_path, output_path)
+    else:
+        doc.save(str(output_path), garbage=1, deflate=True)
+        doc.close()
+
+
+def _unique_name(name: str, existing: set[str]) -> str:
+    """Return name, or "stem (2).ext" style variants when it is already taken."""
+    if name not in existing:
+        return name
+    stem, dot, ext = name.rpartition(".")
+    if not dot:
+        stem, ext = name, ""
+    counter = 2
+    while True:
+        candidate = f"{stem} ({counter}).{ext}" if dot else f"{stem} ({counter})"
+        if candidate not in existing:
+            return candidate
+        counter += 1
+
+
+def add_attachments(input_path: Path, output_path: Path, files: list[dict[str, Any]]) -> list[dict[str, Any]]:
+    """
+    Embed files as embedded-file streams with a filespec in the /EmbeddedFiles tree.
+
+    Each entry has `file_path` plus optional `name` (display name, defaults to the
+    file name) and `description`. PyMuPDF records creation and modification
+    dates on the filespec. Names already in use get a numbered suffix.
+    Returns the updated attachment list.
+    """
+    for entry in files:
+        if not Path(entry["file_path"]).is_file():
+            raise ValueError(f"File not found: {entry['file_path']}")
+
+    doc = fitz.open(str(input_path))
+    existing = set(doc.embfile_names())
+
+    for entry in files:
+        source = Path(entry["file_path"])
+        name = _unique_name(entry.get("name") or source.name, existing)
+        existing.add(name)
+        doc.embfile_add(
+            name,
+            source.read_bytes(),
+            filename=source.name,
+            desc=entry.get("description") or "",
+        )
+
+    _save(doc, input_path, output_path)
+    return list_attachments(output_path)
+
+
+def delete_attachments(input_path: Path, output_path: Path, name_or_index: str | int | None) -> dict[str, Any]:
+    """
+    Delete one embedded file by name or index (like extract), or all when None.
+
+    The entry is removed from the /EmbeddedFiles name tree and the save garbage
+    collects the now unreferenced filespec and file stream, so the content is
+    gone from the output rather than just unlinked. Indexes disambiguate
+    attachments sharing a display name.
+    """
+    doc = fitz.open(str(input_path))
+    count = doc.embfile_count()
+
+    if name_or_index is None:
+        targets = list(range(count))
+    elif isinstance(name_or_index, int) or name_or_index.isdigit():
+        idx = int(name_or_index)
+        if idx >= count:
+            doc.close()
+            raise ValueError(f"No attachment at index {idx} (document has {count})")
+        targets = [idx]
+    else:
+        names = doc.embfile_names()
+        if name_or_index not in names:
+            doc.close()
+            raise ValueError(f"No attachment named '{name_or_index}'")
+        targets = [names.index(name_or_index)]
+
+    # Delete from the end so earlier indexes stay valid
+    for idx in sorted(targets, reverse=True):
+        doc.embfile_del(idx)
+
+    _save(doc, input_path, output_path)
+    return {
+        "removed": len(targets),
+        "attachments": list_attachments(output_path),
+    }
+
+
+def detect_file_type(content: bytes) -> str:
+    """Identify content by magic bytes, falling back to text/binary."""
+    for magic, file_type in MAGIC_SIGNATURES:
+        if content.startswith(magic):
+            return file_type
+
+    sample = content[:4096]
+    if b"\x00" not in sample:
+        try:
+            sample.decode("utf-8")
+            return "text"
+        except UnicodeDecodeError:
+            pass
+    return "binary"
+
+
+def assess_attachment(name: str, content: bytes) -> dict[str, Any]:
+    """Classify one embedded file and assign a low/medium/high risk level."""
+    ext = name.rsplit(".", 1)[-1].lower() if "." in name else ""
+    detected = detect_file_type(content)
+    expected = EXPECTED_TYPES.get(ext)
+    # Partial reads can cut UTF-8 mid-character; treat text/binary as compatible
+    mismatch = (
+        expected is not None
+        and detected not in expected
+        and not (expected == {"text"} and detected == "binary")
+    )
+
+    if detected in EXECUTABLE_TYPES:
+        risk = "high"
+        if ext in EXPECTED_TYPES and "exe" not in EXPECTED_TYPES[ext]:
+            reason = f"Executable ({detected}) disguised as .{ext}"
+        else:
+            reason = f"Executable program ({detected})"
+    elif detected == "script" or ext in SCRIPT_EXTENSIONS:
+        risk = "high"
+        reason = "Script or runnable file" + (f" (.{ext})" if ext else "")
+    elif ext in MACRO_EXTENSIONS:
+        risk = "high" if mismatch else "medium"
+        reason = f"Macro-enabled Office document (.{ext})"
+    elif mismatch:
+        risk = "medium"
+        reason = f"Content is {detected} but extension is .{ext}"
+    elif detected in ARCHIVE_TYPES:
+        risk = "medium"
+        reason = f"Archive ({detected}) may contain files that cannot be inspected here"
+    elif detected == "ole":
+        risk = "medium"
+        reason = "Legacy Office document, may contain macros"
+    elif ext in ("html", "htm", "svg"):
+        risk = "medium"
+        reason = f".{ext} files can embed scripts"
+    else:
+        risk = "low"
+        reason = "No known risk indicators"
+
+    return {
+        "name": name,
+        "detected_type": detected,
+        "risk": risk,
+        "reason": reason,
+    }
+
+
+def scan_attachments(input_path: Path) -> list[dict[str, Any]]:
+    """Inspect every embedded file's content and report its risk level."""
+    doc = fitz.open(str(input_path))
+
+    results = []
+    for i in range(doc.embfile_count()):
+        info = doc.embfile_info(i)
+        name = info.get("filename") or info.get("name") or f"attachment_{i}"
+        try:
+            results.append(assess_attachment(name, doc.embfile_get(i)))
+        except Exception as e:
+            results.append({
+                "name": name,
+                "detected_type": "unknown",
+                "risk": "medium",
+                "reason": f"Could not read content: {e}",
+            })
+
+    doc.close()
+    return results
+
+
 def main() -> int:
     parser = argparse.ArgumentParser(description="PDF attachment operations")
     subparsers = parser.add_subparsers(dest="command", required=True)
@@ -259,6 +502,35 @@ def main() -> int:
     preview_parser.add_argument("--input", required=True, help="Input PDF path")
     preview_parser.add_argument("--name", required=True, help="File name or index")
 
+    # Scan command
+    scan_parser = subparsers.add_parser("scan", help="Assess embedded files for security risks")
+    scan_parser.add_argument("--input", required=True, help="Input PDF path")
+
+    # Add command
+    add_parser = subparsers.add_parser("add", help="Embed a file")
+    add_parser.add_argument("--input", required=True, help="Input PDF path")
+    add_parser.add_argument("--output", required=True, help="Output PDF path")
+    add_parser.add_argument("--file", required=True, help="File to embed")
+    add_parser.add_argument("--name", help="Display name (defaults to the file name)")
+    add_parser.add_argument("--description", help="Attachment description")
+
+    # Add many command
+    add_many_parser = subparsers.add_parser("add-many", help="Embed several files")
+    add_many_parser.add_argument("--input", required=True, help="Input PDF path")
+    add_many_parser.add_argument("--output", required=True, help="Output PDF path")
+    add_many_parser.add_argument("--files-json", required=True,
+                                 help='JSON list of {"file_path", "name", "description"}')
+
+    # Delete commands
+    delete_parser = subparsers.add_parser("delete", help="Delete an embedded file")
+    delete_parser.add_argument("--input", required=True, help="Input PDF path")
+    delete_parser.add_argument("--output", required=True, help="Output PDF path")
+    delete_parser.add_argument("--name", required=True, help="File name or index")
+
+    delete_all_parser = subparsers.add_parser("delete-all", help="Delete all embedded files")
+    delete_all_parser.add_argument("--input", required=True, help="Input PDF path")
+    delete_all_parser.add_argument("--output", required=True, help="Output PDF path")
+
     args = parser.parse_args()
 
     try:
@@ -288,10 +560,39 @@ def main() -> int:
             )
             print(json.dumps(result))
 
+        elif args.command == "scan":
+            result = scan_attachments(Path(args.input))
+            print(json.dumps(result))
+
+        elif args.command == "add":
+            result = add_attachments(
+                Path(args.input),
+                Path(args.output),
+                [{"file_path": args.file, "name": args.name, "description": args.description}],
+            )
+            print(json.dumps(result))
+
+        elif args.command == "add-many":
+            result = add_attachments(
+                Path(args.input),
+                Path(args.output),
+                json.loads(args.files_json),
+            )
+            print(json.dumps(result))
+
+        elif args.command == "delete":
+            result = delete_attachments(Path(args.input), Path(args.output), args.name)
+            print(json.dumps(result))
+
+        elif args.command == "delete-all":
+            result = delete_attachments(Path(args.input), Path(args.output), None)
+            print(json.dumps(result))
+
         return 0
 
     except Exception as e:
-        print(json.dumps({"error": str(e)}), file=sys.stderr)
+        report_error(e)
+        print(f"Error: {e}", file=sys.stderr)
         return 1
 
 
@@ -4,17 +4,26 @@ Image conversion module for Tlacuilo.
 Handles bidirectional conversion between images and PDF:
 - Multiple images → single PDF
 - PDF pages → individual images
+- PDF pages → single multipage TIFF
+- Embedded images → files in their original format and resolution
+- PDF → image-only PDF (flattened, no text or vector content)
 
 Uses PyMuPDF (fitz) for all operations.
 
 CLI usage:
   python pdf_convert.py images-to-pdf --inputs img1.jpg img2.png --output out.pdf
+  python pdf_convert.py images-to-pdf --inputs a.png b.png --output long.pdf --single-page --margin 5
   python pdf_convert.py pdf-to-images --input doc.pdf --output-dir ./images --format png
+  python pdf_convert.py pdf-to-tiff --input doc.pdf --output doc.tiff --compression lzw
+  python pdf_convert.py extract-images --input doc.pdf --output-dir ./images --min-size 64
+  python pdf_convert.py flatten-to-images --input doc.pdf --output flat.pdf --dpi 150 --format jpg
 """
 
 from __future__ import annotations
 
 import argparse
+import json
+import os
 import sys
 from pathlib import Path
 from typing import Sequence
@@ -28,6 +37,8 @@ try:
         validate_files_exist,
         ensure_output_dir,
         get_extension,
+        report_error,
+        report_progress,
     )
 except ImportError:
     from .exceptions import ConversionError, InvalidFileTypeError
@@ -36,6 +47,8 @@ except ImportError:
         validate_files_exist,
         ensure_output_dir,
         get_extension,
+        report_error,
+        report_progress,
     )
 
 
@@ -43,6 +56,20 @@ except ImportError:
 INPUT_IMAGE_FORMATS = ["jpg", "jpeg", "png", "webp", "tiff", "tif", "bmp", "gif"]
 OUTPUT_IMAGE_FORMATS = ["png", "jpg", "jpeg", "webp", "tiff"]
 
+# Multipage TIFF compression -> Pillow codec ("ccitt" requires bitonal pages)
+TIFF_COMPRESSIONS = {
+    "lzw": "tiff_lzw",
+    "deflate": "tiff_adobe_deflate",
+    "ccitt": "group4",
+}
+
+# Page image formats for flattening (PDF can embed these directly)
+FLATTEN_FORMATS = ["png", "jpg"]
+
+# Tallest single page to build (200 inches, the PDF 1.x page size limit that
+# many viewers still enforce)
+MAX_PAGE_HEIGHT_PT = 14400
+
 # Page sizes in points (72 points = 1 inch)
 PAGE_SIZES = {
     "a4": fitz.paper_size("a4"),           # 595 x 842
@@ -67,6 +94,89 @@ def get_supported_formats() -> dict[str, list[str]]:
     }
 
 
+def _prepare_image(img_path: Path, transform: dict | None, temp_files: list[Path]) -> Path:
+    """
+    Return a file PyMuPDF can insert for `img_path`: the image itself, or a
+    temporary copy when it needs rotating/flipping or is WebP (which PyMuPDF
+    can't open). Temporary copies are added to `temp_files` for cleanup.
+    """
+    suffix = img_path.suffix if hasattr(img_path, 'suffix') else Path(str(img_path)).suffix
+    is_webp = suffix.lower() == '.webp'
+    needs_transform = transform and (transform.get("rotation") or transform.get("flip_h") or transform.get("flip_v"))
+    if not (needs_transform or is_webp):
+        return img_path
+
+    from PIL import Image
+    import tempfile
+
+    pil_img = Image.open(str(img_path))
+
+    # Apply rotation if needed
+    if needs_transform:
+        rotation = transform.get("rotation", 0)
+        if rotation:
+            # PIL rotates counter-clockwise, we want clockwise
+            pil_img = pil_img.rotate(-rotation, expand=True)
+
+        # Apply flips
+        if transform.get("flip_h"):
+            pil_img = pil_img.transpose(Image.FLIP_LEFT_RIGHT)
+        if transform.get("flip_v"):
+            pil_img = pil_img.transpose(Image.FLIP_TOP_BOTTOM)
+
+    # Save to temp file - always use PNG for webp since PyMuPDF doesn't support webp
+    out_suffix = '.png' if is_webp else suffix
+    temp_fd, temp_path = tempfile.mkstemp(suffix=out_suffix)
+    temp_path = Path(temp_path)
+    temp_files.append(temp_path)
+
+    # Convert RGBA to RGB for JPEG
+    if pil_img.mode == 'RGBA' and out_suffix.lower() in ['.jpg', '.jpeg']:
+        pil_img = pil_img.convert('RGB')
+
+    pil_img.save(str(temp_path))
+    pil_img.close()
+    return temp_path
+
+
+def _limit_image_dpi(
+    img_path: Path, shown_width: float, max_dpi: int, quality: int, temp_files: list[Path]
+) -> tuple[Path, int]:
+    """
+    Downsample `img_path` if showing it `shown_width` points wide exceeds
+    `max_dpi`. Opaque images become JPEG at `quality`, transparent ones PNG.
+    Returns the file to embed and the bytes saved (0 when the image is kept).
+    """
+    from PIL import Image
+    import tempfile
+
+    with Image.open(str(img_path)) as pil_img:
+        dpi = pil_img.width / (shown_width / 72) if shown_width > 0 else 0
+        if dpi <= max_dpi:
+            return img_path, 0
+
+        scale = max_dpi / dpi
+        size = (max(1, round(pil_img.width * scale)), max(1, round(pil_img.height * scale)))
+        has_alpha = pil_img.mode in ("RGBA", "LA", "PA") or "transparency" in pil_img.info
+        resized = pil_img.convert("RGBA" if has_alpha else "RGB").resize(size, Image.LANCZOS)
+
+    out_suffix = ".png" if has_alpha else ".jpg"
+    temp_fd, temp_path = tempfile.mkstemp(suffix=out_suffix)
+    os.close(temp_fd)
+    temp_path = Path(temp_path)
+    temp_files.append(temp_path)
+    if has_alpha:
+        resized.save(str(temp_path), "PNG", optimize=True)
+    else:
+        resized.save(str(temp_path), "JPEG", quality=quality, optimize=True)
+
+    # Re-encoding a well-compressed source can come out larger
+    saved = img_path.stat().st_size - temp_path.stat().st_size
+    if saved <= 0:
+        return img_path, 0
+    return temp_path, saved
+
+
 def images_to_pdf(
     image_paths: Sequence[Path | str],
     output: Path | str,
@@ -76,7 +186,9 @@ def images_to_pdf(
     margin_mm: float = 0,
     quality: int = 95,
     transforms: Sequence[dict] | None = None,
-) -> Path:
+    append_to: Path | str | None = None,
+    max_image_dpi: int | None = None,
+) -> dict:
     """
     Convert multiple images to a single PDF.
 
@@ -86,11 +198,16 @@ def images_to_pdf(
         page_size: Page size ("a4", "letter", "legal", "a3", "a5", "fit")
         orientation: "auto", "portrait", or "landscape"
         margin_mm: Margin in millimeters
-        quality: JPEG quality for compression (1-100)
+        quality: JPEG quality for downsampled images (1-100)
         transforms: List of transform dicts with rotation, flip_h, flip_v per image
+        append_to: Existing PDF to add the image pages to. Its pages, outline
+            and metadata are kept; size/orientation/margin only shape the new pages.
+            May be the same file as output.
+        max_image_dpi: Downsample images that would be shown above this
+            resolution on their page. None embeds images as they are.
 
     Returns:
-        Path to created PDF.
+        {"output": path, "bytes_saved": bytes trimmed by downsampling}
 
     Raises:
         InvalidFileTypeError: If image format is not supported.
@@ -101,6 +218,7 @@ def images_to_pdf(
 
     # Validate inputs
     validated_paths = validate_files_exist(image_paths, INPUT_IMAGE_FORMATS)
+    base_path = validate_file_exists(append_to, ["pdf"]) if append_to else None
     output_path = Path(output).resolve()
     ensure_output_dir(output_path.parent)
 
@@ -115,53 +233,15 @@ def images_to_pdf(
     base_size = PAGE_SIZES[size_key]
 
     try:
-        doc = fitz.open()
+        doc = fitz.open(str(base_path)) if base_path else fitz.open()
+        existing_pages = len(doc)
         temp_files: list[Path] = []
+        bytes_saved = 0
 
         for idx, img_path in enumerate(validated_paths):
             # Get transform for this image if provided
             transform = transforms[idx] if transforms and idx < len(transforms) else None
-            actual_img_path = img_path
-
-            # Check if we need PIL processing:
-            # 1. Transforms are needed (rotation, flip)
-            # 2. File is webp (PyMuPDF can't open webp directly)
-            suffix = img_path.suffix if hasattr(img_path, 'suffix') else Path(str(img_path)).suffix
-            is_webp = suffix.lower() == '.webp'
-            needs_transform = transform and (transform.get("rotation") or transform.get("flip_h") or transform.get("flip_v"))
-
-            if needs_transform or is_webp:
-                from PIL import Image
-                import tempfile
-
-                pil_img = Image.open(str(img_path))
-
-                # Apply rotation if needed
-                if needs_transform:
-                    rotation = transform.get("rotation", 0)
-                    if rotation:
-                        # PIL rotates counter-clockwise, we want clockwise
-                        pil_img = pil_img.rotate(-rotation, expand=True)
-
-                    # Apply flips
-                    if transform.get("flip_h"):
-                        pil_img = pil_img.transpose(Image.FLIP_LEFT_RIGHT)
-                    if transform.get("flip_v"):
-                        pil_img = pil_img.transpose(Image.FLIP_TOP_BOTTOM)
-
-                # Save to temp file - always use PNG for webp since PyMuPDF doesn't support webp
-                out_suffix = '.png' if is_webp else suffix
-                temp_fd, temp_path = tempfile.mkstemp(suffix=out_suffix)
-                temp_path = Path(temp_path)
-                temp_files.append(temp_path)
-
-                # Convert RGBA to RGB for JPEG
-                if pil_img.mode == 'RGBA' and out_suffix.lower() in ['.jpg', '.jpeg']:
-                    pil_img = pil_img.convert('RGB')
-
-                pil_img.save(str(temp_path))
-                pil_img.close()
-                actual_img_path = temp_path
+            actual_img_path = _prepare_image(img_path, transform, temp_files)
 
             # Open image to get dimensions
             img_doc = fitz.open(str(actual_img_path))
@@ -222,13 +302,32 @@ def images_to_pdf(
                 y_offset + final_height,
             )
 
+            img_doc.close()
+            if max_image_dpi:
+                actual_img_path, saved = _limit_image_dpi(
+                    actual_img_path, final_width, max_image_dpi, quality, temp_files
+                )
+                bytes_saved += saved
+
             # Insert image
             page.insert_image(img_rect, filename=str(actual_img_path))
-            img_doc.close()
+
+        expected_pages = existing_pages + len(validated_paths)
+        if len(doc) != expected_pages:
+            raise ConversionError(
+                "images", "pdf",
+                f"Expected {expected_pages} pages after appending, got {len(doc)}",
+            )
+
+        # PyMuPDF can't fully rewrite the file it has open, so appending in
+        # place goes through a temporary file
+        save_path = output_path
+        if base_path and base_path == output_path:
+            save_path = output_path.with_name(f".{output_path.name}.tmp")
 
         # Save with compression
         doc.save(
-            str(output_path),
+            str(save_path),
             garbage=4,  # Maximum garbage collection
             deflate=True,
             deflate_images=True,
@@ -236,6 +335,9 @@ def images_to_pdf(
         )
         doc.close()
 
+        if save_path != output_path:
+            save_path.replace(output_path)
+
         # Cleanup temp files
         for temp_file in temp_files:
             try:
@@ -243,7 +345,7 @@ def images_to_pdf(
             except OSError:
                 pass
 
-        return output_path
+        return {"output": str(output_path), "bytes_saved": bytes_saved}
 
     except Exception as e:
         # Cleanup temp files on error too
@@ -255,6 +357,118 @@ def images_to_pdf(
         raise ConversionError("images", "pdf", str(e)) from e
 
 
+def images_to_long_page(
+    image_paths: Sequence[Path | str],
+    output: Path | str,
+    *,
+    page_size: str = "fit",
+    orientation: str = "auto",
+    margin_mm: float = 0,
+    quality: int = 95,
+    transforms: Sequence[dict] | None = None,
+    max_height: float = MAX_PAGE_HEIGHT_PT,
+    max_image_dpi: int | None = None,
+) -> dict:
+    """
+    Stack images top to bottom on a single tall page (receipts, chat screenshots).
+
+    The page is as wide as the widest image ("fit") or as the page size in the
+    given orientation ("auto" means portrait); wider images are scaled down and
+    narrower ones centered. The margin surrounds the page and separates the
+    images. A page taller than `max_height` points is scaled down to fit.
+    `max_image_dpi` and `quality` downsample as in `images_to_pdf`.
+
+    Returns:
+        {"output": path, "width": points, "height": points, "bytes_saved": bytes}
+    """
+    if not image_paths:
+        raise ConversionError("images", "pdf", "No images provided")
+
+    validated_paths = validate_files_exist(image_paths, INPUT_IMAGE_FORMATS)
+    output_path = Path(output).resolve()
+    ensure_output_dir(output_path.parent)
+
+    margin_pt = margin_mm * 2.834645669
+    size_key = page_size.lower()
+    if size_key not in PAGE_SIZES:
+        raise ConversionError("images", "pdf", f"Unknown page size: {page_size}")
+    base_size = PAGE_SIZES[size_key]
+
+    temp_files: list[Path] = []
+    try:
+        prepared: list[tuple[Path, float, float]] = []
+        for idx, img_path in enumerate(validated_paths):
+            transform = transforms[idx] if transforms and idx < len(transforms) else None
+            actual_img_path = _prepare_image(img_path, transform, temp_files)
+            with fitz.open(str(actual_img_path)) as img_doc:
+                rect = img_doc[0].rect
+            prepared.append((actual_img_path, rect.width, rect.height))
+
+        if base_size is None:
+            content_width = max(w for _, w, _ in prepared)
+        elif orientation == "landscape":
+            content_width = max(base_size) - 2 * margin_pt
+        else:
+            content_width = min(base_size) - 2 * margin_pt
+        if content_width <= 0:
+            raise ConversionError("images", "pdf", "Margin leaves no room for the images")
+
+        # Fit each image to the content width (never upscale)
+        sizes = [(w * min(1.0, content_width / w), h * min(1.0, content_width / w)) for _, w, h in prepared]
+        gaps = margin_pt * (len(sizes) + 1)
+        images_height = sum(h for _, h in sizes)
+        if images_height + gaps > max_height:
+            factor = (max_height - gaps) / images_height
+            if factor <= 0:
+                raise ConversionError("images", "pdf", "Too many images for the maximum page height")
+            sizes = [(w * factor, h * factor) for w, h in sizes]
+            images_height *= factor
+
+        if base_size is None:
+            page_width = max(w for w, _ in sizes) + 2 * margin_pt
+        else:
+            page_width = content_width + 2 * margin_pt
+        page_height = images_height + gaps
+
+        doc = fitz.open()
+        page = doc.new_page(width=page_width, height=page_height)
+        bytes_saved = 0
+        y = margin_pt
+        for (img_path, _, _), (w, h) in zip(prepared, sizes):
+            if max_image_dpi:
+                img_path, saved = _limit_image_dpi(img_path, w, max_image_dpi, quality, temp_files)
+                bytes_saved += saved
+            x = (page_width - w) / 2
+            page.insert_image(fitz.Rect(x, y, x + w, y + h), filename=str(img_path))
+            y += h + margin_pt
+
+        doc.save(
+            str(output_path),
+            garbage=4,
+            deflate=True,
+            deflate_images=True,
+            deflate_fonts=True,
+        )
+        doc.close()
+        return {
+            "output": str(output_path),
+            "width": page_width,
+            "height": page_height,
+            "bytes_saved": bytes_saved,
+        }
+
+    except ConversionError:
+        raise
+    except Exception as e:
+        raise ConversionError("images", "pdf", str(e)) from e
+    finally:
+        for temp_file in temp_files:
+            try:
+                temp_file.unlink()
+            except OSError:
+                pass
+
+
 def pdf_to_images(
     pdf_path: Path | str,
     output_dir: Path | str,
@@ -306,7 +520,8 @@ def pdf_to_images(
 
         output_paths: list[Path] = []
 
-        for idx in page_indices:
+        for done, idx in enumerate(page_indices):
+            report_progress("render", done, len(page_indices))
             if idx < 0 or idx >= total_pages:
                 continue
 
@@ -339,6 +554,7 @@ def pdf_to_images(
 
             output_paths.append(out_path)
 
+        report_progress("render", len(page_indices), len(page_indices))
         doc.close()
         return output_paths
 
@@ -346,6 +562,241 @@ def pdf_to_images(
         raise ConversionError("pdf", format, str(e)) from e
 
 
+def extract_images(
+    pdf_path: Path | str,
+    output_dir: Path | str,
+    *,
+    pages: str | None = None,
+    min_size: int = 0,
+) -> list[dict]:
+    """
+    Write the images embedded in a PDF as files, without re-rendering.
+
+    Each image keeps its stored format and resolution (JPEG stays JPEG); images
+    with a soft mask are written as PNG so transparency survives. An image
+    reused on several pages is written once, for the first page it appears on.
+    A soft mask that can't be applied (e.g. its size doesn't match the image)
+    is dropped with a "warning" on the entry; an image that can't be read at
+    all is skipped so the rest are still extracted.
+
+    Args:
+        pdf_path: Input PDF path
+        output_dir: Directory for the extracted images
+        pages: Page range string (e.g., "1-3,5,7") or None for all
+        min_size: Skip images whose width or height is below this many pixels
+
+    Returns:
+        [{"page", "index", "width", "height", "format", "path", "warning"?}, ...]
+        with 1-indexed pages and per-page indices.
+    """
+    input_path = validate_file_exists(pdf_path, ["pdf"])
+    out_dir = ensure_output_dir(output_dir)
+
+    try:
+        doc = fitz.open(str(input_path))
+        total_pages = len(doc)
+        page_indices = _parse_page_range(pages, total_pages) if pages else list(range(total_pages))
+
+        results: list[dict] = []
+        seen: set[int] = set()
+        for done, idx in enumerate(page_indices):
+            report_progress("extract", done, len(page_indices))
+            index = 0
+            for image in doc[idx].get_images(full=True):
+                xref, smask = image[0], image[1]
+                if xref in seen:
+                    continue
+                seen.add(xref)
+
+                try:
+                    info = doc.extract_image(xref)
+                except Exception as e:
+                    print(f"Skipping image {xref} on page {idx + 1}: {e}", file=sys.stderr)
+                    continue
+                if not info:
+                    continue
+                width, height = info["width"], info["height"]
+                if min(width, height) < min_size:
+                    continue
+
+                index += 1
+                stem = out_dir / f"page{idx + 1:04d}_img{index:02d}"
+                entry = {"page": idx + 1, "index": index, "width": width, "height": height}
+                pix = None
+                if smask:
+                    try:
+                        pix = fitz.Pixmap(fitz.Pixmap(doc, xref), fitz.Pixmap(doc, smask))
+                    except Exception as e:
+                        entry["warning"] = f"Soft mask not applied: {e}"
+                try:
+                    if pix is not None:
+                        if pix.n - pix.alpha >= 4:  # CMYK can't go to PNG
+                            pix = fitz.Pixmap(fitz.csRGB, pix)
+                        ext = "png"
+                        out_path = stem.with_suffix(".png")
+                        pix.save(str(out_path))
+                    else:
+                        ext = info["ext"]
+                        out_path = stem.with_suffix(f".{ext}")
+                        out_path.write_bytes(info["image"])
+                except Exception as e:
+                    print(f"Skipping image {xref} on page {idx + 1}: {e}", file=sys.stderr)
+                    index -= 1
+                    continue
+
+                entry.update({"format": ext, "path": str(out_path)})
+                results.append(entry)
+
+        report_progress("extract", len(page_indices), len(page_indices))
+        doc.close()
+        return results
+
+    except Exception as e:
+        raise ConversionError("pdf", "images", str(e)) from e
+
+
+def pdf_to_multipage_tiff(
+    pdf_path: Path | str,
+    output_path: Path | str,
+    *,
+    dpi: int = 200,
+    compression: str = "lzw",
+) -> int:
+    """
+    Convert all PDF pages into a single multipage TIFF.
+
+    Args:
+        pdf_path: Input PDF path
+        output_path: Output TIFF path
+        dpi: Resolution in dots per inch
+        compression: "lzw", "deflate", or "ccitt" (pages are converted to bitonal)
+
+    Returns:
+        Number of pages written.
+
+    Raises:
+        InvalidFileTypeError: If compression is not supported.
+        ConversionError: If conversion fails.
+    """
+    codec = TIFF_COMPRESSIONS.get(compression.lower())
+    if codec is None:
+        raise InvalidFileTypeError(compression, list(TIFF_COMPRESSIONS))
+
+    input_path = validate_file_exists(pdf_path, ["pdf"])
+    out_path = Path(output_path)
+    ensure_output_dir(out_path.parent)
+
+    try:
+        from PIL import Image, TiffImagePlugin
+
+        doc = fitz.open(str(input_path))
+        page_count = len(doc)
+        if not page_count:
+            doc.close()
+            raise ValueError("PDF has no pages")
+
+        zoom = dpi / 72.0
+        matrix = fitz.Matrix(zoom, zoom)
+        bitonal = codec == "group4"
+
+        # Each page is rendered, appended and dropped before the next one, so
+        # memory stays at one frame whatever the page count
+        try:
+            with TiffImagePlugin.AppendingTiffWriter(str(out_path), True) as tiff:
+                for page in doc:
+                    report_progress("render", page.number, page_count)
+                    colorspace = fitz.csGRAY if bitonal else fitz.csRGB
+                    pix = page.get_pixmap(matrix=matrix, colorspace=colorspace, alpha=False)
+                    mode = "L" if bitonal else "RGB"
+                    img = Image.frombytes(mode, (pix.width, pix.height), pix.samples)
+                    pix = None
+                    if bitonal:
+                        img = img.point(lambda v: 255 if v >= 128 else 0).convert("1")
+                    img.save(tiff, "TIFF", compression=codec, dpi=(dpi, dpi))
+                    tiff.newFrame()
+        except Exception:
+            out_path.unlink(missing_ok=True)
+            raise
+        finally:
+            doc.close()
+
+        report_progress("render", page_count, page_count)
+        return page_count
+
+    except Exception as e:
+        raise ConversionError("pdf", "tiff", str(e)) from e
+
+
+def pdf_flatten_to_images(
+    pdf_path: Path | str,
+    output_path: Path | str,
+    *,
+    dpi: int = 150,
+    format: str = "png",
+    quality: int = 90,
+) -> int:
+    """
+    Rebuild a PDF with every page replaced by a rendered image of itself.
+
+    The result has no selectable text or vector content. Pages keep their
+    size and orientation; annotations are rendered into the image.
+
+    Args:
+        pdf_path: Input PDF path
+        output_path: Output PDF path
+        dpi: Render resolution
+        format: Page image format ("png" lossless, "jpg" smaller)
+        quality: JPEG quality (1-100)
+
+    Returns:
+        Number of pages written.
+
+    Raises:
+        InvalidFileTypeError: If format is not supported.
+        ConversionError: If conversion fails.
+    """
+    fmt = format.lower()
+    if fmt == "jpeg":
+        fmt = "jpg"
+    if fmt not in FLATTEN_FORMATS:
+        raise InvalidFileTypeError(format, FLATTEN_FORMATS)
+
+    input_path = validate_file_exists(pdf_path, ["pdf"])
+    out_path = Path(output_path)
+    ensure_output_dir(out_path.parent)
+
+    try:
+        src = fitz.open(str(input_path))
+        out = fitz.open()
+        zoom = dpi / 72.0
+        matrix = fitz.Matrix(zoom, zoom)
+
+        for page in src:
+            report_progress("render", page.number, len(src))
+            pix = page.get_pixmap(matrix=matrix, alpha=False)
+            if fmt == "jpg":
+                data = pix.tobytes("jpeg", jpg_quality=quality)
+            else:
+                data = pix.tobytes("png")
+            # page.rect already reflects /Rotate, so the image goes in upright
+            new_page = out.new_page(width=page.rect.width, height=page.rect.height)
+            new_page.insert_image(new_page.rect, stream=data)
+
+        report_progress("render", len(src), len(src))
+        pages = len(out)
+        src.close()
+
+        if pages == 0:
+            raise ValueError("PDF has no pages")
+
+        out.save(str(out_path), garbage=3, deflate=True)
+        out.close()
+        return pages
+
+    except Exception as e:
+        raise ConversionError("pdf", "pdf", str(e)) from e
+
+
 def _parse_page_range(range_str: str, total_pages: int) -> list[int]:
     """
     Parse a page range string into a list of 0-indexed page numbers.
@@ -424,8 +875,15 @@ def _build_parser() -> argparse.ArgumentParser:
     img2pdf.add_argument("--page-size", default="a4", help="Page size (a4, letter, fit, etc.)")
     img2pdf.add_argument("--orientation", default="auto", help="portrait, landscape, or auto")
     img2pdf.add_argument("--margin", type=float, default=0, help="Margin in mm")
-    img2pdf.add_argument("--quality", type=int, default=95, help="Image quality (1-100)")
+    img2pdf.add_argument("--quality", type=int, default=95, help="JPEG quality for downsampled images (1-100)")
+    img2pdf.add_argument("--max-image-dpi", type=int, help="Downsample images shown above this DPI")
     img2pdf.add_argument("--transforms", help="JSON array of transforms [{rotation, flip_h, flip_v}, ...]")
+    img2pdf.add_argument("--append-to", help="Existing PDF to append the image pages to")
+    img2pdf.add_argument("--single-page", action="store_true", help="Stack all images on one tall page")
+    img2pdf.add_argument(
+        "--max-height", type=float, default=MAX_PAGE_HEIGHT_PT,
+        help="Tallest single page in points; taller stacks are scaled down",
+    )
 
     # pdf-to-images
     pdf2img = sub.add_parser("pdf-to-images", help="Convert PDF to images")
@@ -436,6 +894,28 @@ def _build_parser() -> argparse.ArgumentParser:
     pdf2img.add_argument("--pages", help="Page range (e.g., '1-3,5,7')")
     pdf2img.add_argument("--prefix", default="page", help="Filename prefix")
 
+    # extract-images
+    extract = sub.add_parser("extract-images", help="Save embedded images in their original format")
+    extract.add_argument("--input", required=True, help="Input PDF path")
+    extract.add_argument("--output-dir", required=True, help="Output directory")
+    extract.add_argument("--pages", help="Page range (e.g., '1-3,5,7')")
+    extract.add_argument("--min-size", type=int, default=0, help="Skip images smaller than this (pixels)")
+
+    # pdf-to-tiff
+    pdf2tiff = sub.add_parser("pdf-to-tiff", help="Convert PDF to a multipage TIFF")
+    pdf2tiff.add_argument("--input", required=True, help="Input PDF path")
+    pdf2tiff.add_argument("--output", required=True, help="Output TIFF path")
+    pdf2tiff.add_argument("--dpi", type=int, default=200, help="Resolution in DPI")
+    pdf2tiff.add_argument("--compression", default="lzw", help="lzw, deflate, or ccitt")
+
+    # flatten-to-images
+    flatten = sub.add_parser("flatten-to-images", help="Rebuild a PDF from rendered page images")
+    flatten.add_argument("--input", required=True, help="Input PDF path")
+    flatten.add_argument("--output", required=True, help="Output PDF path")
+    flatten.add_argument("--dpi", type=int, default=150, help="Resolution in DPI")
+    flatten.add_argument("--format", default="png", help="Page image format (png, jpg)")
+    flatten.add_argument("--quality", type=int, default=90, help="JPEG quality (1-100)")
+
     return parser
 
 
@@ -448,9 +928,25 @@ def _main(argv: list[str]) -> int:
             # Parse transforms if provided
             transforms = None
             if args.transforms:
-                import json
                 transforms = json.loads(args.transforms)
 
+            if args.single_page:
+                if args.append_to:
+                    raise ValueError("--single-page can't be combined with --append-to")
+                page = images_to_long_page(
+                    [Path(p) for p in args.inputs],
+                    Path(args.output),
+                    page_size=args.page_size,
+                    orientation=args.orientation,
+                    margin_mm=args.margin,
+                    quality=args.quality,
+                    transforms=transforms,
+                    max_height=args.max_height,
+                    max_image_dpi=args.max_image_dpi,
+                )
+                print(json.dumps(page))
+                return 0
+
             result = images_to_pdf(
                 [Path(p) for p in args.inputs],
                 Path(args.output),
@@ -459,8 +955,10 @@ def _main(argv: list[str]) -> int:
                 margin_mm=args.margin,
                 quality=args.quality,
                 transforms=transforms,
+                append_to=args.append_to,
+                max_image_dpi=args.max_image_dpi,
             )
-            print(f"Created: {result}")
+            print(json.dumps(result))
 
         elif args.command == "pdf-to-images":
             results = pdf_to_images(
@@ -475,7 +973,36 @@ def _main(argv: list[str]) -> int:
             for p in results:
                 print(f"  {p}")
 
+        elif args.command == "extract-images":
+            results = extract_images(
+                Path(args.input),
+                Path(args.output_dir),
+                pages=args.pages,
+                min_size=args.min_size,
+            )
+            print(json.dumps(results))
+
+        elif args.command == "pdf-to-tiff":
+            pages = pdf_to_multipage_tiff(
+                Path(args.input),
+                Path(args.output),
+                dpi=args.dpi,
+                compression=args.compression,
+            )
+            print(f"Created {pages} pages: {args.output}")
+
+        elif args.command == "flatten-to-images":
+            pages = pdf_flatten_to_images(
+                Path(args.input),
+                Path(args.output),
+                dpi=args.dpi,
+                format=args.format,
+                quality=args.quality,
+            )
+            print(f"Created {pages} pages: {args.output}")
+
     except Exception as exc:
+        report_error(exc)
         print(f"Error: {exc}", file=sys.stderr)
         return 1
 
@@ -8,6 +8,12 @@ CLI usage (dev):
   python pdf_edit.py text-blocks --input doc.pdf --page 0
   python pdf_edit.py insert-text --input doc.pdf --output out.pdf --page 0 --x 100 --y 100 --text "Hello"
   python pdf_edit.py preview --input doc.pdf --page 0 --edits '{"ops": [...]}'
+  python pdf_edit.py transitions --input doc.pdf --output out.pdf --transition wipe --duration 5
+  python pdf_edit.py auto-rotate --input doc.pdf --output out.pdf --bake
+  python pdf_edit.py add-link --input doc.pdf --output out.pdf --page 1 --rect 0.1,0.2,0.3,0.05 --target-page 12
+  python pdf_edit.py optimize-content --input doc.pdf --output out.pdf
+  python pdf_edit.py downsample-images --input doc.pdf --output out.pdf --max-dpi 150 --quality 70
+  python pdf_edit.py crop --input doc.pdf --output out.pdf --auto-trim --padding 6
 """
 
 from __future__ import annotations
@@ -18,12 +24,24 @@ import json
 import base64
 import io
 import math
+import os
 import subprocess
+from urllib.parse import urlparse
 from pathlib import Path
 from typing import Optional, Tuple
 
 import fitz  # PyMuPDF
 
+try:
+    from pdf_pages import parse_ranges
+except ImportError:
+    from .pdf_pages import parse_ranges
+
+try:
+    from utils import run_main
+except ImportError:
+    from .utils import run_main
+
 
 # Cache for system font lookups (font_name -> font_file_path or None)
 _font_cache: dict[str, Optional[str]] = {}
@@ -350,6 +368,62 @@ def replace_text_area(
     return result
 
 
+def _delete_area_redactions(page: fitz.Page) -> None:
+    """
+    Apply pending redactions as a pure deletion: text and vector art inside
+    the areas go, images and everything outside stay. Annotations must be
+    added with fill=False so no box is painted over the hole.
+    """
+    page.apply_redactions(
+        images=fitz.PDF_REDACT_IMAGE_NONE,
+        graphics=fitz.PDF_REDACT_LINE_ART_REMOVE_IF_COVERED,
+    )
+
+
+def delete_area(
+    input_path: Path,
+    output_path: Path,
+    page_num: int,
+    x0: float,
+    y0: float,
+    x1: float,
+    y1: float,
+) -> dict:
+    """
+    Remove text and vector content within a rectangle.
+
+    Unlike replace_text_area nothing is painted over the area and nothing is
+    inserted: the background (images, content outside the rect) is left as is.
+    """
+    result = {
+        "success": False,
+        "message": "",
+    }
+
+    try:
+        doc = fitz.open(input_path)
+
+        if page_num < 0 or page_num >= len(doc):
+            result["message"] = f"Invalid page number: {page_num}"
+            doc.close()
+            return result
+
+        page = doc[page_num]
+        page.add_redact_annot(fitz.Rect(x0, y0, x1, y1), fill=False)
+        _delete_area_redactions(page)
+
+        doc.save(output_path, garbage=4, deflate=True)
+        doc.close()
+
+        result["success"] = True
+        result["message"] = "Area content deleted"
+
+    except Exception as e:
+        result["message"] = str(e)
+
+    return result
+
+
 def insert_image(
     input_path: Path,
     output_path: Path,
@@ -407,7 +481,7 @@ def apply_edits(
     {
         "ops": [
             {
-                "type": "insert_text" | "replace_text" | "draw_shape",
+                "type": "insert_text" | "replace_text" | "draw_shape" | "delete",
                 "page": 0,  # 0-indexed
                 "rect": { "x": 0.1, "y": 0.1, "width": 0.3, "height": 0.05 },  # normalized
                 "text": "Hello",  # for text ops
@@ -455,6 +529,8 @@ def apply_edits(
 
         # Collect replace_text operations by page for batched processing
         replace_ops_by_page: dict = {}  # page_num -> list of op data
+        # delete rects share the same single apply_redactions() per page
+        delete_rects_by_page: dict = {}  # page_num -> list of fitz.Rect
 
         for op in ops:
             op_type = op.get("type")
@@ -560,6 +636,9 @@ def apply_edits(
                     "x0": x0, "y0": y0, "x1": x1, "y1": y1,
                 })
 
+            elif op_type == "delete":
+                delete_rects_by_page.setdefault(page_num, []).append(fitz.Rect(x0, y0, x1, y1))
+
             elif op_type == "draw_shape":
                 shape_type = op.get("shape", "rect")
                 stroke_color_str = op.get("strokeColor", "#000000")
@@ -587,10 +666,12 @@ def apply_edits(
                 shape.commit()
                 applied_count += 1
 
-        # PASS 2: Process all replace_text operations by page (batched)
+        # PASS 2: Process all replace_text and delete operations by page (batched)
         # This ensures apply_redactions() is only called once per page
-        for page_num, ops_list in replace_ops_by_page.items():
+        for page_num in sorted(set(replace_ops_by_page) | set(delete_rects_by_page)):
             page = doc[page_num]
+            ops_list = replace_ops_by_page.get(page_num, [])
+            delete_rects = delete_rects_by_page.get(page_num, [])
 
             # Step 1: Draw all white rectangles first (for OCR'd PDFs)
             # Use cover_rect (full size) to completely cover original scanned text
@@ -600,12 +681,19 @@ def apply_edits(
                 shape.finish(fill=(1, 1, 1), color=(1, 1, 1), width=0)
                 shape.commit()
 
-            # Step 2: Add all redaction annotations
+            # Step 2: Add all redaction annotations (deletions without a fill)
             for op_data in ops_list:
                 page.add_redact_annot(op_data["redact_rect"], fill=(1, 1, 1))
-
-            # Step 3: Apply all redactions ONCE
-            page.apply_redactions()
+            for delete_rect in delete_rects:
+                page.add_redact_annot(delete_rect, fill=False)
+
+            # Step 3: Apply all redactions ONCE. Deletions must keep images, which
+            # is harmless for replacements since their areas are already covered
+            if delete_rects:
+                _delete_area_redactions(page)
+                applied_count += len(delete_rects)
+            else:
+                page.apply_redactions()
 
             # Step 4: Insert all new text
             for op_data in ops_list:
@@ -918,6 +1006,10 @@ def render_page_preview(
                         )
                         current_y += line_height
 
+            elif op_type == "delete":
+                page.add_redact_annot(fitz.Rect(x0, y0, x1, y1), fill=False)
+                _delete_area_redactions(page)
+
             elif op_type == "draw_shape":
                 shape_type = op.get("shape", "rect")
                 stroke_color_str = op.get("strokeColor", "#000000")
@@ -1227,7 +1319,574 @@ def draw_shape(
     return result
 
 
-def analyze_fonts(input_path: Path) -> dict:
+# PDF transition styles (/S values in a /Trans dictionary), keyed by lowercase name
+TRANSITION_STYLES = {
+    "split": "Split",
+    "blinds": "Blinds",
+    "box": "Box",
+    "wipe": "Wipe",
+    "dissolve": "Dissolve",
+    "glitter": "Glitter",
+    "replace": "R",
+    "fly": "Fly",
+    "push": "Push",
+    "cover": "Cover",
+    "uncover": "Uncover",
+    "fade": "Fade",
+}
+
+
+def parse_page_selection(pages: Optional[str], total: int) -> list:
+    """
+    Parse a 1-indexed page selection like "1-3,5" (or "all"/None) into
+    sorted 0-indexed page numbers, via pdf_pages.parse_ranges.
+    """
+    if not pages or pages.strip().lower() == "all":
+        return list(range(total))
+    return sorted(set(parse_ranges(pages, total)))
+
+
+def _save(doc: fitz.Document, input_path: Path, output_path: Path) -> None:
+    """Save to output_path, going through a temp file when overwriting the input."""
+    if output_path.resolve() == input_path.resolve():
+        tmp_path = output_path.with_name(output_path.name + ".tmp")
+        doc.save(str(tmp_path), garbage=1)
+        doc.close()
+        os.replace(tmp_path, output_path)
+    else:
+        doc.save(str(output_path), garbage=1)
+        doc.close()
+
+
+def set_page_transitions(
+    input_path: Path,
+    output_path: Path,
+    transition: str,
+    duration: float,
+    pages: Optional[str] = None,
+) -> dict:
+    """
+    Write /Trans (transition effect) and /Dur (auto-advance seconds) entries
+    on the selected pages, for presentation-mode playback.
+    """
+    result = {
+        "success": False,
+        "message": "",
+        "pages_set": 0,
+    }
+
+    try:
+        style = TRANSITION_STYLES.get(transition.strip().lower())
+        if style is None:
+            result["message"] = (
+                f"Unknown transition: {transition} "
+                f"(expected one of: {', '.join(TRANSITION_STYLES)})"
+            )
+            return result
+
+        if not math.isfinite(duration) or duration <= 0:
+            result["message"] = f"Duration must be a positive number of seconds, got {duration}"
+            return result
+
+        doc = fitz.open(input_path)
+        page_indices = parse_page_selection(pages, len(doc))
+
+        for page_idx in page_indices:
+            xref = doc[page_idx].xref
+            # Transition effect lasts one second; /Dur is how long the page stays up
+            doc.xref_set_key(xref, "Trans", f"<</Type/Trans/S/{style}/D 1>>")
+            doc.xref_set_key(xref, "Dur", f"{duration:g}")
+
+        _save(doc, input_path, output_path)
+
+        result["success"] = True
+        result["pages_set"] = len(page_indices)
+        result["message"] = f"{style} transition set on {len(page_indices)} pages"
+
+    except Exception as e:
+        result["message"] = str(e)
+
+    return result
+
+
+def detect_text_angle(page: fitz.Page) -> Tuple[float, int]:
+    """
+    Estimate the dominant reading direction of text on a page as displayed
+    (i.e. after /Rotate is applied).
+
+    Returns (angle, chars) where angle is in degrees counter-clockwise from
+    upright, weighted by the number of characters on each line.
+    """
+    weights: dict[int, int] = {}
+    total = 0
+
+    for block in page.get_text("dict")["blocks"]:
+        if block.get("type") != 0:
+            continue
+        for line in block.get("lines", []):
+            chars = sum(len(span["text"].strip()) for span in line.get("spans", []))
+            if chars == 0:
+                continue
+            dx, dy = line["dir"]
+            # Page y axis points down, so flip dy for a counter-clockwise angle
+            angle = round(math.degrees(math.atan2(-dy, dx))) % 360
+            weights[angle] = weights.get(angle, 0) + chars
+            total += chars
+
+    if not weights:
+        return 0.0, 0
+
+    return float(max(weights, key=weights.get)), total
+
+
+def auto_rotate(
+    input_path: Path,
+    output_path: Path,
+    bake: bool = False,
+    pages: Optional[str] = None,
+) -> dict:
+    """
+    Detect per-page text orientation and tell apart rotation declared via
+    /Rotate from content drawn at an angle in the content stream.
+
+    With bake=True, pages whose text still reads at a right angle are redrawn
+    upright (content transformed, /Rotate cleared). Annotations on baked pages
+    are not carried over.
+    """
+    result = {
+        "success": False,
+        "message": "",
+        "pages": [],
+    }
+
+    try:
+        src = fitz.open(input_path)
+        selected = set(parse_page_selection(pages, len(src)))
+        # The corrected document is only assembled when baking
+        out = fitz.open() if bake else None
+        corrected_count = 0
+
+        for page_idx in range(len(src)):
+            page = src[page_idx]
+            corrected = False
+
+            if page_idx in selected:
+                text_angle, chars = detect_text_angle(page)
+                # Snap to the nearest right angle; skewed scans are left to deskew
+                snapped = int(round(text_angle / 90.0)) % 4 * 90
+                entry = {
+                    "page": page_idx + 1,
+                    "rotate_attribute": page.rotation,
+                    "text_angle": text_angle,
+                    "has_text": chars > 0,
+                    "baked_rotation": chars > 0 and snapped != 0,
+                    "corrected": False,
+                }
+
+                if bake and entry["baked_rotation"]:
+                    rect = page.rect
+                    if snapped in (90, 270):
+                        width, height = rect.height, rect.width
+                    else:
+                        width, height = rect.width, rect.height
+                    new_page = out.new_page(width=width, height=height)
+                    # Turn the content back by the detected angle (clockwise)
+                    new_page.show_pdf_page(new_page.rect, src, page_idx, rotate=-snapped)
+                    corrected = True
+                    corrected_count += 1
+
+                entry["corrected"] = corrected
+                result["pages"].append(entry)
+
+            if bake and not corrected:
+                out.insert_pdf(src, from_page=page_idx, to_page=page_idx)
+
+        if bake:
+            out.save(output_path, garbage=3, deflate=True)
+            out.close()
+        src.close()
+
+        result["success"] = True
+        baked = sum(1 for p in result["pages"] if p["baked_rotation"])
+        if bake:
+            result["message"] = f"Corrected {corrected_count} of {baked} pages with rotated content"
+        else:
+            result["message"] = f"Found {baked} pages with rotated content"
+
+    except Exception as e:
+        result["message"] = str(e)
+
+    return result
+
+
+# Render resolution for auto-trim content detection
+TRIM_DPI = 50
+# Gray levels a pixel may differ from the margin color and still count as margin
+TRIM_THRESHOLD = 40
+
+
+def detect_content_bounds(page: fitz.Page, padding: float) -> Optional[fitz.Rect]:
+    """
+    Bounds of the visible content on a page as displayed, grown by `padding`
+    points and clipped to the page.
+
+    The margin color is taken from the page corners, so both white paper and
+    the dark borders of scans are trimmed. Returns None for blank pages.
+    """
+    from PIL import Image, ImageChops
+
+    zoom = TRIM_DPI / 72.0
+    pix = page.get_pixmap(matrix=fitz.Matrix(zoom, zoom), colorspace=fitz.csGRAY, alpha=False)
+    img = Image.frombytes("L", (pix.width, pix.height), pix.samples)
+
+    w, h = img.size
+    corners = sorted(img.getpixel(xy) for xy in [(0, 0), (w - 1, 0), (0, h - 1), (w - 1, h - 1)])
+    background = (corners[1] + corners[2]) // 2
+
+    diff = ImageChops.difference(img, Image.new("L", img.size, background))
+    mask = diff.point(lambda v: 255 if v > TRIM_THRESHOLD else 0)
+    bbox = mask.getbbox()
+    if bbox is None:
+        return None
+
+    x0, y0, x1, y1 = (v / zoom for v in bbox)
+    rect = fitz.Rect(x0 - padding, y0 - padding, x1 + padding, y1 + padding)
+    return rect & page.rect
+
+
+def crop_pages(
+    input_path: Path,
+    output_path: Path,
+    box: Optional[Tuple[float, float, float, float]] = None,
+    auto_trim: bool = False,
+    padding: float = 6.0,
+    pages: Optional[str] = None,
+) -> dict:
+    """
+    Set the CropBox of the selected pages, either to `box` or, with auto_trim,
+    to each page's detected content bounds plus `padding`.
+
+    Boxes are in points with a top-left origin, as the page is displayed
+    (after /Rotate). Reported boxes use the same coordinates.
+    """
+    result = {
+        "success": False,
+        "message": "",
+        "pages": [],
+    }
+
+    if (box is None) == (not auto_trim):
+        result["message"] = "Provide either a crop box or auto trim, not both"
+        return result
+
+    try:
+        doc = fitz.open(input_path)
+        cropped = 0
+
+        for page_idx in parse_page_selection(pages, len(doc)):
+            page = doc[page_idx]
+            if auto_trim:
+                rect = detect_content_bounds(page, padding)
+            else:
+                rect = fitz.Rect(box) & page.rect
+
+            if rect is None or rect.is_empty:
+                result["pages"].append({"page": page_idx + 1, "box": None})
+                continue
+
+            # Displayed coordinates -> unrotated, relative to the MediaBox
+            unrotated = (rect * page.derotation_matrix).normalize()
+            dx, dy = page.cropbox_position
+            unrotated += (dx, dy, dx, dy)
+            page.set_cropbox(unrotated)
+
+            result["pages"].append({
+                "page": page_idx + 1,
+                "box": [rect.x0, rect.y0, rect.x1, rect.y1],
+            })
+            cropped += 1
+
+        doc.save(output_path, garbage=1)
+        doc.close()
+
+        result["success"] = True
+        result["message"] = f"Cropped {cropped} pages"
+
+    except Exception as e:
+        result["message"] = str(e)
+
+    return result
+
+
+# URI schemes accepted for link annotations
+LINK_URI_SCHEMES = ("http", "https", "mailto", "ftp")
+
+
+def validate_link_uri(uri: str) -> Optional[str]:
+    """Return an error message if the URI is not an absolute link we allow, else None."""
+    parsed = urlparse(uri.strip())
+    if parsed.scheme.lower() not in LINK_URI_SCHEMES:
+        return f"Unsupported URI scheme in '{uri}' (allowed: {', '.join(LINK_URI_SCHEMES)})"
+    if parsed.scheme.lower() == "mailto":
+        if not parsed.path:
+            return f"Missing address in '{uri}'"
+    elif not parsed.netloc:
+        return f"Missing host in '{uri}'"
+    return None
+
+
+def add_link(
+    input_path: Path,
+    output_path: Path,
+    page_num: int,
+    rect: Tuple[float, float, float, float],
+    uri: Optional[str] = None,
+    target_page: Optional[int] = None,
+    target_y: Optional[float] = None,
+) -> dict:
+    """
+    Add a clickable link annotation over a region.
+
+    page_num and target_page are 1-indexed; rect is (x, y, width, height) and
+    target_y a fraction of the target page height, both normalized 0-1 from
+    the top-left like the viewer's coordinates.
+    """
+    result = {
+        "success": False,
+        "message": "",
+    }
+
+    try:
+        if (uri is None) == (target_page is None):
+            result["message"] = "Provide exactly one of a URI or a target page"
+            return result
+
+        if uri is not None:
+            error = validate_link_uri(uri)
+            if error:
+                result["message"] = error
+                return result
+
+        x, y, width, height = rect
+        if width <= 0 or height <= 0 or x < 0 or y < 0 or x + width > 1 or y + height > 1:
+            result["message"] = "Link rect must be non-empty and within the page (normalized 0-1)"
+            return result
+
+        doc = fitz.open(input_path)
+
+        if page_num < 1 or page_num > len(doc):
+            result["message"] = f"Invalid page number: {page_num} ({len(doc)} pages)"
+            doc.close()
+            return result
+
+        page = doc[page_num - 1]
+        pw, ph = page.rect.width, page.rect.height
+        link_rect = fitz.Rect(x * pw, y * ph, (x + width) * pw, (y + height) * ph)
+
+        if uri is not None:
+            link = {"kind": fitz.LINK_URI, "from": link_rect, "uri": uri.strip()}
+            description = uri.strip()
+        else:
+            if target_page < 1 or target_page > len(doc):
+                result["message"] = f"Target page {target_page} out of range ({len(doc)} pages)"
+                doc.close()
+                return result
+            dest_height = doc[target_page - 1].rect.height
+            link = {
+                "kind": fitz.LINK_GOTO,
+                "from": link_rect,
+                "page": target_page - 1,
+                "to": fitz.Point(0, (target_y or 0) * dest_height),
+            }
+            description = f"page {target_page}"
+
+        page.insert_link(link)
+
+        doc.save(output_path, garbage=1)
+        doc.close()
+
+        result["success"] = True
+        result["message"] = f"Link to {description} added on page {page_num}"
+
+    except Exception as e:
+        result["message"] = str(e)
+
+    return result
+
+
+def optimize_content_streams(input_path: Path, output_path: Path) -> dict:
+    """
+    Re-emit each page's content streams as one compact, syntactically clean
+    stream (redundant state operators dropped, fragments merged).
+    Images and fonts are written through untouched.
+    """
+    result = {
+        "success": False,
+        "message": "",
+        "output_path": str(output_path),
+        "bytes_saved": 0,
+        "pages_processed": 0,
+    }
+
+    try:
+        original_size = Path(input_path).stat().st_size
+        doc = fitz.open(input_path)
+
+        for page in doc:
+            page.clean_contents(sanitize=True)
+            result["pages_processed"] += 1
+
+        # garbage=1 drops the replaced streams; images/fonts keep their encoding
+        doc.save(
+            output_path,
+            garbage=1,
+            deflate=True,
+            deflate_images=False,
+            deflate_fonts=False,
+        )
+        doc.close()
+
+        result["bytes_saved"] = original_size - Path(output_path).stat().st_size
+        result["success"] = True
+        result["message"] = (
+            f"Rebuilt content streams on {result['pages_processed']} pages, "
+            f"saved {result['bytes_saved']} bytes"
+        )
+
+    except Exception as e:
+        result["message"] = str(e)
+
+    return result
+
+
+def downsample_images(input_path: Path, output_path: Path, max_dpi: int, quality: int) -> dict:
+    """
+    Re-encode images displayed above `max_dpi` as JPEG at that resolution, and
+    recompress existing JPEGs at `quality`. An image is only replaced when the
+    new stream is smaller. Images with transparency masks are left alone.
+    """
+    from PIL import Image
+
+    result = {
+        "success": False,
+        "message": "",
+        "output_path": str(output_path),
+        "images_rewritten": 0,
+    }
+
+    try:
+        doc = fitz.open(input_path)
+        seen = set()
+
+        for page in doc:
+            for img in page.get_images(full=True):
+                xref, smask, width, height = img[0], img[1], img[2], img[3]
+                if xref in seen or smask:
+                    continue
+                seen.add(xref)
+
+                # Effective resolution at the largest size the image is shown on this page
+                rects = page.get_image_rects(xref)
+                shown_width = max((r.width for r in rects), default=0)
+                if shown_width <= 0:
+                    continue
+                dpi = width / (shown_width / 72)
+
+                is_jpeg = doc.xref_get_key(xref, "Filter")[1] == "/DCTDecode"
+                if dpi <= max_dpi and not is_jpeg:
+                    continue
+
+                pix = fitz.Pixmap(doc, xref)
+                if pix.alpha:
+                    pix = fitz.Pixmap(pix, 0)
+                if pix.n not in (1, 3):
+                    pix = fitz.Pixmap(fitz.csRGB, pix)
+
+                mode = "L" if pix.n == 1 else "RGB"
+                image = Image.frombytes(mode, (pix.width, pix.height), pix.samples)
+                if dpi > max_dpi:
+                    scale = max_dpi / dpi
+                    size = (max(1, round(width * scale)), max(1, round(height * scale)))
+                    image = image.resize(size, Image.LANCZOS)
+
+                buffer = io.BytesIO()
+                image.save(buffer, "JPEG", quality=quality, optimize=True)
+                encoded = buffer.getvalue()
+
+                if len(encoded) < len(doc.xref_stream_raw(xref) or b""):
+                    page.replace_image(xref, stream=encoded)
+                    result["images_rewritten"] += 1
+
+        doc.save(output_path, garbage=3, deflate=True)
+        doc.close()
+
+        result["success"] = True
+        result["message"] = f"Rewrote {result['images_rewritten']} images (max {max_dpi} DPI, quality {quality})"
+
+    except Exception as e:
+        result["message"] = str(e)
+
+    return result
+
+
+def _used_glyphs_by_font(doc: fitz.Document) -> dict:
+    """
+    Map each font xref to the (character, glyph id) pairs drawn with it.
+
+    Spans only carry a font name, so they're matched to the fonts of their own
+    page: distinct subsets of one font stay apart.
+    """
+    used = {}
+    for page in doc:
+        by_name = {}
+        for font in page.get_fonts(full=True):
+            xref, basefont = font[0], font[3]
+            by_name.setdefault(basefont, xref)
+            by_name.setdefault(basefont.split("+", 1)[-1], xref)
+        for span in page.get_texttrace():
+            xref = by_name.get(span["font"]) or by_name.get(span["font"].split("+", 1)[-1])
+            if xref is None:
+                continue
+            glyphs = used.setdefault(xref, set())
+            for unicode, gid, *_ in span["chars"]:
+                char = chr(unicode) if unicode >= 0 else "\ufffd"
+                if not char.isspace():
+                    glyphs.add((char, gid))
+    return used
+
+
+def _glyph_coverage(doc: fitz.Document, xref: int, glyphs: set) -> Tuple[list, Optional[float]]:
+    """
+    Check the (character, glyph id) pairs drawn with the font at `xref`.
+
+    A character is missing when it was drawn with .notdef or a glyph id past
+    the end of the embedded program, or when ToUnicode gives no mapping for it
+    (U+FFFD). Glyph ids are checked rather than the font's own cmap, which
+    subset CID fonts usually don't have.
+
+    Returns (missing characters, coverage percent). Coverage is None when the
+    font isn't embedded or its program can't be loaded.
+    """
+    if not glyphs:
+        return [], None
+    buffer = doc.extract_font(xref)[3]
+    if not buffer:
+        return [], None
+    try:
+        font = fitz.Font(fontbuffer=buffer)
+    except Exception:
+        return [], None
+
+    chars = {char for char, _ in glyphs}
+    missing = sorted({
+        char for char, gid in glyphs
+        if char == "\ufffd" or gid <= 0 or gid >= font.glyph_count
+    })
+    coverage = round(100 * (len(chars) - len(missing)) / len(chars), 1)
+    return missing, coverage
+
+
+def analyze_fonts(input_path: Path, check_coverage: bool = False) -> dict:
     """
     Analyze fonts used in a PDF document.
 
@@ -1236,6 +1895,7 @@ def analyze_fonts(input_path: Path) -> dict:
     - Whether it's embedded/subset
     - System font availability
     - Suggested alternatives with similarity scores
+    - With check_coverage, the used characters missing from the embedded font
     """
     result = {
         "success": False,
@@ -1384,7 +2044,7 @@ def analyze_fonts(input_path: Path) -> dict:
                     all_fonts[clean_name] = {
                         "original_name": font_name,
                         "clean_name": clean_name,
-                        "xref": xref,
+                        "xrefs": [],
                         "type": font_type,
                         "encoding": encoding,
                         "pages": [],
@@ -1392,9 +2052,13 @@ def analyze_fonts(input_path: Path) -> dict:
                         "is_embedded": ext not in ["", None, "n/a"],
                     }
 
+                if xref not in all_fonts[clean_name]["xrefs"]:
+                    all_fonts[clean_name]["xrefs"].append(xref)
                 if page_num + 1 not in all_fonts[clean_name]["pages"]:
                     all_fonts[clean_name]["pages"].append(page_num + 1)
 
+        used_glyphs = _used_glyphs_by_font(doc) if check_coverage else {}
+
         # Analyze each font
         fonts_list = []
         missing_count = 0
@@ -1430,6 +2094,23 @@ def analyze_fonts(input_path: Path) -> dict:
             font_info["bestMatch"] = matches[0] if matches else None
             font_info["bestMatchScore"] = matches[0]["similarity"] if matches else 0
 
+            font_info["missingGlyphs"] = []
+            font_info["coveragePercent"] = None
+            if check_coverage and font_data["is_embedded"]:
+                # Each subset sharing this name is its own font program
+                missing, used = set(), set()
+                checked = False
+                for xref in font_data["xrefs"]:
+                    glyphs = used_glyphs.get(xref, set())
+                    xref_missing, coverage = _glyph_coverage(doc, xref, glyphs)
+                    if coverage is not None:
+                        checked = True
+                        missing.update(xref_missing)
+                        used.update(char for char, _ in glyphs)
+                font_info["missingGlyphs"] = sorted(missing)
+                if checked and used:
+                    font_info["coveragePercent"] = round(100 * (len(used) - len(missing)) / len(used), 1)
+
             # Check if we have a good match
             if font_info["bestMatchScore"] < 85:
                 low_match_count += 1
@@ -1499,6 +2180,17 @@ def main():
     replace_parser.add_argument("--text", "-t", required=True, help="New text")
     replace_parser.add_argument("--json", action="store_true", help="Output as JSON")
 
+    # Delete area command
+    delete_parser = subparsers.add_parser("delete-area", help="Remove text and vector content in an area")
+    delete_parser.add_argument("--input", "-i", required=True, help="Input PDF path")
+    delete_parser.add_argument("--output", "-o", required=True, help="Output PDF path")
+    delete_parser.add_argument("--page", "-p", type=int, required=True, help="Page number")
+    delete_parser.add_argument("--x0", type=float, required=True)
+    delete_parser.add_argument("--y0", type=float, required=True)
+    delete_parser.add_argument("--x1", type=float, required=True)
+    delete_parser.add_argument("--y1", type=float, required=True)
+    delete_parser.add_argument("--json", action="store_true", help="Output as JSON")
+
     # Apply edits batch command
     apply_parser = subparsers.add_parser("apply-edits", help="Apply multiple edits from JSON")
     apply_parser.add_argument("--input", "-i", required=True, help="Input PDF path")
@@ -1523,8 +2215,62 @@ def main():
     # Analyze fonts command
     analyze_parser = subparsers.add_parser("analyze-fonts", help="Analyze fonts in PDF")
     analyze_parser.add_argument("--input", "-i", required=True, help="Input PDF path")
+    analyze_parser.add_argument("--coverage", action="store_true",
+                                help="Check used characters against embedded font glyphs (slower)")
     analyze_parser.add_argument("--json", action="store_true", help="Output as JSON")
 
+    # Page transitions command
+    trans_parser = subparsers.add_parser("transitions", help="Set presentation transitions on pages")
+    trans_parser.add_argument("--input", "-i", required=True, help="Input PDF path")
+    trans_parser.add_argument("--output", "-o", required=True, help="Output PDF path")
+    trans_parser.add_argument("--transition", "-t", required=True, help="Transition style (wipe, dissolve, fade, ...)")
+    trans_parser.add_argument("--duration", "-d", type=float, required=True, help="Seconds before auto-advance")
+    trans_parser.add_argument("--pages", "-p", default=None, help="Page selection like 1-3,5 (default: all)")
+    trans_parser.add_argument("--json", action="store_true", help="Output as JSON")
+
+    # Auto-rotate command
+    rotate_parser = subparsers.add_parser("auto-rotate", help="Detect rotated text and optionally bake it upright")
+    rotate_parser.add_argument("--input", "-i", required=True, help="Input PDF path")
+    rotate_parser.add_argument("--output", "-o", default=None, help="Output PDF path (required with --bake)")
+    rotate_parser.add_argument("--bake", action="store_true", help="Redraw pages with rotated content upright")
+    rotate_parser.add_argument("--pages", "-p", default=None, help="Page selection like 1-3,5 (default: all)")
+    rotate_parser.add_argument("--json", action="store_true", help="Output as JSON")
+
+    # Add link command
+    link_parser = subparsers.add_parser("add-link", help="Add a link annotation over a region")
+    link_parser.add_argument("--input", "-i", required=True, help="Input PDF path")
+    link_parser.add_argument("--output", "-o", required=True, help="Output PDF path")
+    link_parser.add_argument("--page", type=int, required=True, help="Page number (1-indexed)")
+    link_parser.add_argument("--rect", required=True, help="Normalized x,y,width,height (0-1, top-left origin)")
+    link_parser.add_argument("--uri", default=None, help="External URI target")
+    link_parser.add_argument("--target-page", type=int, default=None, help="Internal target page (1-indexed)")
+    link_parser.add_argument("--target-y", type=float, default=None, help="Normalized Y on the target page")
+    link_parser.add_argument("--json", action="store_true", help="Output as JSON")
+
+    # Optimize content streams command
+    optimize_parser = subparsers.add_parser("optimize-content", help="Rebuild page content streams compactly")
+    optimize_parser.add_argument("--input", "-i", required=True, help="Input PDF path")
+    optimize_parser.add_argument("--output", "-o", required=True, help="Output PDF path")
+    optimize_parser.add_argument("--json", action="store_true", help="Output as JSON")
+
+    # Downsample images command
+    downsample_parser = subparsers.add_parser("downsample-images", help="Cap image resolution and recompress as JPEG")
+    downsample_parser.add_argument("--input", "-i", required=True, help="Input PDF path")
+    downsample_parser.add_argument("--output", "-o", required=True, help="Output PDF path")
+    downsample_parser.add_argument("--max-dpi", type=int, required=True, help="Maximum effective image DPI")
+    downsample_parser.add_argument("--quality", type=int, default=75, help="JPEG quality (1-100)")
+    downsample_parser.add_argument("--json", action="store_true", help="Output as JSON")
+
+    # Crop pages command
+    crop_parser = subparsers.add_parser("crop", help="Set page crop boxes explicitly or by trimming margins")
+    crop_parser.add_argument("--input", "-i", required=True, help="Input PDF path")
+    crop_parser.add_argument("--output", "-o", required=True, help="Output PDF path")
+    crop_parser.add_argument("--box", help="Crop box x0,y0,x1,y1 in points, top-left origin")
+    crop_parser.add_argument("--auto-trim", action="store_true", help="Crop each page to its content")
+    crop_parser.add_argument("--padding", type=float, default=6.0, help="Space kept around content (points)")
+    crop_parser.add_argument("--pages", help="Pages to crop (e.g. 1-3,5); default all")
+    crop_parser.add_argument("--json", action="store_true", help="Output as JSON")
+
     args = parser.parse_args()
 
     if args.command == "text-blocks":
@@ -1574,6 +2320,22 @@ def main():
             print(result["message"])
             sys.exit(0 if result["success"] else 1)
 
+    elif args.command == "delete-area":
+        result = delete_area(
+            Path(args.input),
+            Path(args.output),
+            args.page,
+            args.x0,
+            args.y0,
+            args.x1,
+            args.y1,
+        )
+        if hasattr(args, 'json') and args.json:
+            print(json.dumps(result))
+        else:
+            print(result["message"])
+            sys.exit(0 if result["success"] else 1)
+
     elif args.command == "apply-edits":
         result = apply_edits(
             Path(args.input),
@@ -1618,7 +2380,7 @@ def main():
                 sys.exit(1)
 
     elif args.command == "analyze-fonts":
-        result = analyze_fonts(Path(args.input))
+        result = analyze_fonts(Path(args.input), check_coverage=args.coverage)
         if hasattr(args, 'json') and args.json:
             print(json.dumps(result))
         else:
@@ -1638,10 +2400,99 @@ def main():
                     match_name = best.get("name", "N/A") if best else "N/A"
                     match_score = f"{best.get('similarity', 0)}%" if best else "N/A"
                     print(f"{status_icon} {font['name']:<28} {font['type']:<8} {match_name:<20} {match_score}")
+                    if font["missingGlyphs"]:
+                        print(f"    {font['coveragePercent']}% coverage, missing: {''.join(font['missingGlyphs'])}")
             else:
                 print(f"Error: {result['error']}")
                 sys.exit(1)
 
+    elif args.command == "transitions":
+        result = set_page_transitions(
+            Path(args.input),
+            Path(args.output),
+            args.transition,
+            args.duration,
+            args.pages,
+        )
+        if hasattr(args, 'json') and args.json:
+            print(json.dumps(result))
+        else:
+            print(result["message"])
+            sys.exit(0 if result["success"] else 1)
+
+    elif args.command == "auto-rotate":
+        if args.bake and not args.output:
+            parser.error("--output is required with --bake")
+        result = auto_rotate(
+            Path(args.input),
+            Path(args.output) if args.output else None,
+            args.bake,
+            args.pages,
+        )
+        if hasattr(args, 'json') and args.json:
+            print(json.dumps(result))
+        else:
+            for p in result["pages"]:
+                print(f"Page {p['page']}: /Rotate {p['rotate_attribute']}, text at {p['text_angle']:g} deg"
+                      f"{' (corrected)' if p['corrected'] else ''}")
+            print(result["message"])
+            sys.exit(0 if result["success"] else 1)
+
+    elif args.command == "add-link":
+        rect = tuple(float(v) for v in args.rect.split(","))
+        if len(rect) != 4:
+            parser.error("--rect needs four comma-separated values")
+        result = add_link(
+            Path(args.input),
+            Path(args.output),
+            args.page,
+            rect,
+            uri=args.uri,
+            target_page=args.target_page,
+            target_y=args.target_y,
+        )
+        if hasattr(args, 'json') and args.json:
+            print(json.dumps(result))
+        else:
+            print(result["message"])
+            sys.exit(0 if result["success"] else 1)
+
+    elif args.command == "optimize-content":
+        result = optimize_content_streams(Path(args.input), Path(args.output))
+        if hasattr(args, 'json') and args.json:
+            print(json.dumps(result))
+        else:
+            print(result["message"])
+            sys.exit(0 if result["success"] else 1)
+
+    elif args.command == "downsample-images":
+        result = downsample_images(Path(args.input), Path(args.output), args.max_dpi, args.quality)
+        if hasattr(args, 'json') and args.json:
+            print(json.dumps(result))
+        else:
+            print(result["message"])
+            sys.exit(0 if result["success"] else 1)
+
+    elif args.command == "crop":
+        box = None
+        if args.box:
+            box = tuple(float(v) for v in args.box.split(","))
+            if len(box) != 4:
+                parser.error("--box needs four comma-separated numbers")
+        result = crop_pages(
+            Path(args.input),
+            Path(args.output),
+            box,
+            args.auto_trim,
+            args.padding,
+            args.pages,
+        )
+        if hasattr(args, 'json') and args.json:
+            print(json.dumps(result))
+        else:
+            print(result["message"])
+            sys.exit(0 if result["success"] else 1)
+
 
 if __name__ == "__main__":
-    main()
+    run_main(main)
@@ -1,13 +1,18 @@
 #!/usr/bin/env python3
 """
 PDF Form Fields (AcroForms) handling using PyMuPDF.
-Supports reading, filling, and saving form fields.
+Supports reading, filling, creating, and saving form fields.
 """
 
 import sys
 import json
 import fitz  # PyMuPDF
 
+try:
+    from utils import report_error
+except ImportError:
+    from .utils import report_error
+
 # Field type constants from PyMuPDF (verified with fitz.PDF_WIDGET_TYPE_*)
 FIELD_TYPES = {
     0: "unknown",       # PDF_WIDGET_TYPE_UNKNOWN
@@ -156,6 +161,762 @@ def fill_form_fields(pdf_path: str, output_path: str, field_values: dict) -> dic
     }
 
 
+# Formats accepted by export/import
+DATA_FORMATS = ("json", "fdf")
+
+
+def _collect_field_values(doc) -> tuple:
+    """
+    Current field values keyed by field name, in the shape fill_form_fields
+    accepts: checkboxes as booleans, radio groups as the selected on-state
+    ("Off" when nothing is selected), everything else as the stored value.
+    Returns (values, read_only_names).
+    """
+    values = {}
+    read_only = []
+
+    for page in doc:
+        for widget in page.widgets():
+            name = widget.field_name
+            if not name or widget.field_type in (fitz.PDF_WIDGET_TYPE_BUTTON, fitz.PDF_WIDGET_TYPE_SIGNATURE):
+                continue
+
+            if widget.field_flags & 1 and name not in read_only:
+                read_only.append(name)
+
+            if widget.field_type == fitz.PDF_WIDGET_TYPE_CHECKBOX:
+                values[name] = values.get(name) is True or widget.field_value == widget.on_state()
+            elif widget.field_type == fitz.PDF_WIDGET_TYPE_RADIOBUTTON:
+                # One widget per option; only the selected one carries its on-state
+                if widget.field_value == widget.on_state() or widget.field_value is True:
+                    values[name] = widget.on_state()
+                else:
+                    values.setdefault(name, "Off")
+            else:
+                values[name] = widget.field_value if widget.field_value is not None else ""
+
+    return values, read_only
+
+
+def _checkbox_on_states(doc) -> dict:
+    """
+    On-state names per checkbox field, read from the keys of each widget's
+    /AP /N dictionary (everything but Off). Forms are free to name the
+    checked state anything, so "Yes" can't be assumed.
+    """
+    states = {}
+    for page in doc:
+        for widget in page.widgets():
+            if widget.field_type != fitz.PDF_WIDGET_TYPE_CHECKBOX or not widget.field_name:
+                continue
+            names = states.setdefault(widget.field_name, [])
+            kind, value = doc.xref_get_key(widget.xref, "AP/N")
+            if kind == "xref":
+                xref = int(value.split()[0])
+                if doc.xref_is_stream(xref):
+                    continue
+                kind, value = "dict", doc.xref_object(xref, compressed=True)
+            if kind != "dict":
+                continue
+            depth = 0
+            for token in _fdf_tokens(value):
+                if token == "<<":
+                    depth += 1
+                elif token == ">>":
+                    depth -= 1
+                elif depth == 1 and token[0] == "name" and token[1] != "Off" and token[1] not in names:
+                    names.append(token[1])
+    return states
+
+
+def _fdf_string(value: str) -> str:
+    """Encode a PDF string: literal when ASCII, UTF-16BE hex otherwise."""
+    if value.isascii():
+        escaped = value.replace("\\", "\\\\").replace("(", "\\(").replace(")", "\\)")
+        escaped = escaped.replace("\r", "\\r").replace("\n", "\\n")
+        return f"({escaped})"
+    return "<FEFF" + value.encode("utf-16-be").hex().upper() + ">"
+
+
+def _fdf_name(value: str) -> str:
+    """Encode a PDF name, escaping delimiters and non-regular characters."""
+    out = []
+    for byte in value.encode("utf-8"):
+        char = chr(byte)
+        if 33 <= byte <= 126 and char not in "#()<>[]{}/%":
+            out.append(char)
+        else:
+            out.append(f"#{byte:02X}")
+    return "/" + "".join(out)
+
+
+def _to_fdf(values: dict, state_fields=()) -> str:
+    """
+    Serialize a value map as an FDF 1.2 document with one flat field per entry.
+    Radio groups named in state_fields get their value written as a name.
+    """
+    entries = []
+    for name, value in values.items():
+        if isinstance(value, bool):
+            encoded = "/Yes" if value else "/Off"
+        elif name in state_fields:
+            encoded = _fdf_name(str(value))
+        elif isinstance(value, list):
+            encoded = "[" + " ".join(_fdf_string(str(v)) for v in value) + "]"
+        else:
+            encoded = _fdf_string(str(value))
+        entries.append(f"<< /T {_fdf_string(name)} /V {encoded} >>")
+
+    fields = "\n".join(entries)
+    return (
+        "%FDF-1.2\n"
+        "1 0 obj\n"
+        f"<< /FDF << /Fields [\n{fields}\n] >> >>\n"
+        "endobj\n"
+        "trailer\n"
+        "<< /Root 1 0 R >>\n"
+        "%%EOF\n"
+    )
+
+
+def _decode_pdf_bytes(raw: bytes) -> str:
+    if raw.startswith(b"\xfe\xff"):
+        return raw[2:].decode("utf-16-be", errors="replace")
+    return raw.decode("latin-1")
+
+
+def _fdf_tokens(text: str):
+    """Tokenize the subset of PDF syntax that FDF field lists use."""
+    i, n = 0, len(text)
+    while i < n:
+        c = text[i]
+        if c.isspace():
+            i += 1
+        elif c == "%":
+            while i < n and text[i] not in "\r\n":
+                i += 1
+        elif text.startswith("<<", i) or text.startswith(">>", i):
+            yield text[i:i + 2]
+            i += 2
+        elif c in "[]":
+            yield c
+            i += 1
+        elif c == "(":
+            # Literal string with nesting and escapes
+            depth, i, raw = 1, i + 1, bytearray()
+            while i < n and depth:
+                c = text[i]
+                if c == "\\" and i + 1 < n:
+                    nxt = text[i + 1]
+                    if nxt in "01234567":
+                        j = i + 1
+                        while j < n and j < i + 4 and text[j] in "01234567":
+                            j += 1
+                        raw.append(int(text[i + 1:j], 8) & 0xFF)
+                        i = j
+                        continue
+                    if nxt in "\r\n":
+                        i += 2
+                        if nxt == "\r" and i < n and text[i] == "\n":
+                            i += 1
+                        continue
+                    raw.append(ord({"n": "\n", "r": "\r", "t": "\t", "b": "\b", "f": "\f"}.get(nxt, nxt)) & 0xFF)
+                    i += 2
+                    continue
+                if c == "(":
+                    depth += 1
+                elif c == ")":
+                    depth -= 1
+                    if not depth:
+                        i += 1
+                        break
+                raw.append(ord(c) & 0xFF)
+                i += 1
+            yield ("str", _decode_pdf_bytes(bytes(raw)))
+        elif c == "<":
+            end = text.index(">", i)
+            digits = "".join(text[i + 1:end].split())
+            if len(digits) % 2:
+                digits += "0"
+            yield ("str", _decode_pdf_bytes(bytes.fromhex(digits)))
+            i = end + 1
+        elif c == "/":
+            j = i + 1
+            while j < n and not text[j].isspace() and text[j] not in "/()<>[]{}%":
+                j += 1
+            raw = text[i + 1:j]
+            name = bytearray()
+            k = 0
+            while k < len(raw):
+                if raw[k] == "#" and k + 2 < len(raw):
+                    name.append(int(raw[k + 1:k + 3], 16))
+                    k += 3
+                else:
+                    name.append(ord(raw[k]) & 0xFF)
+                    k += 1
+            yield ("name", name.decode("utf-8", errors="replace"))
+            i = j
+        else:
+            j = i
+            while j < n and not text[j].isspace() and text[j] not in "/()<>[]{}%":
+                j += 1
+            yield ("word", text[i:j])
+            i = j
+
+
+class _EndOfArray(Exception):
+    pass
+
+
+def _parse_fdf_value(tokens):
+    token = next(tokens)
+    if token == "<<":
+        result = {}
+        while True:
+            key = next(tokens)
+            if key == ">>":
+                return result
+            result[key[1]] = _parse_fdf_value(tokens)
+    if token == "[":
+        items = []
+        while True:
+            try:
+                items.append(_parse_fdf_value(tokens))
+            except _EndOfArray:
+                return items
+    if token == "]":
+        raise _EndOfArray()
+    return token
+
+
+def _from_fdf(text: str) -> dict:
+    """Read /T and /V pairs out of an FDF document, following /Kids for hierarchical names."""
+    start = text.find("/Fields")
+    if start < 0:
+        raise ValueError("FDF data has no /Fields array")
+
+    tokens = _fdf_tokens(text[start:])
+    next(tokens)  # /Fields
+    fields = _parse_fdf_value(tokens)
+    if not isinstance(fields, list):
+        raise ValueError("FDF /Fields is not an array")
+
+    values = {}
+
+    def plain(value):
+        if isinstance(value, tuple):
+            kind, inner = value
+            if kind == "name":
+                return False if inner == "Off" else inner
+            return inner
+        if isinstance(value, list):
+            return [plain(v) for v in value]
+        return value
+
+    def walk(field: dict, prefix: str):
+        if not isinstance(field, dict):
+            return
+        partial = plain(field.get("T", ("str", "")))
+        name = f"{prefix}.{partial}" if prefix and partial else (partial or prefix)
+        if "V" in field and name:
+            values[name] = plain(field["V"])
+        for kid in field.get("Kids", []):
+            walk(kid, name)
+
+    for field in fields:
+        walk(field, "")
+
+    return values
+
+
+def export_form_data(pdf_path: str, data_format: str = "json") -> dict:
+    """
+    Export current field values as JSON or FDF.
+
+    JSON data is {"values": {...}, "read_only": [...]}; "values" is exactly
+    what fill_form_fields accepts, so it can be edited and imported back.
+    Read-only fields are included and listed so callers can tell them apart.
+
+    Returns:
+        Dict with the format, the serialized data and the read-only names
+    """
+    if data_format not in DATA_FORMATS:
+        return {"success": False, "error": f"Unsupported format: {data_format}"}
+
+    doc = fitz.open(pdf_path)
+    if not doc.is_form_pdf:
+        doc.close()
+        return {"success": False, "error": "PDF does not contain form fields"}
+
+    values, read_only = _collect_field_values(doc)
+    radio_groups = {
+        widget.field_name
+        for page in doc
+        for widget in page.widgets()
+        if widget.field_type == fitz.PDF_WIDGET_TYPE_RADIOBUTTON
+    }
+    checkbox_states = _checkbox_on_states(doc)
+    checked_states = {
+        widget.field_name: widget.on_state()
+        for page in doc
+        for widget in page.widgets()
+        if widget.field_type == fitz.PDF_WIDGET_TYPE_CHECKBOX and widget.field_value == widget.on_state()
+    }
+    doc.close()
+
+    if data_format == "fdf":
+        # Write checkboxes with the form's own on-state so a re-import matches it
+        state_fields = set(radio_groups)
+        for name, on_states in checkbox_states.items():
+            if on_states and isinstance(values.get(name), bool):
+                values[name] = checked_states.get(name, on_states[0]) if values[name] else "Off"
+                state_fields.add(name)
+        data = _to_fdf(values, state_fields)
+    else:
+        data = json.dumps({"values": values, "read_only": read_only}, indent=2, ensure_ascii=False)
+
+    return {
+        "success": True,
+        "format": data_format,
+        "data": data,
+        "field_count": len(values),
+        "read_only": read_only,
+    }
+
+
+def import_form_data(pdf_path: str, output_path: str, data: str, data_format: str = "json") -> dict:
+    """
+    Fill a form from exported JSON or FDF data.
+
+    JSON may be the export envelope or a bare name -> value map. Read-only
+    fields and names the form doesn't have are skipped and reported.
+
+    Returns:
+        fill_form_fields result plus skipped_read_only and unknown_fields
+    """
+    if data_format not in DATA_FORMATS:
+        return {"success": False, "error": f"Unsupported format: {data_format}"}
+
+    if data_format == "fdf":
+        values = _from_fdf(data)
+    else:
+        parsed = json.loads(data)
+        if not isinstance(parsed, dict):
+            return {"success": False, "error": "JSON data must be an object"}
+        values = parsed["values"] if isinstance(parsed.get("values"), dict) else parsed
+
+    doc = fitz.open(pdf_path)
+    if not doc.is_form_pdf:
+        doc.close()
+        return {"success": False, "error": "PDF does not contain form fields"}
+    current, read_only = _collect_field_values(doc)
+    checkbox_states = _checkbox_on_states(doc)
+    doc.close()
+
+    skipped = [name for name in values if name in read_only]
+    unknown = [name for name in values if name not in current]
+    to_fill = {}
+    for name, value in values.items():
+        if name not in current or name in read_only:
+            continue
+        # FDF carries checkbox states as names. A name the widgets define is
+        # passed through so only the matching widget turns on; anything else
+        # is unchecked. Widgets without appearance states fall back to "not Off".
+        if isinstance(current[name], bool) and isinstance(value, str):
+            on_states = checkbox_states.get(name)
+            if not on_states:
+                value = value != "Off"
+            elif value not in on_states:
+                value = False
+        to_fill[name] = value
+
+    result = fill_form_fields(pdf_path, output_path, to_fill)
+    result["skipped_read_only"] = skipped
+    result["unknown_fields"] = unknown
+    return result
+
+
+# Field types accepted when creating widgets
+CREATE_FIELD_TYPES = {
+    "text": fitz.PDF_WIDGET_TYPE_TEXT,
+    "checkbox": fitz.PDF_WIDGET_TYPE_CHECKBOX,
+    "combobox": fitz.PDF_WIDGET_TYPE_COMBOBOX,
+    "choice": fitz.PDF_WIDGET_TYPE_COMBOBOX,
+    "listbox": fitz.PDF_WIDGET_TYPE_LISTBOX,
+    "signature": fitz.PDF_WIDGET_TYPE_SIGNATURE,
+}
+
+
+def create_form_fields(pdf_path: str, output_path: str, fields: list) -> dict:
+    """
+    Create new AcroForm widgets.
+
+    Args:
+        pdf_path: Path to source PDF
+        output_path: Path to save the PDF with new fields
+        fields: List of {"name", "field_type", "page" (1-indexed),
+                "rect": [x0, y0, x1, y1] in points, "choices" (optional)}
+
+    Returns:
+        Dict with success status and created field names
+    """
+    doc = fitz.open(pdf_path)
+
+    # Names must be unique among new fields and existing ones
+    existing = set()
+    for page in doc:
+        for widget in page.widgets():
+            if widget.field_name:
+                existing.add(widget.field_name)
+
+    seen = set()
+    for spec in fields:
+        name = spec.get("name") or ""
+        if not name:
+            doc.close()
+            return {"success": False, "error": "Field name must not be empty"}
+        if name in seen or name in existing:
+            doc.close()
+            return {"success": False, "error": f"Duplicate field name: {name}"}
+        seen.add(name)
+
+        field_type = spec.get("field_type", "text")
+        if field_type not in CREATE_FIELD_TYPES:
+            doc.close()
+            return {"success": False, "error": f"Unsupported field type for {name}: {field_type}"}
+
+        page_num = int(spec.get("page", 0))
+        if page_num < 1 or page_num > len(doc):
+            doc.close()
+            return {"success": False, "error": f"Page {page_num} out of range for {name} ({len(doc)} pages)"}
+
+        rect = fitz.Rect(spec.get("rect", []))
+        page_rect = doc[page_num - 1].rect
+        if rect.is_empty or not page_rect.contains(rect):
+            doc.close()
+            return {"success": False, "error": f"Rect for {name} is empty or outside page {page_num}"}
+
+    created = []
+    for spec in fields:
+        page = doc[int(spec["page"]) - 1]
+
+        widget = fitz.Widget()
+        widget.field_name = spec["name"]
+        widget.field_type = CREATE_FIELD_TYPES[spec.get("field_type", "text")]
+        widget.rect = fitz.Rect(spec["rect"])
+
+        if widget.field_type in (fitz.PDF_WIDGET_TYPE_LISTBOX, fitz.PDF_WIDGET_TYPE_COMBOBOX):
+            widget.choice_values = spec.get("choices") or []
+        elif widget.field_type == fitz.PDF_WIDGET_TYPE_CHECKBOX:
+            widget.field_value = False
+        elif widget.field_type == fitz.PDF_WIDGET_TYPE_TEXT:
+            widget.field_value = ""
+
+        page.add_widget(widget)
+        created.append(spec["name"])
+
+    doc.save(output_path)
+    doc.close()
+
+    return {
+        "success": True,
+        "created": len(created),
+        "names": created,
+        "output_path": output_path,
+    }
+
+
+def _appearance_xref(doc, widget) -> int:
+    """
+    Xref of the widget's normal appearance stream, 0 if it has none. Checkboxes
+    and radio buttons keep one stream per state under /AP /N; the one matching
+    the current /AS is the visible one.
+    """
+    kind, value = doc.xref_get_key(widget.xref, "AP/N")
+    if kind == "xref":
+        return int(value.split()[0])
+    if kind == "dict":
+        state_kind, state = doc.xref_get_key(widget.xref, "AS")
+        if state_kind != "name":
+            return 0
+        kind, value = doc.xref_get_key(widget.xref, f"AP/N/{state.lstrip('/')}")
+        if kind == "xref":
+            return int(value.split()[0])
+    return 0
+
+
+def _pdf_rect(doc, xref: int, key: str):
+    """Read a 4-number array (Rect, BBox) in unrotated PDF coordinates."""
+    kind, value = doc.xref_get_key(xref, key)
+    if kind != "array":
+        return None
+    numbers = [float(n) for n in value.strip("[]").split()]
+    return fitz.Rect(numbers) if len(numbers) == 4 else None
+
+
+# Annotation flags (PDF 32000-1, table 165) that keep a widget off screen
+ANNOT_FLAG_HIDDEN = 1 << 1
+ANNOT_FLAG_NOVIEW = 1 << 5
+
+
+def _is_hidden(doc, widget) -> bool:
+    """Whether the widget's /F flags keep it from being displayed."""
+    kind, value = doc.xref_get_key(widget.xref, "F")
+    return kind == "int" and bool(int(value) & (ANNOT_FLAG_HIDDEN | ANNOT_FLAG_NOVIEW))
+
+
+def _page_resources(doc, page) -> tuple[int, str]:
+    """
+    (xref, key prefix) under which entries of the page's /Resources live.
+
+    A page may inherit /Resources from its /Pages ancestors; those are copied
+    onto the page first, so adding an entry doesn't hide the inherited fonts
+    and images or change the resources of sibling pages.
+    """
+    kind, value = doc.xref_get_key(page.xref, "Resources")
+    if kind == "null":
+        node = page.xref
+        while kind == "null":
+            parent_kind, parent = doc.xref_get_key(node, "Parent")
+            if parent_kind != "xref":
+                break
+            node = int(parent.split()[0])
+            kind, value = doc.xref_get_key(node, "Resources")
+        if kind == "xref":
+            value = doc.xref_object(int(value.split()[0]), compressed=True)
+        if kind in ("xref", "dict"):
+            doc.xref_set_key(page.xref, "Resources", value)
+        kind = "dict"
+
+    if kind == "xref":
+        return int(value.split()[0]), ""
+    return page.xref, "Resources/"
+
+
+def _stamp_appearance(doc, page, widget, name: str) -> bool:
+    """
+    Paint the widget's current appearance stream into the page content as a
+    Form XObject placed on the widget rectangle. Returns False when there is
+    nothing to paint (e.g. a checkbox without an appearance for its state, or
+    a widget flagged Hidden or NoView).
+    """
+    if _is_hidden(doc, widget):
+        return False
+    ap_xref = _appearance_xref(doc, widget)
+    if not ap_xref:
+        return False
+
+    rect = _pdf_rect(doc, widget.xref, "Rect")
+    bbox = _pdf_rect(doc, ap_xref, "BBox")
+    if rect is None or bbox is None or rect.is_empty or bbox.is_empty:
+        return False
+
+    # Map the appearance bbox (after its own /Matrix) onto the widget rect,
+    # as a viewer would when drawing the annotation
+    kind, value = doc.xref_get_key(ap_xref, "Matrix")
+    if kind == "array":
+        bbox = bbox * fitz.Matrix(*[float(n) for n in value.strip("[]").split()])
+    sx = rect.width / bbox.width
+    sy = rect.height / bbox.height
+    tx = rect.x0 - bbox.x0 * sx
+    ty = rect.y0 - bbox.y0 * sy
+
+    holder, prefix = _page_resources(doc, page)
+    doc.xref_set_key(holder, f"{prefix}XObject/{name}", f"{ap_xref} 0 R")
+
+    stream = f"q {sx:g} 0 0 {sy:g} {tx:g} {ty:g} cm /{name} Do Q\n".encode()
+    content_xref = doc.get_new_xref()
+    doc.update_object(content_xref, "<<>>")
+    doc.update_stream(content_xref, stream)
+
+    contents = page.get_contents()
+    refs = " ".join(f"{x} 0 R" for x in contents + [content_xref])
+    doc.xref_set_key(page.xref, "Contents", f"[{refs}]")
+    return True
+
+
+def flatten_form_fields(pdf_path: str, output_path: str, field_names: list = None) -> dict:
+    """
+    Burn form field appearances into the page content and remove the widgets.
+
+    Args:
+        pdf_path: Path to source PDF
+        output_path: Path to save the flattened PDF
+        field_names: Only flatten these fields (all fields when None). Every
+                     widget of a named field is flattened, so a radio group
+                     goes as a whole.
+
+    Returns:
+        Dict with success status, flattened field names and remaining fields
+    """
+    doc = fitz.open(pdf_path)
+
+    if not doc.is_form_pdf:
+        doc.close()
+        return {"success": False, "error": "PDF has no form fields"}
+
+    wanted = set(field_names) if field_names else None
+    if wanted is not None:
+        existing = {w.field_name for page in doc for w in page.widgets()}
+        missing = sorted(wanted - existing)
+        if missing:
+            doc.close()
+            return {"success": False, "error": f"Unknown field(s): {', '.join(missing)}"}
+
+    flattened = []
+    stamp = 0
+    for page in doc:
+        # The original content gets its own q/Q so stamps start from a clean state
+        page.wrap_contents()
+        targets = [w for w in page.widgets() if wanted is None or w.field_name in wanted]
+        for widget in targets:
+            # Fields without an appearance stream (never rendered) get one first
+            if doc.xref_get_key(widget.xref, "AP/N")[0] == "null":
+                widget.update()
+            stamp += 1
+            _stamp_appearance(doc, page, widget, f"Flat{stamp}")
+            if widget.field_name not in flattened:
+                flattened.append(widget.field_name)
+            page.delete_widget(widget)
+
+    remaining = sorted({w.field_name for page in doc for w in page.widgets()})
+    if not remaining:
+        # Drop the now-empty AcroForm so readers no longer treat it as a form
+        doc.xref_set_key(doc.pdf_catalog(), "AcroForm", "null")
+
+    doc.save(output_path, garbage=1, deflate=True)
+    doc.close()
+
+    return {
+        "success": True,
+        "flattened": flattened,
+        "flattened_count": len(flattened),
+        "remaining": remaining,
+        "output_path": output_path,
+    }
+
+
+# Signature page layout (points)
+SIGNATURE_PAGE_MARGIN = 72
+SIGNATURE_BLOCK_HEIGHT = 120
+
+
+def _field_slug(text: str) -> str:
+    """Lowercase alphanumeric slug for use in field names."""
+    slug = "".join(c if c.isalnum() else "_" for c in text.strip().lower())
+    return "_".join(part for part in slug.split("_") if part) or "signer"
+
+
+def add_signature_page(pdf_path: str, output_path: str, signers: list) -> dict:
+    """
+    Append page(s) with a signature block per signer: signature line with an
+    AcroForm signature field, printed name and role, and a date line with a
+    text field. Pages match the size of the document's last page.
+
+    Args:
+        pdf_path: Path to source PDF
+        output_path: Path to save the PDF with the signature page
+        signers: List of {"name", "role"}
+
+    Returns:
+        Dict with success status and created field names
+    """
+    if not signers:
+        return {"success": False, "error": "At least one signer is required"}
+
+    doc = fitz.open(pdf_path)
+
+    existing = set()
+    for page in doc:
+        for widget in page.widgets():
+            if widget.field_name:
+                existing.add(widget.field_name)
+
+    def unique_name(base: str) -> str:
+        name, counter = base, 2
+        while name in existing:
+            name = f"{base}_{counter}"
+            counter += 1
+        existing.add(name)
+        return name
+
+    last_rect = doc[-1].rect if len(doc) else fitz.paper_rect("letter")
+    width, height = last_rect.width, last_rect.height
+    line_width = width - 2 * SIGNATURE_PAGE_MARGIN
+    sig_width = line_width * 0.6
+    date_x = SIGNATURE_PAGE_MARGIN + sig_width + 24
+
+    created = []
+    page = None
+    y = 0.0
+
+    for signer in signers:
+        name = (signer.get("name") or "").strip()
+        role = (signer.get("role") or "").strip()
+        if not name:
+            doc.close()
+            return {"success": False, "error": "Every signer needs a name"}
+
+        # Start a new page when the block would run past the bottom margin
+        if page is None or y + SIGNATURE_BLOCK_HEIGHT > height - SIGNATURE_PAGE_MARGIN:
+            page = doc.new_page(width=width, height=height)
+            page.insert_text(
+                (SIGNATURE_PAGE_MARGIN, SIGNATURE_PAGE_MARGIN),
+                "Signatures",
+                fontname="hebo",
+                fontsize=16,
+            )
+            y = SIGNATURE_PAGE_MARGIN + 40
+
+        line_y = y + 50
+        slug = _field_slug(name)
+
+        # Signature line with the field sitting on it
+        page.draw_line(
+            (SIGNATURE_PAGE_MARGIN, line_y),
+            (SIGNATURE_PAGE_MARGIN + sig_width, line_y),
+            color=(0, 0, 0),
+            width=0.75,
+        )
+        sig_widget = fitz.Widget()
+        sig_widget.field_name = unique_name(f"signature_{slug}")
+        sig_widget.field_type = fitz.PDF_WIDGET_TYPE_SIGNATURE
+        sig_widget.rect = fitz.Rect(SIGNATURE_PAGE_MARGIN, y + 8, SIGNATURE_PAGE_MARGIN + sig_width, line_y - 1)
+        page.add_widget(sig_widget)
+        created.append(sig_widget.field_name)
+
+        # Date line with a text field
+        page.draw_line(
+            (date_x, line_y),
+            (SIGNATURE_PAGE_MARGIN + line_width, line_y),
+            color=(0, 0, 0),
+            width=0.75,
+        )
+        date_widget = fitz.Widget()
+        date_widget.field_name = unique_name(f"date_{slug}")
+        date_widget.field_type = fitz.PDF_WIDGET_TYPE_TEXT
+        date_widget.rect = fitz.Rect(date_x, line_y - 20, SIGNATURE_PAGE_MARGIN + line_width, line_y - 1)
+        date_widget.field_value = ""
+        page.add_widget(date_widget)
+        created.append(date_widget.field_name)
+
+        # Printed name and role under the lines
+        label = f"{name}, {role}" if role else name
+        page.insert_text((SIGNATURE_PAGE_MARGIN, line_y + 14), label, fontname="helv", fontsize=10)
+        page.insert_text((date_x, line_y + 14), "Date", fontname="helv", fontsize=10)
+
+        y += SIGNATURE_BLOCK_HEIGHT
+
+    doc.save(output_path)
+    doc.close()
+
+    return {
+        "success": True,
+        "output_path": output_path,
+        "fields_created": created,
+    }
+
+
 def main():
     """CLI interface for form operations."""
     if len(sys.argv) < 3:
@@ -177,13 +938,48 @@ def main():
             field_values = json.loads(sys.argv[4])
             result = fill_form_fields(pdf_path, output_path, field_values)
 
+        elif operation == "export":
+            data_format = sys.argv[3] if len(sys.argv) > 3 else "json"
+            result = export_form_data(pdf_path, data_format)
+
+        elif operation == "import":
+            if len(sys.argv) < 6:
+                print(json.dumps({"error": "Usage: pdf_forms.py import <pdf_path> <output_path> <format> <data>"}))
+                sys.exit(1)
+            result = import_form_data(pdf_path, sys.argv[3], sys.argv[5], sys.argv[4])
+
+        elif operation == "create":
+            if len(sys.argv) < 5:
+                print(json.dumps({"error": "Usage: pdf_forms.py create <pdf_path> <output_path> <json_fields>"}))
+                sys.exit(1)
+            output_path = sys.argv[3]
+            fields = json.loads(sys.argv[4])
+            result = create_form_fields(pdf_path, output_path, fields)
+
+        elif operation == "flatten":
+            if len(sys.argv) < 4:
+                print(json.dumps({"error": "Usage: pdf_forms.py flatten <pdf_path> <output_path> [json_field_names]"}))
+                sys.exit(1)
+            output_path = sys.argv[3]
+            field_names = json.loads(sys.argv[4]) if len(sys.argv) > 4 else None
+            result = flatten_form_fields(pdf_path, output_path, field_names)
+
+        elif operation == "signature-page":
+            if len(sys.argv) < 5:
+                print(json.dumps({"error": "Usage: pdf_forms.py signature-page <pdf_path> <output_path> <json_signers>"}))
+                sys.exit(1)
+            output_path = sys.argv[3]
+            signers = json.loads(sys.argv[4])
+            result = add_signature_page(pdf_path, output_path, signers)
+
         else:
             result = {"error": f"Unknown operation: {operation}"}
 
         print(json.dumps(result))
 
     except Exception as e:
-        print(json.dumps({"error": str(e)}))
+        report_error(e)
+        print(f"Error: {e}", file=sys.stderr)
         sys.exit(1)
 
 
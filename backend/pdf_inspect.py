@@ -0,0 +1,506 @@
+"""
+PDF structure inspection - object-level analysis of what a file contains.
+
+Walks the cross-reference table to report what is taking space and where.
+Uses PyMuPDF for low-level object access.
+
+CLI usage (dev):
+  python pdf_inspect.py composition --input doc.pdf --json
+  python pdf_inspect.py oversized-images --input doc.pdf --max-megapixels 24 --json
+  python pdf_inspect.py text-extractable --input doc.pdf --json
+  python pdf_inspect.py repair-tounicode --input doc.pdf --output fixed.pdf --json
+"""
+
+from __future__ import annotations
+
+import argparse
+import json
+import re
+import sys
+from pathlib import Path
+
+import fitz  # PyMuPDF
+
+try:
+    from utils import run_main
+except ImportError:
+    from .utils import run_main
+
+
+FONT_FILE_KEYS = ("FontFile", "FontFile2", "FontFile3")
+
+# Images stored larger than this are flagged regardless of pixel count
+DEFAULT_MAX_IMAGE_BYTES = 5 * 1024 * 1024
+
+# Simple-font encodings that map to Unicode without a ToUnicode CMap
+STANDARD_ENCODINGS = ("WinAnsiEncoding", "MacRomanEncoding", "StandardEncoding", "PDFDocEncoding")
+
+# Glyph names that carry no character information (subsetter output like /g12, /glyph3, /cid42)
+OPAQUE_GLYPH_NAME = re.compile(r"^(g|glyph|cid|index|G)\d+$")
+
+# bfchar entries per block (CMap limit)
+CMAP_BLOCK_SIZE = 100
+
+
+def _object_size(doc: fitz.Document, xref: int) -> int:
+    """Size of an object as stored: raw (still compressed) stream bytes plus its dictionary."""
+    size = len(doc.xref_object(xref, compressed=True))
+    if doc.xref_is_stream(xref):
+        size += len(doc.xref_stream_raw(xref) or b"")
+    return size
+
+
+def _page_ownership(doc: fitz.Document) -> tuple[dict, dict]:
+    """
+    Map xrefs to the first page (1-indexed) that uses them.
+    Returns (content_stream_pages, resource_pages).
+    """
+    content_pages: dict[int, int] = {}
+    resource_pages: dict[int, int] = {}
+
+    for page_num in range(len(doc)):
+        page = doc[page_num]
+        for xref in page.get_contents():
+            content_pages.setdefault(xref, page_num + 1)
+        for img in page.get_images(full=True):
+            resource_pages.setdefault(img[0], page_num + 1)
+        for font in page.get_fonts(full=True):
+            resource_pages.setdefault(font[0], page_num + 1)
+
+    return content_pages, resource_pages
+
+
+def _font_file_xrefs(doc: fitz.Document) -> set:
+    """Collect xrefs of embedded font programs referenced from font descriptors."""
+    font_files = set()
+    for xref in range(1, doc.xref_length()):
+        try:
+            if doc.xref_get_key(xref, "Type")[1] != "/FontDescriptor":
+                continue
+            for key in FONT_FILE_KEYS:
+                kind, value = doc.xref_get_key(xref, key)
+                if kind == "xref":
+                    font_files.add(int(value.split()[0]))
+        except Exception:
+            continue
+    return font_files
+
+
+def get_composition(input_path: Path, top: int = 10) -> dict:
+    """
+    Sum stored object sizes by category (images, fonts, content streams,
+    metadata, other) and list the largest objects.
+    """
+    result = {
+        "success": False,
+        "total": 0,
+        "images": 0,
+        "fonts": 0,
+        "content_streams": 0,
+        "metadata": 0,
+        "other": 0,
+        "largest_objects": [],
+        "error": None,
+    }
+
+    try:
+        doc = fitz.open(input_path)
+        content_pages, resource_pages = _page_ownership(doc)
+        font_files = _font_file_xrefs(doc)
+
+        objects = []
+        for xref in range(1, doc.xref_length()):
+            try:
+                size = _object_size(doc, xref)
+            except Exception:
+                continue  # Free or broken entry
+
+            obj_type = doc.xref_get_key(xref, "Type")[1]
+            subtype = doc.xref_get_key(xref, "Subtype")[1]
+
+            if subtype == "/Image":
+                category = "images"
+            elif xref in font_files or obj_type in ("/Font", "/FontDescriptor"):
+                category = "fonts"
+            elif xref in content_pages or subtype == "/Form":
+                category = "content_streams"
+            elif obj_type == "/Metadata":
+                category = "metadata"
+            else:
+                category = "other"
+
+            result[category] += size
+            objects.append({
+                "xref": xref,
+                "type": category,
+                "size": size,
+                "page": content_pages.get(xref) or resource_pages.get(xref),
+            })
+
+        doc.close()
+
+        result["total"] = Path(input_path).stat().st_size
+        objects.sort(key=lambda o: o["size"], reverse=True)
+        result["largest_objects"] = objects[:top]
+        result["success"] = True
+
+    except Exception as e:
+        result["error"] = str(e)
+
+    return result
+
+
+def find_oversized_images(
+    input_path: Path,
+    max_megapixels: float,
+    max_bytes: int = DEFAULT_MAX_IMAGE_BYTES,
+) -> dict:
+    """
+    Flag image XObjects whose pixel count or stored size exceeds the thresholds.
+    Each image is reported once, on the first page that uses it.
+    """
+    result = {
+        "success": False,
+        "images": [],
+        "error": None,
+    }
+
+    try:
+        doc = fitz.open(input_path)
+        seen = set()
+
+        for page_num in range(len(doc)):
+            for img in doc[page_num].get_images(full=True):
+                xref, width, height = img[0], img[2], img[3]
+                if xref in seen:
+                    continue
+                seen.add(xref)
+
+                megapixels = width * height / 1_000_000
+                size_bytes = len(doc.xref_stream_raw(xref) or b"")
+
+                if megapixels > max_megapixels or size_bytes > max_bytes:
+                    result["images"].append({
+                        "page": page_num + 1,
+                        "xref": xref,
+                        "width": width,
+                        "height": height,
+                        "megapixels": round(megapixels, 2),
+                        "size_bytes": size_bytes,
+                    })
+
+        doc.close()
+
+        result["images"].sort(key=lambda i: i["size_bytes"], reverse=True)
+        result["success"] = True
+
+    except Exception as e:
+        result["error"] = str(e)
+
+    return result
+
+
+def _font_key(doc: fitz.Document, xref: int, key: str) -> tuple[str, str]:
+    """Read a font dictionary key, returning ("null", "null") when absent."""
+    try:
+        return doc.xref_get_key(xref, key)
+    except Exception:
+        return ("null", "null")
+
+
+def _differences(doc: fitz.Document, font_xref: int) -> dict:
+    """Parse the font's /Encoding /Differences array into {code: glyph_name}."""
+    kind, value = _font_key(doc, font_xref, "Encoding/Differences")
+    if kind != "array":
+        return {}
+
+    mapping = {}
+    code = 0
+    for token in re.findall(r"/[^\s/\[\]]+|\d+", value):
+        if token.startswith("/"):
+            mapping[code] = token[1:]
+            code += 1
+        else:
+            code = int(token)
+    return mapping
+
+
+def _tounicode_problem(doc: fitz.Document, font_xref: int, subtype: str) -> str | None:
+    """Return why a font's text can't be mapped to Unicode, or None if it can."""
+    if _font_key(doc, font_xref, "ToUnicode")[0] != "null":
+        return None
+
+    if subtype == "Type0":
+        kind, encoding = _font_key(doc, font_xref, "Encoding")
+        # Predefined Unicode CMaps (e.g. UniGB-UCS2-H) are extractable as-is
+        if kind == "name" and "Uni" in encoding:
+            return None
+        return "CID font without ToUnicode"
+
+    if subtype == "Type3":
+        return "Type3 font without ToUnicode"
+
+    differences = _differences(doc, font_xref)
+    if any(OPAQUE_GLYPH_NAME.match(name) for name in differences.values()):
+        return "custom encoding with unnamed glyphs"
+
+    kind, encoding = _font_key(doc, font_xref, "Encoding")
+    if kind == "null" or encoding.lstrip("/") in STANDARD_ENCODINGS or differences:
+        return None
+
+    return "non-standard encoding without ToUnicode"
+
+
+def _problem_fonts(doc: fitz.Document) -> dict:
+    """Map font xref -> {font, reason, subtype, pages} for fonts lacking usable ToUnicode."""
+    problems = {}
+    checked = {}
+
+    for page_num in range(len(doc)):
+        for xref, _ext, subtype, basefont, _name, _encoding in doc.get_page_fonts(page_num):
+            if xref not in checked:
+                checked[xref] = _tounicode_problem(doc, xref, subtype)
+            reason = checked[xref]
+            if reason is None:
+                continue
+            entry = problems.setdefault(xref, {
+                "font": basefont.split("+", 1)[-1] or f"xref {xref}",
+                "reason": reason,
+                "subtype": subtype,
+                "pages": [],
+            })
+            if page_num + 1 not in entry["pages"]:
+                entry["pages"].append(page_num + 1)
+
+    return problems
+
+
+def check_text_extractable(input_path: Path) -> dict:
+    """Report fonts whose text can't be copied or searched because they lack a usable ToUnicode map."""
+    result = {
+        "success": False,
+        "fully_extractable": True,
+        "problem_fonts": [],
+        "error": None,
+    }
+
+    try:
+        doc = fitz.open(input_path)
+        problems = _problem_fonts(doc)
+        doc.close()
+
+        result["problem_fonts"] = [
+            {"font": p["font"], "reason": p["reason"], "pages": p["pages"]}
+            for p in problems.values()
+        ]
+        result["fully_extractable"] = not problems
+        result["success"] = True
+
+    except Exception as e:
+        result["error"] = str(e)
+
+    return result
+
+
+def _glyph_unicode(name: str) -> int | None:
+    """Unicode value for a glyph name (AGL names, uniXXXX, uXXXX[XX])."""
+    base = name.split(".", 1)[0]
+    match = re.match(r"^uni([0-9A-Fa-f]{4})$", base) or re.match(r"^u([0-9A-Fa-f]{4,6})$", base)
+    if match:
+        return int(match.group(1), 16)
+    if OPAQUE_GLYPH_NAME.match(base):
+        return None
+    value = fitz.glyph_name_to_unicode(base)
+    # PyMuPDF returns 0xFFFD for unknown names
+    return value if value and value != 0xFFFD else None
+
+
+def _cid_unicode_map(doc: fitz.Document, font_xref: int) -> dict:
+    """CID -> Unicode for an Identity-encoded CID font, via the embedded font's own cmap."""
+    _name, _ext, _type, buffer = doc.extract_font(font_xref)
+    if not buffer:
+        return {}
+
+    font = fitz.Font(fontbuffer=buffer)
+    mapping = {}
+    for codepoint in font.valid_codepoints():
+        gid = font.has_glyph(codepoint)
+        # Identity CIDToGIDMap: CID == GID. Keep the first (lowest) codepoint per glyph.
+        if gid and gid not in mapping:
+            mapping[gid] = codepoint
+    return mapping
+
+
+def _build_cmap(mapping: dict, two_byte: bool) -> bytes:
+    """Serialize {code: unicode} as a ToUnicode CMap stream."""
+    width = 4 if two_byte else 2
+    space = "<0000> <FFFF>" if two_byte else "<00> <FF>"
+
+    lines = [
+        "/CIDInit /ProcSet findresource begin",
+        "12 dict begin",
+        "begincmap",
+        "/CIDSystemInfo << /Registry (Adobe) /Ordering (UCS) /Supplement 0 >> def",
+        "/CMapName /Adobe-Identity-UCS def",
+        "/CMapType 2 def",
+        "1 begincodespacerange",
+        space,
+        "endcodespacerange",
+    ]
+
+    items = sorted(mapping.items())
+    for start in range(0, len(items), CMAP_BLOCK_SIZE):
+        block = items[start:start + CMAP_BLOCK_SIZE]
+        lines.append(f"{len(block)} beginbfchar")
+        for code, unicode in block:
+            utf16 = chr(unicode).encode("utf-16-be").hex().upper()
+            lines.append(f"<{code:0{width}X}> <{utf16}>")
+        lines.append("endbfchar")
+
+    lines += [
+        "endcmap",
+        "CMapName currentdict /CMap defineresource pop",
+        "end",
+        "end",
+    ]
+    return "\n".join(lines).encode("ascii")
+
+
+def repair_tounicode(input_path: Path, output_path: Path) -> dict:
+    """
+    Synthesize ToUnicode maps for fonts that lack one.
+
+    Simple fonts are mapped from their /Differences glyph names; Identity-encoded
+    CID fonts from the embedded font program's cmap. Fonts with no recoverable
+    names (opaque subset glyphs, Type3) are reported as unrepaired.
+    """
+    result = {
+        "success": False,
+        "output_path": str(output_path),
+        "repaired": [],
+        "unrepaired": [],
+        "error": None,
+    }
+
+    try:
+        doc = fitz.open(input_path)
+
+        for xref, problem in _problem_fonts(doc).items():
+            mapping = {}
+            two_byte = problem["subtype"] == "Type0"
+
+            if two_byte:
+                encoding = _font_key(doc, xref, "Encoding")[1]
+                if encoding in ("/Identity-H", "/Identity-V"):
+                    mapping = _cid_unicode_map(doc, xref)
+            elif problem["subtype"] != "Type3":
+                for code, name in _differences(doc, xref).items():
+                    unicode = _glyph_unicode(name)
+                    if unicode is not None:
+                        mapping[code] = unicode
+
+            if not mapping:
+                result["unrepaired"].append(problem["font"])
+                continue
+
+            cmap_xref = doc.get_new_xref()
+            doc.update_object(cmap_xref, "<<>>")
+            doc.update_stream(cmap_xref, _build_cmap(mapping, two_byte))
+            doc.xref_set_key(xref, "ToUnicode", f"{cmap_xref} 0 R")
+            result["repaired"].append(problem["font"])
+
+        doc.save(output_path, garbage=1, deflate=True)
+        doc.close()
+
+        result["success"] = True
+
+    except Exception as e:
+        result["error"] = str(e)
+
+    return result
+
+
+def main():
+    parser = argparse.ArgumentParser(description="PDF structure inspection")
+    subparsers = parser.add_subparsers(dest="command", required=True)
+
+    # Composition command
+    comp_parser = subparsers.add_parser("composition", help="Break down file size by object category")
+    comp_parser.add_argument("--input", "-i", required=True, help="Input PDF path")
+    comp_parser.add_argument("--top", type=int, default=10, help="Number of largest objects to list")
+    comp_parser.add_argument("--json", action="store_true", help="Output as JSON")
+
+    # Oversized images command
+    big_parser = subparsers.add_parser("oversized-images", help="Find images above a pixel or byte threshold")
+    big_parser.add_argument("--input", "-i", required=True, help="Input PDF path")
+    big_parser.add_argument("--max-megapixels", type=float, required=True, help="Pixel threshold in megapixels")
+    big_parser.add_argument("--max-bytes", type=int, default=DEFAULT_MAX_IMAGE_BYTES, help="Stored size threshold in bytes")
+    big_parser.add_argument("--json", action="store_true", help="Output as JSON")
+
+    # Text extractability command
+    text_parser = subparsers.add_parser("text-extractable", help="Find fonts without usable ToUnicode maps")
+    text_parser.add_argument("--input", "-i", required=True, help="Input PDF path")
+    text_parser.add_argument("--json", action="store_true", help="Output as JSON")
+
+    # ToUnicode repair command
+    repair_parser = subparsers.add_parser("repair-tounicode", help="Synthesize missing ToUnicode maps")
+    repair_parser.add_argument("--input", "-i", required=True, help="Input PDF path")
+    repair_parser.add_argument("--output", "-o", required=True, help="Output PDF path")
+    repair_parser.add_argument("--json", action="store_true", help="Output as JSON")
+
+    args = parser.parse_args()
+
+    if args.command == "composition":
+        result = get_composition(Path(args.input), args.top)
+        if args.json:
+            print(json.dumps(result))
+        else:
+            if result["error"]:
+                print(f"Error: {result['error']}")
+                sys.exit(1)
+            print(f"Total: {result['total']} bytes")
+            for key in ("images", "fonts", "content_streams", "metadata", "other"):
+                print(f"  {key:<16} {result[key]}")
+            for obj in result["largest_objects"]:
+                print(f"  xref {obj['xref']:<6} {obj['type']:<16} {obj['size']} (page {obj['page']})")
+
+    elif args.command == "oversized-images":
+        result = find_oversized_images(Path(args.input), args.max_megapixels, args.max_bytes)
+        if args.json:
+            print(json.dumps(result))
+        else:
+            if result["error"]:
+                print(f"Error: {result['error']}")
+                sys.exit(1)
+            for img in result["images"]:
+                print(f"  page {img['page']}: {img['width']}x{img['height']} "
+                      f"({img['megapixels']} MP, {img['size_bytes']} bytes)")
+            print(f"{len(result['images'])} oversized images")
+
+    elif args.command == "text-extractable":
+        result = check_text_extractable(Path(args.input))
+        if args.json:
+            print(json.dumps(result))
+        else:
+            if result["error"]:
+                print(f"Error: {result['error']}")
+                sys.exit(1)
+            for font in result["problem_fonts"]:
+                print(f"  {font['font']}: {font['reason']} (pages {font['pages']})")
+            print("Fully extractable" if result["fully_extractable"] else "Some text cannot be extracted")
+
+    elif args.command == "repair-tounicode":
+        result = repair_tounicode(Path(args.input), Path(args.output))
+        if args.json:
+            print(json.dumps(result))
+        else:
+            if result["error"]:
+                print(f"Error: {result['error']}")
+                sys.exit(1)
+            print(f"Repaired: {', '.join(result['repaired']) or 'none'}")
+            if result["unrepaired"]:
+                print(f"Could not repair: {', '.join(result['unrepaired'])}")
+
+
+if __name__ == "__main__":
+    run_main(main)
@@ -4,11 +4,16 @@ PDF Layers (Optional Content Groups) operations.
 CLI usage (dev):
   python pdf_layers.py list --input doc.pdf
   python pdf_layers.py toggle --input doc.pdf --output out.pdf --layer "Layer1" --visible true
+  python pdf_layers.py clean-orphans --input doc.pdf --output out.pdf
+  python pdf_layers.py create --input doc.pdf --output out.pdf --name "Draft stamp" --annotations id1 id2
+  python pdf_layers.py delete --input doc.pdf --output out.pdf --xref 12 --flatten
 """
 
 from __future__ import annotations
 
 import argparse
+import re
+import shutil
 import sys
 import json
 from pathlib import Path
@@ -16,6 +21,27 @@ from typing import Optional
 
 import fitz  # PyMuPDF
 
+try:
+    from utils import run_main
+except ImportError:
+    from .utils import run_main
+
+
+# Marked-content sections tied to optional content: /OC /name BDC
+MARKED_CONTENT_OC = re.compile(rb"/OC\s*/([^\s/<>\[\]()]+)\s*BDC")
+# Direct optional-content membership on XObjects and annotations: /OC 12 0 R
+DIRECT_OC_REF = re.compile(r"/OC\s+(\d+)\s+0\s+R")
+# Resource name -> object reference inside a /Properties dict
+PROPERTY_ENTRY = re.compile(r"/([^\s/<>\[\]()]+)\s+(\d+)\s+0\s+R")
+INDIRECT_REF = re.compile(r"(\d+)\s+0\s+R")
+# Operands of a marked-content operator that ties it to a layer: /OC /name
+OC_OPERANDS = re.compile(rb"\s*/OC\s*/([^\s/<>\[\]()]+)\s*")
+# End of inline image data
+INLINE_IMAGE_END = re.compile(rb"\sEI(?=\s|$)")
+
+CONTENT_WHITESPACE = b" \t\r\n\f\x00"
+CONTENT_DELIMITERS = b"()<>[]{}/%"
+
 
 def get_layers(input_path: Path) -> dict:
     """
@@ -154,6 +180,437 @@ def set_all_layers(
     return result
 
 
+def _parse_page_selection(pages: str, total: int) -> list[int]:
+    """Parse "1-3,5" (1-indexed) into sorted 0-based indices within the document."""
+    indices = set()
+    for part in pages.split(","):
+        part = part.strip()
+        if not part:
+            continue
+        if "-" in part:
+            start, end = part.split("-", 1)
+            indices.update(range(max(1, int(start)) - 1, min(total, int(end))))
+        elif 1 <= int(part) <= total:
+            indices.add(int(part) - 1)
+    return sorted(indices)
+
+
+def create_layer(
+    input_path: Path,
+    output_path: Path,
+    name: str,
+    default_on: bool = True,
+    from_pages: Optional[str] = None,
+    annotation_ids: Optional[list[str]] = None,
+) -> dict:
+    """
+    Create a new optional content group and optionally move annotations into it.
+
+    Annotations are picked by id (the id stored in the annotation's subject by
+    pdf_annotations.py, or its /NM name) and/or by page: `from_pages` moves
+    every markup annotation on those pages (links and form widgets excluded).
+    With neither, an empty layer is created.
+    """
+    result = {
+        "success": False,
+        "message": "",
+        "name": name,
+        "xref": None,
+        "assigned": []
+    }
+
+    try:
+        if not name.strip():
+            result["message"] = "Layer name must not be empty"
+            return result
+
+        doc = fitz.open(input_path)
+
+        existing = doc.get_ocgs() or {}
+        if any(info.get("name") == name for info in existing.values()):
+            result["message"] = f"A layer named '{name}' already exists"
+            doc.close()
+            return result
+
+        ocg_xref = doc.add_ocg(name, on=default_on)
+
+        wanted_ids = set(annotation_ids or [])
+        pages = set(_parse_page_selection(from_pages, len(doc))) if from_pages else set()
+        found_ids = set()
+
+        for page in doc:
+            for annot in page.annots():
+                info = annot.info
+                ids = {info.get("subject"), info.get("id")} - {None, ""}
+                matched = ids & wanted_ids
+                if matched or page.number in pages:
+                    annot.set_oc(ocg_xref)
+                    found_ids.update(matched)
+                    result["assigned"].append({
+                        "page": page.number + 1,
+                        "xref": annot.xref,
+                        "id": info.get("subject") or info.get("id") or None,
+                    })
+
+        missing = sorted(wanted_ids - found_ids)
+        if missing:
+            result["message"] = f"Annotation(s) not found: {', '.join(missing)}"
+            doc.close()
+            return result
+
+        doc.save(output_path)
+        doc.close()
+
+        result["success"] = True
+        result["xref"] = ocg_xref
+        result["message"] = f"Created layer '{name}' with {len(result['assigned'])} annotation(s)"
+
+    except Exception as e:
+        result["message"] = f"Failed to create layer: {str(e)}"
+
+    return result
+
+
+def _properties_dicts(doc: fitz.Document) -> list[tuple[int, str, str]]:
+    """Find every /Properties resource dict as (holder xref, key path, dict source)."""
+    found = []
+    for xref in range(1, doc.xref_length()):
+        for key in ("Resources/Properties", "Properties"):
+            try:
+                kind, value = doc.xref_get_key(xref, key)
+            except Exception:
+                continue
+            if kind == "xref":
+                target = int(value.split()[0])
+                found.append((target, "", doc.xref_object(target, compressed=True)))
+            elif kind == "dict":
+                found.append((xref, key, value))
+    return found
+
+
+def _used_ocgs(doc: fitz.Document, properties: list[tuple[int, str, str]]) -> set:
+    """Xrefs of OCGs actually referenced by content, XObjects or annotations."""
+    used = set()
+    marked_names = set()
+
+    for xref in range(1, doc.xref_length()):
+        try:
+            source = doc.xref_object(xref, compressed=True)
+        except Exception:
+            continue
+
+        used.update(int(x) for x in DIRECT_OC_REF.findall(source))
+
+        # Content and form streams; image data never carries marked content
+        if doc.xref_is_stream(xref) and "/Subtype/Image" not in source.replace(" ", ""):
+            try:
+                data = doc.xref_stream(xref) or b""
+            except Exception:
+                continue
+            marked_names.update(n.decode("latin-1") for n in MARKED_CONTENT_OC.findall(data))
+
+    for _holder, _key, source in properties:
+        for name, ref in PROPERTY_ENTRY.findall(source):
+            if name in marked_names:
+                used.add(int(ref))
+
+    # Membership dicts (OCMDs) make their member OCGs visible-dependent, so count them as used
+    for xref in list(used):
+        try:
+            if doc.xref_get_key(xref, "Type")[1] == "/OCMD":
+                used.update(int(x) for x in INDIRECT_REF.findall(doc.xref_object(xref, compressed=True)))
+        except Exception:
+            continue
+
+    return used
+
+
+def _strip_refs(source: str, xrefs: set) -> str:
+    """Remove indirect references to the given xrefs from an object's source."""
+    for xref in xrefs:
+        source = re.sub(rf"(?<!\d){xref}\s+0\s+R", "", source)
+    return source
+
+
+def _remove_ocgs(doc: fitz.Document, xrefs: set, all_ocgs: set, properties: list[tuple[int, str, str]]) -> None:
+    """
+    Unlist the given OCGs from /OCProperties (every config and array it reaches)
+    and drop resource names pointing at them. The OCG objects themselves are
+    left for garbage collection on save.
+    """
+    catalog = doc.pdf_catalog()
+    kind, value = doc.xref_get_key(catalog, "OCProperties")
+    if kind == "dict":
+        cleaned = _strip_refs(value, xrefs)
+        doc.xref_set_key(catalog, "OCProperties", cleaned)
+        pending = [int(x) for x in INDIRECT_REF.findall(cleaned)]
+    else:
+        pending = [int(value.split()[0])]
+
+    visited = set()
+    while pending:
+        xref = pending.pop()
+        if xref in visited or xref in all_ocgs:
+            continue
+        visited.add(xref)
+        cleaned = _strip_refs(doc.xref_object(xref, compressed=True), xrefs)
+        doc.update_object(xref, cleaned)
+        pending.extend(int(x) for x in INDIRECT_REF.findall(cleaned))
+
+    # Drop resource names that point at removed layers
+    for holder, key, source in properties:
+        entries = [
+            (name, ref) for name, ref in PROPERTY_ENTRY.findall(source)
+            if int(ref) in xrefs
+        ]
+        if not entries:
+            continue
+        for name, ref in entries:
+            source = re.sub(rf"/{re.escape(name)}\s+{ref}\s+0\s+R", "", source)
+        if key:
+            doc.xref_set_key(holder, key, source)
+        else:
+            doc.update_object(holder, source)
+
+
+def _copy_unchanged(input_path: Path, output_path: Path) -> None:
+    """Write the input as the output when there is nothing to change."""
+    if Path(input_path).resolve() != Path(output_path).resolve():
+        shutil.copyfile(input_path, output_path)
+
+
+def clean_orphan_layers(input_path: Path, output_path: Path) -> dict:
+    """
+    Remove OCGs listed in /OCProperties that no marked content, XObject or
+    annotation uses, along with their entries in every layer configuration.
+    """
+    result = {
+        "success": False,
+        "message": "",
+        "removed": []
+    }
+
+    try:
+        doc = fitz.open(input_path)
+        ocgs = doc.get_ocgs()
+
+        if not ocgs:
+            doc.close()
+            _copy_unchanged(input_path, output_path)
+            result["success"] = True
+            result["message"] = "No layers found in document"
+            return result
+
+        properties = _properties_dicts(doc)
+        orphans = set(ocgs) - _used_ocgs(doc, properties)
+
+        if not orphans:
+            doc.close()
+            _copy_unchanged(input_path, output_path)
+            result["success"] = True
+            result["message"] = "No orphaned layers found"
+            return result
+
+        _remove_ocgs(doc, orphans, set(ocgs), properties)
+
+        result["removed"] = sorted(ocgs[x].get("name", f"Layer {x}") for x in orphans)
+
+        # Garbage collection drops the now-unreferenced OCG objects
+        doc.save(output_path, garbage=1)
+        doc.close()
+
+        result["success"] = True
+        result["message"] = f"Removed {len(orphans)} orphaned layer(s)"
+
+    except Exception as e:
+        result["message"] = f"Failed to clean layers: {str(e)}"
+
+    return result
+
+
+def _skip_literal_string(data: bytes, i: int) -> int:
+    """Index just past the (possibly nested) literal string starting at data[i]."""
+    depth = 0
+    while i < len(data):
+        c = data[i]
+        if c == 0x5C:  # backslash escapes the next byte
+            i += 2
+            continue
+        if c == 0x28:
+            depth += 1
+        elif c == 0x29:
+            depth -= 1
+            if depth == 0:
+                return i + 1
+        i += 1
+    return i
+
+
+def _layer_section_spans(data: bytes, names: set, keep_content: bool) -> tuple[list[tuple[int, int]], int]:
+    """
+    Byte ranges to cut from a content stream for the marked-content sections
+    tagged `/OC /name BDC` with one of `names`: whole sections, or with
+    `keep_content` only their BDC and matching EMC operators. Also returns the
+    number of sections affected.
+    """
+    spans = []
+    sections = 0
+    stack = []  # per open BMC/BDC: whether it is one of the layer's sections
+    removing = None  # (stack depth, start offset) of the section being cut
+    operands_start = 0
+    i, n = 0, len(data)
+
+    while i < n:
+        c = data[i:i + 1]
+        if c in CONTENT_WHITESPACE:
+            i += 1
+            continue
+        if c == b"%":
+            end = data.find(b"\n", i)
+            i = n if end < 0 else end + 1
+            continue
+        if c == b"(":
+            i = _skip_literal_string(data, i)
+            continue
+        if c == b"<":
+            if data[i + 1:i + 2] == b"<":
+                i += 2
+            else:
+                end = data.find(b">", i)
+                i = n if end < 0 else end + 1
+            continue
+        if c in b">[]{}":
+            i += 1
+            continue
+
+        start = i
+        i += 1
+        while i < n and data[i:i + 1] not in CONTENT_WHITESPACE and data[i:i + 1] not in CONTENT_DELIMITERS:
+            i += 1
+        token = data[start:i]
+        if c == b"/" or c in b"+-." or c.isdigit() or token in (b"true", b"false", b"null"):
+            continue  # operand
+
+        if token == b"ID":
+            match = INLINE_IMAGE_END.search(data, i)
+            i = match.end() if match else n
+        elif token in (b"BMC", b"BDC"):
+            match = OC_OPERANDS.fullmatch(data, operands_start, start) if token == b"BDC" else None
+            ours = bool(match) and match.group(1) in names
+            stack.append(ours)
+            if ours and removing is None:
+                sections += 1
+                if keep_content:
+                    spans.append((operands_start, i))
+                else:
+                    removing = (len(stack), operands_start)
+        elif token == b"EMC" and stack:
+            ours = stack.pop()
+            if removing and len(stack) + 1 == removing[0]:
+                spans.append((removing[1], i))
+                removing = None
+            elif ours and keep_content and removing is None:
+                spans.append((start, i))
+        operands_start = i
+
+    if removing:
+        spans.append((removing[1], n))
+    return spans, sections
+
+
+def delete_layer(input_path: Path, output_path: Path, layer_xref: int, flatten: bool = False) -> dict:
+    """
+    Delete a layer (OCG). Without `flatten` everything drawn in it goes too:
+    its marked-content sections, XObjects and annotations. With `flatten` that
+    content stays but is no longer optional (always shown and printed).
+    """
+    result = {
+        "success": False,
+        "message": "",
+        "xref": layer_xref,
+        "flattened": flatten,
+        "sections": 0,
+        "xobjects": 0,
+        "annotations": 0,
+        "has_layers": False
+    }
+
+    try:
+        doc = fitz.open(input_path)
+        ocgs = doc.get_ocgs() or {}
+
+        if layer_xref not in ocgs:
+            result["message"] = f"Layer {layer_xref} not found"
+            doc.close()
+            return result
+
+        layer_name = ocgs[layer_xref].get("name", f"Layer {layer_xref}")
+        properties = _properties_dicts(doc)
+        names = {
+            name.encode("latin-1")
+            for _holder, _key, source in properties
+            for name, ref in PROPERTY_ENTRY.findall(source)
+            if int(ref) == layer_xref
+        }
+
+        # Annotations in the layer
+        for page in doc:
+            xrefs = [annot.xref for annot in page.annots() if annot.get_oc() == layer_xref]
+            for xref in xrefs:
+                annot = page.load_annot(xref)
+                if flatten:
+                    annot.set_oc(0)
+                else:
+                    page.delete_annot(annot)
+            result["annotations"] += len(xrefs)
+
+        for xref in range(1, doc.xref_length()):
+            if not doc.xref_is_stream(xref):
+                continue
+
+            # XObjects in the layer: drop the membership, or turn them into empty forms
+            kind, value = doc.xref_get_key(xref, "OC")
+            if kind == "xref" and int(value.split()[0]) == layer_xref:
+                if flatten:
+                    doc.xref_set_key(xref, "OC", "null")
+                else:
+                    doc.update_object(xref, "<</Type/XObject/Subtype/Form/BBox[0 0 0 0]>>")
+                    doc.update_stream(xref, b"")
+                result["xobjects"] += 1
+                continue
+
+            # Marked content in page and form streams
+            if not names or doc.xref_get_key(xref, "Subtype")[1] == "/Image":
+                continue
+            data = doc.xref_stream(xref) or b""
+            spans, sections = _layer_section_spans(data, names, keep_content=flatten)
+            if not spans:
+                continue
+            parts, last = [], 0
+            for start, end in spans:
+                parts.append(data[last:start])
+                last = end
+            parts.append(data[last:])
+            doc.update_stream(xref, b"".join(parts))
+            result["sections"] += sections
+
+        _remove_ocgs(doc, {layer_xref}, set(ocgs), properties)
+        result["has_layers"] = len(ocgs) > 1
+
+        # Garbage collection drops the unreferenced OCG object
+        doc.save(output_path, garbage=1)
+        doc.close()
+
+        result["success"] = True
+        action = "Flattened" if flatten else "Deleted"
+        result["message"] = f"{action} layer '{layer_name}'"
+
+    except Exception as e:
+        result["message"] = f"Failed to delete layer: {str(e)}"
+
+    return result
+
+
 def main():
     parser = argparse.ArgumentParser(description="PDF Layers operations")
     subparsers = parser.add_subparsers(dest="command", required=True)
@@ -181,6 +638,32 @@ def main():
                             help="Set all to visible (true/false)")
     all_parser.add_argument("--json", action="store_true", help="Output as JSON")
 
+    # Clean orphaned layers
+    clean_parser = subparsers.add_parser("clean-orphans", help="Remove layers no content uses")
+    clean_parser.add_argument("--input", "-i", required=True, help="Input PDF path")
+    clean_parser.add_argument("--output", "-o", required=True, help="Output PDF path")
+    clean_parser.add_argument("--json", action="store_true", help="Output as JSON")
+
+    # Create a layer, optionally moving annotations into it
+    create_parser = subparsers.add_parser("create", help="Create a layer and assign annotations to it")
+    create_parser.add_argument("--input", "-i", required=True, help="Input PDF path")
+    create_parser.add_argument("--output", "-o", required=True, help="Output PDF path")
+    create_parser.add_argument("--name", "-n", required=True, help="Layer name")
+    create_parser.add_argument("--default-on", type=lambda x: x.lower() == 'true', default=True,
+                               help="Initial visibility (true/false)")
+    create_parser.add_argument("--from-pages", help="Move all annotations on these pages, e.g. 1-3,5")
+    create_parser.add_argument("--annotations", nargs="*", default=[], help="Annotation ids to move")
+    create_parser.add_argument("--json", action="store_true", help="Output as JSON")
+
+    # Delete a layer, or flatten it into the page
+    delete_parser = subparsers.add_parser("delete", help="Delete a layer and its content, or flatten it")
+    delete_parser.add_argument("--input", "-i", required=True, help="Input PDF path")
+    delete_parser.add_argument("--output", "-o", required=True, help="Output PDF path")
+    delete_parser.add_argument("--xref", type=int, required=True, help="Layer xref")
+    delete_parser.add_argument("--flatten", action="store_true",
+                               help="Keep the layer's content, always visible")
+    delete_parser.add_argument("--json", action="store_true", help="Output as JSON")
+
     args = parser.parse_args()
 
     if args.command == "list":
@@ -232,6 +715,42 @@ def main():
             print(result["message"])
             sys.exit(0 if result["success"] else 1)
 
+    elif args.command == "clean-orphans":
+        result = clean_orphan_layers(Path(args.input), Path(args.output))
+
+        if hasattr(args, 'json') and args.json:
+            print(json.dumps(result))
+        else:
+            print(result["message"])
+            for name in result["removed"]:
+                print(f"  {name}")
+            sys.exit(0 if result["success"] else 1)
+
+    elif args.command == "create":
+        result = create_layer(
+            Path(args.input),
+            Path(args.output),
+            args.name,
+            default_on=args.default_on,
+            from_pages=args.from_pages,
+            annotation_ids=args.annotations,
+        )
+
+        if hasattr(args, 'json') and args.json:
+            print(json.dumps(result))
+        else:
+            print(result["message"])
+            sys.exit(0 if result["success"] else 1)
+
+    elif args.command == "delete":
+        result = delete_layer(Path(args.input), Path(args.output), args.xref, flatten=args.flatten)
+
+        if hasattr(args, 'json') and args.json:
+            print(json.dumps(result))
+        else:
+            print(result["message"])
+            sys.exit(0 if result["success"] else 1)
+
 
 if __name__ == "__main__":
-    main()
+    run_main(main)
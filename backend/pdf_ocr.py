@@ -13,10 +13,14 @@ Provides OCR functionality for scanned PDFs:
 
 import argparse
 import json
+import os
 import sys
 import re
+import shutil
 import subprocess
 import tempfile
+import urllib.error
+import urllib.request
 from pathlib import Path
 from typing import List, Dict, Any, Optional
 from xml.etree import ElementTree
@@ -34,6 +38,45 @@ try:
 except ImportError:
     HAS_PYMUPDF = False
 
+try:
+    from utils import report_progress, run_main
+except ImportError:
+    from .utils import report_progress, run_main
+
+
+def _list_langs_output() -> Optional[str]:
+    """Raw `tesseract --list-langs` output, or None if Tesseract can't run."""
+    try:
+        lang_output = subprocess.run(
+            ["tesseract", "--list-langs"],
+            capture_output=True,
+            text=True
+        )
+        if lang_output.returncode == 0:
+            return lang_output.stdout
+    except Exception:
+        pass
+    return None
+
+
+def installed_languages() -> List[str]:
+    """Tesseract language codes with installed traineddata (empty if unavailable)."""
+    output = _list_langs_output()
+    if output is None:
+        return []
+    # Skip first line (header) and get language codes
+    langs = output.strip().split('\n')[1:]
+    return [l.strip() for l in langs if l.strip()]
+
+
+def tessdata_dir() -> Optional[str]:
+    """Directory Tesseract loads traineddata from, parsed from the --list-langs header."""
+    output = _list_langs_output()
+    if output is None:
+        return None
+    match = re.search(r'"([^"]+)"', output.split('\n', 1)[0])
+    return match.group(1) if match else None
+
 
 def check_dependencies() -> dict:
     """Check if OCRmyPDF and Tesseract are available."""
@@ -51,19 +94,7 @@ def check_dependencies() -> dict:
             result["tesseract_installed"] = True
 
             # Get available languages
-            try:
-                import subprocess
-                lang_output = subprocess.run(
-                    ["tesseract", "--list-langs"],
-                    capture_output=True,
-                    text=True
-                )
-                if lang_output.returncode == 0:
-                    # Skip first line (header) and get language codes
-                    langs = lang_output.stdout.strip().split('\n')[1:]
-                    result["available_languages"] = [l.strip() for l in langs if l.strip()]
-            except Exception:
-                pass
+            result["available_languages"] = installed_languages()
 
             # Get tesseract version
             try:
@@ -99,6 +130,7 @@ def run_ocr(
     pdf_renderer: str = "auto",
     optimize: int = 1,
     pdfa_image_compression: str = "auto",
+    sidecar: Optional[str] = None,
 ) -> dict:
     """
     Run OCR on a PDF file.
@@ -117,6 +149,8 @@ def run_ocr(
         pdf_renderer: PDF renderer (auto, hocr, sandwich, hocr-docker)
         optimize: Optimization level (0-3)
         pdfa_image_compression: Image compression for PDF/A (auto, jpeg, lossless)
+        sidecar: Also write the recognized text to this .txt path (pages
+            separated by form feeds)
 
     Returns:
         dict with success status and details
@@ -150,15 +184,36 @@ def run_ocr(
             "optimize": optimize,
             "progress_bar": False,
         }
+        if sidecar:
+            kwargs["sidecar"] = sidecar
+
+        # OCRmyPDF doesn't expose per-page callbacks; report start and finish
+        total_pages = 1
+        if HAS_PYMUPDF:
+            with fitz.open(input_path) as doc:
+                total_pages = max(len(doc), 1)
+        report_progress("ocr", 0, total_pages)
 
         # Run OCR
         exit_code = ocrmypdf.ocr(input_path, output_path, **kwargs)
 
+        report_progress("ocr", total_pages, total_pages)
+
+        # OCRmyPDF doesn't report recognition confidence, only the sidecar text
+        sidecar_info = {}
+        if sidecar and Path(sidecar).exists():
+            text = Path(sidecar).read_text(encoding="utf-8", errors="replace")
+            sidecar_info = {
+                "sidecar_path": sidecar,
+                "char_count": sum(1 for c in text if not c.isspace()),
+            }
+
         if exit_code == ExitCode.ok:
             return {
                 "success": True,
                 "output_path": output_path,
                 "exit_code": exit_code.value,
+                **sidecar_info,
             }
         elif exit_code == ExitCode.already_done_ocr:
             return {
@@ -166,6 +221,7 @@ def run_ocr(
                 "output_path": output_path,
                 "exit_code": exit_code.value,
                 "message": "File already contains text layer",
+                **sidecar_info,
             }
         else:
             return {
@@ -583,6 +639,7 @@ def run_editable_ocr(
             zoom = dpi / 72.0
 
             print(f"[INFO] Processing page {page_num + 1}/{len(doc)}...", file=sys.stderr)
+            report_progress("ocr", page_num, len(doc))
 
             # Detect image/graphic regions to preserve
             image_regions = get_image_regions(page, zoom) if preserve_images else []
@@ -748,6 +805,8 @@ def run_editable_ocr(
             finally:
                 Path(tmp_path).unlink(missing_ok=True)
 
+        report_progress("ocr", len(doc), len(doc))
+
         # Embed metrics in PDF metadata if requested
         if embed_metrics:
             metrics_json = json.dumps(all_metrics)
@@ -819,6 +878,508 @@ def get_embedded_metrics(input_path: str) -> dict:
         }
 
 
+# =============================================================================
+# Scanned form detection - find empty field regions on scanned pages
+# =============================================================================
+
+def _find_horizontal_segments(gray: bytes, width: int, height: int, min_len: int) -> list:
+    """
+    Find horizontal dark line segments in a grayscale image.
+
+    Returns merged segments as (x0, y0, x1, y1) in pixels; consecutive rows
+    with overlapping runs (thick lines) are merged into one segment.
+    """
+    raw = []
+    for y in range(height):
+        row = gray[y * width:(y + 1) * width]
+        run_start = None
+        for x in range(width):
+            if row[x] < 128:
+                if run_start is None:
+                    run_start = x
+            elif run_start is not None:
+                if x - run_start >= min_len:
+                    raw.append((run_start, y, x, y))
+                run_start = None
+        if run_start is not None and width - run_start >= min_len:
+            raw.append((run_start, y, width, y))
+
+    merged = []
+    for seg in raw:
+        for i, m in enumerate(merged):
+            if seg[1] - m[3] <= 2 and abs(seg[0] - m[0]) <= 4 and abs(seg[2] - m[2]) <= 4:
+                merged[i] = (min(m[0], seg[0]), m[1], max(m[2], seg[2]), seg[3])
+                break
+        else:
+            merged.append(seg)
+    return merged
+
+
+def _region_has_words(region: tuple, words: list) -> bool:
+    """Check if any OCR word bbox overlaps the region (pixels)."""
+    x0, y0, x1, y1 = region
+    for w in words:
+        wx0, wy0, wx1, wy1 = w["bbox"]
+        if wx0 < x1 and wx1 > x0 and wy0 < y1 and wy1 > y0:
+            return True
+    return False
+
+
+def _guess_label(region: tuple, lines: list, max_gap: float) -> Optional[str]:
+    """
+    Pick the OCR line most likely labelling a field: the nearest line that ends
+    to the left of it on the same band, or failing that, the line just above.
+    """
+    x0, y0, x1, y1 = region
+    best = None
+    best_dist = None
+
+    for line in lines:
+        lx0, ly0, lx1, ly1 = line["bbox"]
+        # Same band, to the left
+        if ly1 > y0 - max_gap / 4 and ly0 < y1 and lx1 <= x0 + 4:
+            dist = x0 - lx1
+        # Directly above, horizontally overlapping
+        elif ly1 <= y0 and lx0 < x1 and lx1 > x0:
+            dist = (y0 - ly1) * 2
+        else:
+            continue
+        if dist <= max_gap and (best_dist is None or dist < best_dist):
+            best = line["text"]
+            best_dist = dist
+
+    return best.strip().rstrip(":").strip() if best else None
+
+
+def detect_scanned_form(
+    input_path: str,
+    language: str = "eng",
+    dpi: int = 150,
+    force_ocr: bool = False,
+) -> dict:
+    """
+    Detect fillable-field structure on scanned pages.
+
+    Pages that already have a text layer are skipped (they aren't scans)
+    unless `force_ocr` is set; their numbers are listed in "skipped_pages".
+    Each remaining page is rendered and OCRed (hOCR), then horizontal rules are found by
+    scanning for long dark pixel runs. Two rules with matching extents form a
+    box; a lone rule is treated as an underline field with the area above it.
+    Regions that contain no OCR text are reported as fields, with nearby text
+    as the label guess. Rects are in PDF points [x0, y0, x1, y1].
+    """
+    if not HAS_PYMUPDF:
+        return {
+            "success": False,
+            "error": "PyMuPDF is required for form detection",
+        }
+
+    if not Path(input_path).exists():
+        return {
+            "success": False,
+            "error": f"Input file not found: {input_path}",
+        }
+
+    try:
+        doc = fitz.open(input_path)
+        zoom = dpi / 72.0
+        min_len = int(dpi * 0.75)  # Field rules are at least 3/4 inch long
+        max_box_height = int(dpi * 1.0)
+        underline_height = int(dpi * 0.3)
+        fields = []
+        skipped_pages = []
+
+        for page_num in range(len(doc)):
+            page = doc[page_num]
+            # More than 50 chars of extractable text means a real text layer, not a scan
+            if not force_ocr and len(page.get_text("text").strip()) > 50:
+                skipped_pages.append(page_num + 1)
+                continue
+
+            pix = page.get_pixmap(matrix=fitz.Matrix(zoom, zoom), colorspace=fitz.csGRAY)
+
+            with tempfile.NamedTemporaryFile(suffix='.png', delete=False) as tmp:
+                pix.save(tmp.name)
+                tmp_path = tmp.name
+
+            try:
+                ocr = subprocess.run(
+                    ['tesseract', tmp_path, 'stdout', '-l', language, 'hocr'],
+                    capture_output=True,
+                    text=True,
+                    timeout=120,
+                )
+            finally:
+                Path(tmp_path).unlink(missing_ok=True)
+
+            if ocr.returncode != 0:
+                print(f"[WARN] Tesseract failed on page {page_num + 1}: {ocr.stderr}", file=sys.stderr)
+                continue
+
+            blocks = parse_hocr(ocr.stdout)
+            lines = [line for block in blocks for line in block["lines"]]
+            words = [word for line in lines for word in line["words"]]
+
+            segments = _find_horizontal_segments(pix.samples, pix.width, pix.height, min_len)
+            segments.sort(key=lambda s: (s[1], s[0]))
+
+            used = set()
+            regions = []
+            for i, top in enumerate(segments):
+                if i in used:
+                    continue
+                # Look for a matching bottom rule to form a box
+                for j in range(i + 1, len(segments)):
+                    bottom = segments[j]
+                    gap = bottom[1] - top[3]
+                    if gap > max_box_height:
+                        break
+                    if gap > 8 and abs(bottom[0] - top[0]) <= 6 and abs(bottom[2] - top[2]) <= 6:
+                        regions.append((top[0] + 3, top[3] + 2, top[2] - 3, bottom[1] - 2))
+                        used.update((i, j))
+                        break
+
+            for i, seg in enumerate(segments):
+                if i not in used:
+                    regions.append((seg[0], max(0, seg[1] - underline_height), seg[2], seg[1] - 1))
+
+            for region in regions:
+                # Allow a little text overlap at the edges (e.g. underline descenders)
+                inset = (region[0] + 4, region[1] + 4, region[2] - 4, region[3] - 2)
+                if inset[2] <= inset[0] or inset[3] <= inset[1] or _region_has_words(inset, words):
+                    continue
+                fields.append({
+                    "page": page_num + 1,
+                    "rect": [round(v / zoom, 2) for v in region],
+                    "label_guess": _guess_label(region, lines, dpi * 2.5),
+                })
+
+        doc.close()
+
+        return {
+            "success": True,
+            "likely_form": len(fields) >= 2,
+            "detected_fields": fields,
+            "skipped_pages": skipped_pages,
+        }
+
+    except Exception as e:
+        return {
+            "success": False,
+            "error": str(e),
+        }
+
+
+# =============================================================================
+# Language Detection - Rank likely OCR languages before running OCR
+# =============================================================================
+
+# OSD script name -> Tesseract language for scripts used by essentially one language
+SCRIPT_LANGUAGES = {
+    "Arabic": "ara",
+    "Armenian": "hye",
+    "Bengali": "ben",
+    "Cyrillic": "rus",
+    "Devanagari": "hin",
+    "Georgian": "kat",
+    "Greek": "ell",
+    "Han": "chi_sim",
+    "Hangul": "kor",
+    "Hebrew": "heb",
+    "Japanese": "jpn",
+    "Katakana": "jpn",
+    "Hiragana": "jpn",
+    "Tamil": "tam",
+    "Thai": "tha",
+}
+
+# Frequent function words used to tell Latin-script languages apart
+LATIN_STOPWORDS = {
+    "eng": {"the", "and", "of", "to", "in", "is", "that", "for", "with", "on", "this", "are", "be", "by"},
+    "spa": {"el", "la", "de", "que", "y", "en", "los", "las", "del", "por", "con", "para", "una", "es"},
+    "fra": {"le", "la", "les", "de", "des", "et", "est", "du", "une", "pour", "dans", "que", "qui", "sur"},
+    "deu": {"der", "die", "das", "und", "ist", "nicht", "mit", "den", "von", "zu", "ein", "eine", "auf", "für"},
+    "ita": {"il", "di", "che", "e", "la", "per", "un", "una", "del", "della", "non", "sono", "con", "gli"},
+    "por": {"de", "que", "e", "o", "a", "do", "da", "em", "um", "para", "com", "não", "uma", "os"},
+    "nld": {"de", "het", "een", "en", "van", "is", "dat", "op", "te", "niet", "voor", "met", "zijn", "aan"},
+}
+
+# ISO 639-1 codes (as returned by langdetect) -> Tesseract codes
+ISO_TO_TESSERACT = {
+    "en": "eng", "es": "spa", "fr": "fra", "de": "deu", "it": "ita", "pt": "por",
+    "nl": "nld", "ru": "rus", "ar": "ara", "el": "ell", "he": "heb", "hi": "hin",
+    "ja": "jpn", "ko": "kor", "zh-cn": "chi_sim", "zh-tw": "chi_tra", "th": "tha",
+    "pl": "pol", "sv": "swe", "da": "dan", "no": "nor", "fi": "fin", "tr": "tur",
+    "cs": "ces", "ro": "ron", "hu": "hun", "ca": "cat", "uk": "ukr",
+}
+
+# Pages sampled for detection, spread across the document
+DETECT_SAMPLE_PAGES = 3
+
+
+def _sample_page_numbers(page_count: int, samples: int = DETECT_SAMPLE_PAGES) -> List[int]:
+    """0-indexed pages evenly spread across the document."""
+    if page_count <= samples:
+        return list(range(page_count))
+    step = (page_count - 1) / (samples - 1)
+    return sorted({round(i * step) for i in range(samples)})
+
+
+def _run_osd(image_path: str) -> Optional[tuple]:
+    """Run Tesseract orientation and script detection; returns (script, confidence)."""
+    osd = subprocess.run(
+        ["tesseract", image_path, "stdout", "--psm", "0"],
+        capture_output=True,
+        text=True,
+        timeout=60,
+    )
+    if osd.returncode != 0:
+        return None
+
+    script = re.search(r"^Script:\s*(\S+)", osd.stdout, re.MULTILINE)
+    confidence = re.search(r"^Script confidence:\s*([\d.]+)", osd.stdout, re.MULTILINE)
+    if not script:
+        return None
+    return script.group(1), float(confidence.group(1)) if confidence else 0.0
+
+
+def _rank_latin_languages(text: str) -> List[tuple]:
+    """Rank Latin-script languages as (code, confidence 0-1), best first."""
+    try:
+        from langdetect import DetectorFactory, detect_langs
+        DetectorFactory.seed = 0  # deterministic results
+        ranked = []
+        for guess in detect_langs(text):
+            code = ISO_TO_TESSERACT.get(guess.lang)
+            if code:
+                ranked.append((code, round(guess.prob, 3)))
+        if ranked:
+            return ranked
+    except Exception:
+        pass  # langdetect missing or text too short; fall back to stopwords
+
+    words = re.findall(r"[^\W\d_]+", text.lower())
+    if not words:
+        return []
+    scores = {
+        code: sum(1 for w in words if w in stopwords)
+        for code, stopwords in LATIN_STOPWORDS.items()
+    }
+    total = sum(scores.values())
+    if total == 0:
+        return []
+    ranked = [(code, round(score / total, 3)) for code, score in scores.items() if score > 0]
+    return sorted(ranked, key=lambda r: r[1], reverse=True)
+
+
+def detect_language(input_path: str, dpi: int = 200) -> dict:
+    """
+    Rank the languages a document is likely written in.
+
+    A few sampled pages are used. Pages with a text layer are read directly;
+    scanned pages are rendered, their script detected with Tesseract OSD
+    (--psm 0), and then OCRed. Scripts specific to one language map straight to
+    it; Latin text is ranked with langdetect when installed, else by counting
+    common function words. Each result says whether its Tesseract language pack
+    is installed; `missing_languages` lists the ones that are not.
+    """
+    if not HAS_PYMUPDF:
+        return {
+            "success": False,
+            "error": "PyMuPDF is required for language detection",
+        }
+
+    if not Path(input_path).exists():
+        return {
+            "success": False,
+            "error": f"Input file not found: {input_path}",
+        }
+
+    try:
+        installed = installed_languages()
+        ocr_language = "eng" if "eng" in installed or not installed else installed[0]
+        doc = fitz.open(input_path)
+        sampled = _sample_page_numbers(len(doc))
+        zoom = dpi / 72.0
+
+        texts = []
+        script_votes: Dict[str, float] = {}
+
+        for page_num in sampled:
+            page = doc[page_num]
+            text = page.get_text("text").strip()
+            if len(text) > 50:
+                texts.append(text)
+                continue
+
+            pix = page.get_pixmap(matrix=fitz.Matrix(zoom, zoom), colorspace=fitz.csGRAY)
+            with tempfile.NamedTemporaryFile(suffix='.png', delete=False) as tmp:
+                pix.save(tmp.name)
+                tmp_path = tmp.name
+
+            try:
+                osd = _run_osd(tmp_path)
+                if osd:
+                    script, confidence = osd
+                    script_votes[script] = script_votes.get(script, 0.0) + confidence
+
+                ocr = subprocess.run(
+                    ['tesseract', tmp_path, 'stdout', '-l', ocr_language],
+                    capture_output=True,
+                    text=True,
+                    timeout=120,
+                )
+                if ocr.returncode == 0:
+                    texts.append(ocr.stdout)
+            finally:
+                Path(tmp_path).unlink(missing_ok=True)
+
+        doc.close()
+
+        script = max(script_votes, key=script_votes.get) if script_votes else None
+        ranked: List[tuple] = []
+        if script and script in SCRIPT_LANGUAGES:
+            total = sum(script_votes.values()) or 1.0
+            ranked.append((SCRIPT_LANGUAGES[script], round(script_votes[script] / total, 3)))
+        else:
+            ranked = _rank_latin_languages("\n".join(texts))
+
+        languages = [
+            {"code": code, "confidence": confidence, "installed": code in installed}
+            for code, confidence in ranked
+        ]
+
+        return {
+            "success": True,
+            "script": script,
+            "sampled_pages": [p + 1 for p in sampled],
+            "languages": languages,
+            "missing_languages": [l["code"] for l in languages if not l["installed"]],
+        }
+
+    except Exception as e:
+        return {
+            "success": False,
+            "error": str(e),
+        }
+
+
+# =============================================================================
+# Language Packs - List installed Tesseract languages and fetch missing ones
+# =============================================================================
+
+# Human-readable names for common Tesseract language codes
+LANGUAGE_NAMES = {
+    "afr": "Afrikaans", "ara": "Arabic", "ben": "Bengali", "bul": "Bulgarian",
+    "cat": "Catalan", "ces": "Czech", "chi_sim": "Chinese (Simplified)",
+    "chi_tra": "Chinese (Traditional)", "dan": "Danish", "deu": "German",
+    "ell": "Greek", "eng": "English", "equ": "Math / equations", "est": "Estonian",
+    "eus": "Basque", "fin": "Finnish", "fra": "French", "glg": "Galician",
+    "heb": "Hebrew", "hin": "Hindi", "hrv": "Croatian", "hun": "Hungarian",
+    "hye": "Armenian", "ind": "Indonesian", "isl": "Icelandic", "ita": "Italian",
+    "jpn": "Japanese", "kat": "Georgian", "kor": "Korean", "lat": "Latin",
+    "lav": "Latvian", "lit": "Lithuanian", "nld": "Dutch", "nor": "Norwegian",
+    "osd": "Orientation and script detection", "pol": "Polish", "por": "Portuguese",
+    "ron": "Romanian", "rus": "Russian", "slk": "Slovak", "slv": "Slovenian",
+    "spa": "Spanish", "srp": "Serbian", "swe": "Swedish", "tam": "Tamil",
+    "tha": "Thai", "tur": "Turkish", "ukr": "Ukrainian", "vie": "Vietnamese",
+}
+
+# Fast (integer) models; the same set distributions package as tesseract-ocr-<code>
+TESSDATA_URL = "https://github.com/tesseract-ocr/tessdata_fast/raw/main/{code}.traineddata"
+
+
+def list_languages() -> dict:
+    """Installed Tesseract languages with display names."""
+    codes = installed_languages()
+    return {
+        "success": True,
+        "tessdata_dir": tessdata_dir(),
+        "languages": [
+            {"code": code, "name": LANGUAGE_NAMES.get(code, code)}
+            for code in codes
+        ],
+    }
+
+
+def _system_install_hint(code: str) -> Optional[str]:
+    """Package manager command that installs a language pack system-wide."""
+    if sys.platform == "darwin":
+        return "brew install tesseract-lang"
+    if sys.platform.startswith("win"):
+        return None
+    pack = code.replace("_", "-")
+    for manager, command in (
+        ("apt-get", f"sudo apt-get install tesseract-ocr-{pack}"),
+        ("dnf", f"sudo dnf install tesseract-langpack-{code}"),
+        ("pacman", f"sudo pacman -S tesseract-data-{code}"),
+        ("zypper", f"sudo zypper install tesseract-ocr-traineddata-{code}"),
+    ):
+        if shutil.which(manager):
+            return command
+    return None
+
+
+def install_language(code: str) -> dict:
+    """
+    Download a language pack into Tesseract's tessdata directory.
+
+    Works when that directory is writable (user installs, Homebrew, Windows
+    installs in the user profile). System-managed directories are left alone;
+    the result then carries the package manager command to run instead.
+    """
+    result = {
+        "success": False,
+        "code": code,
+        "message": "",
+        "install_command": None,
+    }
+
+    if not re.fullmatch(r"[a-z]{3}(_[a-z]+)?", code):
+        result["message"] = f"Invalid language code: {code}"
+        return result
+
+    if code in installed_languages():
+        result["success"] = True
+        result["message"] = f"Language pack '{code}' is already installed"
+        return result
+
+    target_dir = tessdata_dir()
+    if target_dir is None:
+        result["message"] = "Tesseract is not installed or its tessdata directory is unknown"
+        return result
+
+    if not os.access(target_dir, os.W_OK):
+        result["install_command"] = _system_install_hint(code)
+        result["message"] = (
+            f"Tesseract's language packs are system-managed ({target_dir}). "
+            + (f"Install with: {result['install_command']}" if result["install_command"]
+               else "Install the language pack with your system package manager.")
+        )
+        return result
+
+    target = Path(target_dir) / f"{code}.traineddata"
+    partial = target.with_suffix(".part")
+    try:
+        with urllib.request.urlopen(TESSDATA_URL.format(code=code), timeout=120) as response:
+            partial.write_bytes(response.read())
+        partial.replace(target)
+    except urllib.error.HTTPError as e:
+        partial.unlink(missing_ok=True)
+        result["message"] = (
+            f"No language pack named '{code}'" if e.code == 404 else f"Download failed: {e}"
+        )
+        return result
+    except Exception as e:
+        partial.unlink(missing_ok=True)
+        result["message"] = f"Failed to install language pack: {e}"
+        return result
+
+    result["success"] = True
+    result["message"] = f"Installed language pack '{code}' to {target_dir}"
+    return result
+
+
 def main():
     parser = argparse.ArgumentParser(description="PDF OCR operations")
     subparsers = parser.add_subparsers(dest="command", required=True)
@@ -843,6 +1404,7 @@ def main():
     ocr_parser.add_argument("--force-ocr", action="store_true", help="Force OCR")
     ocr_parser.add_argument("--redo-ocr", action="store_true", help="Redo existing OCR")
     ocr_parser.add_argument("--optimize", type=int, default=1, help="Optimization level 0-3")
+    ocr_parser.add_argument("--sidecar", help="Also write recognized text to this .txt path")
 
     # OCR Editable command (editable - real text objects with visual metrics)
     editable_parser = subparsers.add_parser("ocr-editable", help="Run editable OCR on PDF")
@@ -858,6 +1420,23 @@ def main():
     metrics_parser = subparsers.add_parser("get-metrics", help="Get embedded OCR metrics from PDF")
     metrics_parser.add_argument("--input", required=True, help="Input PDF path")
 
+    # Scanned form detection command
+    form_parser = subparsers.add_parser("detect-form", help="Detect empty form fields on scanned pages")
+    form_parser.add_argument("--input", required=True, help="Input PDF path")
+    form_parser.add_argument("--language", default="eng", help="OCR language(s)")
+    form_parser.add_argument("--dpi", type=int, default=150, help="DPI for rendering (default: 150)")
+    form_parser.add_argument("--force-ocr", action="store_true", help="Also OCR pages that already have text")
+
+    # Language detection command
+    lang_parser = subparsers.add_parser("detect-language", help="Rank likely document languages")
+    lang_parser.add_argument("--input", required=True, help="Input PDF path")
+    lang_parser.add_argument("--dpi", type=int, default=200, help="DPI for rendering (default: 200)")
+
+    # Language pack commands
+    subparsers.add_parser("list-languages", help="List installed Tesseract languages")
+    install_lang_parser = subparsers.add_parser("install-language", help="Install a Tesseract language pack")
+    install_lang_parser.add_argument("--code", required=True, help="Tesseract language code, e.g. spa")
+
     args = parser.parse_args()
 
     if args.command == "check":
@@ -877,6 +1456,7 @@ def main():
             force_ocr=args.force_ocr,
             redo_ocr=args.redo_ocr,
             optimize=args.optimize,
+            sidecar=args.sidecar,
         )
     elif args.command == "ocr-editable":
         result = run_editable_ocr(
@@ -890,6 +1470,19 @@ def main():
         )
     elif args.command == "get-metrics":
         result = get_embedded_metrics(args.input)
+    elif args.command == "detect-form":
+        result = detect_scanned_form(
+            args.input,
+            language=args.language,
+            dpi=args.dpi,
+            force_ocr=args.force_ocr,
+        )
+    elif args.command == "detect-language":
+        result = detect_language(args.input, dpi=args.dpi)
+    elif args.command == "list-languages":
+        result = list_languages()
+    elif args.command == "install-language":
+        result = install_language(args.code)
     else:
         result = {"error": f"Unknown command: {args.command}"}
 
@@ -897,4 +1490,4 @@ def main():
 
 
 if __name__ == "__main__":
-    main()
+    run_main(main)
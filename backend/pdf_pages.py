@@ -3,26 +3,67 @@ Page operations: merge, split, reorder, rotate.
 
 CLI usage (dev):
   python pdf_pages.py merge --inputs in1.pdf in2.pdf --output merged.pdf
+  python pdf_pages.py merge --inputs ch1.pdf ch2.pdf --output book.pdf --no-bookmarks
+  python pdf_pages.py split-every --input in.pdf --n 10 --output-dir parts/
+  python pdf_pages.py split-bookmarks --input book.pdf --output-dir chapters/ --level 1
+  python pdf_pages.py interleave --front odd.pdf --back even.pdf --output book.pdf --reverse-back
+  python pdf_pages.py impose --input in.pdf --output booklet.pdf --layout 2x1 --booklet
 """
 
 from __future__ import annotations
 
 import argparse
+import json
+import re
 import sys
 from pathlib import Path
 from typing import Iterable, Sequence
 
-from pypdf import PdfReader, PdfWriter
+from pypdf import PdfReader, PdfWriter, Transformation
+from pypdf.generic import Fit, RectangleObject
 
+try:
+    from utils import report_error
+except ImportError:
+    from .utils import report_error
 
-def merge_pdfs(inputs: Sequence[Path], output: Path) -> None:
+OVERLAY_POSITIONS = ("center", "top-left", "top-right", "bottom-left", "bottom-right")
+
+
+def _copy_outline(reader: PdfReader, writer: PdfWriter, items: list, parent, offset: int) -> None:
+    """
+    Re-create a source outline under `parent`, shifting page targets by `offset`.
+    In pypdf's outline list a nested list holds the children of the item before it.
+    """
+    last = None
+    for item in items:
+        if isinstance(item, list):
+            _copy_outline(reader, writer, item, last if last is not None else parent, offset)
+            continue
+        page = reader.get_destination_page_number(item)
+        if page is None or page < 0:
+            page = 0
+        top = item.top if isinstance(item.top, (int, float)) else None
+        fit = Fit.xyz(top=top) if top is not None else Fit.fit()
+        last = writer.add_outline_item(item.title or "Untitled", offset + page, parent=parent, fit=fit)
+
+
+def merge_pdfs(inputs: Sequence[Path], output: Path, preserve_bookmarks: bool = True) -> None:
+    """
+    Concatenate PDFs. With `preserve_bookmarks`, each source gets a top-level
+    bookmark named after its file, holding that source's own outline.
+    """
     if len(inputs) < 2:
         raise ValueError("At least two input PDFs are required.")
     writer = PdfWriter()
     for path in inputs:
         reader = PdfReader(str(path))
+        offset = len(writer.pages)
         for page in reader.pages:
             writer.add_page(page)
+        if preserve_bookmarks and reader.pages:
+            root = writer.add_outline_item(path.stem, offset)
+            _copy_outline(reader, writer, reader.outline, root, offset)
     with output.open("wb") as fh:
         writer.write(fh)
 
@@ -54,6 +95,39 @@ def merge_pages(page_specs: Sequence[tuple[Path, int]], output: Path) -> None:
         writer.write(fh)
 
 
+def merge_interleave(front: Path, back: Path, output: Path, reverse_back: bool = False) -> list[dict]:
+    """
+    Interleave two scans of a double-sided document: front 1, back 1, front 2, ...
+    With reverse_back the back stack is read last-to-first, as it comes out of a
+    simplex scanner when the pile is flipped over. When the counts differ the
+    leftover pages are appended at the end.
+    Returns the final order as [{"source": "front"|"back", "page": 1-indexed}, ...].
+    """
+    front_reader = PdfReader(str(front))
+    back_reader = PdfReader(str(back))
+
+    front_pages = [("front", i) for i in range(len(front_reader.pages))]
+    back_pages = [("back", i) for i in range(len(back_reader.pages))]
+    if reverse_back:
+        back_pages.reverse()
+
+    order: list[tuple[str, int]] = []
+    for pair in zip(front_pages, back_pages):
+        order.extend(pair)
+    shared = min(len(front_pages), len(back_pages))
+    order.extend(front_pages[shared:])
+    order.extend(back_pages[shared:])
+
+    readers = {"front": front_reader, "back": back_reader}
+    writer = PdfWriter()
+    for source, idx in order:
+        writer.add_page(readers[source].pages[idx])
+    with output.open("wb") as fh:
+        writer.write(fh)
+
+    return [{"source": source, "page": idx + 1} for source, idx in order]
+
+
 def reorder_pages(input_path: Path, order: Sequence[int], output: Path) -> None:
     reader = PdfReader(str(input_path))
     writer = PdfWriter()
@@ -88,6 +162,119 @@ def split_pdf(input_path: Path, ranges: Iterable[str], output_dir: Path) -> list
     return outputs
 
 
+def extract_range(input_path: Path, ranges: Iterable[str], output: Path) -> list[int]:
+    """
+    Write the pages selected by `ranges` (e.g. "3-7", "10", "15-20") to a
+    single PDF, in the order given. Returns the 1-indexed pages written.
+    """
+    reader = PdfReader(str(input_path))
+    total = len(reader.pages)
+
+    page_indices: list[int] = []
+    for range_expr in ranges:
+        page_indices.extend(parse_ranges(range_expr, total))
+    if not page_indices:
+        raise ValueError("No pages selected.")
+
+    writer = PdfWriter()
+    for page_index in page_indices:
+        writer.add_page(reader.pages[page_index])
+    with output.open("wb") as fh:
+        writer.write(fh)
+    return [i + 1 for i in page_indices]
+
+
+def split_every_n(input_path: Path, n: int, output_dir: Path) -> list[dict]:
+    """
+    Split into consecutive chunks of n pages (the last chunk may be shorter).
+    Files are named part_001.pdf, part_002.pdf, ... so they sort correctly.
+    Returns [{"path": ..., "pages": [1-indexed page numbers]}, ...].
+    """
+    if n < 1:
+        raise ValueError("Chunk size must be at least 1.")
+    reader = PdfReader(str(input_path))
+    output_dir.mkdir(parents=True, exist_ok=True)
+    total = len(reader.pages)
+
+    chunk_count = (total + n - 1) // n
+    width = max(3, len(str(chunk_count)))
+
+    outputs: list[dict] = []
+    for chunk_idx, start in enumerate(range(0, total, n), start=1):
+        writer = PdfWriter()
+        page_indices = list(range(start, min(start + n, total)))
+        for page_index in page_indices:
+            writer.add_page(reader.pages[page_index])
+        out_path = output_dir / f"part_{chunk_idx:0{width}d}.pdf"
+        with out_path.open("wb") as fh:
+            writer.write(fh)
+        outputs.append({"path": str(out_path), "pages": [i + 1 for i in page_indices]})
+    return outputs
+
+
+def _outline_starts(reader: PdfReader, items: list, level: int, depth: int = 1) -> list[tuple[str, int]]:
+    """(title, 0-based page) of the outline items `level` deep, in outline order."""
+    starts: list[tuple[str, int]] = []
+    for item in items:
+        if isinstance(item, list):
+            if depth < level:
+                starts.extend(_outline_starts(reader, item, level, depth + 1))
+            continue
+        if depth == level:
+            page = reader.get_destination_page_number(item)
+            if page is not None and page >= 0:
+                starts.append((item.title or "Untitled", page))
+    return starts
+
+
+def _safe_filename(title: str, max_length: int = 100) -> str:
+    """Bookmark title usable as a file name on every platform."""
+    name = re.sub(r'[<>:"/\\|?*\x00-\x1f]', "", title)
+    name = re.sub(r"\s+", " ", name).strip(" .")
+    return name[:max_length].rstrip(" .") or "Untitled"
+
+
+def split_by_bookmarks(input_path: Path, output_dir: Path, level: int = 1) -> list[dict]:
+    """
+    Split at the outline entries `level` deep (1 = top level), one file per
+    entry named after its title. Entries starting on the same page share a
+    file; pages before the first entry go to a "Front matter" file.
+    Returns [{"title": ..., "path": ..., "pages": [1-indexed]}, ...] per bookmark.
+    """
+    if level < 1:
+        raise ValueError("Bookmark level must be at least 1.")
+    reader = PdfReader(str(input_path))
+    total = len(reader.pages)
+    starts = _outline_starts(reader, reader.outline, level)
+    if not starts:
+        raise ValueError(f"The document has no bookmarks at level {level}.")
+
+    # Group titles by start page, keeping outline order within a page
+    groups: dict[int, list[str]] = {}
+    for title, page in sorted(starts, key=lambda s: s[1]):
+        groups.setdefault(page, []).append(title)
+    if 0 not in groups:
+        groups = {0: ["Front matter"], **groups}
+
+    output_dir.mkdir(parents=True, exist_ok=True)
+    first_pages = sorted(groups)
+    width = max(2, len(str(len(first_pages))))
+
+    results: list[dict] = []
+    for index, start in enumerate(first_pages, start=1):
+        end = first_pages[index] if index < len(first_pages) else total
+        titles = groups[start]
+        writer = PdfWriter()
+        for page_index in range(start, end):
+            writer.add_page(reader.pages[page_index])
+        out_path = output_dir / f"{index:0{width}d} {_safe_filename(titles[0])}.pdf"
+        with out_path.open("wb") as fh:
+            writer.write(fh)
+        pages = [i + 1 for i in range(start, end)]
+        results.extend({"title": title, "path": str(out_path), "pages": pages} for title in titles)
+    return results
+
+
 def rotate_pages(input_path: Path, rotations: dict[int, int], output: Path) -> None:
     reader = PdfReader(str(input_path))
     writer = PdfWriter()
@@ -100,19 +287,213 @@ def rotate_pages(input_path: Path, rotations: dict[int, int], output: Path) -> N
         writer.write(fh)
 
 
+def overlay_pdf(
+    base_path: Path,
+    overlay_path: Path,
+    output: Path,
+    overlay_page: int = 1,
+    position: str = "center",
+    layer: str = "under",
+) -> int:
+    """
+    Stamp one page of an overlay PDF onto every page of the base PDF.
+    The overlay is scaled (keeping aspect ratio) to fit each base page, then
+    placed at `position`. `layer` is "under" (the default) or "over" the base content.
+    Returns the number of pages stamped.
+    """
+    if position not in OVERLAY_POSITIONS:
+        raise ValueError(f"Invalid position '{position}'. Expected one of: {', '.join(OVERLAY_POSITIONS)}")
+    if layer not in ("over", "under"):
+        raise ValueError(f"Invalid layer '{layer}'. Expected 'over' or 'under'.")
+
+    overlay_reader = PdfReader(str(overlay_path))
+    if overlay_page < 1 or overlay_page > len(overlay_reader.pages):
+        raise ValueError(
+            f"Overlay page {overlay_page} out of bounds for {overlay_path} ({len(overlay_reader.pages)} pages)"
+        )
+    stamp = overlay_reader.pages[overlay_page - 1]
+    stamp_box = stamp.mediabox
+    stamp_w = float(stamp_box.width)
+    stamp_h = float(stamp_box.height)
+
+    reader = PdfReader(str(base_path))
+    writer = PdfWriter()
+    for page in reader.pages:
+        box = page.mediabox
+        page_w = float(box.width)
+        page_h = float(box.height)
+
+        scale = min(page_w / stamp_w, page_h / stamp_h)
+        placed_w = stamp_w * scale
+        placed_h = stamp_h * scale
+
+        if position == "center":
+            x = (page_w - placed_w) / 2
+            y = (page_h - placed_h) / 2
+        else:
+            vertical, horizontal = position.split("-")
+            x = 0.0 if horizontal == "left" else page_w - placed_w
+            y = 0.0 if vertical == "bottom" else page_h - placed_h
+
+        # Move the stamp's own origin to 0,0 before scaling, then into the page box
+        transform = (
+            Transformation()
+            .translate(-float(stamp_box.left), -float(stamp_box.bottom))
+            .scale(scale, scale)
+            .translate(float(box.left) + x, float(box.bottom) + y)
+        )
+        page.merge_transformed_page(stamp, transform, over=(layer == "over"))
+        writer.add_page(page)
+
+    with output.open("wb") as fh:
+        writer.write(fh)
+    return len(reader.pages)
+
+
+def parse_layout(layout: str) -> tuple[int, int]:
+    """Parse an n-up layout like "2x1" (columns x rows)."""
+    try:
+        cols_str, rows_str = layout.lower().split("x", 1)
+        cols, rows = int(cols_str), int(rows_str)
+    except ValueError:
+        raise ValueError(f"Invalid layout '{layout}'. Expected columns x rows, e.g. 2x1 or 2x2.") from None
+    if cols < 1 or rows < 1 or cols * rows < 2:
+        raise ValueError(f"Layout '{layout}' must place at least two pages per sheet.")
+    return cols, rows
+
+
+def booklet_order(total: int) -> list[int | None]:
+    """
+    Page order for saddle-stitch printing, two pages per sheet side.
+    The page count is padded to a multiple of four with blanks (None);
+    each sheet is front (last, first) then back (second, second-to-last).
+    """
+    padded = (total + 3) // 4 * 4
+    order: list[int | None] = []
+    for i in range(padded // 4):
+        front_left = padded - 1 - 2 * i
+        front_right = 2 * i
+        back_left = 2 * i + 1
+        back_right = padded - 2 - 2 * i
+        for idx in (front_left, front_right, back_left, back_right):
+            order.append(idx if idx < total else None)
+    return order
+
+
+def _visible_box(page) -> RectangleObject:
+    """
+    The page's crop box clipped to its media box, set back as the crop box.
+    merge_transformed_page clips merged content to the source crop box, so
+    once this is set nothing outside the visible area reaches the sheet.
+    """
+    media, crop = page.mediabox, page.cropbox
+    box = RectangleObject([
+        max(float(crop.left), float(media.left)),
+        max(float(crop.bottom), float(media.bottom)),
+        min(float(crop.right), float(media.right)),
+        min(float(crop.top), float(media.top)),
+    ])
+    if box.width <= 0 or box.height <= 0:
+        box = RectangleObject(media)
+    page.cropbox = box
+    return box
+
+
+def impose_pages(input_path: Path, output: Path, layout: str = "2x1", booklet: bool = False) -> list[dict]:
+    """
+    Place several pages on each output sheet in a columns x rows grid, filled
+    left to right, top to bottom. Sheets keep the visible (crop box) size of
+    the first page, turned to whichever orientation fits the grid best; pages
+    are scaled to fit their cell, centered and clipped to it. Booklet mode needs a 2x1 layout and reorders pages
+    into printer spreads for folding.
+    Returns [{"sheet": n, "pages": [1-indexed page or None per cell]}, ...].
+    """
+    cols, rows = parse_layout(layout)
+    if booklet and (cols, rows) != (2, 1):
+        raise ValueError("Booklet imposition uses a 2x1 layout.")
+
+    reader = PdfReader(str(input_path))
+    total = len(reader.pages)
+    if total == 0:
+        raise ValueError("Input PDF has no pages.")
+
+    order: list[int | None] = booklet_order(total) if booklet else list(range(total))
+    per_sheet = cols * rows
+
+    first = reader.pages[0]
+    first.transfer_rotation_to_content()
+    first_box = _visible_box(first)
+    page_w, page_h = float(first_box.width), float(first_box.height)
+
+    def fit(sheet_w: float, sheet_h: float) -> float:
+        return min(sheet_w / cols / page_w, sheet_h / rows / page_h)
+
+    # Pick the sheet orientation that gives the pages the most room
+    sheet_w, sheet_h = page_w, page_h
+    if fit(page_h, page_w) > fit(page_w, page_h):
+        sheet_w, sheet_h = page_h, page_w
+    cell_w, cell_h = sheet_w / cols, sheet_h / rows
+
+    writer = PdfWriter()
+    mapping: list[dict] = []
+    for sheet_idx, start in enumerate(range(0, len(order), per_sheet), start=1):
+        cells = order[start:start + per_sheet]
+        sheet = writer.add_blank_page(width=sheet_w, height=sheet_h)
+        for cell_idx, page_idx in enumerate(cells):
+            if page_idx is None:
+                continue
+            page = reader.pages[page_idx]
+            page.transfer_rotation_to_content()
+            box = _visible_box(page)
+            w, h = float(box.width), float(box.height)
+            scale = min(cell_w / w, cell_h / h)
+
+            col = cell_idx % cols
+            row = cell_idx // cols
+            # PDF y grows upwards, so row 0 is the top of the sheet
+            x = col * cell_w + (cell_w - w * scale) / 2
+            y = sheet_h - (row + 1) * cell_h + (cell_h - h * scale) / 2
+
+            transform = (
+                Transformation()
+                .translate(-float(box.left), -float(box.bottom))
+                .scale(scale, scale)
+                .translate(x, y)
+            )
+            sheet.merge_transformed_page(page, transform)
+        mapping.append({
+            "sheet": sheet_idx,
+            "pages": [None if idx is None else idx + 1 for idx in cells],
+        })
+
+    with output.open("wb") as fh:
+        writer.write(fh)
+    return mapping
+
+
 def parse_ranges(expr: str, total_pages: int) -> list[int]:
+    """
+    0-based page indices for an expression like "3-7,10". This is the one
+    page-range parser: split and extract both go through it.
+    """
     result: list[int] = []
     parts = [p.strip() for p in expr.split(",") if p.strip()]
     for part in parts:
         if "-" in part:
             start_str, end_str = part.split("-", 1)
-            start = int(start_str) - 1
-            end = int(end_str) - 1
+            try:
+                start = int(start_str) - 1
+                end = int(end_str) - 1
+            except ValueError:
+                raise ValueError(f"Invalid page range '{part}'.") from None
             if start < 0 or end < start or end >= total_pages:
                 raise ValueError(f"Invalid range '{part}' for {total_pages} pages.")
             result.extend(range(start, end + 1))
         else:
-            idx = int(part) - 1
+            try:
+                idx = int(part) - 1
+            except ValueError:
+                raise ValueError(f"Invalid page range '{part}'.") from None
             if idx < 0 or idx >= total_pages:
                 raise ValueError(f"Page {part} out of bounds for {total_pages} pages.")
             result.append(idx)
@@ -126,6 +507,11 @@ def _build_parser() -> argparse.ArgumentParser:
     merge_p = sub.add_parser("merge", help="Merge multiple PDFs")
     merge_p.add_argument("--inputs", nargs="+", required=True, help="Input PDF paths")
     merge_p.add_argument("--output", required=True, help="Output PDF path")
+    merge_p.add_argument(
+        "--no-bookmarks",
+        action="store_true",
+        help="Drop source outlines instead of nesting each under a bookmark per file",
+    )
 
     reorder_p = sub.add_parser("reorder", help="Reorder pages of a PDF")
     reorder_p.add_argument("--input", required=True, help="Input PDF path")
@@ -137,6 +523,21 @@ def _build_parser() -> argparse.ArgumentParser:
     split_p.add_argument("--ranges", nargs="*", help="Ranges like 1-3,5; empty = split every page")
     split_p.add_argument("--output-dir", required=True, help="Directory for split PDFs")
 
+    extract_p = sub.add_parser("extract", help="Extract page ranges into a single PDF")
+    extract_p.add_argument("--input", required=True, help="Input PDF path")
+    extract_p.add_argument("--ranges", nargs="+", required=True, help="Ranges like 3-7,10 15-20")
+    extract_p.add_argument("--output", required=True, help="Output PDF path")
+
+    split_every_p = sub.add_parser("split-every", help="Split PDF into chunks of N pages")
+    split_every_p.add_argument("--input", required=True, help="Input PDF path")
+    split_every_p.add_argument("--n", type=int, required=True, help="Pages per output file")
+    split_every_p.add_argument("--output-dir", required=True, help="Directory for the parts")
+
+    split_bookmarks_p = sub.add_parser("split-bookmarks", help="Split PDF at its bookmarks, one file per entry")
+    split_bookmarks_p.add_argument("--input", required=True, help="Input PDF path")
+    split_bookmarks_p.add_argument("--output-dir", required=True, help="Directory for the parts")
+    split_bookmarks_p.add_argument("--level", type=int, default=1, help="Outline depth to split at (default 1 = top level)")
+
     rotate_p = sub.add_parser("rotate", help="Rotate pages")
     rotate_p.add_argument("--input", required=True, help="Input PDF path")
     rotate_p.add_argument(
@@ -147,6 +548,14 @@ def _build_parser() -> argparse.ArgumentParser:
     rotate_p.add_argument("--degrees", type=int, default=90, help="Degrees when rotation list is empty (default 90)")
     rotate_p.add_argument("--output", required=True, help="Output PDF path")
 
+    overlay_p = sub.add_parser("overlay", help="Stamp an overlay PDF page onto every base page")
+    overlay_p.add_argument("--base", required=True, help="Base PDF path")
+    overlay_p.add_argument("--overlay", required=True, help="Overlay (template) PDF path")
+    overlay_p.add_argument("--output", required=True, help="Output PDF path")
+    overlay_p.add_argument("--overlay-page", type=int, default=1, help="1-based overlay page to stamp (default 1)")
+    overlay_p.add_argument("--position", default="center", choices=OVERLAY_POSITIONS, help="Placement on the page")
+    overlay_p.add_argument("--layer", default="under", choices=("over", "under"), help="Stamp over or under content")
+
     merge_pages_p = sub.add_parser("merge-pages", help="Merge specific pages from multiple PDFs")
     merge_pages_p.add_argument(
         "--pages",
@@ -156,6 +565,18 @@ def _build_parser() -> argparse.ArgumentParser:
     )
     merge_pages_p.add_argument("--output", required=True, help="Output PDF path")
 
+    interleave_p = sub.add_parser("interleave", help="Interleave front and back scans of double-sided pages")
+    interleave_p.add_argument("--front", required=True, help="PDF with the front (odd) pages")
+    interleave_p.add_argument("--back", required=True, help="PDF with the back (even) pages")
+    interleave_p.add_argument("--output", required=True, help="Output PDF path")
+    interleave_p.add_argument("--reverse-back", action="store_true", help="Back pages were scanned last-to-first")
+
+    impose_p = sub.add_parser("impose", help="Place several pages per sheet (n-up) or build a booklet")
+    impose_p.add_argument("--input", required=True, help="Input PDF path")
+    impose_p.add_argument("--output", required=True, help="Output PDF path")
+    impose_p.add_argument("--layout", default="2x1", help="Columns x rows per sheet, e.g. 2x1, 2x2")
+    impose_p.add_argument("--booklet", action="store_true", help="Reorder pages for saddle-stitch folding (2x1)")
+
     return parser
 
 
@@ -165,12 +586,25 @@ def _main(argv: list[str]) -> int:
 
     try:
         if args.command == "merge":
-            merge_pdfs([Path(p) for p in args.inputs], Path(args.output))
+            merge_pdfs(
+                [Path(p) for p in args.inputs],
+                Path(args.output),
+                preserve_bookmarks=not args.no_bookmarks,
+            )
         elif args.command == "reorder":
             reorder_pages(Path(args.input), args.order, Path(args.output))
         elif args.command == "split":
             ranges = args.ranges or []
             split_pdf(Path(args.input), ranges, Path(args.output_dir))
+        elif args.command == "extract":
+            pages = extract_range(Path(args.input), args.ranges, Path(args.output))
+            print(json.dumps(pages))
+        elif args.command == "split-every":
+            parts = split_every_n(Path(args.input), args.n, Path(args.output_dir))
+            print(json.dumps(parts))
+        elif args.command == "split-bookmarks":
+            parts = split_by_bookmarks(Path(args.input), Path(args.output_dir), level=args.level)
+            print(json.dumps(parts))
         elif args.command == "rotate":
             rotations: dict[int, int] = {}
             if args.rotation:
@@ -183,6 +617,15 @@ def _main(argv: list[str]) -> int:
                 for idx in range(len(reader.pages)):
                     rotations[idx] = args.degrees
             rotate_pages(Path(args.input), rotations, Path(args.output))
+        elif args.command == "overlay":
+            overlay_pdf(
+                Path(args.base),
+                Path(args.overlay),
+                Path(args.output),
+                overlay_page=args.overlay_page,
+                position=args.position,
+                layer=args.layer,
+            )
         elif args.command == "merge-pages":
             page_specs: list[tuple[Path, int]] = []
             for spec in args.pages:
@@ -192,9 +635,26 @@ def _main(argv: list[str]) -> int:
                 file_part, page_part = spec.rsplit(":", 1)
                 page_specs.append((Path(file_part), int(page_part)))
             merge_pages(page_specs, Path(args.output))
+        elif args.command == "interleave":
+            order = merge_interleave(
+                Path(args.front),
+                Path(args.back),
+                Path(args.output),
+                reverse_back=args.reverse_back,
+            )
+            print(json.dumps(order))
+        elif args.command == "impose":
+            mapping = impose_pages(
+                Path(args.input),
+                Path(args.output),
+                layout=args.layout,
+                booklet=args.booklet,
+            )
+            print(json.dumps(mapping))
         else:
             parser.error("Unknown command")
     except Exception as exc:  # noqa: BLE001
+        report_error(exc)
         print(f"Error: {exc}", file=sys.stderr)
         return 1
     return 0
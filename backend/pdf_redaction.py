@@ -7,19 +7,30 @@ cover content with a black box - it actually deletes the underlying text and ima
 CLI usage (dev):
   python pdf_redaction.py mark --input doc.pdf --output out.pdf --page 0 --x0 100 --y0 100 --x1 300 --y1 150
   python pdf_redaction.py apply --input doc.pdf --output out.pdf
+  python pdf_redaction.py apply --input doc.pdf --output out.pdf --audit-output audit.csv
+  python pdf_redaction.py pattern --input doc.pdf --output out.pdf --pattern "\\d{3}-\\d{2}-\\d{4}" --regex
   python pdf_redaction.py verify --input doc.pdf
+  python pdf_redaction.py verify-all --input doc.pdf --term "Jane Doe" --term 555-0100
 """
 
 from __future__ import annotations
 
 import argparse
+import csv
+import re
 import sys
 import json
+from datetime import datetime, timezone
 from pathlib import Path
 from typing import Optional
 
 import fitz  # PyMuPDF
 
+try:
+    from utils import run_main
+except ImportError:
+    from .utils import run_main
+
 
 def add_redaction_mark(
     input_path: Path,
@@ -78,15 +89,53 @@ def add_redaction_mark(
     return result
 
 
+def _apply_page_redactions(page, images: bool, graphics: bool) -> None:
+    """Permanently remove the content under a page's redaction annotations."""
+    page.apply_redactions(
+        images=fitz.PDF_REDACT_IMAGE_REMOVE if images else fitz.PDF_REDACT_IMAGE_NONE,
+        graphics=fitz.PDF_REDACT_LINE_ART_IF_TOUCHED if graphics else fitz.PDF_REDACT_LINE_ART_NONE,
+    )
+
+
+AUDIT_FIELDS = ["page", "x0", "y0", "x1", "y1", "text"]
+
+
+def write_redaction_audit(audit_path: Path, source: Path, entries: list[dict]) -> None:
+    """
+    Record applied redactions as JSON, or CSV when `audit_path` ends in .csv.
+
+    The CSV carries the source file and timestamp on every row so each line
+    stands on its own.
+    """
+    timestamp = datetime.now(timezone.utc).isoformat()
+    if audit_path.suffix.lower() == ".csv":
+        with open(audit_path, "w", newline="", encoding="utf-8") as f:
+            writer = csv.DictWriter(f, fieldnames=["source", "timestamp"] + AUDIT_FIELDS)
+            writer.writeheader()
+            for entry in entries:
+                writer.writerow({"source": source.name, "timestamp": timestamp, **entry})
+    else:
+        audit = {
+            "source": source.name,
+            "timestamp": timestamp,
+            "redactions": entries,
+        }
+        audit_path.write_text(json.dumps(audit, indent=2), encoding="utf-8")
+
+
 def apply_redactions(
     input_path: Path,
     output_path: Path,
     images: bool = True,  # Whether to redact images
     graphics: bool = True,  # Whether to redact graphics/drawings
+    audit_output: Optional[Path] = None,
 ) -> dict:
     """
     Apply all pending redactions in the document.
 
+    With `audit_output`, also writes a record of every applied redaction
+    (page, rect and the text under it, captured before removal).
+
     WARNING: This PERMANENTLY removes the marked content. It cannot be undone.
     The content is completely removed from the PDF, not just covered.
     """
@@ -95,7 +144,9 @@ def apply_redactions(
         "message": "",
         "pages_affected": 0,
         "redactions_applied": 0,
+        "audit_output": None,
     }
+    audit_entries = []
 
     try:
         doc = fitz.open(input_path)
@@ -114,11 +165,20 @@ def apply_redactions(
                 pages_affected.add(page_num)
                 total_redactions += len(redact_annots)
 
+                if audit_output:
+                    for annot in redact_annots:
+                        rect = annot.rect
+                        audit_entries.append({
+                            "page": page_num,
+                            "x0": rect.x0,
+                            "y0": rect.y0,
+                            "x1": rect.x1,
+                            "y1": rect.y1,
+                            "text": page.get_text("text", clip=rect).strip(),
+                        })
+
                 # Apply redactions for this page
-                page.apply_redactions(
-                    images=fitz.PDF_REDACT_IMAGE_REMOVE if images else fitz.PDF_REDACT_IMAGE_NONE,
-                    graphics=fitz.PDF_REDACT_LINE_ART_IF_TOUCHED if graphics else fitz.PDF_REDACT_LINE_ART_NONE,
-                )
+                _apply_page_redactions(page, images, graphics)
 
         if total_redactions == 0:
             result["message"] = "No redaction marks found in document"
@@ -129,6 +189,10 @@ def apply_redactions(
         doc.save(output_path, garbage=4, deflate=True)
         doc.close()
 
+        if audit_output:
+            write_redaction_audit(audit_output, input_path, audit_entries)
+            result["audit_output"] = str(audit_output)
+
         result["success"] = True
         result["pages_affected"] = len(pages_affected)
         result["redactions_applied"] = total_redactions
@@ -140,6 +204,129 @@ def apply_redactions(
     return result
 
 
+def _find_pattern_rects(page, regex: re.Pattern) -> list[tuple[str, list[fitz.Rect]]]:
+    """
+    Find every match of `regex` on a page.
+
+    Text is matched per block with lines joined by a space, so a match may
+    wrap onto the next line. Each match yields one rect per line it touches.
+    """
+    hits = []
+    raw = page.get_text("rawdict")
+
+    for block in raw["blocks"]:
+        if block.get("type") != 0:
+            continue
+
+        # Parallel lists: block text and, per character, (line index, bbox)
+        text_parts = []
+        boxes = []
+        for line_idx, line in enumerate(block["lines"]):
+            if line_idx > 0:
+                text_parts.append(" ")
+                boxes.append(None)
+            for span in line["spans"]:
+                for ch in span["chars"]:
+                    text_parts.append(ch["c"])
+                    boxes.append((line_idx, fitz.Rect(ch["bbox"])))
+        text = "".join(text_parts)
+
+        for match in regex.finditer(text):
+            if match.start() == match.end():
+                continue
+            per_line: dict[int, fitz.Rect] = {}
+            for entry in boxes[match.start():match.end()]:
+                if entry is None:
+                    continue
+                line_idx, bbox = entry
+                if line_idx in per_line:
+                    per_line[line_idx] |= bbox
+                else:
+                    per_line[line_idx] = fitz.Rect(bbox)
+            if per_line:
+                hits.append((match.group(0), list(per_line.values())))
+
+    return hits
+
+
+def redact_pattern(
+    input_path: Path,
+    output_path: Path,
+    pattern: str,
+    is_regex: bool = False,
+    case_sensitive: bool = False,
+    whole_word: bool = False,
+    images: bool = True,
+    graphics: bool = True,
+) -> dict:
+    """
+    Redact every occurrence of a text pattern across all pages.
+
+    Matches are marked as redaction annotations and applied in one pass with
+    the same settings as apply_redactions(). Returns each redacted rect so the
+    result can be audited.
+
+    WARNING: This PERMANENTLY removes the matched content.
+    """
+    result = {
+        "success": False,
+        "message": "",
+        "match_count": 0,
+        "pages_affected": 0,
+        "redactions": [],
+    }
+
+    try:
+        source = pattern if is_regex else re.escape(pattern)
+        if whole_word:
+            source = rf"\b(?:{source})\b"
+        regex = re.compile(source, 0 if case_sensitive else re.IGNORECASE)
+    except re.error as e:
+        result["message"] = f"Invalid pattern: {e}"
+        return result
+
+    try:
+        doc = fitz.open(input_path)
+
+        for page_num, page in enumerate(doc):
+            hits = _find_pattern_rects(page, regex)
+            if not hits:
+                continue
+
+            for matched, rects in hits:
+                result["match_count"] += 1
+                for rect in rects:
+                    page.add_redact_annot(rect, fill=(0, 0, 0))
+                    result["redactions"].append({
+                        "page": page_num,
+                        "x0": rect.x0,
+                        "y0": rect.y0,
+                        "x1": rect.x1,
+                        "y1": rect.y1,
+                        "text": matched,
+                    })
+
+            result["pages_affected"] += 1
+            _apply_page_redactions(page, images, graphics)
+
+        if result["match_count"] == 0:
+            doc.save(output_path)
+            result["message"] = "No matches found"
+        else:
+            doc.save(output_path, garbage=4, deflate=True)
+            result["message"] = (
+                f"Redacted {result['match_count']} match(es) on {result['pages_affected']} page(s)"
+            )
+        doc.close()
+
+        result["success"] = True
+
+    except Exception as e:
+        result["message"] = f"Failed to redact pattern: {str(e)}"
+
+    return result
+
+
 def get_pending_redactions(input_path: Path) -> dict:
     """
     Get all pending redaction marks in the document.
@@ -178,6 +365,13 @@ def get_pending_redactions(input_path: Path) -> dict:
     return result
 
 
+def _area_contents(page, rect: fitz.Rect) -> tuple[str, int]:
+    """Text and number of images still inside `rect` on `page`."""
+    text = page.get_text("text", clip=rect).strip()
+    images = len(page.get_images(clip=rect))
+    return text, images
+
+
 def verify_redaction(
     input_path: Path,
     page_num: int,
@@ -207,23 +401,100 @@ def verify_redaction(
             return result
 
         page = doc[page_num]
-        rect = fitz.Rect(x0, y0, x1, y1)
+        text, images = _area_contents(page, fitz.Rect(x0, y0, x1, y1))
 
-        # Check for remaining text
-        text = page.get_text("text", clip=rect).strip()
         if text:
             result["area_clear"] = False
             result["text_found"] = text[:100]  # First 100 chars
 
-        # Check for remaining images
-        images = page.get_images(clip=rect)
         if images:
             result["area_clear"] = False
-            result["images_found"] = len(images)
+            result["images_found"] = images
+
+        doc.close()
+
+    except Exception as e:
+        result["error"] = str(e)
+
+    return result
+
+
+def verify_all_redactions(input_path: Path, terms: list[str]) -> dict:
+    """
+    Scan the whole document for sensitive terms that survived redaction.
+
+    Looks at page text, annotation contents and metadata for any of `terms`
+    (case-insensitive), and at redaction marks that were placed but never
+    applied while text or images still sit under them. `passed` is True only
+    when nothing was found.
+
+    Text inside images isn't read (that would need OCR): `unscanned_images`
+    counts the images whose contents went unchecked.
+    """
+    result = {
+        "passed": True,
+        "leaks": [],
+        "unscanned_images": 0,
+        "error": None,
+    }
+
+    terms = [t for t in terms if t.strip()]
+    if not terms:
+        result["error"] = "No terms to check"
+        return result
+
+    regex = re.compile("|".join(re.escape(t) for t in terms), re.IGNORECASE)
+
+    try:
+        doc = fitz.open(input_path)
+
+        for key, value in (doc.metadata or {}).items():
+            if value:
+                for match in regex.finditer(value):
+                    result["leaks"].append({"kind": "metadata", "page": None, "field": key, "text": match.group(0)})
+
+        images = set()
+        for page_num, page in enumerate(doc):
+            images.update(image[0] for image in page.get_images(full=True))
+            for matched, rects in _find_pattern_rects(page, regex):
+                for rect in rects:
+                    result["leaks"].append({
+                        "kind": "text",
+                        "page": page_num,
+                        "rect": [rect.x0, rect.y0, rect.x1, rect.y1],
+                        "text": matched,
+                    })
+
+            for annot in page.annots():
+                if not annot:
+                    continue
+                rect = annot.rect
+                if annot.type[0] == fitz.PDF_ANNOT_REDACT:
+                    text, images = _area_contents(page, rect)
+                    if text or images:
+                        result["leaks"].append({
+                            "kind": "unapplied_redaction",
+                            "page": page_num,
+                            "rect": [rect.x0, rect.y0, rect.x1, rect.y1],
+                            "text": text[:100],
+                            "images": images,
+                        })
+                    continue
+                content = annot.info.get("content", "")
+                for match in regex.finditer(content):
+                    result["leaks"].append({
+                        "kind": "annotation",
+                        "page": page_num,
+                        "rect": [rect.x0, rect.y0, rect.x1, rect.y1],
+                        "text": match.group(0),
+                    })
 
         doc.close()
+        result["unscanned_images"] = len(images)
+        result["passed"] = not result["leaks"]
 
     except Exception as e:
+        result["passed"] = False
         result["error"] = str(e)
 
     return result
@@ -251,8 +522,21 @@ def main():
     apply_parser.add_argument("--output", "-o", required=True, help="Output PDF path")
     apply_parser.add_argument("--no-images", action="store_true", help="Don't redact images")
     apply_parser.add_argument("--no-graphics", action="store_true", help="Don't redact graphics")
+    apply_parser.add_argument("--audit-output", help="Write an audit log of applied redactions (.json or .csv)")
     apply_parser.add_argument("--json", action="store_true", help="Output as JSON")
 
+    # Pattern command - find and redact every match of a text pattern
+    pattern_parser = subparsers.add_parser("pattern", help="Redact every match of a text pattern")
+    pattern_parser.add_argument("--input", "-i", required=True, help="Input PDF path")
+    pattern_parser.add_argument("--output", "-o", required=True, help="Output PDF path")
+    pattern_parser.add_argument("--pattern", required=True, help="Text or regular expression to redact")
+    pattern_parser.add_argument("--regex", action="store_true", help="Treat pattern as a regular expression")
+    pattern_parser.add_argument("--case-sensitive", action="store_true", help="Match case exactly")
+    pattern_parser.add_argument("--whole-word", action="store_true", help="Only match whole words")
+    pattern_parser.add_argument("--no-images", action="store_true", help="Don't redact images")
+    pattern_parser.add_argument("--no-graphics", action="store_true", help="Don't redact graphics")
+    pattern_parser.add_argument("--json", action="store_true", help="Output as JSON")
+
     # Pending command - list pending redactions
     pending_parser = subparsers.add_parser("pending", help="List pending redaction marks")
     pending_parser.add_argument("--input", "-i", required=True, help="Input PDF path")
@@ -268,6 +552,12 @@ def main():
     verify_parser.add_argument("--y1", type=float, required=True, help="Top Y coordinate")
     verify_parser.add_argument("--json", action="store_true", help="Output as JSON")
 
+    # Verify-all command - scan the whole document for sensitive terms
+    verify_all_parser = subparsers.add_parser("verify-all", help="Check the whole document for leaked terms")
+    verify_all_parser.add_argument("--input", "-i", required=True, help="Input PDF path")
+    verify_all_parser.add_argument("--term", action="append", default=[], help="Sensitive term (repeatable)")
+    verify_all_parser.add_argument("--json", action="store_true", help="Output as JSON")
+
     args = parser.parse_args()
 
     if args.command == "mark":
@@ -294,6 +584,25 @@ def main():
             Path(args.output),
             images=not args.no_images,
             graphics=not args.no_graphics,
+            audit_output=Path(args.audit_output) if args.audit_output else None,
+        )
+
+        if hasattr(args, 'json') and args.json:
+            print(json.dumps(result))
+        else:
+            print(result["message"])
+            sys.exit(0 if result["success"] else 1)
+
+    elif args.command == "pattern":
+        result = redact_pattern(
+            Path(args.input),
+            Path(args.output),
+            args.pattern,
+            is_regex=args.regex,
+            case_sensitive=args.case_sensitive,
+            whole_word=args.whole_word,
+            images=not args.no_images,
+            graphics=not args.no_graphics,
         )
 
         if hasattr(args, 'json') and args.json:
@@ -344,6 +653,24 @@ def main():
                     print(f"  Images found: {result['images_found']}")
                 sys.exit(1)
 
+    elif args.command == "verify-all":
+        result = verify_all_redactions(Path(args.input), args.term)
+
+        if hasattr(args, 'json') and args.json:
+            print(json.dumps(result))
+        else:
+            if result.get("error"):
+                print(f"Error: {result['error']}")
+                sys.exit(1)
+            if result["passed"]:
+                print("Verification PASSED: No sensitive terms found")
+            else:
+                print(f"Verification FAILED: {len(result['leaks'])} leak(s) found")
+                for leak in result["leaks"]:
+                    where = "metadata" if leak["page"] is None else f"page {leak['page'] + 1}"
+                    print(f"  [{leak['kind']}] {where}: {leak['text']}")
+                sys.exit(1)
+
 
 if __name__ == "__main__":
-    main()
+    run_main(main)
@@ -13,22 +13,105 @@ CLI usage (dev):
   python pdf_sanitize.py --input doc.pdf --output clean.pdf
   python pdf_sanitize.py --input doc.pdf --output clean.pdf --keep-metadata
   python pdf_sanitize.py info --input doc.pdf
+  python pdf_sanitize.py anonymize-ids --input doc.pdf --output anon.pdf
 """
 
 from __future__ import annotations
 
 import argparse
+import fnmatch
+import re
 import sys
 import json
 from pathlib import Path
 from typing import Optional
+from urllib.parse import urlparse
 
 import fitz  # PyMuPDF
 
+try:
+    from utils import run_main
+except ImportError:
+    from .utils import run_main
 
-def get_sanitization_info(input_path: Path) -> dict:
+
+# Longest JavaScript excerpt included in a sanitization preview
+JS_SNIPPET_LENGTH = 200
+
+
+def _javascript_actions(doc) -> list[dict]:
+    """Every JavaScript action object in the file, with a truncated source excerpt."""
+    actions = []
+    for xref in range(1, doc.xref_length()):
+        try:
+            if doc.xref_get_key(xref, "S")[1] != "/JavaScript":
+                continue
+            js_type, js_value = doc.xref_get_key(xref, "JS")
+            if js_type == "xref":
+                code = doc.xref_stream(int(js_value.split()[0])).decode("latin-1", errors="replace")
+            else:
+                code = js_value
+        except Exception:
+            continue
+        truncated = len(code) > JS_SNIPPET_LENGTH
+        actions.append({
+            "xref": xref,
+            "snippet": code[:JS_SNIPPET_LENGTH],
+            "truncated": truncated,
+        })
+    return actions
+
+
+def _sanitization_details(doc) -> dict:
+    """Concrete items `sanitize_pdf` would remove, for showing before cleaning."""
+    details = {
+        "metadata": {
+            key: value for key, value in (doc.metadata or {}).items()
+            if value and value.strip()
+        },
+        "javascript": _javascript_actions(doc),
+        "form_fields": [],
+        "embedded_files": [],
+        "links": [],
+    }
+
+    try:
+        details["embedded_files"] = doc.embfile_names()
+    except Exception:
+        pass
+
+    for page in doc:
+        # Widgets go with the JavaScript since they can carry field actions
+        for widget in page.widgets():
+            details["form_fields"].append({
+                "page": page.number + 1,
+                "name": widget.field_name or "",
+            })
+        for link in page.get_links():
+            kind = link.get("kind")
+            if kind == fitz.LINK_URI:
+                target, kind_name = link.get("uri", ""), "uri"
+            elif kind == fitz.LINK_LAUNCH:
+                target, kind_name = link.get("file", ""), "launch"
+            elif kind == fitz.LINK_GOTOR:
+                target, kind_name = link.get("file", ""), "remote"
+            else:
+                continue
+            details["links"].append({
+                "page": page.number + 1,
+                "kind": kind_name,
+                "target": target,
+            })
+
+    return details
+
+
+def get_sanitization_info(input_path: Path, details: bool = False) -> dict:
     """
     Analyze a PDF for items that would be removed during sanitization.
+
+    With `details`, also lists the items themselves (metadata values, script
+    excerpts, attachment names, link targets) under "details".
     """
     result = {
         "has_metadata": False,
@@ -108,6 +191,9 @@ def get_sanitization_info(input_path: Path) -> dict:
             result["has_links"] = True
             result["links_count"] = link_count
 
+        if details:
+            result["details"] = _sanitization_details(doc)
+
         doc.close()
 
     except Exception as e:
@@ -116,6 +202,37 @@ def get_sanitization_info(input_path: Path) -> dict:
     return result
 
 
+# Link filters besides a domain pattern
+LINK_FILTERS = ("all", "external", "internal")
+
+
+def _link_category(link: dict) -> str:
+    """'internal' for jumps within the document, 'external' for everything else."""
+    if link.get("kind") in (fitz.LINK_GOTO, fitz.LINK_NAMED):
+        return "internal"
+    return "external"
+
+
+def _host_matches(host: str, pattern: str) -> bool:
+    """`example.com` matches the domain and its subdomains; globs like `*.example.*` work too."""
+    host = host.lower()
+    pattern = pattern.lower()
+    return host == pattern or host.endswith("." + pattern) or fnmatch.fnmatch(host, pattern)
+
+
+def link_matches_filter(link: dict, link_filter: str) -> bool:
+    """Whether `link` should be removed under `link_filter` (see LINK_FILTERS, else a domain)."""
+    category = _link_category(link)
+    if link_filter == "all":
+        return True
+    if link_filter in ("external", "internal"):
+        return category == link_filter
+    if link.get("kind") != fitz.LINK_URI:
+        return False
+    host = urlparse(link.get("uri", "")).hostname
+    return bool(host) and _host_matches(host, link_filter)
+
+
 def sanitize_pdf(
     input_path: Path,
     output_path: Path,
@@ -124,10 +241,18 @@ def sanitize_pdf(
     remove_embedded_files: bool = True,
     remove_links: bool = False,  # Off by default, might break navigation
     remove_annotations: bool = False,  # Off by default, might remove important notes
+    link_filter: str | None = None,
 ) -> dict:
     """
     Sanitize a PDF by removing potentially sensitive content.
+
+    `link_filter` picks which links go: "all", "external" (web, launch and
+    other-file links), "internal" (page jumps and named destinations) or a
+    domain pattern matched against web link hosts. `remove_links` alone means "all".
     """
+    if link_filter is None and remove_links:
+        link_filter = "all"
+
     result = {
         "success": False,
         "message": "",
@@ -137,6 +262,10 @@ def sanitize_pdf(
             "embedded_files": 0,
             "links": 0,
             "annotations": 0,
+        },
+        "links": {
+            "internal": {"removed": 0, "kept": 0},
+            "external": {"removed": 0, "kept": 0},
         }
     }
 
@@ -179,12 +308,6 @@ def sanitize_pdf(
                     except:
                         pass
 
-                # Remove link annotations if requested
-                if remove_links:
-                    if annot_type == fitz.PDF_ANNOT_LINK:
-                        annots_to_remove.append(annot)
-                        result["removed"]["links"] += 1
-
                 # Remove all annotations if requested
                 if remove_annotations:
                     if annot not in annots_to_remove:
@@ -199,15 +322,19 @@ def sanitize_pdf(
                     pass
 
             # Remove links from page (separate from annotations)
-            if remove_links:
-                links = page.get_links()
-                for i, link in enumerate(links):
-                    if link.get("kind") in [fitz.LINK_URI, fitz.LINK_LAUNCH]:
-                        try:
-                            page.delete_link(link)
-                            result["removed"]["links"] += 1
-                        except:
-                            pass
+            for link in page.get_links():
+                if link.get("kind") == fitz.LINK_NONE:
+                    continue
+                counts = result["links"][_link_category(link)]
+                if link_filter and link_matches_filter(link, link_filter):
+                    try:
+                        page.delete_link(link)
+                        counts["removed"] += 1
+                        result["removed"]["links"] += 1
+                        continue
+                    except:
+                        pass
+                counts["kept"] += 1
 
         # Save with garbage collection to remove orphaned objects
         doc.save(output_path, garbage=4, deflate=True, clean=True)
@@ -239,6 +366,117 @@ def sanitize_pdf(
     return result
 
 
+# XMP Media Management properties that carry tracking identifiers
+XMP_ID_PROPERTIES = [
+    "xmpMM:DocumentID",
+    "xmpMM:InstanceID",
+    "xmpMM:OriginalDocumentID",
+    "xmpMM:History",
+    "xmpMM:DerivedFrom",
+]
+
+# Descriptive XMP properties that anonymizing must leave in place
+XMP_KEPT_PROPERTIES = [
+    "dc:title",
+    "dc:creator",
+    "dc:description",
+    "dc:rights",
+]
+
+
+def _strip_xmp_property(xmp: str, prop: str) -> tuple[str, bool]:
+    """
+    Remove an XMP property in both attribute form (prop="...") and
+    element form (<prop>...</prop> or <prop/>). Returns (xmp, removed).
+    """
+    escaped = re.escape(prop)
+    patterns = [
+        rf'\s{escaped}\s*=\s*"[^"]*"',
+        rf"\s{escaped}\s*=\s*'[^']*'",
+        rf"<{escaped}\b[^>]*/>",
+        rf"<{escaped}\b[^>]*>.*?</{escaped}>",
+    ]
+    removed = False
+    for pattern in patterns:
+        xmp, count = re.subn(pattern, "", xmp, flags=re.DOTALL)
+        if count:
+            removed = True
+    return xmp, removed
+
+
+def _has_xmp_property(xmp: str, prop: str) -> bool:
+    """Whether an XMP packet still sets prop, as an attribute or an element."""
+    escaped = re.escape(prop)
+    return re.search(rf"(\s{escaped}\s*=|<{escaped}\b)", xmp) is not None
+
+
+def _verify_anonymized(output_path: Path, kept: list[str]) -> list[str]:
+    """
+    Reopen a saved file and list what anonymize_ids got wrong: identifiers
+    still present, or descriptive properties in `kept` that were lost.
+    """
+    doc = fitz.open(output_path)
+    try:
+        xmp = doc.get_xml_metadata() or ""
+        problems = [f"{prop} still present" for prop in XMP_ID_PROPERTIES if _has_xmp_property(xmp, prop)]
+        if doc.xref_get_key(-1, "ID")[0] != "null":
+            problems.append("trailer /ID still present")
+        problems += [f"{prop} was lost" for prop in kept if not _has_xmp_property(xmp, prop)]
+        return problems
+    finally:
+        doc.close()
+
+
+def anonymize_ids(input_path: Path, output_path: Path) -> dict:
+    """
+    Strip document/instance identifiers from the XMP packet and the trailer
+    /ID, keeping descriptive metadata (title, author, rights, ...) intact.
+    """
+    result = {
+        "success": False,
+        "message": "",
+        "removed": [],
+    }
+
+    try:
+        doc = fitz.open(input_path)
+
+        xmp = doc.get_xml_metadata()
+        kept = [prop for prop in XMP_KEPT_PROPERTIES if xmp and _has_xmp_property(xmp, prop)]
+        if xmp:
+            for prop in XMP_ID_PROPERTIES:
+                xmp, removed = _strip_xmp_property(xmp, prop)
+                if removed:
+                    result["removed"].append(prop)
+            if result["removed"]:
+                doc.set_xml_metadata(xmp)
+
+        # Trailer /ID (file identifier pair)
+        id_type, _ = doc.xref_get_key(-1, "ID")
+        if id_type != "null":
+            doc.xref_set_key(-1, "ID", "null")
+            result["removed"].append("trailer:ID")
+
+        doc.save(output_path, garbage=1)
+        doc.close()
+
+        problems = _verify_anonymized(output_path, kept)
+        if problems:
+            result["message"] = f"Anonymization failed: {'; '.join(problems)}"
+            return result
+
+        result["success"] = True
+        if result["removed"]:
+            result["message"] = f"Removed: {', '.join(result['removed'])}"
+        else:
+            result["message"] = "No identifiers found"
+
+    except Exception as e:
+        result["message"] = f"Anonymization failed: {str(e)}"
+
+    return result
+
+
 def main():
     parser = argparse.ArgumentParser(description="PDF Sanitization")
     subparsers = parser.add_subparsers(dest="command")
@@ -246,6 +484,8 @@ def main():
     # Info command
     info_parser = subparsers.add_parser("info", help="Analyze PDF for sanitizable content")
     info_parser.add_argument("--input", "-i", required=True, help="Input PDF path")
+    info_parser.add_argument("--details", action="store_true",
+                             help="List the items that would be removed, not just counts")
     info_parser.add_argument("--json", action="store_true", help="Output as JSON")
 
     # Sanitize command (default)
@@ -257,8 +497,16 @@ def main():
     sanitize_parser.add_argument("--keep-embedded", action="store_true", help="Don't remove embedded files")
     sanitize_parser.add_argument("--remove-links", action="store_true", help="Also remove links")
     sanitize_parser.add_argument("--remove-annotations", action="store_true", help="Also remove annotations")
+    sanitize_parser.add_argument("--link-filter",
+                                 help="Links to remove: all, external, internal or a domain pattern")
     sanitize_parser.add_argument("--json", action="store_true", help="Output as JSON")
 
+    # Anonymize identifiers command
+    anon_parser = subparsers.add_parser("anonymize-ids", help="Strip XMP/trailer document identifiers")
+    anon_parser.add_argument("--input", "-i", required=True, help="Input PDF path")
+    anon_parser.add_argument("--output", "-o", required=True, help="Output PDF path")
+    anon_parser.add_argument("--json", action="store_true", help="Output as JSON")
+
     # Also support direct usage without subcommand
     parser.add_argument("--input", "-i", help="Input PDF path")
     parser.add_argument("--output", "-o", help="Output PDF path")
@@ -267,13 +515,14 @@ def main():
     parser.add_argument("--keep-embedded", action="store_true")
     parser.add_argument("--remove-links", action="store_true")
     parser.add_argument("--remove-annotations", action="store_true")
+    parser.add_argument("--link-filter")
     parser.add_argument("--json", action="store_true")
 
     args = parser.parse_args()
 
     # Handle subcommands
     if args.command == "info":
-        result = get_sanitization_info(Path(args.input))
+        result = get_sanitization_info(Path(args.input), details=args.details)
 
         if args.json:
             print(json.dumps(result))
@@ -288,6 +537,25 @@ def main():
             print(f"  Embedded Files: {'Yes - ' + str(result['embedded_files_count']) + ' file(s)' if result['has_embedded_files'] else 'None'}")
             print(f"  External Links: {'Yes - ' + str(result['links_count']) + ' link(s)' if result['has_links'] else 'None'}")
 
+            if "details" in result:
+                for key, value in result["details"]["metadata"].items():
+                    print(f"  [metadata] {key}: {value}")
+                for action in result["details"]["javascript"]:
+                    print(f"  [javascript] xref {action['xref']}: {action['snippet']}")
+                for name in result["details"]["embedded_files"]:
+                    print(f"  [embedded] {name}")
+                for link in result["details"]["links"]:
+                    print(f"  [link] page {link['page']}: {link['target']}")
+
+    elif args.command == "anonymize-ids":
+        result = anonymize_ids(Path(args.input), Path(args.output))
+
+        if args.json:
+            print(json.dumps(result))
+        else:
+            print(result["message"])
+            sys.exit(0 if result["success"] else 1)
+
     elif args.command == "clean" or (args.input and args.output):
         input_path = args.input
         output_path = args.output
@@ -304,6 +572,7 @@ def main():
             remove_embedded_files=not getattr(args, 'keep_embedded', False),
             remove_links=getattr(args, 'remove_links', False),
             remove_annotations=getattr(args, 'remove_annotations', False),
+            link_filter=getattr(args, 'link_filter', None),
         )
 
         if getattr(args, 'json', False):
@@ -317,4 +586,4 @@ def main():
 
 
 if __name__ == "__main__":
-    main()
+    run_main(main)
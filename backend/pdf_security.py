@@ -5,17 +5,52 @@ CLI usage (dev):
   python pdf_security.py unlock --input locked.pdf --output unlocked.pdf
   python pdf_security.py unlock --input locked.pdf --output unlocked.pdf --password secret
   python pdf_security.py encrypt --input in.pdf --output encrypted.pdf --user-password view --owner-password admin
+  python pdf_security.py encrypt --input in.pdf --output encrypted.pdf --owner-password admin --encryption-level aes_128 --no-assemble
 """
 
 from __future__ import annotations
 
 import argparse
+import os
 import sys
 import json
 from pathlib import Path
 
 import pikepdf
 
+try:
+    from utils import run_main
+except ImportError:
+    from .utils import run_main
+
+# Encryption levels: (security handler revision, AES, display name).
+# R3 is RC4 only; R4 supports RC4 or AES-128; R6 is AES-256.
+ENCRYPTION_LEVELS = {
+    "rc4_128": (3, False, "RC4-128"),
+    "aes_128": (4, True, "AES-128"),
+    "aes_256": (6, True, "AES-256"),
+}
+DEFAULT_ENCRYPTION_LEVEL = "aes_256"
+
+# Environment variable carrying the JSON password list for unlock-batch.
+# Passwords are never taken from the command line, where other users can see them.
+UNLOCK_PASSWORDS_ENV = "TLACUILO_UNLOCK_PASSWORDS"
+
+# Environment variables carrying the passwords for set-permissions
+OWNER_PASSWORD_ENV = "TLACUILO_OWNER_PASSWORD"
+USER_PASSWORD_ENV = "TLACUILO_USER_PASSWORD"
+
+# set-permissions option name -> pikepdf.Permissions fields it controls
+PERMISSION_FIELDS = {
+    "printing": ("print_lowres", "print_highres"),
+    "copying": ("extract",),
+    "modifying": ("modify_other",),
+    "annotating": ("modify_annotation",),
+    "filling_forms": ("modify_form",),
+    "accessibility": ("accessibility",),
+    "assembly": ("modify_assembly",),
+}
+
 
 def unlock_pdf(
     input_path: Path,
@@ -75,6 +110,93 @@ def unlock_pdf(
     return result
 
 
+def _batch_output_paths(inputs: list[Path], output_dir: Path, suffix: str) -> list[Path]:
+    """
+    Output path in output_dir for each input, named after it.
+
+    Inputs already in output_dir get `suffix` on their stem and same-named
+    inputs get "_2", "_3", ... so no output overwrites another or any input.
+    """
+    out_dir = output_dir.resolve()
+    sources = {p.resolve() for p in inputs}
+    taken: set[Path] = set()
+    outputs = []
+    for input_path in inputs:
+        stem = input_path.stem
+        if input_path.resolve().parent == out_dir:
+            stem += suffix
+        counter = 1
+        while True:
+            name = f"{stem}{input_path.suffix}" if counter == 1 else f"{stem}_{counter}{input_path.suffix}"
+            candidate = out_dir / name
+            if candidate not in sources and candidate not in taken:
+                break
+            counter += 1
+        taken.add(candidate)
+        outputs.append(output_dir / name)
+    return outputs
+
+
+def unlock_batch(inputs: list[Path], output_dir: Path, passwords: list[str]) -> dict:
+    """
+    Unlock many PDFs, trying each known password per file.
+
+    Files that open without a password (restrictions only) are unlocked directly.
+    Each result reports the index of the password that worked, never the
+    password itself; files no password opens are marked `needs_password`.
+    Outputs keep the input names, deduplicated by `_batch_output_paths`.
+    """
+    result = {
+        "results": [],
+        "unlocked": 0,
+        "needs_password": 0
+    }
+
+    output_dir.mkdir(parents=True, exist_ok=True)
+
+    for input_path, output_path in zip(inputs, _batch_output_paths(inputs, output_dir, "_unlocked")):
+        entry = {
+            "input": str(input_path),
+            "output": None,
+            "success": False,
+            "needs_password": False,
+            "password_index": None,
+            "message": ""
+        }
+
+        try:
+            pdf = None
+            try:
+                pdf = pikepdf.open(input_path)
+            except pikepdf.PasswordError:
+                for index, password in enumerate(passwords):
+                    try:
+                        pdf = pikepdf.open(input_path, password=password)
+                        entry["password_index"] = index
+                        break
+                    except pikepdf.PasswordError:
+                        continue
+
+            if pdf is None:
+                entry["needs_password"] = True
+                entry["message"] = "None of the passwords opened this PDF"
+                result["needs_password"] += 1
+            else:
+                pdf.save(output_path)
+                pdf.close()
+                entry["success"] = True
+                entry["output"] = str(output_path)
+                entry["message"] = "PDF unlocked successfully"
+                result["unlocked"] += 1
+
+        except Exception as e:
+            entry["message"] = f"Failed to unlock PDF: {str(e)}"
+
+        result["results"].append(entry)
+
+    return result
+
+
 def encrypt_pdf(
     input_path: Path,
     output_path: Path,
@@ -83,19 +205,38 @@ def encrypt_pdf(
     allow_printing: bool = True,
     allow_copying: bool = True,
     allow_modifying: bool = False,
-    allow_annotating: bool = True
+    allow_annotating: bool = True,
+    allow_filling_forms: bool | None = None,
+    allow_accessibility: bool = True,
+    allow_assembly: bool | None = None,
+    encryption_level: str = DEFAULT_ENCRYPTION_LEVEL,
 ) -> dict:
     """
     Encrypt a PDF with optional user/owner passwords and permissions.
 
     - user_password: Required to open the PDF (can be empty for no open password)
     - owner_password: Required to change permissions/remove encryption
+    - encryption_level: "rc4_128", "aes_128" or "aes_256" (RC4 is broken; only
+      for readers that can't open AES)
+
+    Form filling follows `allow_annotating` and assembly follows `allow_modifying`
+    unless set explicitly.
     """
     result = {
         "success": False,
         "message": ""
     }
 
+    if encryption_level not in ENCRYPTION_LEVELS:
+        result["message"] = f"Unsupported encryption level: {encryption_level}"
+        return result
+    revision, use_aes, cipher = ENCRYPTION_LEVELS[encryption_level]
+
+    if allow_filling_forms is None:
+        allow_filling_forms = allow_annotating
+    if allow_assembly is None:
+        allow_assembly = allow_modifying
+
     try:
         pdf = pikepdf.open(input_path)
 
@@ -106,9 +247,9 @@ def encrypt_pdf(
             extract=allow_copying,
             modify_other=allow_modifying,
             modify_annotation=allow_annotating,
-            modify_form=allow_annotating,
-            modify_assembly=allow_modifying,
-            accessibility=True  # Always allow accessibility
+            modify_form=allow_filling_forms,
+            modify_assembly=allow_assembly,
+            accessibility=allow_accessibility
         )
 
         # At least one password must be set
@@ -120,13 +261,17 @@ def encrypt_pdf(
             encryption=pikepdf.Encryption(
                 user=user_password or "",
                 owner=owner_password or user_password or "",
-                allow=permissions
+                allow=permissions,
+                R=revision,
+                aes=use_aes
             )
         )
         pdf.close()
 
         result["success"] = True
-        result["message"] = "PDF encrypted successfully"
+        result["message"] = f"PDF encrypted successfully ({cipher})"
+        result["encryption_level"] = encryption_level
+        result["cipher"] = cipher
 
     except Exception as e:
         result["message"] = f"Failed to encrypt PDF: {str(e)}"
@@ -134,6 +279,103 @@ def encrypt_pdf(
     return result
 
 
+def permission_map(allow) -> dict:
+    """Flatten pikepdf.Permissions into the option names used by set-permissions."""
+    return {
+        name: all(getattr(allow, field) for field in fields)
+        for name, fields in PERMISSION_FIELDS.items()
+    }
+
+
+def _opens_without_password(input_path: Path) -> bool:
+    try:
+        pikepdf.open(input_path).close()
+        return True
+    except pikepdf.PasswordError:
+        return False
+
+
+def set_permissions(
+    input_path: Path,
+    output_path: Path,
+    owner_password: str,
+    changes: dict,
+    user_password: str | None = None,
+) -> dict:
+    """
+    Rewrite the permission bits of an encrypted PDF, keeping its passwords and cipher.
+
+    `changes` maps PERMISSION_FIELDS names to the new value; anything not listed
+    keeps its current setting. The user password is recovered from the owner
+    password where the encryption revision allows it (R2-R4); AES-256 files
+    need it passed in `user_password`.
+    """
+    result = {
+        "success": False,
+        "message": "",
+        "permissions": {}
+    }
+
+    try:
+        try:
+            pdf = pikepdf.open(input_path, password=owner_password)
+        except pikepdf.PasswordError:
+            result["message"] = "Incorrect owner password"
+            return result
+
+        if not pdf.is_encrypted:
+            pdf.close()
+            result["message"] = "PDF is not encrypted; use encrypt to set permissions"
+            return result
+
+        if not pdf.owner_password_matched:
+            pdf.close()
+            result["message"] = "Incorrect owner password"
+            return result
+
+        info = pdf.encryption
+        if user_password is None:
+            user_password = info.user_password.decode("latin-1") if info.user_password else ""
+            if not user_password and info.R >= 5 and not _opens_without_password(input_path):
+                pdf.close()
+                result["message"] = "The user password is needed to keep AES-256 encryption"
+                return result
+
+        current = pdf.allow
+        allow = {
+            field: getattr(current, field)
+            for fields in PERMISSION_FIELDS.values()
+            for field in fields
+        }
+        for name, value in changes.items():
+            for field in PERMISSION_FIELDS[name]:
+                allow[field] = value
+
+        pdf.save(
+            output_path,
+            encryption=pikepdf.Encryption(
+                user=user_password,
+                owner=owner_password,
+                allow=pikepdf.Permissions(**allow),
+                R=info.R,
+                aes=info.stream_method in (pikepdf.EncryptionMethod.aes, pikepdf.EncryptionMethod.aesv3)
+            )
+        )
+        pdf.close()
+
+        # Report what a reader opening the file with the user password gets
+        with pikepdf.open(output_path, password=user_password) as saved:
+            result["permissions"] = permission_map(saved.allow)
+
+        result["success"] = True
+        result["message"] = "Permissions updated successfully"
+
+    except Exception as e:
+        result["message"] = f"Failed to set permissions: {str(e)}"
+
+    return result
+
+
 def check_pdf_security(input_path: Path) -> dict:
     """
     Check the security status of a PDF.
@@ -188,6 +430,12 @@ def main():
     unlock_parser.add_argument("--password", "-p", help="Password if required")
     unlock_parser.add_argument("--json", action="store_true", help="Output as JSON")
 
+    # Batch unlock command (passwords come from the environment, see UNLOCK_PASSWORDS_ENV)
+    batch_parser = subparsers.add_parser("unlock-batch", help="Unlock many PDFs with a list of known passwords")
+    batch_parser.add_argument("--inputs", nargs="+", required=True, help="Input PDF paths")
+    batch_parser.add_argument("--output-dir", "-o", required=True, help="Output directory")
+    batch_parser.add_argument("--json", action="store_true", help="Output as JSON")
+
     # Encrypt command
     encrypt_parser = subparsers.add_parser("encrypt", help="Encrypt PDF")
     encrypt_parser.add_argument("--input", "-i", required=True, help="Input PDF path")
@@ -197,8 +445,25 @@ def main():
     encrypt_parser.add_argument("--no-print", action="store_true", help="Disable printing")
     encrypt_parser.add_argument("--no-copy", action="store_true", help="Disable copying")
     encrypt_parser.add_argument("--no-modify", action="store_true", help="Disable modifying")
+    encrypt_parser.add_argument("--no-annotate", action="store_true", help="Disable adding/editing annotations")
+    encrypt_parser.add_argument("--no-fill-forms", action="store_true", help="Disable filling form fields")
+    encrypt_parser.add_argument("--no-accessibility", action="store_true",
+                                help="Disable text extraction for accessibility tools")
+    encrypt_parser.add_argument("--no-assemble", action="store_true",
+                                help="Disable inserting, rotating and deleting pages")
+    encrypt_parser.add_argument("--encryption-level", choices=list(ENCRYPTION_LEVELS),
+                                default=DEFAULT_ENCRYPTION_LEVEL, help="Cipher to use")
     encrypt_parser.add_argument("--json", action="store_true", help="Output as JSON")
 
+    # Set permissions command (passwords come from OWNER_PASSWORD_ENV / USER_PASSWORD_ENV)
+    perms_parser = subparsers.add_parser("set-permissions", help="Change permissions, keeping passwords")
+    perms_parser.add_argument("--input", "-i", required=True, help="Input PDF path")
+    perms_parser.add_argument("--output", "-o", required=True, help="Output PDF path")
+    for name in PERMISSION_FIELDS:
+        perms_parser.add_argument(f"--{name.replace('_', '-')}", choices=["allow", "deny"],
+                                  help=f"Allow or deny {name.replace('_', ' ')} (unchanged if omitted)")
+    perms_parser.add_argument("--json", action="store_true", help="Output as JSON")
+
     # Check command
     check_parser = subparsers.add_parser("check", help="Check PDF security status")
     check_parser.add_argument("--input", "-i", required=True, help="Input PDF path")
@@ -218,6 +483,17 @@ def main():
             print(result["message"])
             sys.exit(0 if result["success"] else 1)
 
+    elif args.command == "unlock-batch":
+        passwords = json.loads(os.environ.get(UNLOCK_PASSWORDS_ENV) or "[]")
+        result = unlock_batch([Path(p) for p in args.inputs], Path(args.output_dir), passwords)
+        if hasattr(args, 'json') and args.json:
+            print(json.dumps(result))
+        else:
+            for entry in result["results"]:
+                status = "unlocked" if entry["success"] else ("needs password" if entry["needs_password"] else "failed")
+                print(f"{entry['input']}: {status}")
+            sys.exit(0 if result["needs_password"] == 0 else 1)
+
     elif args.command == "encrypt":
         result = encrypt_pdf(
             Path(args.input),
@@ -226,7 +502,31 @@ def main():
             args.owner_password,
             allow_printing=not args.no_print,
             allow_copying=not args.no_copy,
-            allow_modifying=not args.no_modify
+            allow_modifying=not args.no_modify,
+            allow_annotating=not args.no_annotate,
+            allow_filling_forms=False if args.no_fill_forms else None,
+            allow_accessibility=not args.no_accessibility,
+            allow_assembly=False if args.no_assemble else None,
+            encryption_level=args.encryption_level
+        )
+        if hasattr(args, 'json') and args.json:
+            print(json.dumps(result))
+        else:
+            print(result["message"])
+            sys.exit(0 if result["success"] else 1)
+
+    elif args.command == "set-permissions":
+        changes = {
+            name: getattr(args, name) == "allow"
+            for name in PERMISSION_FIELDS
+            if getattr(args, name) is not None
+        }
+        result = set_permissions(
+            Path(args.input),
+            Path(args.output),
+            os.environ.get(OWNER_PASSWORD_ENV, ""),
+            changes,
+            os.environ.get(USER_PASSWORD_ENV)
         )
         if hasattr(args, 'json') and args.json:
             print(json.dumps(result))
@@ -248,4 +548,4 @@ def main():
 
 
 if __name__ == "__main__":
-    main()
+    run_main(main)
@@ -0,0 +1,231 @@
+"""
+PDF Digital Signatures: cryptographic PAdES signing and validation via pyHanko.
+
+Unlike pdf_signatures.py (visual overlays only), these signatures cover the
+document's byte range with a CMS/PKCS#7 signature made from a PKCS#12 (.pfx/.p12)
+certificate, so any later modification is detectable.
+
+CLI usage (dev):
+  python pdf_sign.py sign --input doc.pdf --output signed.pdf --pfx cert.pfx --pfx-password secret
+  python pdf_sign.py sign --input doc.pdf --output signed.pdf --pfx cert.pfx --reason "Approved" --location "CDMX" --field Signature1
+  python pdf_sign.py validate --input signed.pdf --json
+"""
+
+from __future__ import annotations
+
+import argparse
+import os
+import sys
+import json
+import tempfile
+from pathlib import Path
+from typing import Optional
+
+try:
+    from pyhanko.pdf_utils.incremental_writer import IncrementalPdfFileWriter
+    from pyhanko.pdf_utils.reader import PdfFileReader
+    from pyhanko.sign import fields, signers
+    from pyhanko.sign.validation import validate_pdf_signature
+    from pyhanko_certvalidator import ValidationContext
+    HAS_PYHANKO = True
+except ImportError:
+    HAS_PYHANKO = False
+
+try:
+    from utils import run_main
+except ImportError:
+    from .utils import run_main
+
+PYHANKO_MISSING = "pyHanko is not installed (pip install pyhanko pyhanko-certvalidator)"
+
+
+def _format_time(value) -> Optional[str]:
+    return value.isoformat() if value is not None else None
+
+
+def sign_pdf(
+    input_path: Path,
+    output_path: Path,
+    pfx_path: Path,
+    pfx_password: Optional[str] = None,
+    reason: Optional[str] = None,
+    location: Optional[str] = None,
+    field_name: str = "Signature1",
+) -> dict:
+    """
+    Apply a PAdES signature as an incremental update.
+
+    An existing empty signature field named `field_name` is filled; otherwise an
+    invisible field with that name is created. Earlier signatures stay valid
+    because the original bytes are left untouched.
+
+    Returns a dict with the signer certificate subject and signing time.
+    """
+    result = {
+        "success": False,
+        "message": "",
+        "signature_type": "digital",
+    }
+
+    if not HAS_PYHANKO:
+        result["message"] = PYHANKO_MISSING
+        return result
+
+    if not pfx_path.exists():
+        result["message"] = f"Certificate file not found: {pfx_path}"
+        return result
+
+    try:
+        passphrase = pfx_password.encode("utf-8") if pfx_password else None
+        signer = signers.SimpleSigner.load_pkcs12(pfx_file=str(pfx_path), passphrase=passphrase)
+        if signer is None:
+            result["message"] = "Could not load certificate (wrong password or unsupported file)"
+            return result
+
+        meta = signers.PdfSignatureMetadata(
+            field_name=field_name,
+            reason=reason,
+            location=location,
+            subfilter=fields.SigSeedSubFilter.PADES,
+        )
+
+        # Sign into a temp file beside the output: the writer keeps reading the
+        # input, which may be the output file itself
+        output_path = Path(output_path)
+        fd, tmp_name = tempfile.mkstemp(prefix=f".{output_path.name}.", suffix=".tmp", dir=output_path.parent)
+        try:
+            with open(input_path, "rb") as inf, os.fdopen(fd, "wb") as outf:
+                writer = IncrementalPdfFileWriter(inf)
+                signers.sign_pdf(writer, meta, signer=signer, output=outf)
+            os.replace(tmp_name, output_path)
+        except BaseException:
+            Path(tmp_name).unlink(missing_ok=True)
+            raise
+
+        # Read the signing time back from the signature we just embedded
+        signing_time = None
+        with open(output_path, "rb") as f:
+            reader = PdfFileReader(f)
+            for sig in reader.embedded_signatures:
+                if sig.field_name == field_name:
+                    signing_time = _format_time(sig.self_reported_timestamp)
+
+        result["success"] = True
+        result["message"] = f"Document signed in field '{field_name}'"
+        result["field_name"] = field_name
+        result["signer_subject"] = signer.signing_cert.subject.human_friendly
+        result["signing_time"] = signing_time
+
+    except Exception as e:
+        result["message"] = f"Failed to sign PDF: {str(e)}"
+
+    return result
+
+
+def validate_signatures(input_path: Path) -> dict:
+    """
+    Validate every embedded signature.
+
+    `valid` means the signature matches the signed bytes and the CMS checks
+    out; `tampered` means the signed bytes changed or later revisions made
+    modifications the signature doesn't permit. Certificates are not checked
+    against any trust store, so `trusted` is reported separately.
+    """
+    result = {
+        "available": HAS_PYHANKO,
+        "signatures": [],
+    }
+
+    if not HAS_PYHANKO:
+        result["error"] = PYHANKO_MISSING
+        return result
+
+    try:
+        with open(input_path, "rb") as f:
+            reader = PdfFileReader(f, strict=False)
+            for sig in reader.embedded_signatures:
+                entry = {
+                    "name": sig.field_name,
+                    "signed": True,
+                    "valid": False,
+                    "tampered": False,
+                    "trusted": False,
+                    "signer_subject": None,
+                    "signing_time": None,
+                }
+                try:
+                    status = validate_pdf_signature(sig, ValidationContext(allow_fetching=False))
+                    entry["valid"] = bool(status.intact and status.valid)
+                    entry["tampered"] = not status.intact or status.docmdp_ok is False
+                    entry["trusted"] = bool(status.trusted)
+                    entry["signer_subject"] = status.signing_cert.subject.human_friendly
+                    entry["signing_time"] = _format_time(status.signer_reported_dt)
+                except Exception as e:
+                    entry["error"] = str(e)
+                result["signatures"].append(entry)
+
+    except Exception as e:
+        result["error"] = str(e)
+
+    return result
+
+
+def main():
+    parser = argparse.ArgumentParser(description="PDF Digital Signatures")
+    subparsers = parser.add_subparsers(dest="command", required=True)
+
+    # Sign command
+    sign_parser = subparsers.add_parser("sign", help="Apply a PAdES digital signature")
+    sign_parser.add_argument("--input", "-i", required=True, help="Input PDF path")
+    sign_parser.add_argument("--output", "-o", required=True, help="Output PDF path")
+    sign_parser.add_argument("--pfx", required=True, help="PKCS#12 certificate (.pfx/.p12)")
+    sign_parser.add_argument("--pfx-password", help="Certificate password")
+    sign_parser.add_argument("--reason", help="Reason for signing")
+    sign_parser.add_argument("--location", help="Signing location")
+    sign_parser.add_argument("--field", default="Signature1", help="Signature field name")
+    sign_parser.add_argument("--json", action="store_true", help="Output as JSON")
+
+    # Validate command
+    validate_parser = subparsers.add_parser("validate", help="Validate embedded signatures")
+    validate_parser.add_argument("--input", "-i", required=True, help="Input PDF path")
+    validate_parser.add_argument("--json", action="store_true", help="Output as JSON")
+
+    args = parser.parse_args()
+
+    if args.command == "sign":
+        result = sign_pdf(
+            input_path=Path(args.input),
+            output_path=Path(args.output),
+            pfx_path=Path(args.pfx),
+            pfx_password=args.pfx_password,
+            reason=args.reason,
+            location=args.location,
+            field_name=args.field,
+        )
+
+        if args.json:
+            print(json.dumps(result))
+        else:
+            print(result["message"])
+            if result["success"]:
+                print(f"Signer: {result['signer_subject']}")
+            sys.exit(0 if result["success"] else 1)
+
+    elif args.command == "validate":
+        result = validate_signatures(Path(args.input))
+
+        if args.json:
+            print(json.dumps(result))
+        else:
+            if result.get("error"):
+                print(f"Error: {result['error']}")
+                sys.exit(1)
+            if not result["signatures"]:
+                print("No digital signatures found")
+            for sig in result["signatures"]:
+                state = "TAMPERED" if sig["tampered"] else ("valid" if sig["valid"] else "invalid")
+                print(f"{sig['name']}: {state} ({sig['signer_subject']})")
+
+
+if __name__ == "__main__":
+    run_main(main)
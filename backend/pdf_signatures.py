@@ -2,7 +2,8 @@
 PDF Graphical Signatures: Apply visual signature overlays to PDF documents.
 
 These are NON-CRYPTOGRAPHIC signatures - purely visual overlays.
-For legal digital signatures (PAdES), see pdf_digital_sign.py (future).
+For legal digital signatures (PAdES), see pdf_sign.py. The `check` command
+uses it to report whether existing signatures are cryptographically valid.
 
 CLI usage (dev):
   python pdf_signatures.py apply --input doc.pdf --output signed.pdf --image sig.png --page 0 --x 100 --y 100 --width 200
@@ -23,6 +24,16 @@ from typing import Optional
 
 import fitz  # PyMuPDF
 
+try:
+    from pdf_sign import validate_signatures
+except ImportError:
+    from .pdf_sign import validate_signatures
+
+try:
+    from utils import run_main
+except ImportError:
+    from .utils import run_main
+
 
 def apply_graphical_signature(
     input_path: Path,
@@ -166,11 +177,14 @@ def check_existing_signatures(input_path: Path) -> dict:
     Check if a PDF has existing digital signatures.
 
     Returns info about existing signatures to warn user before modifying.
+    Signed fields also carry `valid`, `tampered`, `trusted`, `signer_subject`
+    and `signing_time` when pyHanko is available.
     """
     result = {
         "has_digital_signatures": False,
         "signature_count": 0,
         "signature_fields": [],
+        "validation_available": False,
         "warning": None
     }
 
@@ -191,7 +205,21 @@ def check_existing_signatures(input_path: Path) -> dict:
 
         doc.close()
 
-        if result["has_digital_signatures"]:
+        # Attach cryptographic status to the matching fields
+        validation = validate_signatures(input_path)
+        result["validation_available"] = validation["available"] and "error" not in validation
+        by_name = {sig["name"]: sig for sig in validation["signatures"]}
+        for field in result["signature_fields"]:
+            status = by_name.get(field["name"])
+            if status:
+                field.update({k: v for k, v in status.items() if k != "name"})
+
+        if any(field.get("tampered") for field in result["signature_fields"]):
+            result["warning"] = (
+                "This PDF contains a signature that no longer matches the document. "
+                "It was modified after signing."
+            )
+        elif result["has_digital_signatures"]:
             result["warning"] = (
                 "This PDF contains digital signatures. "
                 "Adding graphical elements may invalidate them."
@@ -277,4 +305,4 @@ def main():
 
 
 if __name__ == "__main__":
-    main()
+    run_main(main)
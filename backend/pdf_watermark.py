@@ -6,8 +6,16 @@ Supports text and image watermarks with configurable position, opacity, and rota
 
 import sys
 import json
+from datetime import date
+from pathlib import Path
+
 import fitz  # PyMuPDF
 
+try:
+    from utils import run_main
+except ImportError:
+    from .utils import run_main
+
 
 def add_text_watermark(
     input_path: str,
@@ -19,7 +27,9 @@ def add_text_watermark(
     rotation: float = -45,
     position: str = "center",  # center, top-left, top-right, bottom-left, bottom-right, tile
     pages: str = "all",  # "all" or "1,3,5" or "1-5"
-    layer: str = "under"  # under or over
+    layer: str = "under",  # under or over
+    tile: bool = False,
+    tile_spacing: float = None,
 ) -> dict:
     """
     Add text watermark to PDF pages.
@@ -35,6 +45,8 @@ def add_text_watermark(
         position: Placement strategy
         pages: Page selection
         layer: "under" content or "over" content
+        tile: Repeat the text in a grid covering the whole page
+        tile_spacing: Gap between tiles in points (default: derived from text size)
 
     Returns:
         dict with success status and message
@@ -42,40 +54,33 @@ def add_text_watermark(
     try:
         doc = fitz.open(input_path)
         page_indices = _parse_pages(pages, len(doc))
+        text_width = fitz.get_text_length(text, fontname="helv", fontsize=font_size)
 
         for page_idx in page_indices:
             page = doc[page_idx]
             rect = page.rect
 
             # Calculate positions based on strategy
-            positions = _get_text_positions(rect, text, font_size, position, rotation)
+            if tile or position == "tile":
+                positions = _get_tile_text_positions(rect, text_width, font_size, tile_spacing)
+            else:
+                positions = _get_text_positions(rect, text_width, font_size, position)
 
+            shape = page.new_shape()
             for pos in positions:
-                # Create text writer for watermark
-                if layer == "under":
-                    # Insert under existing content
-                    shape = page.new_shape()
-                    shape.insert_text(
-                        pos,
-                        text,
-                        fontsize=font_size,
-                        color=font_color,
-                        rotate=rotation,
-                    )
-                    shape.finish(opacity=opacity)
-                    shape.commit(overlay=False)
-                else:
-                    # Insert over existing content
-                    shape = page.new_shape()
-                    shape.insert_text(
-                        pos,
-                        text,
-                        fontsize=font_size,
-                        color=font_color,
-                        rotate=rotation,
-                    )
-                    shape.finish(opacity=opacity)
-                    shape.commit(overlay=True)
+                # Rotate each copy about its own center so tiles keep their grid
+                pivot = fitz.Point(pos.x + text_width / 2, pos.y - font_size / 3)
+                shape.insert_text(
+                    pos,
+                    text,
+                    fontsize=font_size,
+                    color=font_color,
+                    morph=(pivot, fitz.Matrix(-rotation)) if rotation else None,
+                    fill_opacity=opacity,
+                    stroke_opacity=opacity,
+                )
+            # Under or over existing content
+            shape.commit(overlay=(layer == "over"))
 
         doc.save(output_path)
         doc.close()
@@ -99,7 +104,9 @@ def add_image_watermark(
     rotation: float = 0,
     position: str = "center",
     pages: str = "all",
-    layer: str = "under"
+    layer: str = "under",
+    tile: bool = False,
+    tile_spacing: float = None,
 ) -> dict:
     """
     Add image watermark to PDF pages.
@@ -114,6 +121,8 @@ def add_image_watermark(
         position: Placement strategy
         pages: Page selection
         layer: "under" content or "over" content
+        tile: Repeat the image in a grid covering the whole page
+        tile_spacing: Gap between tiles in points (default: half the image size)
 
     Returns:
         dict with success status and message
@@ -121,6 +130,8 @@ def add_image_watermark(
     try:
         doc = fitz.open(input_path)
         page_indices = _parse_pages(pages, len(doc))
+        if tile:
+            position = "tile"
 
         # Load image once
         img = fitz.Pixmap(image_path)
@@ -134,7 +145,7 @@ def add_image_watermark(
             img_height = img_width * (img.height / img.width)
 
             # Get positions
-            positions = _get_image_positions(rect, img_width, img_height, position)
+            positions = _get_image_positions(rect, img_width, img_height, position, tile_spacing)
 
             for img_rect in positions:
                 # Insert image with transparency
@@ -182,14 +193,10 @@ def _parse_pages(pages: str, total: int) -> list:
     return sorted(indices)
 
 
-def _get_text_positions(rect: fitz.Rect, text: str, font_size: float, position: str, rotation: float) -> list:
-    """Calculate text insertion points based on position strategy."""
+def _get_text_positions(rect: fitz.Rect, text_width: float, font_size: float, position: str) -> list:
+    """Calculate text insertion points (baseline start) based on position strategy."""
     positions = []
 
-    # Approximate text width (rough estimate)
-    text_width = len(text) * font_size * 0.5
-    text_height = font_size
-
     if position == "center":
         x = rect.width / 2 - text_width / 2
         y = rect.height / 2
@@ -207,23 +214,33 @@ def _get_text_positions(rect: fitz.Rect, text: str, font_size: float, position:
     elif position == "bottom-right":
         positions.append(fitz.Point(rect.width - text_width - 50, rect.height - 50))
 
-    elif position == "tile":
-        # Create a grid of watermarks
-        spacing_x = max(text_width * 1.5, 200)
-        spacing_y = max(text_height * 3, 150)
+    return positions
 
-        y = spacing_y
-        while y < rect.height:
-            x = spacing_x / 2
-            while x < rect.width:
-                positions.append(fitz.Point(x, y))
-                x += spacing_x
-            y += spacing_y
 
-    return positions
+def _tile_centers(rect: fitz.Rect, step_x: float, step_y: float) -> list:
+    """
+    Centers of a grid covering the page. The grid overshoots the edges by a
+    step so rotated tiles still reach the corners.
+    """
+    centers = []
+    y = -step_y / 2
+    while y < rect.height + step_y:
+        x = -step_x / 2
+        while x < rect.width + step_x:
+            centers.append(fitz.Point(x, y))
+            x += step_x
+        y += step_y
+    return centers
+
+
+def _get_tile_text_positions(rect: fitz.Rect, text_width: float, font_size: float, spacing: float = None) -> list:
+    """Insertion points for a grid of text tiles, `spacing` points apart."""
+    gap = spacing if spacing is not None else max(font_size * 2, 60)
+    centers = _tile_centers(rect, text_width + gap, font_size + gap)
+    return [fitz.Point(c.x - text_width / 2, c.y + font_size / 3) for c in centers]
 
 
-def _get_image_positions(rect: fitz.Rect, img_width: float, img_height: float, position: str) -> list:
+def _get_image_positions(rect: fitz.Rect, img_width: float, img_height: float, position: str, spacing: float = None) -> list:
     """Calculate image rects based on position strategy."""
     positions = []
     margin = 30
@@ -250,8 +267,8 @@ def _get_image_positions(rect: fitz.Rect, img_width: float, img_height: float, p
         positions.append(fitz.Rect(x, y, x + img_width, y + img_height))
 
     elif position == "tile":
-        spacing_x = img_width * 1.5
-        spacing_y = img_height * 1.5
+        spacing_x = img_width + (spacing if spacing is not None else img_width / 2)
+        spacing_y = img_height + (spacing if spacing is not None else img_height / 2)
 
         y = spacing_y / 2
         while y + img_height < rect.height:
@@ -264,6 +281,214 @@ def _get_image_positions(rect: fitz.Rect, img_width: float, img_height: float, p
     return positions
 
 
+# Bates stamp placement
+BATES_POSITIONS = ("bottom-right", "bottom-left", "bottom-center", "top-right", "top-left", "top-center")
+# Distances from the page edge to try, outermost last
+BATES_MARGINS = (36, 24, 12)
+
+
+def _occupied_rects(page: fitz.Page) -> list:
+    """Rects of existing text, drawings and images on the page (visible coordinates)."""
+    rects = [fitz.Rect(block[:4]) for block in page.get_text("blocks")]
+    rects += [drawing["rect"] for drawing in page.get_drawings()]
+    rects += [fitz.Rect(info["bbox"]) for info in page.get_image_info()]
+    # Full-page backgrounds would block every candidate; ignore them
+    page_area = page.rect.width * page.rect.height
+    return [r for r in rects if not r.is_empty and r.width * r.height < page_area * 0.9]
+
+
+def _bates_rect(rect: fitz.Rect, width: float, height: float, position: str, margin: float) -> fitz.Rect:
+    vertical, horizontal = position.split("-")
+    if horizontal == "left":
+        x0 = rect.x0 + margin
+    elif horizontal == "right":
+        x0 = rect.x1 - margin - width
+    else:
+        x0 = rect.x0 + (rect.width - width) / 2
+    y0 = rect.y0 + margin if vertical == "top" else rect.y1 - margin - height
+    return fitz.Rect(x0, y0, x0 + width, y0 + height)
+
+
+def add_bates_numbers(
+    input_path: str,
+    output_path: str,
+    prefix: str = "",
+    start_number: int = 1,
+    digits: int = 6,
+    position: str = "bottom-right",
+    font_size: float = 10,
+    pages: str = "all",
+) -> dict:
+    """
+    Stamp sequential Bates numbers (prefix + zero-padded number) on pages.
+
+    The stamp goes at the given corner, moving toward the page edge when the
+    default margin would overlap existing content. Pages where every margin
+    overlaps get the stamp on a white box at the outermost margin and are
+    reported in overlapping_pages.
+
+    Returns:
+        dict with first/last number applied and next_number for continuing a batch
+    """
+    if position not in BATES_POSITIONS:
+        return {"success": False, "message": f"Invalid position: {position}"}
+
+    try:
+        doc = fitz.open(input_path)
+        page_indices = _parse_pages(pages, len(doc))
+        if not page_indices:
+            doc.close()
+            return {"success": False, "message": "No pages selected"}
+
+        number = start_number
+        labels = []
+        overlapping = []
+
+        for page_idx in page_indices:
+            page = doc[page_idx]
+            label = f"{prefix}{number:0{digits}d}"
+            width = fitz.get_text_length(label, fontname="helv", fontsize=font_size)
+            height = font_size * 1.2
+
+            occupied = _occupied_rects(page)
+            stamp_rect = None
+            for margin in BATES_MARGINS:
+                candidate = _bates_rect(page.rect, width, height, position, margin)
+                if not any(candidate.intersects(r) for r in occupied):
+                    stamp_rect = candidate
+                    break
+
+            if stamp_rect is None:
+                stamp_rect = _bates_rect(page.rect, width, height, position, BATES_MARGINS[-1])
+                overlapping.append(page_idx + 1)
+                page.draw_rect(
+                    (stamp_rect + (-2, -1, 2, 1)) * page.derotation_matrix,
+                    color=None,
+                    fill=(1, 1, 1),
+                    overlay=True,
+                )
+
+            # Baseline sits a descender above the bottom of the box; rotated pages
+            # take the point in unrotated space and rotate the text back upright
+            baseline = fitz.Point(stamp_rect.x0, stamp_rect.y1 - font_size * 0.25)
+            page.insert_text(
+                baseline * page.derotation_matrix,
+                label,
+                fontname="helv",
+                fontsize=font_size,
+                color=(0, 0, 0),
+                rotate=page.rotation,
+            )
+            labels.append(label)
+            number += 1
+
+        doc.save(output_path)
+        doc.close()
+
+        return {
+            "success": True,
+            "message": f"Bates numbers {labels[0]}-{labels[-1]} applied to {len(labels)} pages",
+            "pages_processed": len(labels),
+            "first_number": labels[0],
+            "last_number": labels[-1],
+            "next_number": number,
+            "overlapping_pages": overlapping,
+        }
+
+    except Exception as e:
+        return {"success": False, "message": str(e)}
+
+
+# Header/footer zones, as (vertical, horizontal) placement
+HEADER_FOOTER_ZONES = {
+    "header_left": ("top", "left"),
+    "header_center": ("top", "center"),
+    "header_right": ("top", "right"),
+    "footer_left": ("bottom", "left"),
+    "footer_center": ("bottom", "center"),
+    "footer_right": ("bottom", "right"),
+}
+# Base-14 fonts available for headers/footers
+HEADER_FOOTER_FONTS = {"sans": "helv", "serif": "tiro", "mono": "cour"}
+
+
+def expand_tokens(template: str, page: int, pages: int, filename: str, today: str) -> str:
+    """Substitute {page}, {pages}, {date} and {filename}; other text is kept as-is."""
+    return (
+        template.replace("{page}", str(page))
+        .replace("{pages}", str(pages))
+        .replace("{date}", today)
+        .replace("{filename}", filename)
+    )
+
+
+def add_header_footer(
+    input_path: str,
+    output_path: str,
+    zones: dict,
+    font: str = "sans",
+    font_size: float = 10,
+    margin: float = 36,
+    pages: str = "all",
+) -> dict:
+    """
+    Write running headers/footers in up to six zones (see HEADER_FOOTER_ZONES).
+
+    Zone text may mix static text with tokens: {page} (1-indexed page number),
+    {pages} (page count), {date} (today, YYYY-MM-DD) and {filename}. `margin`
+    is the distance in points from the page edge to the text box.
+    """
+    unknown = [zone for zone in zones if zone not in HEADER_FOOTER_ZONES]
+    if unknown:
+        return {"success": False, "message": f"Unknown zone: {unknown[0]}"}
+    zones = {zone: text for zone, text in zones.items() if text}
+    if not zones:
+        return {"success": False, "message": "No header or footer text given"}
+    fontname = HEADER_FOOTER_FONTS.get(font)
+    if fontname is None:
+        return {"success": False, "message": f"Unknown font: {font}"}
+
+    try:
+        doc = fitz.open(input_path)
+        page_indices = _parse_pages(pages, len(doc))
+        if not page_indices:
+            doc.close()
+            return {"success": False, "message": "No pages selected"}
+
+        filename = Path(input_path).name
+        today = date.today().isoformat()
+        height = font_size * 1.2
+
+        for page_idx in page_indices:
+            page = doc[page_idx]
+            for zone, template in zones.items():
+                text = expand_tokens(template, page_idx + 1, len(doc), filename, today)
+                width = fitz.get_text_length(text, fontname=fontname, fontsize=font_size)
+                rect = _bates_rect(page.rect, width, height, "-".join(HEADER_FOOTER_ZONES[zone]), margin)
+                # Same upright placement on rotated pages as Bates stamps
+                baseline = fitz.Point(rect.x0, rect.y1 - font_size * 0.25)
+                page.insert_text(
+                    baseline * page.derotation_matrix,
+                    text,
+                    fontname=fontname,
+                    fontsize=font_size,
+                    color=(0, 0, 0),
+                    rotate=page.rotation,
+                )
+
+        doc.save(output_path)
+        doc.close()
+
+        return {
+            "success": True,
+            "message": f"Header/footer added to {len(page_indices)} pages",
+            "pages_processed": len(page_indices),
+        }
+
+    except Exception as e:
+        return {"success": False, "message": str(e)}
+
+
 def get_preview_info(input_path: str) -> dict:
     """Get PDF info for watermark preview."""
     try:
@@ -288,7 +513,7 @@ def get_preview_info(input_path: str) -> dict:
         return {"success": False, "message": str(e)}
 
 
-if __name__ == "__main__":
+def main():
     if len(sys.argv) < 2:
         print(json.dumps({"success": False, "message": "No command specified"}))
         sys.exit(1)
@@ -322,7 +547,9 @@ if __name__ == "__main__":
             rotation=options.get("rotation", -45),
             position=options.get("position", "center"),
             pages=options.get("pages", "all"),
-            layer=options.get("layer", "under")
+            layer=options.get("layer", "under"),
+            tile=options.get("tile", False),
+            tile_spacing=options.get("tile_spacing"),
         )
         print(json.dumps(result))
         sys.exit(0 if result["success"] else 1)
@@ -352,7 +579,57 @@ if __name__ == "__main__":
             rotation=options.get("rotation", 0),
             position=options.get("position", "center"),
             pages=options.get("pages", "all"),
-            layer=options.get("layer", "under")
+            layer=options.get("layer", "under"),
+            tile=options.get("tile", False),
+            tile_spacing=options.get("tile_spacing"),
+        )
+        print(json.dumps(result))
+        sys.exit(0 if result["success"] else 1)
+
+    elif command == "bates":
+        if len(sys.argv) < 4:
+            print(json.dumps({"success": False, "message": "Usage: bates <input> <output> [options_json]"}))
+            sys.exit(1)
+
+        options = {}
+        if len(sys.argv) > 4:
+            try:
+                options = json.loads(sys.argv[4])
+            except json.JSONDecodeError:
+                pass
+
+        result = add_bates_numbers(
+            sys.argv[2],
+            sys.argv[3],
+            prefix=options.get("prefix", ""),
+            start_number=options.get("start_number", 1),
+            digits=options.get("digits", 6),
+            position=options.get("position", "bottom-right"),
+            font_size=options.get("font_size", 10),
+            pages=options.get("pages", "all"),
+        )
+        print(json.dumps(result))
+        sys.exit(0 if result["success"] else 1)
+
+    elif command == "header-footer":
+        if len(sys.argv) < 5:
+            print(json.dumps({"success": False, "message": "Usage: header-footer <input> <output> <options_json>"}))
+            sys.exit(1)
+
+        try:
+            options = json.loads(sys.argv[4])
+        except json.JSONDecodeError as e:
+            print(json.dumps({"success": False, "message": f"Invalid options: {e}"}))
+            sys.exit(1)
+
+        result = add_header_footer(
+            sys.argv[2],
+            sys.argv[3],
+            options.get("zones", {}),
+            font=options.get("font", "sans"),
+            font_size=options.get("font_size", 10),
+            margin=options.get("margin", 36),
+            pages=options.get("pages", "all"),
         )
         print(json.dumps(result))
         sys.exit(0 if result["success"] else 1)
@@ -369,3 +646,7 @@ if __name__ == "__main__":
     else:
         print(json.dumps({"success": False, "message": f"Unknown command: {command}"}))
         sys.exit(1)
+
+
+if __name__ == "__main__":
+    run_main(main)
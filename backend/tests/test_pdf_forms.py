@@ -0,0 +1,135 @@
+"""
+Tests for the FDF reader/writer in pdf_forms.
+
+Run from the repo root with: python3 -m unittest discover -s backend/tests
+The parser is pure Python, so PyMuPDF is stubbed out when it isn't installed.
+"""
+
+import sys
+import unittest
+from pathlib import Path
+from unittest import mock
+
+sys.path.insert(0, str(Path(__file__).resolve().parent.parent))
+
+try:
+    import fitz  # noqa: F401
+except ImportError:
+    sys.modules["fitz"] = mock.MagicMock()
+
+import pdf_forms  # noqa: E402
+
+
+def _fdf(fields: str) -> str:
+    return f"%FDF-1.2\n1 0 obj\n<< /FDF << /Fields [\n{fields}\n] >> >>\nendobj\ntrailer\n<< /Root 1 0 R >>\n%%EOF\n"
+
+
+class FdfParserTests(unittest.TestCase):
+    def test_literal_string_escapes(self):
+        values = pdf_forms._from_fdf(_fdf(r"<< /T (note) /V (a\(b\)c \\ \101\102 tab\there (nested (parens))) >>"))
+        self.assertEqual(values["note"], "a(b)c \\ AB tab\there (nested (parens))")
+
+    def test_line_continuation_and_newlines(self):
+        values = pdf_forms._from_fdf(_fdf("<< /T (text) /V (first\\nsecond \\\r\njoined) >>"))
+        self.assertEqual(values["text"], "first\nsecond joined")
+
+    def test_octal_escape_stops_after_three_digits(self):
+        values = pdf_forms._from_fdf(_fdf(r"<< /T (n) /V (\0611) >>"))
+        self.assertEqual(values["n"], "11")
+
+    def test_utf16_hex_string(self):
+        encoded = "<FEFF" + "Día ✓".encode("utf-16-be").hex().upper() + ">"
+        values = pdf_forms._from_fdf(_fdf(f"<< /T (name) /V {encoded} >>"))
+        self.assertEqual(values["name"], "Día ✓")
+
+    def test_name_hex_escapes(self):
+        values = pdf_forms._from_fdf(_fdf("<< /T (choice) /V /Option#20A >>"))
+        self.assertEqual(values["choice"], "Option A")
+
+    def test_off_state_is_false(self):
+        values = pdf_forms._from_fdf(_fdf("<< /T (agree) /V /Off >>\n<< /T (opt) /V /On >>"))
+        self.assertIs(values["agree"], False)
+        self.assertEqual(values["opt"], "On")
+
+    def test_nested_kids_build_dotted_names(self):
+        values = pdf_forms._from_fdf(_fdf(
+            "<< /T (person) /Kids [\n"
+            "  << /T (name) /Kids [ << /T (first) /V (Ana) >> << /T (last) /V (Ruiz) >> ] >>\n"
+            "  << /T (age) /V (41) >>\n"
+            "] >>"
+        ))
+        self.assertEqual(values, {"person.name.first": "Ana", "person.name.last": "Ruiz", "person.age": "41"})
+
+    def test_array_values(self):
+        values = pdf_forms._from_fdf(_fdf("<< /T (list) /V [(one) (two)] >>"))
+        self.assertEqual(values["list"], ["one", "two"])
+
+    def test_missing_fields_array(self):
+        with self.assertRaises(ValueError):
+            pdf_forms._from_fdf("%FDF-1.2\n1 0 obj\n<< /FDF << >> >>\nendobj\n")
+
+    def test_round_trip(self):
+        values = {
+            "text": "paren ( and \\ backslash\nnewline",
+            "unicode": "naïve",
+            "group": "Choice 2",
+            "agree": False,
+        }
+        parsed = pdf_forms._from_fdf(pdf_forms._to_fdf(values, {"group"}))
+        self.assertEqual(parsed, values)
+
+
+class _FakeWidget:
+    def __init__(self, name, xref):
+        self.field_name = name
+        self.field_type = pdf_forms.fitz.PDF_WIDGET_TYPE_CHECKBOX
+        self.xref = xref
+
+
+class _FakePage:
+    def __init__(self, widgets):
+        self._widgets = widgets
+
+    def widgets(self):
+        return iter(self._widgets)
+
+
+class _FakeDoc:
+    """Just enough of fitz.Document for _checkbox_on_states."""
+
+    def __init__(self, pages, keys, objects):
+        self._pages, self._keys, self._objects = pages, keys, objects
+
+    def __iter__(self):
+        return iter(self._pages)
+
+    def xref_get_key(self, xref, key):
+        return self._keys.get((xref, key), ("null", "null"))
+
+    def xref_is_stream(self, xref):
+        return False
+
+    def xref_object(self, xref, compressed=False):
+        return self._objects[xref]
+
+
+class CheckboxStateTests(unittest.TestCase):
+    def test_on_states_come_from_appearance_keys(self):
+        doc = _FakeDoc(
+            [_FakePage([_FakeWidget("agree", 10), _FakeWidget("size", 11), _FakeWidget("size", 12)])],
+            {
+                (10, "AP/N"): ("dict", "<</Off 20 0 R/On 21 0 R>>"),
+                (11, "AP/N"): ("xref", "30 0 R"),
+                (12, "AP/N"): ("dict", "<</Large 23 0 R/Off 24 0 R>>"),
+            },
+            {30: "<<\n  /Small 22 0 R\n  /Off 25 0 R\n>>"},
+        )
+        self.assertEqual(pdf_forms._checkbox_on_states(doc), {"agree": ["On"], "size": ["Small", "Large"]})
+
+    def test_widget_without_appearance_has_no_states(self):
+        doc = _FakeDoc([_FakePage([_FakeWidget("plain", 10)])], {}, {})
+        self.assertEqual(pdf_forms._checkbox_on_states(doc), {"plain": []})
+
+
+if __name__ == "__main__":
+    unittest.main()
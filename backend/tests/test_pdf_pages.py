@@ -0,0 +1,113 @@
+"""
+Tests for pdf_pages.
+
+Run from the repo root with: python3 -m unittest discover -s backend/tests
+Tests that write PDFs need pypdf; without it they are skipped and pypdf is
+stubbed so the pure helpers can still be imported.
+"""
+
+import sys
+import tempfile
+import unittest
+from pathlib import Path
+from unittest import mock
+
+sys.path.insert(0, str(Path(__file__).resolve().parent.parent))
+
+try:
+    import pypdf
+
+    HAVE_PYPDF = True
+except ImportError:
+    HAVE_PYPDF = False
+    sys.modules["pypdf"] = mock.MagicMock()
+    sys.modules["pypdf.generic"] = mock.MagicMock()
+
+import pdf_pages  # noqa: E402
+
+
+class ParseRangesTests(unittest.TestCase):
+    def test_ranges_and_single_pages_in_order(self):
+        self.assertEqual(pdf_pages.parse_ranges("3-5, 10,1", 10), [2, 3, 4, 9, 0])
+
+    def test_out_of_bounds_cites_the_token(self):
+        with self.assertRaisesRegex(ValueError, "'15-20'"):
+            pdf_pages.parse_ranges("1,15-20", 18)
+        with self.assertRaisesRegex(ValueError, "Page 0"):
+            pdf_pages.parse_ranges("0", 5)
+
+    def test_reversed_range(self):
+        with self.assertRaisesRegex(ValueError, "'5-3'"):
+            pdf_pages.parse_ranges("5-3", 5)
+
+    def test_malformed_token_cites_the_token(self):
+        with self.assertRaisesRegex(ValueError, "'a-b'"):
+            pdf_pages.parse_ranges("a-b", 5)
+        with self.assertRaisesRegex(ValueError, "'x'"):
+            pdf_pages.parse_ranges("1,x", 5)
+
+    def test_empty_expression(self):
+        self.assertEqual(pdf_pages.parse_ranges(" , ", 5), [])
+
+
+def _write_pdf(path: Path, page_count: int, outline: list) -> None:
+    """Blank pages plus an outline of (title, 0-based page, [(child title, page), ...])."""
+    writer = pypdf.PdfWriter()
+    for _ in range(page_count):
+        writer.add_blank_page(width=200, height=200)
+    for title, page, children in outline:
+        parent = writer.add_outline_item(title, page)
+        for child_title, child_page in children:
+            writer.add_outline_item(child_title, child_page, parent=parent)
+    with path.open("wb") as fh:
+        writer.write(fh)
+
+
+def _flatten_outline(reader, items, depth: int = 0) -> list:
+    """(title, 0-based target page, depth) for every outline item, in order."""
+    flat = []
+    for item in items:
+        if isinstance(item, list):
+            flat.extend(_flatten_outline(reader, item, depth + 1))
+        else:
+            flat.append((item.title, reader.get_destination_page_number(item), depth))
+    return flat
+
+
+@unittest.skipUnless(HAVE_PYPDF, "pypdf is not installed")
+class MergeOutlineTests(unittest.TestCase):
+    def test_merged_outline_points_at_offset_pages(self):
+        with tempfile.TemporaryDirectory() as tmp:
+            tmp = Path(tmp)
+            first, second, merged = tmp / "first.pdf", tmp / "second.pdf", tmp / "merged.pdf"
+            _write_pdf(first, 3, [("Intro", 0, []), ("End", 2, [])])
+            _write_pdf(second, 4, [("Chapter 1", 1, [("Section 1.1", 2)]), ("Chapter 2", 3, [])])
+
+            pdf_pages.merge_pdfs([first, second], merged)
+
+            reader = pypdf.PdfReader(str(merged))
+            self.assertEqual(len(reader.pages), 7)
+            self.assertEqual(_flatten_outline(reader, reader.outline), [
+                ("first", 0, 0),
+                ("Intro", 0, 1),
+                ("End", 2, 1),
+                ("second", 3, 0),
+                ("Chapter 1", 4, 1),
+                ("Section 1.1", 5, 2),
+                ("Chapter 2", 6, 1),
+            ])
+
+    def test_without_bookmarks_no_outline_is_written(self):
+        with tempfile.TemporaryDirectory() as tmp:
+            tmp = Path(tmp)
+            first, second, merged = tmp / "first.pdf", tmp / "second.pdf", tmp / "merged.pdf"
+            _write_pdf(first, 1, [("Intro", 0, [])])
+            _write_pdf(second, 1, [("Other", 0, [])])
+
+            pdf_pages.merge_pdfs([first, second], merged, preserve_bookmarks=False)
+
+            self.assertEqual(pypdf.PdfReader(str(merged)).outline, [])
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,96 @@
+"""
+Tests for anonymize_ids in pdf_sanitize.
+
+Run from the repo root with: python3 -m unittest discover -s backend/tests
+Tests that write PDFs need PyMuPDF; without it they are skipped and fitz is
+stubbed so the XMP helpers can still be imported.
+"""
+
+import sys
+import tempfile
+import unittest
+from pathlib import Path
+from unittest import mock
+
+sys.path.insert(0, str(Path(__file__).resolve().parent.parent))
+
+try:
+    import fitz
+
+    # Another test module may already have stubbed it
+    HAVE_FITZ = not isinstance(fitz, mock.Mock)
+except ImportError:
+    HAVE_FITZ = False
+    sys.modules["fitz"] = mock.MagicMock()
+
+import pdf_sanitize  # noqa: E402
+
+# Identifiers in both forms MuPDF and Acrobat write: attributes and elements
+XMP = """<?xpacket begin="﻿" id="W5M0MpCehiHzreSzNTczkc9d"?>
+<x:xmpmeta xmlns:x="adobe:ns:meta/">
+ <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+  <rdf:Description rdf:about=""
+    xmlns:dc="http://purl.org/dc/elements/1.1/"
+    xmlns:xmpMM="http://ns.adobe.com/xap/1.0/mm/"
+    xmlns:stRef="http://ns.adobe.com/xap/1.0/sType/ResourceRef#"
+    xmpMM:DocumentID="uuid:0f1c2d3e-aaaa-bbbb-cccc-123456789abc">
+   <xmpMM:InstanceID>uuid:9e8d7c6b-dddd-eeee-ffff-cba987654321</xmpMM:InstanceID>
+   <xmpMM:History><rdf:Seq><rdf:li>saved by Editor 1.0</rdf:li></rdf:Seq></xmpMM:History>
+   <xmpMM:DerivedFrom stRef:documentID="uuid:1111" stRef:instanceID="uuid:2222"/>
+   <dc:title><rdf:Alt><rdf:li xml:lang="x-default">Quarterly report</rdf:li></rdf:Alt></dc:title>
+   <dc:rights><rdf:Alt><rdf:li xml:lang="x-default">CC BY 4.0</rdf:li></rdf:Alt></dc:rights>
+  </rdf:Description>
+ </rdf:RDF>
+</x:xmpmeta>
+<?xpacket end="w"?>"""
+
+IDENTIFIERS = ["xmpMM:DocumentID", "xmpMM:InstanceID", "xmpMM:History", "xmpMM:DerivedFrom"]
+
+
+class StripXmpPropertyTests(unittest.TestCase):
+    def test_strips_attribute_and_element_forms(self):
+        xmp = XMP
+        for prop in IDENTIFIERS:
+            xmp, removed = pdf_sanitize._strip_xmp_property(xmp, prop)
+            self.assertTrue(removed, prop)
+            self.assertFalse(pdf_sanitize._has_xmp_property(xmp, prop), prop)
+        self.assertTrue(pdf_sanitize._has_xmp_property(xmp, "dc:title"))
+        self.assertIn("CC BY 4.0", xmp)
+
+    def test_missing_property_is_not_reported(self):
+        xmp, removed = pdf_sanitize._strip_xmp_property(XMP, "xmpMM:OriginalDocumentID")
+        self.assertFalse(removed)
+        self.assertEqual(xmp, XMP)
+
+
+@unittest.skipUnless(HAVE_FITZ, "PyMuPDF is not installed")
+class AnonymizeIdsTests(unittest.TestCase):
+    def test_saved_file_has_no_identifiers_and_keeps_descriptive_metadata(self):
+        with tempfile.TemporaryDirectory() as tmp:
+            tmp = Path(tmp)
+            source, anonymized = tmp / "source.pdf", tmp / "anonymized.pdf"
+            doc = fitz.open()
+            doc.new_page()
+            doc.set_xml_metadata(XMP)
+            doc.save(source)
+            doc.close()
+
+            result = pdf_sanitize.anonymize_ids(source, anonymized)
+            self.assertTrue(result["success"], result["message"])
+
+            doc = fitz.open(anonymized)
+            try:
+                self.assertEqual(doc.xref_get_key(-1, "ID")[0], "null")
+                xmp = doc.get_xml_metadata()
+            finally:
+                doc.close()
+            for prop in IDENTIFIERS:
+                self.assertNotIn(prop, xmp)
+            self.assertIn("<dc:title>", xmp)
+            self.assertIn("Quarterly report", xmp)
+            self.assertIn("<dc:rights>", xmp)
+            self.assertIn("CC BY 4.0", xmp)
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -7,19 +7,21 @@ and system command detection.
 
 from __future__ import annotations
 
+import json
 import os
 import shutil
+import sys
 import tempfile
 import uuid
 from pathlib import Path
-from typing import Sequence
+from typing import Callable, Sequence
 
 try:
     from exceptions import FileNotFoundError as TlacuiloFileNotFoundError
-    from exceptions import InvalidFileTypeError, DependencyMissingError
+    from exceptions import InvalidFileTypeError, DependencyMissingError, TlacuiloError
 except ImportError:
     from .exceptions import FileNotFoundError as TlacuiloFileNotFoundError
-    from .exceptions import InvalidFileTypeError, DependencyMissingError
+    from .exceptions import InvalidFileTypeError, DependencyMissingError, TlacuiloError
 
 # Use our custom FileNotFoundError (shadowing builtin intentionally)
 FileNotFoundError = TlacuiloFileNotFoundError
@@ -254,6 +256,66 @@ def safe_filename(name: str, max_length: int = 200) -> str:
     return name or "unnamed"
 
 
+def report_progress(phase: str, current: int, total: int) -> None:
+    """
+    Emit a progress line for the Rust bridge to forward to the frontend.
+
+    Written to stdout as `PROGRESS {json}`; the bridge strips these lines
+    from the script's regular output.
+
+    Args:
+        phase: Short label for the current step (e.g., "ocr", "render")
+        current: Units completed so far
+        total: Total units of work
+    """
+    payload = json.dumps({"phase": phase, "current": current, "total": total})
+    print(f"PROGRESS {payload}", flush=True)
+
+
+def report_error(exc: BaseException) -> None:
+    """
+    Emit the error envelope the Rust bridge turns into a coded PythonError.
+
+    Written to stdout as `{"error": {"code": ..., "message": ..., "details": ...}}`
+    so the frontend can show a localized message instead of a traceback.
+    Call it before exiting with a non-zero status.
+
+    Args:
+        exc: The exception that ended the script. TlacuiloError subclasses
+            carry their own code; ValueError maps to "invalid_argument" and
+            anything else to "internal".
+    """
+    if isinstance(exc, TlacuiloError):
+        code, message, details = exc.code, exc.message, exc.details
+    elif isinstance(exc, ValueError):
+        code, message, details = "invalid_argument", str(exc), None
+    else:
+        code, message, details = "internal", str(exc), None
+    payload = {"code": code, "message": message, "details": details}
+    print(json.dumps({"error": payload}), flush=True)
+
+
+def run_main(main: Callable[[], int | None]) -> None:
+    """
+    Run a script's entry point and exit with its status.
+
+    Every backend script starts through here, so an uncaught exception ends
+    in the same error envelope (via report_error) instead of a traceback.
+    Operation results that carry their own "success"/"error" fields are
+    printed by the scripts as before; this only covers failures that escape.
+
+    Args:
+        main: The script's main function; its return value is the exit status.
+    """
+    try:
+        status = main()
+    except Exception as exc:  # noqa: BLE001
+        report_error(exc)
+        print(f"Error: {exc}", file=sys.stderr)
+        sys.exit(1)
+    sys.exit(status or 0)
+
+
 def cleanup_temp_files(*paths: Path | str) -> None:
     """
     Safely delete temporary files/directories.
@@ -0,0 +1,110 @@
+"""
+Long-lived script worker for Tlacuilo.
+
+Reads newline-delimited JSON requests on stdin and writes one JSON response
+per line on stdout. Each request runs a backend script exactly as the CLI
+would, but inside this process, so heavy imports (PyMuPDF, PIL, ...) stay
+warm between calls.
+
+Protocol:
+  request:  {"id": 1, "method": "pdf_edit.py", "params": ["preview", "--input", "doc.pdf", "--json"]}
+  response: {"id": 1, "stdout": "...", "stderr": "...", "exit_code": 0}
+  failure:  {"id": 1, "error": {"code": "WORKER_ERROR", "message": "..."}}
+
+CLI usage (dev):
+  echo '{"id": 1, "method": "pdf_edit.py", "params": ["text-blocks", "--input", "doc.pdf", "--page", "0", "--json"]}' | python worker.py
+"""
+
+from __future__ import annotations
+
+import io
+import json
+import os
+import runpy
+import sys
+from contextlib import redirect_stderr, redirect_stdout
+from pathlib import Path
+
+SCRIPTS_DIR = Path(__file__).resolve().parent
+
+try:
+    from utils import report_error
+except ImportError:
+    from .utils import report_error
+
+
+def _script_path(method: str) -> Path:
+    """Resolve a method name to a backend script, refusing anything outside SCRIPTS_DIR."""
+    name = method if method.endswith(".py") else f"{method}.py"
+    path = (SCRIPTS_DIR / name).resolve()
+    if path.parent != SCRIPTS_DIR or not path.is_file() or path.name == "worker.py":
+        raise ValueError(f"Unknown method: {method}")
+    return path
+
+
+def _exit_code(exc: SystemExit) -> int:
+    if exc.code is None:
+        return 0
+    if isinstance(exc.code, int):
+        return exc.code
+    print(exc.code, file=sys.stderr)
+    return 1
+
+
+def run_request(method: str, params: list[str]) -> dict:
+    """Run one script invocation with captured output and return its result."""
+    path = _script_path(method)
+    stdout, stderr = io.StringIO(), io.StringIO()
+    saved_argv = sys.argv
+    sys.argv = [str(path), *[str(p) for p in params]]
+    exit_code = 0
+    try:
+        with redirect_stdout(stdout), redirect_stderr(stderr):
+            try:
+                runpy.run_path(str(path), run_name="__main__")
+            except SystemExit as exc:
+                exit_code = _exit_code(exc)
+            except Exception as exc:
+                # Scripts report their own failures; this catches ones that
+                # never reach run_main, such as a failing import
+                report_error(exc)
+                print(f"{type(exc).__name__}: {exc}", file=sys.stderr)
+                exit_code = 1
+    finally:
+        sys.argv = saved_argv
+    return {"stdout": stdout.getvalue(), "stderr": stderr.getvalue(), "exit_code": exit_code}
+
+
+def serve(requests, responses) -> None:
+    """Answer requests line by line until stdin closes."""
+    for line in requests:
+        line = line.strip()
+        if not line:
+            continue
+        request_id = None
+        try:
+            request = json.loads(line)
+            request_id = request.get("id")
+            params = request.get("params") or []
+            if not isinstance(params, list):
+                raise ValueError("params must be a list of arguments")
+            response = run_request(str(request["method"]), params)
+        except Exception as exc:
+            response = {"error": {"code": "WORKER_ERROR", "message": str(exc)}}
+        response["id"] = request_id
+        responses.write(json.dumps(response) + "\n")
+        responses.flush()
+
+
+def main() -> None:
+    # Keep the protocol on a private copy of stdout and point fd 1 at stderr,
+    # so native libraries or subprocesses writing to stdout can't corrupt it.
+    responses = os.fdopen(os.dup(sys.stdout.fileno()), "w", encoding="utf-8")
+    os.dup2(sys.stderr.fileno(), sys.stdout.fileno())
+    if str(SCRIPTS_DIR) not in sys.path:
+        sys.path.insert(0, str(SCRIPTS_DIR))
+    serve(sys.stdin, responses)
+
+
+if __name__ == "__main__":
+    main()
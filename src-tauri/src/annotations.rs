@@ -1,6 +1,19 @@
+use crate::command_error::CommandError;
+use crate::pdf_viewer::{render_page_region, DocumentCache, NormalizedRect};
+use crate::thumbnail_cache::path_hash;
+use base64::Engine;
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_store::StoreExt;
+
+/// Store file shared with the frontend settings
+const SETTINGS_STORE: &str = "settings.json";
+
+/// Settings key holding the directory sidecars are written to (empty: next to the PDF)
+const ANNOTATIONS_DIR_KEY: &str = "annotationsDir";
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Rect {
@@ -10,6 +23,29 @@ pub struct Rect {
     pub height: f64,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+/// One freehand stroke of an ink annotation (normalized points)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct InkPath {
+    pub points: Vec<Point>,
+    #[serde(rename = "strokeWidth")]
+    pub stroke_width: f64,
+    pub color: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Fill {
+    pub enabled: bool,
+    pub color: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub opacity: Option<f64>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Annotation {
     pub id: String,
@@ -17,15 +53,42 @@ pub struct Annotation {
     pub annotation_type: String,
     pub page: u32,
     pub rect: Rect,
+    /// Per-line quads for text markup, normalized `[x1, y1, x2, y2, x3, y3, x4, y4]`
+    /// ordered upper-left, upper-right, lower-left, lower-right
+    #[serde(rename = "quadPoints", default, skip_serializing_if = "Option::is_none")]
+    pub quad_points: Option<Vec<[f64; 8]>>,
     pub color: String,
     pub opacity: f64,
     pub text: Option<String>,
+    /// Ink strokes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub paths: Option<Vec<InkPath>>,
+    /// FreeText font size in points
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fontsize: Option<f64>,
+    /// FreeText font: "sans", "serif" or "mono"
+    #[serde(rename = "fontFamily", default, skip_serializing_if = "Option::is_none")]
+    pub font_family: Option<String>,
+    /// Shape / FreeText background
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fill: Option<Fill>,
+    /// Normalized stroke (or FreeText border) width
+    #[serde(rename = "strokeWidth", default, skip_serializing_if = "Option::is_none")]
+    pub stroke_width: Option<f64>,
+    /// FreeText border color
+    #[serde(rename = "borderColor", default, skip_serializing_if = "Option::is_none")]
+    pub border_color: Option<String>,
     #[serde(rename = "createdAt")]
     pub created_at: String,
     #[serde(rename = "modifiedAt")]
     pub modified_at: String,
 }
 
+/// Sidecar schema version written by this build. Version history:
+/// 1. initial format
+/// 2. text markup annotations always carry `quadPoints`
+pub const ANNOTATIONS_FILE_VERSION: u32 = 2;
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AnnotationsFile {
     pub version: u32,
@@ -33,6 +96,50 @@ pub struct AnnotationsFile {
     pub annotations: std::collections::HashMap<u32, Vec<Annotation>>,
 }
 
+/// v1 -> v2: give markup annotations without quads a single quad covering their rect
+fn migrate_v1_to_v2(file: &mut serde_json::Value) {
+    let Some(pages) = file.get_mut("annotations").and_then(|a| a.as_object_mut()) else {
+        return;
+    };
+    for annotation in pages.values_mut().filter_map(|p| p.as_array_mut()).flatten() {
+        let is_markup = matches!(
+            annotation.get("type").and_then(|t| t.as_str()),
+            Some("highlight" | "underline" | "strikethrough")
+        );
+        if !is_markup || annotation.get("quadPoints").is_some_and(|q| !q.is_null()) {
+            continue;
+        }
+        let Ok(rect) = serde_json::from_value::<Rect>(annotation["rect"].clone()) else {
+            continue;
+        };
+        let (x0, y0) = (rect.x, rect.y);
+        let (x1, y1) = (rect.x + rect.width, rect.y + rect.height);
+        annotation["quadPoints"] = serde_json::json!([[x0, y0, x1, y0, x0, y1, x1, y1]]);
+    }
+}
+
+/// Parse a sidecar of any supported version, migrating it to the current schema
+fn parse_annotations_file(content: &str) -> Result<AnnotationsFile, String> {
+    let mut value: serde_json::Value = serde_json::from_str(content)
+        .map_err(|e| format!("Failed to parse annotations file: {}", e))?;
+
+    let version = value.get("version").and_then(|v| v.as_u64()).unwrap_or(1) as u32;
+    if version > ANNOTATIONS_FILE_VERSION {
+        return Err(format!(
+            "Annotations file version {} is newer than this app supports (up to {}); update the app to open it",
+            version, ANNOTATIONS_FILE_VERSION
+        ));
+    }
+
+    if version < 2 {
+        migrate_v1_to_v2(&mut value);
+    }
+    value["version"] = ANNOTATIONS_FILE_VERSION.into();
+
+    serde_json::from_value(value).map_err(|e| format!("Failed to parse annotations file: {}", e))
+}
+
+/// Legacy sidecar location: hidden `.{name}.annotations.json` next to the PDF
 fn get_annotations_path(pdf_path: &str) -> PathBuf {
     let mut path = PathBuf::from(pdf_path);
     let file_name = path.file_name().unwrap().to_string_lossy().to_string();
@@ -40,9 +147,48 @@ fn get_annotations_path(pdf_path: &str) -> PathBuf {
     path
 }
 
+/// Sidecar inside `dir`, named by a hash of the absolute PDF path so
+/// same-named PDFs from different folders don't collide
+fn sidecar_in_dir(dir: &Path, pdf_path: &str) -> PathBuf {
+    let absolute = fs::canonicalize(pdf_path).unwrap_or_else(|_| PathBuf::from(pdf_path));
+    let file_name = absolute
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    dir.join(format!("{}-{}.annotations.json", path_hash(&absolute.to_string_lossy()), file_name))
+}
+
+/// The sidecar directory configured in the settings store, if any
+fn configured_sidecar_dir(app: &AppHandle) -> Option<PathBuf> {
+    let store = app.store(SETTINGS_STORE).ok()?;
+    let dir = store.get(ANNOTATIONS_DIR_KEY)?.as_str()?.trim().to_string();
+    (!dir.is_empty()).then(|| PathBuf::from(dir))
+}
+
+/// Where sidecars are written: the configured directory, else next to the PDF
+fn sidecar_write_path(app: &AppHandle, pdf_path: &str) -> PathBuf {
+    match configured_sidecar_dir(app) {
+        Some(dir) => sidecar_in_dir(&dir, pdf_path),
+        None => get_annotations_path(pdf_path),
+    }
+}
+
+/// Existing sidecar for `pdf_path`: the configured location first, then the
+/// legacy one next to the PDF
+fn sidecar_read_path(app: &AppHandle, pdf_path: &str) -> Option<PathBuf> {
+    let configured = configured_sidecar_dir(app).map(|dir| sidecar_in_dir(&dir, pdf_path));
+    configured
+        .into_iter()
+        .chain(std::iter::once(get_annotations_path(pdf_path)))
+        .find(|path| path.exists())
+}
+
 #[tauri::command]
-pub fn annotations_save(pdf_path: String, annotations_json: String) -> Result<String, String> {
-    let annotations_path = get_annotations_path(&pdf_path);
+pub fn annotations_save(app: AppHandle, pdf_path: String, annotations_json: String) -> Result<String, String> {
+    let annotations_path = sidecar_write_path(&app, &pdf_path);
+    if let Some(dir) = annotations_path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create annotations directory: {}", e))?;
+    }
 
     // Parse and re-serialize to validate JSON
     let annotations: std::collections::HashMap<u32, Vec<Annotation>> =
@@ -50,7 +196,7 @@ pub fn annotations_save(pdf_path: String, annotations_json: String) -> Result<St
             .map_err(|e| format!("Invalid annotations JSON: {}", e))?;
 
     let file = AnnotationsFile {
-        version: 1,
+        version: ANNOTATIONS_FILE_VERSION,
         pdf_path: pdf_path.clone(),
         annotations,
     };
@@ -65,18 +211,15 @@ pub fn annotations_save(pdf_path: String, annotations_json: String) -> Result<St
 }
 
 #[tauri::command]
-pub fn annotations_load(pdf_path: String) -> Result<Option<String>, String> {
-    let annotations_path = get_annotations_path(&pdf_path);
-
-    if !annotations_path.exists() {
+pub fn annotations_load(app: AppHandle, pdf_path: String) -> Result<Option<String>, String> {
+    let Some(annotations_path) = sidecar_read_path(&app, &pdf_path) else {
         return Ok(None);
-    }
+    };
 
     let content = fs::read_to_string(&annotations_path)
         .map_err(|e| format!("Failed to read annotations file: {}", e))?;
 
-    let file: AnnotationsFile = serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse annotations file: {}", e))?;
+    let file = parse_annotations_file(&content)?;
 
     let annotations_json = serde_json::to_string(&file.annotations)
         .map_err(|e| format!("Failed to serialize annotations: {}", e))?;
@@ -85,13 +228,380 @@ pub fn annotations_load(pdf_path: String) -> Result<Option<String>, String> {
 }
 
 #[tauri::command]
-pub fn annotations_delete(pdf_path: String) -> Result<(), String> {
-    let annotations_path = get_annotations_path(&pdf_path);
-
-    if annotations_path.exists() {
+pub fn annotations_delete(app: AppHandle, pdf_path: String) -> Result<(), String> {
+    // Remove both the configured and the legacy sidecar
+    while let Some(annotations_path) = sidecar_read_path(&app, &pdf_path) {
         fs::remove_file(&annotations_path)
             .map_err(|e| format!("Failed to delete annotations file: {}", e))?;
     }
 
     Ok(())
 }
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AnnotationPreview {
+    pub id: String,
+    /// Base64-encoded PNG
+    pub image_b64: String,
+}
+
+/// Fraction of the annotation's size added around it for context
+const PREVIEW_PADDING: f64 = 0.25;
+/// Smallest preview region, as a fraction of the page, so tiny marks keep legible context
+const PREVIEW_MIN_EXTENT: f64 = 0.1;
+
+/// Expand an annotation rect (normalized) into the page region shown in its preview
+fn preview_region(rect: &Rect) -> NormalizedRect {
+    let expand = |start: f64, extent: f64| -> (f64, f64) {
+        let padded = (extent * (1.0 + 2.0 * PREVIEW_PADDING)).clamp(PREVIEW_MIN_EXTENT, 1.0);
+        let begin = (start + extent / 2.0 - padded / 2.0).clamp(0.0, 1.0 - padded);
+        (begin, padded)
+    };
+    let (x, width) = expand(rect.x, rect.width);
+    let (y, height) = expand(rect.y, rect.height);
+
+    NormalizedRect {
+        x: x as f32,
+        y: y as f32,
+        width: width as f32,
+        height: height as f32,
+    }
+}
+
+fn parse_hex_color(color: &str) -> [u8; 3] {
+    let hex = color.trim_start_matches('#');
+    let channel = |i: usize| {
+        hex.get(i..i + 2)
+            .and_then(|c| u8::from_str_radix(c, 16).ok())
+            .unwrap_or(0)
+    };
+    if hex.len() >= 6 {
+        [channel(0), channel(2), channel(4)]
+    } else {
+        [255, 200, 0]
+    }
+}
+
+/// Bounding rect of each quad, or the annotation rect when it has no quads
+fn markup_rects(annotation: &Annotation) -> Vec<Rect> {
+    let Some(quads) = annotation.quad_points.as_ref().filter(|q| !q.is_empty()) else {
+        let r = &annotation.rect;
+        return vec![Rect { x: r.x, y: r.y, width: r.width, height: r.height }];
+    };
+
+    quads
+        .iter()
+        .map(|q| {
+            let xs = [q[0], q[2], q[4], q[6]];
+            let ys = [q[1], q[3], q[5], q[7]];
+            let min = |v: [f64; 4]| v.iter().copied().fold(f64::MAX, f64::min);
+            let max = |v: [f64; 4]| v.iter().copied().fold(f64::MIN, f64::max);
+            Rect { x: min(xs), y: min(ys), width: max(xs) - min(xs), height: max(ys) - min(ys) }
+        })
+        .collect()
+}
+
+/// Draw an approximation of the annotation onto an RGB preview region
+fn draw_annotation(samples: &mut [u8], width: usize, height: usize, region: &NormalizedRect, annotation: &Annotation) {
+    let is_markup = matches!(annotation.annotation_type.as_str(), "highlight" | "underline" | "strikethrough");
+    if is_markup {
+        // Multi-line markup is drawn line by line
+        for rect in markup_rects(annotation) {
+            draw_rect(samples, width, height, region, annotation, &rect);
+        }
+    } else {
+        draw_rect(samples, width, height, region, annotation, &annotation.rect);
+    }
+}
+
+fn draw_rect(samples: &mut [u8], width: usize, height: usize, region: &NormalizedRect, annotation: &Annotation, rect: &Rect) {
+    let stride = samples.len() / height.max(1);
+    let n = stride / width.max(1);
+    let to_px = |v: f64, origin: f32, extent: f32, size: usize| -> usize {
+        (((v as f32 - origin) / extent * size as f32).max(0.0) as usize).min(size)
+    };
+
+    let x0 = to_px(rect.x, region.x, region.width, width);
+    let x1 = to_px(rect.x + rect.width, region.x, region.width, width).max(x0 + 1).min(width);
+    let y0 = to_px(rect.y, region.y, region.height, height);
+    let y1 = to_px(rect.y + rect.height, region.y, region.height, height).max(y0 + 1).min(height);
+
+    let color = parse_hex_color(&annotation.color);
+    let alpha = annotation.opacity.clamp(0.1, 1.0);
+    let thickness = (width.max(height) / 64).max(1);
+
+    let mut blend = |x: usize, y: usize, a: f64| {
+        let i = y * stride + x * n;
+        for (c, &target) in color.iter().enumerate() {
+            let v = samples[i + c] as f64 * (1.0 - a) + target as f64 * a;
+            samples[i + c] = v.round() as u8;
+        }
+    };
+
+    match annotation.annotation_type.as_str() {
+        "highlight" => {
+            for y in y0..y1 {
+                for x in x0..x1 {
+                    blend(x, y, alpha.min(0.5));
+                }
+            }
+        }
+        "underline" | "strikethrough" => {
+            let mid = if annotation.annotation_type == "underline" {
+                y1.saturating_sub(thickness)
+            } else {
+                (y0 + y1) / 2
+            };
+            for y in mid..(mid + thickness).min(height) {
+                for x in x0..x1 {
+                    blend(x, y, alpha);
+                }
+            }
+        }
+        _ => {
+            // Shapes, ink, comments, stamps: outline the annotation bounds
+            for y in y0..y1 {
+                for x in x0..x1 {
+                    let edge = x < x0 + thickness || x + thickness >= x1 || y < y0 + thickness || y + thickness >= y1;
+                    if edge {
+                        blend(x, y, alpha);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Render small previews of sidecar annotations (page region around each rect, annotation drawn).
+/// The PDF is opened through the viewer's `DocumentCache`, so encrypted documents
+/// need `password` the first time, as with the other viewer renders.
+#[tauri::command]
+pub async fn annotations_render_previews(
+    app: AppHandle,
+    pdf_path: String,
+    ids: Vec<String>,
+    size: u32,
+    password: Option<String>,
+) -> Result<Vec<AnnotationPreview>, CommandError> {
+    let size = size.clamp(16, 1024);
+
+    // Renders a page region per annotation: keep it off the main thread
+    crate::run_blocking(move || {
+        let annotations_path = sidecar_read_path(&app, &pdf_path)
+            .ok_or_else(|| "No annotations saved for this document".to_string())?;
+        let content = fs::read_to_string(&annotations_path)
+            .map_err(|e| format!("Failed to read annotations file: {}", e))?;
+        let file = parse_annotations_file(&content)?;
+
+        let cache = app.state::<DocumentCache>();
+        cache.with_document(&pdf_path, password.as_deref(), |document| {
+            let mut previews = Vec::with_capacity(ids.len());
+
+            for id in ids {
+                let Some(annotation) = file.annotations.values().flatten().find(|a| a.id == id) else {
+                    log::warn!("Annotation {} not found in sidecar", id);
+                    continue;
+                };
+
+                let region = preview_region(&annotation.rect);
+                let mut pixmap = render_page_region(document, annotation.page, &region, size)?;
+
+                let (width, height) = (pixmap.width() as usize, pixmap.height() as usize);
+                draw_annotation(pixmap.samples_mut(), width, height, &region, annotation);
+
+                let mut png_data = Vec::new();
+                pixmap
+                    .write_to(&mut Cursor::new(&mut png_data), mupdf::ImageFormat::PNG)
+                    .map_err(|e| format!("Failed to encode PNG: {:?}", e))?;
+
+                previews.push(AnnotationPreview {
+                    id,
+                    image_b64: base64::engine::general_purpose::STANDARD.encode(&png_data),
+                });
+            }
+
+            Ok(previews)
+        })
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v1_file() -> serde_json::Value {
+        serde_json::json!({
+            "version": 1,
+            "pdf_path": "/docs/report.pdf",
+            "annotations": {
+                "1": [
+                    {
+                        "id": "h1",
+                        "type": "highlight",
+                        "page": 1,
+                        "rect": {"x": 0.1, "y": 0.2, "width": 0.5, "height": 0.25},
+                        "color": "#ffff00",
+                        "opacity": 0.5,
+                        "text": null,
+                        "createdAt": "",
+                        "modifiedAt": ""
+                    },
+                    {
+                        "id": "c1",
+                        "type": "comment",
+                        "page": 1,
+                        "rect": {"x": 0.5, "y": 0.5, "width": 0.02, "height": 0.02},
+                        "color": "#ffcc00",
+                        "opacity": 1.0,
+                        "text": "Check this",
+                        "createdAt": "",
+                        "modifiedAt": ""
+                    }
+                ]
+            }
+        })
+    }
+
+    #[test]
+    fn test_load_v1_file() {
+        let file = parse_annotations_file(&v1_file().to_string()).unwrap();
+        assert_eq!(file.version, ANNOTATIONS_FILE_VERSION);
+
+        let page = &file.annotations[&1];
+        assert_eq!(page[0].quad_points, Some(vec![[0.1, 0.2, 0.6, 0.2, 0.1, 0.45, 0.6, 0.45]]));
+        // Only text markup gets quads
+        assert!(page[1].quad_points.is_none());
+    }
+
+    #[test]
+    fn test_load_keeps_existing_quads() {
+        let mut value = v1_file();
+        value["annotations"]["1"][0]["quadPoints"] = serde_json::json!([[0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 1.0, 1.0]]);
+        let file = parse_annotations_file(&value.to_string()).unwrap();
+        assert_eq!(file.annotations[&1][0].quad_points, Some(vec![[0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 1.0, 1.0]]));
+    }
+
+    #[test]
+    fn test_load_newer_version() {
+        let mut value = v1_file();
+        value["version"] = (ANNOTATIONS_FILE_VERSION + 1).into();
+        let err = parse_annotations_file(&value.to_string()).unwrap_err();
+        assert!(err.contains("newer than this app supports"));
+    }
+
+    #[test]
+    fn test_sidecar_paths() {
+        assert_eq!(
+            get_annotations_path("/docs/report.pdf"),
+            PathBuf::from("/docs/.report.pdf.annotations.json")
+        );
+
+        let dir = Path::new("/sidecars");
+        let a = sidecar_in_dir(dir, "/a/report.pdf");
+        let b = sidecar_in_dir(dir, "/b/report.pdf");
+        assert_eq!(a.parent(), Some(dir));
+        assert!(a.to_string_lossy().ends_with("-report.pdf.annotations.json"));
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_preview_region() {
+        let rect = Rect { x: 0.4, y: 0.4, width: 0.2, height: 0.02 };
+        let region = preview_region(&rect);
+        assert!((region.width - 0.3).abs() < 1e-6);
+        assert!((region.height - 0.1).abs() < 1e-6);
+        assert!((region.x - 0.35).abs() < 1e-6);
+
+        // Regions near the edge are shifted to stay on the page
+        let corner = Rect { x: 0.95, y: 0.0, width: 0.05, height: 0.05 };
+        let region = preview_region(&corner);
+        assert!(region.x + region.width <= 1.0 + 1e-6);
+        assert!(region.y >= 0.0);
+    }
+
+    #[test]
+    fn test_markup_rects() {
+        let mut annotation: Annotation = serde_json::from_value(serde_json::json!({
+            "id": "a1",
+            "type": "highlight",
+            "page": 1,
+            "rect": {"x": 0.1, "y": 0.1, "width": 0.8, "height": 0.1},
+            "quadPoints": [
+                [0.5, 0.1, 0.9, 0.1, 0.5, 0.15, 0.9, 0.15],
+                [0.1, 0.15, 0.4, 0.15, 0.1, 0.2, 0.4, 0.2]
+            ],
+            "color": "#ffff00",
+            "opacity": 0.5,
+            "text": null,
+            "createdAt": "",
+            "modifiedAt": ""
+        }))
+        .unwrap();
+
+        let rects = markup_rects(&annotation);
+        assert_eq!(rects.len(), 2);
+        assert!((rects[0].x - 0.5).abs() < 1e-9 && (rects[0].width - 0.4).abs() < 1e-9);
+        assert!((rects[1].y - 0.15).abs() < 1e-9 && (rects[1].height - 0.05).abs() < 1e-9);
+
+        // Quads survive a sidecar round-trip
+        let json = serde_json::to_value(&annotation).unwrap();
+        assert_eq!(json["quadPoints"].as_array().map(|q| q.len()), Some(2));
+
+        annotation.quad_points = None;
+        assert_eq!(markup_rects(&annotation).len(), 1);
+        assert!(serde_json::to_value(&annotation).unwrap().get("quadPoints").is_none());
+    }
+
+    #[test]
+    fn test_ink_and_freetext_round_trip() {
+        let ink = serde_json::json!({
+            "id": "ink1",
+            "type": "ink",
+            "page": 2,
+            "rect": {"x": 0.1, "y": 0.1, "width": 0.2, "height": 0.2},
+            "paths": [{
+                "points": [{"x": 0.1, "y": 0.1}, {"x": 0.15, "y": 0.2}, {"x": 0.3, "y": 0.25}],
+                "strokeWidth": 0.003,
+                "color": "#ff0000"
+            }],
+            "color": "#ff0000",
+            "opacity": 1.0,
+            "text": null,
+            "createdAt": "",
+            "modifiedAt": ""
+        });
+        let annotation: Annotation = serde_json::from_value(ink.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&annotation).unwrap()["paths"], ink["paths"]);
+
+        let freetext: Annotation = serde_json::from_value(serde_json::json!({
+            "id": "ft1",
+            "type": "freetext",
+            "page": 1,
+            "rect": {"x": 0.1, "y": 0.1, "width": 0.3, "height": 0.05},
+            "color": "#000000",
+            "opacity": 1.0,
+            "text": "Callout",
+            "fontsize": 14.0,
+            "fontFamily": "serif",
+            "fill": {"enabled": true, "color": "#ffffcc"},
+            "strokeWidth": 0.002,
+            "borderColor": "#333333",
+            "createdAt": "",
+            "modifiedAt": ""
+        }))
+        .unwrap();
+        let json = serde_json::to_value(&freetext).unwrap();
+        assert_eq!(json["fontsize"], 14.0);
+        assert_eq!(json["fontFamily"], "serif");
+        assert_eq!(json["borderColor"], "#333333");
+        assert_eq!(json["fill"]["color"], "#ffffcc");
+        assert!(json.get("paths").is_none());
+    }
+
+    #[test]
+    fn test_parse_hex_color() {
+        assert_eq!(parse_hex_color("#ff8000"), [255, 128, 0]);
+        assert_eq!(parse_hex_color("bad"), [255, 200, 0]);
+    }
+}
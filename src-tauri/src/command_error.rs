@@ -0,0 +1,205 @@
+//! Structured errors for Tauri commands.
+//!
+//! Commands returning `CommandError` give the frontend a stable `code` to
+//! branch on (and localize) instead of matching message text. Codes use the
+//! same snake_case names as the Python error envelopes (`exceptions.py`), so a
+//! script's own code passes straight through.
+//!
+//! Helpers that still return `String` convert with `?`: the password
+//! sentinels from the document cache keep their codes, anything else becomes
+//! the generic `error`.
+
+use crate::pdf_viewer::{PASSWORD_INCORRECT, PASSWORD_REQUIRED};
+use crate::python_bridge::{PythonError, PythonErrorKind};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Stable error codes sent to the frontend
+pub mod codes {
+    /// Anything without a more specific code
+    pub const ERROR: &str = "error";
+    pub const FILE_NOT_FOUND: &str = "file_not_found";
+    /// The document is encrypted and no password was given
+    pub const PASSWORD_REQUIRED: &str = "password_required";
+    /// The given password doesn't open the document
+    pub const PASSWORD_INCORRECT: &str = "password_incorrect";
+    pub const INVALID_ARGUMENT: &str = "invalid_argument";
+    /// MuPDF failed to read or write the document
+    pub const PDF_ERROR: &str = "pdf_error";
+    pub const PYTHON_NOT_FOUND: &str = "python_not_found";
+    pub const SCRIPT_NOT_FOUND: &str = "script_not_found";
+    pub const MISSING_DEPENDENCY: &str = "missing_dependency";
+    /// A script exited with an error and didn't report its own code
+    pub const SCRIPT_FAILED: &str = "script_failed";
+    /// A script's output couldn't be parsed
+    pub const BAD_OUTPUT: &str = "bad_output";
+    pub const TIMEOUT: &str = "timeout";
+}
+
+/// Error returned to the frontend: a stable `code`, a readable `message` and
+/// optional `detail` (e.g. script stderr) for logs and bug reports
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CommandError {
+    pub code: String,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+pub type CommandResult<T> = Result<T, CommandError>;
+
+impl CommandError {
+    pub fn new(code: &str, message: impl Into<String>) -> Self {
+        Self {
+            code: code.to_string(),
+            message: message.into(),
+            detail: None,
+        }
+    }
+
+    pub fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into()).filter(|d: &String| !d.trim().is_empty());
+        self
+    }
+
+    /// Prefix the message (e.g. "OCR failed: ..."), keeping the code
+    pub fn context(mut self, context: &str) -> Self {
+        self.message = format!("{}: {}", context, self.message);
+        self
+    }
+
+    /// A script's output that didn't parse as the expected JSON
+    pub fn bad_output(what: &str, error: impl std::fmt::Display) -> Self {
+        Self::new(
+            codes::BAD_OUTPUT,
+            format!("Failed to parse {}: {}", what, error),
+        )
+    }
+
+    /// A MuPDF failure while doing `action` (e.g. "Failed to load PDF")
+    pub fn pdf(action: &str, error: impl std::fmt::Debug) -> Self {
+        Self::new(codes::PDF_ERROR, format!("{}: {:?}", action, error))
+    }
+}
+
+/// `file_not_found` unless `path` exists
+pub fn ensure_exists(path: &str) -> CommandResult<()> {
+    if Path::new(path).exists() {
+        return Ok(());
+    }
+    Err(CommandError::new(
+        codes::FILE_NOT_FOUND,
+        format!("File not found: {}", path),
+    ))
+}
+
+impl std::fmt::Display for CommandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+impl From<PythonError> for CommandError {
+    fn from(error: PythonError) -> Self {
+        let code = error.code.clone().unwrap_or_else(|| {
+            match error.kind {
+                PythonErrorKind::PythonNotFound => codes::PYTHON_NOT_FOUND,
+                PythonErrorKind::ScriptNotFound => codes::SCRIPT_NOT_FOUND,
+                PythonErrorKind::SpawnFailed => codes::PYTHON_NOT_FOUND,
+                PythonErrorKind::ExecutionFailed => codes::SCRIPT_FAILED,
+                PythonErrorKind::MissingDependency => codes::MISSING_DEPENDENCY,
+                PythonErrorKind::InvalidArgs => codes::INVALID_ARGUMENT,
+                PythonErrorKind::Timeout => codes::TIMEOUT,
+            }
+            .to_string()
+        });
+        let detail = error.stderr.filter(|s| !s.trim().is_empty());
+        // Without an envelope the exception is only on stderr; its last line
+        // ("ValueError: ...") is what the user needs to see
+        let message = match detail
+            .as_deref()
+            .and_then(|s| s.lines().rev().find(|l| !l.trim().is_empty()))
+        {
+            Some(line)
+                if error.code.is_none() && error.kind == PythonErrorKind::ExecutionFailed =>
+            {
+                format!("{}: {}", error.message, line.trim())
+            }
+            _ => error.message,
+        };
+        Self {
+            code,
+            message,
+            detail,
+        }
+    }
+}
+
+impl From<String> for CommandError {
+    fn from(message: String) -> Self {
+        let code = match message.as_str() {
+            PASSWORD_REQUIRED => codes::PASSWORD_REQUIRED,
+            PASSWORD_INCORRECT => codes::PASSWORD_INCORRECT,
+            _ => codes::ERROR,
+        };
+        Self::new(code, message)
+    }
+}
+
+impl From<&str> for CommandError {
+    fn from(message: &str) -> Self {
+        message.to_string().into()
+    }
+}
+
+/// For `String`-returning callers of converted commands
+impl From<CommandError> for String {
+    fn from(error: CommandError) -> Self {
+        error.message
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_python_error_codes() {
+        let err: CommandError =
+            PythonError::execution_failed("boom", None, Some("trace".into()), Some(1)).into();
+        assert_eq!(err.code, codes::SCRIPT_FAILED);
+        assert_eq!(err.message, "boom: trace");
+        assert_eq!(err.detail.as_deref(), Some("trace"));
+
+        let err: CommandError =
+            PythonError::script_error("file_not_found", "missing.pdf", None, None, Some(1)).into();
+        assert_eq!(err.code, codes::FILE_NOT_FOUND);
+        assert_eq!(err.message, "missing.pdf");
+    }
+
+    #[test]
+    fn test_string_errors_keep_password_codes() {
+        assert_eq!(
+            CommandError::from(PASSWORD_REQUIRED.to_string()).code,
+            codes::PASSWORD_REQUIRED
+        );
+        assert_eq!(
+            CommandError::from(PASSWORD_INCORRECT).code,
+            codes::PASSWORD_INCORRECT
+        );
+        assert_eq!(CommandError::from("Page 9 out of range").code, codes::ERROR);
+    }
+
+    #[test]
+    fn test_serializes_without_empty_detail() {
+        let json =
+            serde_json::to_value(CommandError::new(codes::TIMEOUT, "slow").with_detail("  "))
+                .unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({"code": "timeout", "message": "slow"})
+        );
+    }
+}
@@ -0,0 +1,407 @@
+//! Edit sessions: the ordered list of `apply-edits` operations made to a document.
+//!
+//! This module provides Tauri commands for:
+//! - Opening a session, which snapshots the document into the app cache
+//! - Applying edits against on-disk snapshots with undo/redo
+//! - Exporting the history as a replayable JSON edit-script
+//! - Committing the result to a file, or closing the session without saving
+
+use crate::python_bridge::PythonBridge;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, State};
+
+/// Current edit-script format version
+pub const EDIT_SCRIPT_VERSION: u32 = 1;
+
+/// Operation types understood by `pdf_edit.py apply-edits`
+const EDIT_OP_TYPES: &[&str] = &["insert_text", "replace_text", "draw_shape", "delete"];
+
+/// One document being edited and its snapshot history
+#[derive(Debug, Clone, Default)]
+pub struct EditSession {
+    pub input: String,
+    pub history: SnapshotHistory,
+}
+
+/// Document snapshots of a session: `snapshots[0]` is the original and
+/// `snapshots[i]` the result of applying `steps[i - 1]` to `snapshots[i - 1]`.
+/// Entries after `cursor` are the redo stack.
+#[derive(Debug, Clone, Default)]
+pub struct SnapshotHistory {
+    pub dir: PathBuf,
+    pub snapshots: Vec<PathBuf>,
+    pub steps: Vec<Vec<serde_json::Value>>,
+    pub cursor: usize,
+}
+
+impl SnapshotHistory {
+    fn new(dir: PathBuf, original: PathBuf) -> Self {
+        Self {
+            dir,
+            snapshots: vec![original],
+            steps: Vec::new(),
+            cursor: 0,
+        }
+    }
+
+    /// Snapshot the current state reflects
+    pub fn current(&self) -> &Path {
+        &self.snapshots[self.cursor]
+    }
+
+    /// Fresh path for the snapshot the next step will write. Never reuses a
+    /// redo entry's file, so a failed apply leaves the redo stack intact.
+    fn next_snapshot(&self) -> PathBuf {
+        self.dir.join(format!("{}.pdf", uuid::Uuid::new_v4()))
+    }
+
+    /// Record a new step on top of the current state, dropping the redo
+    /// stack. Returns the snapshots that are no longer reachable.
+    fn push(&mut self, snapshot: PathBuf, ops: Vec<serde_json::Value>) -> Vec<PathBuf> {
+        let discarded = self.snapshots.split_off(self.cursor + 1);
+        self.steps.truncate(self.cursor);
+        self.snapshots.push(snapshot);
+        self.steps.push(ops);
+        self.cursor += 1;
+        discarded
+    }
+
+    fn undo(&mut self) -> bool {
+        if self.cursor == 0 {
+            return false;
+        }
+        self.cursor -= 1;
+        true
+    }
+
+    fn redo(&mut self) -> bool {
+        if self.cursor + 1 >= self.snapshots.len() {
+            return false;
+        }
+        self.cursor += 1;
+        true
+    }
+
+    /// Operations that produced the current state, in order
+    pub fn ops(&self) -> Vec<serde_json::Value> {
+        self.steps[..self.cursor].iter().flatten().cloned().collect()
+    }
+}
+
+/// Where a snapshot session stands, returned by every history command
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EditSessionState {
+    pub session_id: String,
+    /// Snapshot reflecting the current state, for rendering
+    pub current: String,
+    /// Steps applied up to the current state
+    pub position: usize,
+    pub can_undo: bool,
+    pub can_redo: bool,
+}
+
+impl EditSessionState {
+    fn of(session_id: &str, history: &SnapshotHistory) -> Self {
+        Self {
+            session_id: session_id.to_string(),
+            current: history.current().to_string_lossy().to_string(),
+            position: history.cursor,
+            can_undo: history.cursor > 0,
+            can_redo: history.cursor + 1 < history.snapshots.len(),
+        }
+    }
+}
+
+/// All open edit sessions, keyed by session id
+#[derive(Default)]
+pub struct EditSessionStore(pub Mutex<HashMap<String, EditSession>>);
+
+/// A replayable sequence of edits (the `ops` of the apply-edits format)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EditScript {
+    pub version: u32,
+    /// Document the edits were recorded on (informational)
+    #[serde(default)]
+    pub source: Option<String>,
+    pub ops: Vec<serde_json::Value>,
+}
+
+fn find_session<'a>(
+    sessions: &'a mut HashMap<String, EditSession>,
+    session_id: &str,
+) -> Result<&'a mut EditSession, String> {
+    sessions
+        .get_mut(session_id)
+        .ok_or_else(|| format!("Unknown edit session: {}", session_id))
+}
+
+/// Drop a session and delete its snapshot directory
+fn remove_session(sessions: &mut HashMap<String, EditSession>, session_id: &str) -> Result<(), String> {
+    let session = sessions
+        .remove(session_id)
+        .ok_or_else(|| format!("Unknown edit session: {}", session_id))?;
+    let _ = std::fs::remove_dir_all(session.history.dir);
+    Ok(())
+}
+
+/// Open a session with undo/redo: the input is copied into a snapshot
+/// directory in the app cache and every applied batch becomes a new snapshot
+#[tauri::command]
+pub fn edit_session_begin(
+    app: AppHandle,
+    store: State<'_, EditSessionStore>,
+    input: String,
+) -> Result<EditSessionState, String> {
+    let session_id = uuid::Uuid::new_v4().to_string();
+    let dir = app
+        .path()
+        .app_cache_dir()
+        .unwrap_or_else(|_| std::env::temp_dir())
+        .join("edit-sessions")
+        .join(&session_id);
+
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create session directory: {}", e))?;
+
+    let original = dir.join("0000.pdf");
+    std::fs::copy(&input, &original).map_err(|e| {
+        let _ = std::fs::remove_dir_all(&dir);
+        format!("Failed to snapshot {}: {}", input, e)
+    })?;
+
+    let history = SnapshotHistory::new(dir, original);
+    let state = EditSessionState::of(&session_id, &history);
+
+    let mut sessions = store.0.lock().map_err(|e| e.to_string())?;
+    sessions.insert(session_id, EditSession { input, history });
+    Ok(state)
+}
+
+/// Apply an `apply-edits` batch to the current snapshot and push the result
+/// onto the undo stack (discarding anything that could have been redone)
+#[tauri::command]
+pub async fn edit_session_apply(
+    app: AppHandle,
+    session_id: String,
+    edits_json: String,
+) -> Result<EditSessionState, String> {
+    crate::run_blocking(move || {
+        let store = app.state::<EditSessionStore>();
+        let edits: serde_json::Value =
+            serde_json::from_str(&edits_json).map_err(|e| format!("Invalid edits JSON: {}", e))?;
+        let ops = edits["ops"].as_array().cloned().unwrap_or_default();
+
+        // Don't hold the store lock while Python runs
+        let (current, next) = {
+            let mut sessions = store.0.lock().map_err(|e| e.to_string())?;
+            let history = &find_session(&mut sessions, &session_id)?.history;
+            (history.current().to_path_buf(), history.next_snapshot())
+        };
+
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+        let current_str = current.to_string_lossy().to_string();
+        let next_str = next.to_string_lossy().to_string();
+        let args: Vec<&str> = vec![
+            "apply-edits",
+            "--input", &current_str,
+            "--output", &next_str,
+            "--edits", &edits_json,
+            "--json",
+        ];
+
+        let applied = bridge
+            .run_script("pdf_edit.py", &args)
+            .map_err(|e| e.to_string())
+            .and_then(|result| {
+                serde_json::from_str::<serde_json::Value>(&result.stdout)
+                    .map_err(|e| format!("Failed to parse result: {}", e))
+            })
+            .and_then(|parsed| match parsed["success"].as_bool() {
+                Some(true) => Ok(()),
+                _ => Err(parsed["message"].as_str().unwrap_or("Failed to apply edits").to_string()),
+            });
+        if let Err(e) = applied {
+            let _ = std::fs::remove_file(&next);
+            return Err(e);
+        }
+
+        let mut sessions = store.0.lock().map_err(|e| e.to_string())?;
+        let history = &mut find_session(&mut sessions, &session_id)?.history;
+        for stale in history.push(next, ops) {
+            let _ = std::fs::remove_file(stale);
+        }
+
+        Ok(EditSessionState::of(&session_id, history))
+    })
+    .await
+}
+
+/// Step back to the previous snapshot
+#[tauri::command]
+pub fn edit_session_undo(
+    store: State<'_, EditSessionStore>,
+    session_id: String,
+) -> Result<EditSessionState, String> {
+    let mut sessions = store.0.lock().map_err(|e| e.to_string())?;
+    let history = &mut find_session(&mut sessions, &session_id)?.history;
+    if !history.undo() {
+        return Err("Nothing to undo".to_string());
+    }
+    Ok(EditSessionState::of(&session_id, history))
+}
+
+/// Step forward to the next snapshot after an undo
+#[tauri::command]
+pub fn edit_session_redo(
+    store: State<'_, EditSessionStore>,
+    session_id: String,
+) -> Result<EditSessionState, String> {
+    let mut sessions = store.0.lock().map_err(|e| e.to_string())?;
+    let history = &mut find_session(&mut sessions, &session_id)?.history;
+    if !history.redo() {
+        return Err("Nothing to redo".to_string());
+    }
+    Ok(EditSessionState::of(&session_id, history))
+}
+
+/// Write the current snapshot to `output`, close the session and delete its snapshots
+#[tauri::command]
+pub fn edit_session_commit(
+    store: State<'_, EditSessionStore>,
+    session_id: String,
+    output: String,
+) -> Result<String, String> {
+    let mut sessions = store.0.lock().map_err(|e| e.to_string())?;
+    let history = &find_session(&mut sessions, &session_id)?.history;
+
+    std::fs::copy(history.current(), &output)
+        .map_err(|e| format!("Failed to write {}: {}", output, e))?;
+
+    remove_session(&mut sessions, &session_id)?;
+    Ok(output)
+}
+
+/// Close a session without writing anything, deleting its snapshots
+#[tauri::command]
+pub fn edit_session_close(store: State<'_, EditSessionStore>, session_id: String) -> Result<(), String> {
+    let mut sessions = store.0.lock().map_err(|e| e.to_string())?;
+    remove_session(&mut sessions, &session_id)
+}
+
+/// Export the operations behind a session's current state as a JSON edit-script
+#[tauri::command]
+pub fn edit_session_export(
+    store: State<'_, EditSessionStore>,
+    session_id: String,
+) -> Result<String, String> {
+    let mut sessions = store.0.lock().map_err(|e| e.to_string())?;
+    let session = find_session(&mut sessions, &session_id)?;
+
+    let script = EditScript {
+        version: EDIT_SCRIPT_VERSION,
+        source: Some(session.input.clone()),
+        ops: session.history.ops(),
+    };
+
+    serde_json::to_string_pretty(&script).map_err(|e| format!("Failed to serialize script: {}", e))
+}
+
+/// Parse an edit-script and check every op fits a target with `page_count` pages.
+/// Pages are 0-indexed and rects normalized (0-1), as in the apply-edits format.
+pub fn validate_edit_script(script: &str, page_count: u32) -> Result<Vec<serde_json::Value>, String> {
+    let script: EditScript =
+        serde_json::from_str(script).map_err(|e| format!("Invalid edit script: {}", e))?;
+
+    if script.version > EDIT_SCRIPT_VERSION {
+        return Err(format!(
+            "Edit script version {} is newer than supported ({})",
+            script.version, EDIT_SCRIPT_VERSION
+        ));
+    }
+    if script.ops.is_empty() {
+        return Err("Edit script contains no operations".to_string());
+    }
+
+    for (i, op) in script.ops.iter().enumerate() {
+        let op_type = op["type"].as_str().unwrap_or("");
+        if !EDIT_OP_TYPES.contains(&op_type) {
+            return Err(format!("Op {}: unsupported type '{}'", i, op_type));
+        }
+
+        let page = op["page"]
+            .as_u64()
+            .ok_or_else(|| format!("Op {}: missing page", i))?;
+        if page >= page_count as u64 {
+            return Err(format!(
+                "Op {}: page {} is out of range for a {}-page document",
+                i,
+                page + 1,
+                page_count
+            ));
+        }
+
+        let rect = &op["rect"];
+        let coord = |key: &str| rect[key].as_f64().ok_or_else(|| format!("Op {}: rect.{} missing", i, key));
+        let (x, y, w, h) = (coord("x")?, coord("y")?, coord("width")?, coord("height")?);
+        // Lines may be zero-width or zero-height, so only reject negative sizes
+        if x < 0.0 || y < 0.0 || w < 0.0 || h < 0.0 || x + w > 1.0 + 1e-6 || y + h > 1.0 + 1e-6 {
+            return Err(format!("Op {}: rect lies outside the page", i));
+        }
+    }
+
+    Ok(script.ops)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn op(n: u64) -> serde_json::Value {
+        serde_json::json!({ "type": "insert_text", "page": n })
+    }
+
+    #[test]
+    fn test_snapshot_history() {
+        let dir = PathBuf::from("/tmp/session");
+        let mut history = SnapshotHistory::new(dir.clone(), dir.join("0000.pdf"));
+        assert!(!history.undo());
+
+        assert!(history.push(dir.join("0001.pdf"), vec![op(0)]).is_empty());
+        assert!(history.push(dir.join("0002.pdf"), vec![op(1), op(2)]).is_empty());
+        assert_eq!(history.current(), dir.join("0002.pdf"));
+        assert_eq!(history.ops().len(), 3);
+
+        assert!(history.undo());
+        assert!(history.undo());
+        assert_eq!(history.current(), dir.join("0000.pdf"));
+        assert!(history.ops().is_empty());
+        assert!(history.redo());
+        assert_eq!(history.ops(), vec![op(0)]);
+
+        // A new step replaces the redo stack
+        let stale = history.push(dir.join("0003.pdf"), vec![op(5)]);
+        assert_eq!(stale, vec![dir.join("0002.pdf")]);
+        assert_eq!(history.ops(), vec![op(0), op(5)]);
+        assert!(!history.redo());
+    }
+
+    #[test]
+    fn test_validate_edit_script() {
+        let script = r#"{"version": 1, "ops": [
+            {"type": "insert_text", "page": 1, "rect": {"x": 0.1, "y": 0.1, "width": 0.3, "height": 0.05}, "text": "Hi"}
+        ]}"#;
+
+        assert_eq!(validate_edit_script(script, 2).unwrap().len(), 1);
+        assert!(validate_edit_script(script, 1).is_err());
+
+        let outside = script.replace("\"x\": 0.1", "\"x\": 0.9");
+        assert!(validate_edit_script(&outside, 2).is_err());
+
+        let unknown = script.replace("insert_text", "erase_everything");
+        assert!(validate_edit_script(&unknown, 2).is_err());
+    }
+}
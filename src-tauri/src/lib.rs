@@ -1,16 +1,21 @@
 use serde::{Deserialize, Serialize};
 
 mod annotations;
+mod command_error;
+mod edit_session;
 mod pdf_compress;
 mod pdf_ocr;
 mod pdf_viewer;
+mod pdf_watermark;
 mod python_bridge;
+mod thumbnail_cache;
 
+use command_error::CommandError;
 use python_bridge::PythonBridge;
 use tauri::menu::{MenuBuilder, MenuItemBuilder, SubmenuBuilder};
-use tauri::{AppHandle, Emitter, Manager};
+use tauri::{AppHandle, Emitter, Manager, State};
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Default, Deserialize, Serialize)]
 struct ImageTransform {
     rotation: Option<i32>,
     flip_h: Option<bool>,
@@ -18,6 +23,83 @@ struct ImageTransform {
     orientation: Option<String>, // "auto", "portrait", "landscape"
 }
 
+impl ImageTransform {
+    /// Whether the user rotated or flipped the image themselves
+    fn is_explicit(&self) -> bool {
+        self.rotation.unwrap_or(0) != 0
+            || self.flip_h.unwrap_or(false)
+            || self.flip_v.unwrap_or(false)
+    }
+}
+
+/// Rotation (degrees clockwise) and horizontal/vertical flips, applied in that
+/// order, that turn a photo upright according to its EXIF orientation tag.
+/// None when the image has no tag or can't be read.
+fn exif_orientation_transform(path: &str) -> Option<(i32, bool, bool)> {
+    use image::metadata::Orientation;
+    use image::ImageDecoder;
+
+    let mut decoder = image::ImageReader::open(path)
+        .ok()?
+        .with_guessed_format()
+        .ok()?
+        .into_decoder()
+        .ok()?;
+    Some(match decoder.orientation().ok()? {
+        Orientation::NoTransforms => return None,
+        Orientation::Rotate90 => (90, false, false),
+        Orientation::Rotate180 => (180, false, false),
+        Orientation::Rotate270 => (270, false, false),
+        Orientation::FlipHorizontal => (0, true, false),
+        Orientation::FlipVertical => (0, false, true),
+        Orientation::Rotate90FlipH => (90, true, false),
+        Orientation::Rotate270FlipH => (270, true, false),
+    })
+}
+
+/// One transform per image, with the EXIF orientation filled in for images whose
+/// orientation is "auto" (per image, else `orientation`, which defaults to "auto")
+/// and that the user didn't rotate or flip explicitly (explicit values win over EXIF)
+fn resolve_image_transforms(
+    images: &[String],
+    transforms: Option<Vec<ImageTransform>>,
+    orientation: Option<&str>,
+) -> Vec<ImageTransform> {
+    let mut transforms = transforms.unwrap_or_default().into_iter();
+    images
+        .iter()
+        .map(|path| {
+            let mut transform = transforms.next().unwrap_or_default();
+            let auto = transform
+                .orientation
+                .as_deref()
+                .or(orientation)
+                .map_or(true, |o| o == "auto");
+            if auto && !transform.is_explicit() {
+                if let Some((rotation, flip_h, flip_v)) = exif_orientation_transform(path) {
+                    transform.rotation = Some(rotation);
+                    transform.flip_h = Some(flip_h);
+                    transform.flip_v = Some(flip_v);
+                }
+            }
+            transform
+        })
+        .collect()
+}
+
+/// Run a command's work on the blocking thread pool. Sync commands run on the
+/// main thread, where waiting for a script slot (or the script itself) would
+/// freeze the UI.
+pub(crate) async fn run_blocking<T, E>(work: impl FnOnce() -> Result<T, E> + Send + 'static) -> Result<T, E>
+where
+    T: Send + 'static,
+    E: From<String> + Send + 'static,
+{
+    tauri::async_runtime::spawn_blocking(work)
+        .await
+        .map_err(|e| E::from(format!("Command task failed: {:?}", e)))?
+}
+
 // ============================================================================
 // Python Bridge Commands
 // ============================================================================
@@ -25,7 +107,7 @@ struct ImageTransform {
 /// Check if Python is available and return version info
 #[tauri::command]
 fn python_check(app: AppHandle) -> Result<PythonStatus, String> {
-    let bridge = PythonBridge::new(&app).map_err(|e| e.to_string())?;
+    let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
 
     let version = bridge.python_version().map_err(|e| e.to_string())?;
     let path = bridge.python_path().to_string_lossy().to_string();
@@ -47,7 +129,7 @@ struct PythonStatus {
 /// Check if specific Python packages are installed
 #[tauri::command]
 fn python_check_packages(app: AppHandle, packages: Vec<String>) -> Result<PackageCheckResult, String> {
-    let bridge = PythonBridge::new(&app).map_err(|e| e.to_string())?;
+    let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
 
     let pkg_refs: Vec<&str> = packages.iter().map(|s| s.as_str()).collect();
     let missing = bridge.check_packages(&pkg_refs).map_err(|e| e.to_string())?;
@@ -67,21 +149,74 @@ struct PackageCheckResult {
 /// Install a Python package
 #[tauri::command]
 fn python_install_package(app: AppHandle, package: String) -> Result<(), String> {
-    let bridge = PythonBridge::new(&app).map_err(|e| e.to_string())?;
+    let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
     bridge.install_package(&package).map_err(|e| e.to_string())
 }
 
+/// Scripts running and waiting for a slot, e.g. to show "2 operations waiting"
+#[tauri::command]
+fn python_queue_status(
+    limiter: State<'_, python_bridge::ScriptLimiter>,
+) -> python_bridge::QueueStatus {
+    limiter.status()
+}
+
+/// Change how many Python scripts may run at once (persisted in settings)
+#[tauri::command]
+fn python_set_max_concurrent(
+    app: AppHandle,
+    limiter: State<'_, python_bridge::ScriptLimiter>,
+    max_concurrent: usize,
+) -> Result<(), String> {
+    limiter.configure(&app, max_concurrent).map_err(|e| e.to_string())
+}
+
+/// Use a specific Python interpreter (or venv/conda directory) for all scripts.
+/// Returns the interpreter's version.
+#[tauri::command]
+fn python_set_interpreter(app: AppHandle, path: String) -> Result<String, String> {
+    PythonBridge::set_interpreter(&app, &path).map_err(|e| e.to_string())
+}
+
 // ============================================================================
 // PDF Compression Commands
 // ============================================================================
 
-/// Compress a PDF file
+/// Compress a PDF file, optionally toward a target size (`target_bytes`).
+/// `detailed` adds a per-stream breakdown of image and font bytes.
 #[tauri::command]
-fn compress_pdf(
+async fn compress_pdf(
+    app: AppHandle,
+    input: String,
+    output: Option<String>,
+    level: Option<String>,
+    verify: Option<bool>,
+    target_bytes: Option<u64>,
+    detailed: Option<bool>,
+) -> Result<pdf_compress::CompressionResult, String> {
+    run_blocking(move || {
+        compress_pdf_blocking(
+            app,
+            input,
+            output,
+            level,
+            verify,
+            target_bytes,
+            detailed,
+        )
+    })
+    .await
+}
+
+/// Blocking body of `compress_pdf`, also run by `batch_run`
+fn compress_pdf_blocking(
     app: AppHandle,
     input: String,
     output: Option<String>,
     level: Option<String>,
+    verify: Option<bool>,
+    target_bytes: Option<u64>,
+    detailed: Option<bool>,
 ) -> Result<pdf_compress::CompressionResult, String> {
     let output_path = output.unwrap_or_else(|| {
         let cache_dir = app
@@ -100,7 +235,81 @@ fn compress_pdf(
         _ => pdf_compress::CompressionLevel::Medium,
     };
 
-    pdf_compress::compress_pdf(&input, &output_path, compression_level)
+    let verify = verify.unwrap_or(false);
+    let detailed = detailed.unwrap_or(false);
+
+    let Some(target_bytes) = target_bytes else {
+        return pdf_compress::compress_pdf(&input, &output_path, compression_level, verify, detailed);
+    };
+
+    // Target-size mode: progressively cap image resolution via the Python helper
+    let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+    let downsample = |src: &str, dst: &str, max_dpi: u32, quality: u8| -> Result<(), String> {
+        let max_dpi = max_dpi.to_string();
+        let quality = quality.to_string();
+        let args: Vec<&str> = vec![
+            "downsample-images",
+            "--input", src,
+            "--output", dst,
+            "--max-dpi", &max_dpi,
+            "--quality", &quality,
+            "--json",
+        ];
+
+        let result = bridge
+            .run_script("pdf_edit.py", &args)
+            .map_err(|e| e.to_string())?;
+
+        let parsed: serde_json::Value = serde_json::from_str(&result.stdout)
+            .map_err(|e| format!("Failed to parse result: {}", e))?;
+
+        if !parsed["success"].as_bool().unwrap_or(false) {
+            return Err(parsed["message"].as_str().unwrap_or("Image downsampling failed").to_string());
+        }
+        Ok(())
+    };
+
+    pdf_compress::compress_to_target(
+        &input,
+        &output_path,
+        compression_level,
+        target_bytes,
+        verify,
+        detailed,
+        downsample,
+    )
+}
+
+/// Dry-run a compression level and render one page (1-indexed) before and after
+#[tauri::command]
+async fn preview_compression(
+    app: AppHandle,
+    input: String,
+    level: Option<String>,
+    page: Option<u32>,
+) -> Result<pdf_compress::CompressionPreview, String> {
+    // A full compression run plus two renders: keep it off the main thread
+    run_blocking(move || {
+        let scratch = app
+            .path()
+            .app_cache_dir()
+            .unwrap_or_else(|_| std::env::temp_dir())
+            .join(format!("tlacuilo-compress-preview-{}.pdf", uuid::Uuid::new_v4()));
+
+        let compression_level = match level.as_deref() {
+            Some("low") => pdf_compress::CompressionLevel::Low,
+            Some("high") => pdf_compress::CompressionLevel::High,
+            _ => pdf_compress::CompressionLevel::Medium,
+        };
+
+        pdf_compress::preview_compression(
+            &input,
+            &scratch.to_string_lossy(),
+            compression_level,
+            page.unwrap_or(1),
+        )
+    })
+    .await
 }
 
 /// Estimate compression potential for a PDF
@@ -115,144 +324,212 @@ fn estimate_compression(input: String) -> Result<pdf_compress::EstimationResult,
 
 /// Check OCR dependencies
 #[tauri::command]
-fn ocr_check_dependencies(app: AppHandle) -> Result<pdf_ocr::OcrDependencies, String> {
-    pdf_ocr::check_dependencies(&app)
+async fn ocr_check_dependencies(app: AppHandle) -> Result<pdf_ocr::OcrDependencies, CommandError> {
+    run_blocking(move || {
+        pdf_ocr::check_dependencies(&app)
+    })
+    .await
 }
 
 /// Analyze PDF for OCR needs
 #[tauri::command]
-fn ocr_analyze_pdf(app: AppHandle, input: String) -> Result<pdf_ocr::OcrAnalysis, String> {
-    pdf_ocr::analyze_pdf(&app, &input)
+async fn ocr_analyze_pdf(app: AppHandle, input: String) -> Result<pdf_ocr::OcrAnalysis, CommandError> {
+    run_blocking(move || {
+        pdf_ocr::analyze_pdf(&app, &input)
+    })
+    .await
 }
 
 /// Run OCR on a PDF
 #[tauri::command]
-fn ocr_run(
+async fn ocr_run(
     app: AppHandle,
     input: String,
     output: Option<String>,
     options: Option<pdf_ocr::OcrOptions>,
-) -> Result<pdf_ocr::OcrResult, String> {
-    let output_path = output.unwrap_or_else(|| {
-        let cache_dir = app
-            .path()
-            .app_cache_dir()
-            .unwrap_or_else(|_| std::env::temp_dir());
-
-        // Create a session directory with UUID to avoid conflicts
-        let session_id = uuid::Uuid::new_v4().to_string();
-        let session_dir = cache_dir.join("ocr-sessions").join(&session_id);
-
-        // Create the session directory if it doesn't exist
-        let _ = std::fs::create_dir_all(&session_dir);
-
-        // Preserve original filename
-        let original_filename = std::path::Path::new(&input)
-            .file_name()
-            .map(|f| f.to_string_lossy().to_string())
-            .unwrap_or_else(|| "document.pdf".to_string());
-
-        session_dir
-            .join(&original_filename)
-            .to_string_lossy()
-            .to_string()
-    });
-
-    let opts = options.unwrap_or_default();
-    pdf_ocr::run_ocr(&app, &input, &output_path, opts)
+) -> Result<pdf_ocr::OcrResult, CommandError> {
+    run_blocking(move || {
+        let output_path = output.unwrap_or_else(|| {
+            let cache_dir = app
+                .path()
+                .app_cache_dir()
+                .unwrap_or_else(|_| std::env::temp_dir());
+
+            // Create a session directory with UUID to avoid conflicts
+            let session_id = uuid::Uuid::new_v4().to_string();
+            let session_dir = cache_dir.join("ocr-sessions").join(&session_id);
+
+            // Create the session directory if it doesn't exist
+            let _ = std::fs::create_dir_all(&session_dir);
+
+            // Preserve original filename
+            let original_filename = std::path::Path::new(&input)
+                .file_name()
+                .map(|f| f.to_string_lossy().to_string())
+                .unwrap_or_else(|| "document.pdf".to_string());
+
+            session_dir
+                .join(&original_filename)
+                .to_string_lossy()
+                .to_string()
+        });
+
+        let opts = options.unwrap_or_default();
+        pdf_ocr::run_ocr(&app, &input, &output_path, opts)
+    })
+    .await
 }
 
 /// Run editable OCR on a PDF (creates real text objects with accurate font sizes)
 #[tauri::command]
-fn ocr_run_editable(
+async fn ocr_run_editable(
     app: AppHandle,
     input: String,
     output: Option<String>,
     options: Option<pdf_ocr::EditableOcrOptions>,
-) -> Result<pdf_ocr::EditableOcrResult, String> {
-    let output_path = output.unwrap_or_else(|| {
-        let cache_dir = app
-            .path()
-            .app_cache_dir()
-            .unwrap_or_else(|_| std::env::temp_dir());
-
-        // Create a session directory with UUID to avoid conflicts
-        let session_id = uuid::Uuid::new_v4().to_string();
-        let session_dir = cache_dir.join("ocr-editable-sessions").join(&session_id);
+) -> Result<pdf_ocr::EditableOcrResult, CommandError> {
+    run_blocking(move || {
+        let output_path = output.unwrap_or_else(|| {
+            let cache_dir = app
+                .path()
+                .app_cache_dir()
+                .unwrap_or_else(|_| std::env::temp_dir());
+
+            // Create a session directory with UUID to avoid conflicts
+            let session_id = uuid::Uuid::new_v4().to_string();
+            let session_dir = cache_dir.join("ocr-editable-sessions").join(&session_id);
+
+            // Create the session directory if it doesn't exist
+            let _ = std::fs::create_dir_all(&session_dir);
+
+            // Preserve original filename
+            let original_filename = std::path::Path::new(&input)
+                .file_name()
+                .map(|f| f.to_string_lossy().to_string())
+                .unwrap_or_else(|| "document.pdf".to_string());
+
+            session_dir
+                .join(&original_filename)
+                .to_string_lossy()
+                .to_string()
+        });
+
+        let opts = options.unwrap_or_default();
+        pdf_ocr::run_editable_ocr(&app, &input, &output_path, opts)
+    })
+    .await
+}
 
-        // Create the session directory if it doesn't exist
-        let _ = std::fs::create_dir_all(&session_dir);
+/// Get embedded OCR metrics from a PDF
+#[tauri::command]
+async fn ocr_get_metrics(app: AppHandle, input: String) -> Result<pdf_ocr::OcrMetricsResult, CommandError> {
+    run_blocking(move || {
+        pdf_ocr::get_ocr_metrics(&app, &input)
+    })
+    .await
+}
 
-        // Preserve original filename
-        let original_filename = std::path::Path::new(&input)
-            .file_name()
-            .map(|f| f.to_string_lossy().to_string())
-            .unwrap_or_else(|| "document.pdf".to_string());
+/// List installed Tesseract language packs
+#[tauri::command]
+async fn ocr_list_languages(app: AppHandle) -> Result<pdf_ocr::LanguageList, CommandError> {
+    run_blocking(move || {
+        pdf_ocr::list_languages(&app)
+    })
+    .await
+}
 
-        session_dir
-            .join(&original_filename)
-            .to_string_lossy()
-            .to_string()
-    });
+/// Install a Tesseract language pack (e.g. "spa")
+#[tauri::command]
+async fn ocr_install_language(app: AppHandle, code: String) -> Result<pdf_ocr::LanguageInstallResult, CommandError> {
+    run_blocking(move || {
+        pdf_ocr::install_language(&app, &code)
+    })
+    .await
+}
 
-    let opts = options.unwrap_or_default();
-    pdf_ocr::run_editable_ocr(&app, &input, &output_path, opts)
+/// Detect the document language(s) so the OCR language can be pre-filled
+#[tauri::command]
+async fn ocr_detect_language(app: AppHandle, input: String) -> Result<pdf_ocr::LanguageDetection, CommandError> {
+    run_blocking(move || {
+        pdf_ocr::detect_language(&app, &input)
+    })
+    .await
 }
 
-/// Get embedded OCR metrics from a PDF
+/// Detect whether a scanned PDF is a form and where its empty fields are
 #[tauri::command]
-fn ocr_get_metrics(app: AppHandle, input: String) -> Result<pdf_ocr::OcrMetricsResult, String> {
-    pdf_ocr::get_ocr_metrics(&app, &input)
+async fn pdf_detect_scanned_form(
+    app: AppHandle,
+    input: String,
+    language: Option<String>,
+    force_ocr: Option<bool>,
+) -> Result<pdf_ocr::ScannedFormDetection, CommandError> {
+    run_blocking(move || {
+        let language = language.unwrap_or_else(|| "eng".to_string());
+        pdf_ocr::detect_scanned_form(&app, &input, &language, force_ocr.unwrap_or(false))
+    })
+    .await
 }
 
 // ============================================================================
 // Annotation Embedding Commands (PythonBridge)
 // ============================================================================
 
-/// Embed annotations from JSON into a PDF file
+/// Embed annotations from JSON into a PDF file.
+/// `incremental` appends the changes instead of rewriting the file, keeping
+/// existing digital signatures valid.
 #[tauri::command]
-fn annotations_embed_in_pdf(
+async fn annotations_embed_in_pdf(
     app: AppHandle,
     input: String,
     annotations_json: String,
     output: Option<String>,
+    incremental: Option<bool>,
 ) -> Result<AnnotationEmbedResult, String> {
-    let output_path = output.unwrap_or_else(|| {
-        let cache_dir = app
-            .path()
-            .app_cache_dir()
-            .unwrap_or_else(|_| std::env::temp_dir());
-        cache_dir
-            .join("tlacuilo-annotated.pdf")
-            .to_string_lossy()
-            .to_string()
-    });
-
-    let bridge = PythonBridge::new(&app).map_err(|e| e.to_string())?;
-
-    let args: Vec<&str> = vec![
-        "embed",
-        "--input", &input,
-        "--annotations", &annotations_json,
-        "--output", &output_path,
-    ];
+    run_blocking(move || {
+        let output_path = output.unwrap_or_else(|| {
+            let cache_dir = app
+                .path()
+                .app_cache_dir()
+                .unwrap_or_else(|_| std::env::temp_dir());
+            cache_dir
+                .join("tlacuilo-annotated.pdf")
+                .to_string_lossy()
+                .to_string()
+        });
+
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+
+        let mut args: Vec<&str> = vec![
+            "embed",
+            "--input", &input,
+            "--annotations", &annotations_json,
+            "--output", &output_path,
+        ];
+        if incremental.unwrap_or(false) {
+            args.push("--incremental");
+        }
 
-    let result = bridge
-        .run_script("pdf_annotations.py", &args)
-        .map_err(|e| e.to_string())?;
+        let result = bridge
+            .run_script("pdf_annotations.py", &args)
+            .map_err(|e| e.to_string())?;
 
-    // Parse the JSON output
-    let stats: serde_json::Value = serde_json::from_str(&result.stdout)
-        .map_err(|e| format!("Failed to parse result: {}", e))?;
+        // Parse the JSON output
+        let stats: serde_json::Value = serde_json::from_str(&result.stdout)
+            .map_err(|e| format!("Failed to parse result: {}", e))?;
 
-    Ok(AnnotationEmbedResult {
-        output_path,
-        total: stats["total"].as_u64().unwrap_or(0) as u32,
-        errors: stats["errors"]
-            .as_array()
-            .map(|arr| arr.iter().filter_map(|v| v.as_str().map(String::from)).collect())
-            .unwrap_or_default(),
+        Ok(AnnotationEmbedResult {
+            output_path,
+            total: stats["total"].as_u64().unwrap_or(0) as u32,
+            errors: stats["errors"]
+                .as_array()
+                .map(|arr| arr.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+                .unwrap_or_default(),
+            signatures_invalidated: string_list(&stats["signatures_invalidated"]),
+            signature_check: stats["signature_check"].as_str().map(String::from),
+        })
     })
+    .await
 }
 
 #[derive(Debug, Serialize)]
@@ -260,45 +537,57 @@ struct AnnotationEmbedResult {
     output_path: String,
     total: u32,
     errors: Vec<String>,
+    /// Signatures valid before an incremental save that no longer validate
+    signatures_invalidated: Vec<String>,
+    /// For incremental saves: "checked", or "unavailable" when signatures
+    /// couldn't be validated (pyHanko missing) and `signatures_invalidated`
+    /// says nothing
+    signature_check: Option<String>,
 }
 
 /// Read annotations from a PDF file and return as JSON
 #[tauri::command]
-fn annotations_read_from_pdf(app: AppHandle, input: String) -> Result<String, String> {
-    let bridge = PythonBridge::new(&app).map_err(|e| e.to_string())?;
+async fn annotations_read_from_pdf(app: AppHandle, input: String) -> Result<String, String> {
+    run_blocking(move || {
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
 
-    let args: Vec<&str> = vec!["read", "--input", &input];
+        let args: Vec<&str> = vec!["read", "--input", &input];
 
-    let result = bridge
-        .run_script("pdf_annotations.py", &args)
-        .map_err(|e| e.to_string())?;
+        let result = bridge
+            .run_script("pdf_annotations.py", &args)
+            .map_err(|e| e.to_string())?;
 
-    // Return the JSON directly
-    Ok(result.stdout.trim().to_string())
+        // Return the JSON directly
+        Ok(result.stdout.trim().to_string())
+    })
+    .await
 }
 
 /// Export annotations from PDF to XFDF format
 #[tauri::command]
-fn annotations_export_xfdf(
+async fn annotations_export_xfdf(
     app: AppHandle,
     input: String,
     output: String,
 ) -> Result<XfdfExportResult, String> {
-    let bridge = PythonBridge::new(&app).map_err(|e| e.to_string())?;
+    run_blocking(move || {
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
 
-    let args: Vec<&str> = vec!["export-xfdf", "--input", &input, "--output", &output];
+        let args: Vec<&str> = vec!["export-xfdf", "--input", &input, "--output", &output];
 
-    let result = bridge
-        .run_script("pdf_annotations.py", &args)
-        .map_err(|e| e.to_string())?;
+        let result = bridge
+            .run_script("pdf_annotations.py", &args)
+            .map_err(|e| e.to_string())?;
 
-    let stats: serde_json::Value = serde_json::from_str(&result.stdout)
-        .map_err(|e| format!("Failed to parse result: {}", e))?;
+        let stats: serde_json::Value = serde_json::from_str(&result.stdout)
+            .map_err(|e| format!("Failed to parse result: {}", e))?;
 
-    Ok(XfdfExportResult {
-        output_path: output,
-        exported: stats["exported"].as_u64().unwrap_or(0) as u32,
+        Ok(XfdfExportResult {
+            output_path: output,
+            exported: stats["exported"].as_u64().unwrap_or(0) as u32,
+        })
     })
+    .await
 }
 
 #[derive(Debug, Serialize)]
@@ -309,47 +598,50 @@ struct XfdfExportResult {
 
 /// Import annotations from XFDF into a PDF
 #[tauri::command]
-fn annotations_import_xfdf(
+async fn annotations_import_xfdf(
     app: AppHandle,
     input: String,
     xfdf: String,
     output: Option<String>,
 ) -> Result<AnnotationEmbedResult, String> {
-    let output_path = output.unwrap_or_else(|| {
-        let cache_dir = app
-            .path()
-            .app_cache_dir()
-            .unwrap_or_else(|_| std::env::temp_dir());
-        cache_dir
-            .join("tlacuilo-xfdf-imported.pdf")
-            .to_string_lossy()
-            .to_string()
-    });
-
-    let bridge = PythonBridge::new(&app).map_err(|e| e.to_string())?;
-
-    let args: Vec<&str> = vec![
-        "import-xfdf",
-        "--input", &input,
-        "--xfdf", &xfdf,
-        "--output", &output_path,
-    ];
-
-    let result = bridge
-        .run_script("pdf_annotations.py", &args)
-        .map_err(|e| e.to_string())?;
-
-    let stats: serde_json::Value = serde_json::from_str(&result.stdout)
-        .map_err(|e| format!("Failed to parse result: {}", e))?;
-
-    Ok(AnnotationEmbedResult {
-        output_path,
-        total: stats["total"].as_u64().unwrap_or(0) as u32,
-        errors: stats["errors"]
-            .as_array()
-            .map(|arr| arr.iter().filter_map(|v| v.as_str().map(String::from)).collect())
-            .unwrap_or_default(),
+    run_blocking(move || {
+        let output_path = output.unwrap_or_else(|| {
+            let cache_dir = app
+                .path()
+                .app_cache_dir()
+                .unwrap_or_else(|_| std::env::temp_dir());
+            cache_dir
+                .join("tlacuilo-xfdf-imported.pdf")
+                .to_string_lossy()
+                .to_string()
+        });
+
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+
+        let args: Vec<&str> = vec![
+            "import-xfdf",
+            "--input", &input,
+            "--xfdf", &xfdf,
+            "--output", &output_path,
+        ];
+
+        let result = bridge
+            .run_script("pdf_annotations.py", &args)
+            .map_err(|e| e.to_string())?;
+
+        let stats: serde_json::Value = serde_json::from_str(&result.stdout)
+            .map_err(|e| format!("Failed to parse result: {}", e))?;
+
+        Ok(AnnotationEmbedResult {
+            output_path,
+            total: stats["total"].as_u64().unwrap_or(0) as u32,
+            errors: stats["errors"]
+                .as_array()
+                .map(|arr| arr.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+                .unwrap_or_default(),
+        })
     })
+    .await
 }
 
 // ============================================================================
@@ -363,42 +655,45 @@ struct PrintPrepareResult {
 
 /// Prepare a PDF for printing by optionally embedding annotations
 #[tauri::command]
-fn print_prepare_pdf(
+async fn print_prepare_pdf(
     app: AppHandle,
     input: String,
     annotations_json: String,
 ) -> Result<PrintPrepareResult, String> {
-    // Create a temp file for the annotated PDF
-    let cache_dir = app
-        .path()
-        .app_cache_dir()
-        .unwrap_or_else(|_| std::env::temp_dir());
-
-    // Ensure the cache directory exists
-    std::fs::create_dir_all(&cache_dir)
-        .map_err(|e| format!("Failed to create cache directory: {}", e))?;
-
-    let temp_path = cache_dir
-        .join(format!("tlacuilo-print-{}.pdf", uuid::Uuid::new_v4()))
-        .to_string_lossy()
-        .to_string();
+    run_blocking(move || {
+        // Create a temp file for the annotated PDF
+        let cache_dir = app
+            .path()
+            .app_cache_dir()
+            .unwrap_or_else(|_| std::env::temp_dir());
 
-    let bridge = PythonBridge::new(&app).map_err(|e| e.to_string())?;
+        // Ensure the cache directory exists
+        std::fs::create_dir_all(&cache_dir)
+            .map_err(|e| format!("Failed to create cache directory: {}", e))?;
 
-    let args: Vec<&str> = vec![
-        "embed",
-        "--input", &input,
-        "--annotations", &annotations_json,
-        "--output", &temp_path,
-    ];
+        let temp_path = cache_dir
+            .join(format!("tlacuilo-print-{}.pdf", uuid::Uuid::new_v4()))
+            .to_string_lossy()
+            .to_string();
 
-    bridge
-        .run_script("pdf_annotations.py", &args)
-        .map_err(|e| e.to_string())?;
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+
+        let args: Vec<&str> = vec![
+            "embed",
+            "--input", &input,
+            "--annotations", &annotations_json,
+            "--output", &temp_path,
+        ];
 
-    Ok(PrintPrepareResult {
-        output_path: temp_path,
+        bridge
+            .run_script("pdf_annotations.py", &args)
+            .map_err(|e| e.to_string())?;
+
+        Ok(PrintPrepareResult {
+            output_path: temp_path,
+        })
     })
+    .await
 }
 
 /// Open a PDF file in the system's print dialog
@@ -464,1111 +759,4230 @@ fn print_pdf(path: String) -> Result<(), String> {
     Ok(())
 }
 
-// ============================================================================
-// PDF Operations Commands (PythonBridge)
-// ============================================================================
-
-#[tauri::command]
-fn merge_pdfs(app: AppHandle, inputs: Vec<String>, output: Option<String>) -> Result<String, String> {
-    if inputs.len() < 2 {
-        return Err("Provide at least two PDF paths to merge.".into());
-    }
+/// Result of `print_pdf_direct`
+#[derive(Debug, Serialize)]
+struct PrintJobResult {
+    /// The job reached the print queue
+    submitted: bool,
+    /// Queue job id (e.g. "Office-42") when the print system reports one
+    job_id: Option<String>,
+    /// The job couldn't be submitted directly, so the print dialog was opened instead
+    used_dialog: bool,
+}
 
-    let output_path = output.unwrap_or_else(|| {
-        let cache_dir = app
-            .path()
-            .app_cache_dir()
-            .unwrap_or_else(|_| std::env::temp_dir());
-        cache_dir.join("tlacuilo-merge.pdf").to_string_lossy().to_string()
-    });
+enum DirectPrint {
+    Submitted(Option<String>),
+    /// The job couldn't be submitted directly (no print tooling, or it refused the job)
+    Unavailable,
+}
 
-    let bridge = PythonBridge::new(&app).map_err(|e| e.to_string())?;
+/// Job id from `lp` output ("request id is Office-42 (1 file(s))")
+#[cfg(unix)]
+fn parse_lp_job_id(stdout: &str) -> Option<String> {
+    stdout
+        .split("request id is ")
+        .nth(1)?
+        .split_whitespace()
+        .next()
+        .map(str::to_string)
+}
 
-    let mut args = vec!["merge", "--output", &output_path, "--inputs"];
-    let input_refs: Vec<&str> = inputs.iter().map(|s| s.as_str()).collect();
-    args.extend(input_refs);
+/// Submit through CUPS `lp`, or BSD `lpr` where `lp` is missing
+#[cfg(unix)]
+fn submit_print_job(path: &str, printer: Option<&str>, copies: u32, duplex: bool) -> DirectPrint {
+    let sides = if duplex {
+        "sides=two-sided-long-edge"
+    } else {
+        "sides=one-sided"
+    };
+    let copies = copies.to_string();
 
-    bridge
-        .run_script("pdf_pages.py", &args)
-        .map_err(|e| e.to_string())?;
+    let mut lp = std::process::Command::new("lp");
+    if let Some(name) = printer {
+        lp.args(["-d", name]);
+    }
+    lp.args(["-n", &copies, "-o", sides, path]);
+    let out = match lp.output() {
+        Ok(out) => out,
+        Err(_) => {
+            let mut lpr = std::process::Command::new("lpr");
+            if let Some(name) = printer {
+                lpr.args(["-P", name]);
+            }
+            lpr.args(["-#", &copies, "-o", sides, path]);
+            match lpr.output() {
+                Ok(out) => out,
+                Err(_) => return DirectPrint::Unavailable,
+            }
+        }
+    };
 
-    Ok(output_path)
+    if out.status.success() {
+        // lpr doesn't report the job id, so this is None on that path
+        DirectPrint::Submitted(parse_lp_job_id(&String::from_utf8_lossy(&out.stdout)))
+    } else {
+        log::warn!(
+            "Direct printing failed, falling back to the print dialog: {}",
+            String::from_utf8_lossy(&out.stderr).trim()
+        );
+        DirectPrint::Unavailable
+    }
 }
 
-#[tauri::command]
-fn merge_pages(
-    app: AppHandle,
-    pages: Vec<(String, i32)>,
-    output: Option<String>,
-) -> Result<String, String> {
-    if pages.is_empty() {
-        return Err("Provide at least one page specification.".into());
+/// Rasterize every page of `path` into `dir` as 300 DPI PNGs, in page order
+#[cfg(windows)]
+fn render_pages_for_print(path: &str, dir: &std::path::Path) -> Result<Vec<String>, String> {
+    const PRINT_DPI: i32 = 300;
+
+    std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create print directory: {}", e))?;
+    let document =
+        mupdf::Document::open(path).map_err(|e| format!("Failed to open PDF: {:?}", e))?;
+    let scale = PRINT_DPI as f32 / 72.0;
+    let mut pages = Vec::new();
+    for (index, page) in document
+        .pages()
+        .map_err(|e| format!("Failed to read pages: {:?}", e))?
+        .enumerate()
+    {
+        let page = page.map_err(|e| format!("Failed to load page {}: {:?}", index + 1, e))?;
+        let mut pixmap = page
+            .to_pixmap(
+                &mupdf::Matrix::new_scale(scale, scale),
+                &mupdf::Colorspace::device_rgb(),
+                false,
+                true,
+            )
+            .map_err(|e| format!("Failed to render page {}: {:?}", index + 1, e))?;
+        // Recorded in the PNG so GDI+ knows the page's physical size
+        pixmap.set_resolution(PRINT_DPI, PRINT_DPI);
+        let page_path = dir.join(format!("page-{:05}.png", index + 1)).to_string_lossy().to_string();
+        pixmap
+            .save_as(&page_path, mupdf::ImageFormat::PNG)
+            .map_err(|e| format!("Failed to write page {}: {:?}", index + 1, e))?;
+        pages.push(page_path);
     }
+    Ok(pages)
+}
 
-    let output_path = output.unwrap_or_else(|| {
-        let cache_dir = app
-            .path()
-            .app_cache_dir()
-            .unwrap_or_else(|_| std::env::temp_dir());
-        cache_dir
-            .join("tlacuilo-merged-pages.pdf")
-            .to_string_lossy()
-            .to_string()
+/// Print through the Windows print API (GDI+ `PrintDocument`): the pages are
+/// rendered with MuPDF and drawn onto the printer as one job, with the copy
+/// count and duplex mode set on the job itself. Windows doesn't hand back a
+/// job id for GDI+ jobs.
+#[cfg(windows)]
+fn submit_print_job(path: &str, printer: Option<&str>, copies: u32, duplex: bool) -> DirectPrint {
+    // Arguments go through the environment so paths and printer names need no quoting
+    const SCRIPT: &str = r#"
+$ErrorActionPreference = 'Stop'
+Add-Type -AssemblyName System.Drawing
+$doc = New-Object System.Drawing.Printing.PrintDocument
+if ($env:TLACUILO_PRINTER) { $doc.PrinterSettings.PrinterName = $env:TLACUILO_PRINTER }
+if (-not $doc.PrinterSettings.IsValid) { throw "Unknown printer: $($doc.PrinterSettings.PrinterName)" }
+$doc.DocumentName = $env:TLACUILO_PRINT_TITLE
+$doc.PrinterSettings.Copies = [int]$env:TLACUILO_PRINT_COPIES
+$doc.PrinterSettings.Collate = $true
+if ($env:TLACUILO_PRINT_DUPLEX -eq '1' -and $doc.PrinterSettings.CanDuplex) {
+    $doc.PrinterSettings.Duplex = [System.Drawing.Printing.Duplex]::Vertical
+} else {
+    $doc.PrinterSettings.Duplex = [System.Drawing.Printing.Duplex]::Simplex
+}
+# No "Printing page N" progress window
+$doc.PrintController = New-Object System.Drawing.Printing.StandardPrintController
+$script:pages = @(Get-Content -LiteralPath $env:TLACUILO_PRINT_PAGES)
+$script:index = 0
+$doc.add_QueryPageSettings({
+    param($sender, $e)
+    $img = [System.Drawing.Image]::FromFile($script:pages[$script:index])
+    try { $e.PageSettings.Landscape = $img.Width -gt $img.Height } finally { $img.Dispose() }
+})
+$doc.add_PrintPage({
+    param($sender, $e)
+    $img = [System.Drawing.Image]::FromFile($script:pages[$script:index])
+    try {
+        # Page units are 1/100 inch; shrink to the printable area if needed, never enlarge
+        $area = $e.PageSettings.PrintableArea
+        if ($e.PageSettings.Landscape) { $w = $area.Height; $h = $area.Width } else { $w = $area.Width; $h = $area.Height }
+        $iw = $img.Width / $img.HorizontalResolution * 100
+        $ih = $img.Height / $img.VerticalResolution * 100
+        $scale = [Math]::Min(1, [Math]::Min($w / $iw, $h / $ih))
+        $dw = $iw * $scale; $dh = $ih * $scale
+        $e.Graphics.DrawImage($img, [single](($w - $dw) / 2), [single](($h - $dh) / 2), [single]$dw, [single]$dh)
+    } finally { $img.Dispose() }
+    $script:index++
+    $e.HasMorePages = $script:index -lt $script:pages.Count
+})
+$doc.Print()
+"#;
+
+    let dir = std::env::temp_dir().join(format!("tlacuilo-print-{}", uuid::Uuid::new_v4()));
+    let result = render_pages_for_print(path, &dir).and_then(|pages| {
+        let list = dir.join("pages.txt");
+        std::fs::write(&list, pages.join("\r\n"))
+            .map_err(|e| format!("Failed to write page list: {}", e))?;
+        let title = std::path::Path::new(path)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let out = std::process::Command::new("powershell")
+            .args(["-NoProfile", "-NonInteractive", "-Command", SCRIPT])
+            .env("TLACUILO_PRINT_PAGES", &list)
+            .env("TLACUILO_PRINT_TITLE", title)
+            .env("TLACUILO_PRINTER", printer.unwrap_or(""))
+            .env("TLACUILO_PRINT_COPIES", copies.to_string())
+            .env("TLACUILO_PRINT_DUPLEX", if duplex { "1" } else { "0" })
+            .output()
+            .map_err(|e| format!("Failed to run PowerShell: {}", e))?;
+        if out.status.success() {
+            Ok(())
+        } else {
+            Err(String::from_utf8_lossy(&out.stderr).trim().to_string())
+        }
     });
+    let _ = std::fs::remove_dir_all(&dir);
 
-    let bridge = PythonBridge::new(&app).map_err(|e| e.to_string())?;
+    match result {
+        Ok(()) => DirectPrint::Submitted(None),
+        Err(e) => {
+            log::warn!("Direct printing failed, falling back to the print dialog: {}", e);
+            DirectPrint::Unavailable
+        }
+    }
+}
 
-    // Convert pages to format: file:page file:page ...
-    let page_args: Vec<String> = pages
-        .iter()
-        .map(|(file, page)| format!("{}:{}", file, page))
-        .collect();
+/// Print without a dialog to `printer` (the system default when omitted).
+/// Falls back to `print_pdf`'s dialog when the job can't be submitted directly,
+/// whether the platform has no way to do it or the print system rejects the job.
+#[tauri::command]
+async fn print_pdf_direct(
+    path: String,
+    printer: Option<String>,
+    copies: Option<u32>,
+    duplex: Option<bool>,
+) -> Result<PrintJobResult, String> {
+    // Waits on lp/lpr, or renders every page for GDI+ on Windows
+    run_blocking(move || {
+        if !std::path::Path::new(&path).exists() {
+            return Err(format!("File not found: {}", path));
+        }
+        let copies = copies.unwrap_or(1);
+        if copies == 0 {
+            return Err("Copies must be at least 1.".into());
+        }
+        let printer = printer.filter(|p| !p.trim().is_empty());
 
-    let mut args = vec!["merge-pages", "--output", &output_path, "--pages"];
-    let page_refs: Vec<&str> = page_args.iter().map(|s| s.as_str()).collect();
-    args.extend(page_refs);
+        match submit_print_job(&path, printer.as_deref(), copies, duplex.unwrap_or(false)) {
+            DirectPrint::Submitted(job_id) => Ok(PrintJobResult {
+                submitted: true,
+                job_id,
+                used_dialog: false,
+            }),
+            DirectPrint::Unavailable => {
+                print_pdf(path)?;
+                Ok(PrintJobResult {
+                    submitted: false,
+                    job_id: None,
+                    used_dialog: true,
+                })
+            }
+        }
+    })
+    .await
+}
 
-    bridge
-        .run_script("pdf_pages.py", &args)
-        .map_err(|e| e.to_string())?;
+/// An installed printer, as listed by `list_printers`
+#[derive(Debug, Serialize, PartialEq)]
+struct PrinterInfo {
+    name: String,
+    is_default: bool,
+    location: Option<String>,
+    /// "idle", "printing", "disabled", "offline", ... ("unknown" when not reported)
+    status: String,
+}
 
-    Ok(output_path)
+/// Printers from `lpstat -l -p` output plus the `lpstat -d` default line
+#[cfg(unix)]
+fn parse_lpstat_printers(printers: &str, default: &str) -> Vec<PrinterInfo> {
+    let default_name = default
+        .trim()
+        .strip_prefix("system default destination:")
+        .map(str::trim);
+    let mut result: Vec<PrinterInfo> = Vec::new();
+    for line in printers.lines() {
+        if let Some(rest) = line.strip_prefix("printer ") {
+            let Some((name, state)) = rest.split_once(' ') else {
+                continue;
+            };
+            let status = if state.starts_with("disabled") {
+                "disabled"
+            } else if state.starts_with("now printing") {
+                "printing"
+            } else if state.starts_with("is idle") {
+                "idle"
+            } else {
+                "unknown"
+            };
+            result.push(PrinterInfo {
+                name: name.to_string(),
+                is_default: default_name == Some(name),
+                location: None,
+                status: status.to_string(),
+            });
+        } else if let Some(location) = line.trim().strip_prefix("Location:") {
+            if let Some(printer) = result.last_mut() {
+                printer.location = Some(location.trim().to_string()).filter(|l| !l.is_empty());
+            }
+        }
+    }
+    result
 }
 
-#[tauri::command]
-fn split_pdf(
-    app: AppHandle,
-    input: String,
-    output_dir: Option<String>,
-    ranges: Option<Vec<String>>,
-) -> Result<Vec<String>, String> {
-    let out_dir = output_dir.unwrap_or_else(|| {
-        let cache_dir = app
-            .path()
-            .app_cache_dir()
-            .unwrap_or_else(|_| std::env::temp_dir());
-        cache_dir.join("tlacuilo-split").to_string_lossy().to_string()
-    });
+/// Printers from the Win32_Printer JSON written by PowerShell
+#[cfg(windows)]
+fn parse_win32_printers(json: &str) -> Result<Vec<PrinterInfo>, String> {
+    #[derive(Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct Win32Printer {
+        name: String,
+        #[serde(default)]
+        default: bool,
+        location: Option<String>,
+        printer_status: Option<u16>,
+    }
 
-    let bridge = PythonBridge::new(&app).map_err(|e| e.to_string())?;
+    if json.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    let printers: Vec<Win32Printer> =
+        serde_json::from_str(json).map_err(|e| format!("Failed to parse printer list: {}", e))?;
+    Ok(printers
+        .into_iter()
+        .map(|p| PrinterInfo {
+            name: p.name,
+            is_default: p.default,
+            location: p.location.filter(|l| !l.trim().is_empty()),
+            status: match p.printer_status {
+                Some(3) => "idle",
+                Some(4) => "printing",
+                Some(5) => "warming_up",
+                Some(6) => "stopped",
+                Some(7) => "offline",
+                _ => "unknown",
+            }
+            .to_string(),
+        })
+        .collect())
+}
 
-    let mut args: Vec<&str> = vec!["split", "--input", &input, "--output-dir", &out_dir];
+/// Installed printers, for picking a `print_pdf_direct` target. No printers
+/// (or no print system at all) gives an empty list, not an error.
+#[tauri::command]
+async fn list_printers() -> Result<Vec<PrinterInfo>, String> {
+    // lpstat can hang on an unresponsive CUPS daemon: keep it off the main thread
+    run_blocking(installed_printers).await
+}
 
-    // Add ranges if provided
-    let range_refs: Vec<String> = ranges.as_ref().map(|r| r.clone()).unwrap_or_default();
-    if !range_refs.is_empty() {
-        args.push("--ranges");
-        for r in &range_refs {
-            args.push(r);
-        }
+/// Blocking body of `list_printers`
+fn installed_printers() -> Result<Vec<PrinterInfo>, String> {
+    #[cfg(unix)]
+    {
+        // lpstat exits non-zero with "No destinations added" when nothing is
+        // installed, and is missing entirely without CUPS
+        let lpstat = |args: &[&str]| {
+            std::process::Command::new("lpstat")
+                .args(args)
+                .env("LC_ALL", "C")
+                .output()
+                .ok()
+                .filter(|out| out.status.success())
+                .map(|out| String::from_utf8_lossy(&out.stdout).to_string())
+                .unwrap_or_default()
+        };
+        Ok(parse_lpstat_printers(&lpstat(&["-l", "-p"]), &lpstat(&["-d"])))
     }
 
-    bridge
-        .run_script("pdf_pages.py", &args)
-        .map_err(|e| e.to_string())?;
-
-    // Return the output directory and the number of files created based on ranges
-    let num_files = ranges.as_ref().map(|r| r.len()).unwrap_or(0);
-    let mut result = vec![out_dir.clone()];
-    for i in 1..=num_files.max(1) {
-        result.push(format!("{}/split_{}.pdf", out_dir, i));
+    #[cfg(windows)]
+    {
+        let output = std::process::Command::new("powershell")
+            .args([
+                "-NoProfile",
+                "-NonInteractive",
+                "-Command",
+                "ConvertTo-Json -Compress -InputObject @(Get-CimInstance Win32_Printer | \
+                    Select-Object Name, Default, Location, PrinterStatus)",
+            ])
+            .output()
+            .map_err(|e| format!("Failed to list printers: {}", e))?;
+        if !output.status.success() {
+            return Err(format!(
+                "Failed to list printers: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        parse_win32_printers(&String::from_utf8_lossy(&output.stdout))
     }
-    Ok(result)
 }
 
+// ============================================================================
+// PDF Operations Commands (PythonBridge)
+// ============================================================================
+
+/// Concatenate PDFs. Unless `preserve_bookmarks` is false, each source's outline
+/// is kept under a top-level bookmark named after the file.
 #[tauri::command]
-fn rotate_pdf(
+async fn merge_pdfs(
     app: AppHandle,
-    input: String,
-    degrees: i32,
+    inputs: Vec<String>,
     output: Option<String>,
-    rotations: Option<Vec<String>>,
+    preserve_bookmarks: Option<bool>,
 ) -> Result<String, String> {
-    let out_path = output.unwrap_or_else(|| {
-        let cache_dir = app
-            .path()
-            .app_cache_dir()
-            .unwrap_or_else(|_| std::env::temp_dir());
-        cache_dir.join("tlacuilo-rotated.pdf").to_string_lossy().to_string()
-    });
-
-    let bridge = PythonBridge::new(&app).map_err(|e| e.to_string())?;
-
-    let degrees_str = degrees.to_string();
-    let mut args: Vec<&str> = vec!["rotate", "--input", &input, "--output", &out_path];
+    run_blocking(move || {
+        if inputs.len() < 2 {
+            return Err("Provide at least two PDF paths to merge.".into());
+        }
 
-    // Clone rotations to extend lifetime
-    let rotation_refs: Vec<String> = rotations.unwrap_or_default();
-    if !rotation_refs.is_empty() {
-        args.push("--rotation");
-        for r in &rotation_refs {
-            args.push(r);
+        let output_path = output.unwrap_or_else(|| {
+            let cache_dir = app
+                .path()
+                .app_cache_dir()
+                .unwrap_or_else(|_| std::env::temp_dir());
+            cache_dir.join("tlacuilo-merge.pdf").to_string_lossy().to_string()
+        });
+
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+
+        let mut args = vec!["merge", "--output", &output_path, "--inputs"];
+        let input_refs: Vec<&str> = inputs.iter().map(|s| s.as_str()).collect();
+        args.extend(input_refs);
+        if !preserve_bookmarks.unwrap_or(true) {
+            args.push("--no-bookmarks");
         }
-    } else {
-        args.push("--degrees");
-        args.push(&degrees_str);
-    }
 
-    bridge
-        .run_script("pdf_pages.py", &args)
-        .map_err(|e| e.to_string())?;
+        bridge
+            .run_script("pdf_pages.py", &args)
+            .map_err(|e| e.to_string())?;
 
-    Ok(out_path)
+        Ok(output_path)
+    })
+    .await
 }
 
 #[tauri::command]
-fn images_to_pdf(
+async fn merge_pages(
     app: AppHandle,
-    images: Vec<String>,
+    pages: Vec<(String, i32)>,
     output: Option<String>,
-    page_size: Option<String>,
-    orientation: Option<String>,
-    margin: Option<f64>,
-    transforms: Option<Vec<ImageTransform>>,
 ) -> Result<String, String> {
-    if images.is_empty() {
-        return Err("Provide at least one image path.".into());
-    }
-
-    let output_path = output.unwrap_or_else(|| {
-        let cache_dir = app
-            .path()
-            .app_cache_dir()
-            .unwrap_or_else(|_| std::env::temp_dir());
-        cache_dir.join("tlacuilo-images.pdf").to_string_lossy().to_string()
-    });
-
-    let bridge = PythonBridge::new(&app).map_err(|e| e.to_string())?;
-
-    let mut args: Vec<String> = vec![
-        "images-to-pdf".to_string(),
-        "--output".to_string(),
-        output_path.clone(),
-        "--inputs".to_string(),
-    ];
-    args.extend(images);
-
-    if let Some(size) = page_size {
-        args.push("--page-size".to_string());
-        args.push(size);
-    }
-    if let Some(orient) = orientation {
-        args.push("--orientation".to_string());
-        args.push(orient);
-    }
-    if let Some(m) = margin {
-        args.push("--margin".to_string());
-        args.push(m.to_string());
-    }
+    run_blocking(move || {
+        if pages.is_empty() {
+            return Err("Provide at least one page specification.".into());
+        }
 
-    // Pass transforms as JSON string if provided
-    if let Some(ref t) = transforms {
-        let transforms_json = serde_json::to_string(t)
-            .map_err(|e| format!("Failed to serialize transforms: {e}"))?;
-        args.push("--transforms".to_string());
-        args.push(transforms_json);
-    }
+        let output_path = output.unwrap_or_else(|| {
+            let cache_dir = app
+                .path()
+                .app_cache_dir()
+                .unwrap_or_else(|_| std::env::temp_dir());
+            cache_dir
+                .join("tlacuilo-merged-pages.pdf")
+                .to_string_lossy()
+                .to_string()
+        });
+
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+
+        // Convert pages to format: file:page file:page ...
+        let page_args: Vec<String> = pages
+            .iter()
+            .map(|(file, page)| format!("{}:{}", file, page))
+            .collect();
+
+        let mut args = vec!["merge-pages", "--output", &output_path, "--pages"];
+        let page_refs: Vec<&str> = page_args.iter().map(|s| s.as_str()).collect();
+        args.extend(page_refs);
+
+        bridge
+            .run_script("pdf_pages.py", &args)
+            .map_err(|e| e.to_string())?;
+
+        Ok(output_path)
+    })
+    .await
+}
 
-    let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
-    bridge
-        .run_script("pdf_convert.py", &args_refs)
-        .map_err(|e| e.to_string())?;
+#[derive(Debug, Serialize, Deserialize)]
+struct InterleavedPage {
+    /// "front" or "back"
+    source: String,
+    /// Page number in the source document (1-indexed)
+    page: u32,
+}
 
-    Ok(output_path)
+#[derive(Debug, Serialize)]
+struct InterleaveResult {
+    output_path: String,
+    order: Vec<InterleavedPage>,
 }
 
+/// Combine front/back scans of a double-sided document into one PDF
+/// (1a, 1b, 2a, 2b, ...), optionally reading the back stack in reverse
 #[tauri::command]
-fn pdf_to_images(
+async fn merge_interleave(
     app: AppHandle,
-    input: String,
-    output_dir: Option<String>,
-    format: Option<String>,
-    dpi: Option<i32>,
-    pages: Option<String>,
-) -> Result<Vec<String>, String> {
-    let out_dir = output_dir.unwrap_or_else(|| {
-        let cache_dir = app
-            .path()
-            .app_cache_dir()
-            .unwrap_or_else(|_| std::env::temp_dir());
-        cache_dir
-            .join("tlacuilo-images")
-            .to_string_lossy()
-            .to_string()
-    });
+    front: String,
+    back: String,
+    output: Option<String>,
+    reverse_back: Option<bool>,
+) -> Result<InterleaveResult, String> {
+    run_blocking(move || {
+        let output_path = output.unwrap_or_else(|| {
+            let cache_dir = app
+                .path()
+                .app_cache_dir()
+                .unwrap_or_else(|_| std::env::temp_dir());
+            cache_dir
+                .join("tlacuilo-interleaved.pdf")
+                .to_string_lossy()
+                .to_string()
+        });
+
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+
+        let mut args: Vec<&str> = vec![
+            "interleave",
+            "--front", &front,
+            "--back", &back,
+            "--output", &output_path,
+        ];
+        if reverse_back.unwrap_or(false) {
+            args.push("--reverse-back");
+        }
 
-    let bridge = PythonBridge::new(&app).map_err(|e| e.to_string())?;
+        let result = bridge
+            .run_script("pdf_pages.py", &args)
+            .map_err(|e| e.to_string())?;
 
-    let mut args: Vec<String> = vec![
-        "pdf-to-images".to_string(),
-        "--input".to_string(),
-        input,
-        "--output-dir".to_string(),
-        out_dir.clone(),
-    ];
+        let order = serde_json::from_str(&result.stdout)
+            .map_err(|e| format!("Failed to parse result: {}", e))?;
 
-    if let Some(fmt) = format {
-        args.push("--format".to_string());
-        args.push(fmt);
-    }
-    if let Some(d) = dpi {
-        args.push("--dpi".to_string());
-        args.push(d.to_string());
-    }
-    if let Some(p) = pages {
-        args.push("--pages".to_string());
-        args.push(p);
-    }
+        Ok(InterleaveResult { output_path, order })
+    })
+    .await
+}
 
-    let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
-    let output = bridge
-        .run_script("pdf_convert.py", &args_refs)
-        .map_err(|e| e.to_string())?;
+#[derive(Debug, Serialize, Deserialize)]
+struct ImposedSheet {
+    /// Output page number (1-indexed)
+    sheet: u32,
+    /// Source page per grid cell, left to right and top to bottom; None is a blank cell
+    pages: Vec<Option<u32>>,
+}
 
-    // Parse output to get list of created files
-    let files: Vec<String> = output
-        .stdout
-        .lines()
-        .filter(|l| {
-            l.trim().starts_with(&out_dir)
-                || l.trim().ends_with(".png")
-                || l.trim().ends_with(".jpg")
-                || l.trim().ends_with(".webp")
-                || l.trim().ends_with(".tiff")
-        })
-        .map(|l| l.trim().to_string())
-        .collect();
+#[derive(Debug, Serialize)]
+struct ImposeResult {
+    output_path: String,
+    page_count: usize,
+    mapping: Vec<ImposedSheet>,
+}
 
-    if files.is_empty() {
-        // Return the output directory at minimum
-        Ok(vec![out_dir])
-    } else {
-        Ok(files)
+/// Columns and rows of an n-up layout like "2x1" or "2x2"
+fn parse_impose_layout(layout: &str) -> Result<(u32, u32), String> {
+    let lower = layout.to_lowercase();
+    let (cols, rows) = lower
+        .split_once('x')
+        .and_then(|(c, r)| Some((c.trim().parse::<u32>().ok()?, r.trim().parse::<u32>().ok()?)))
+        .ok_or_else(|| format!("Invalid layout '{}': expected columns x rows, e.g. 2x1", layout))?;
+    if cols == 0 || rows == 0 || cols * rows < 2 {
+        return Err(format!("Layout '{}' must place at least two pages per sheet", layout));
     }
+    Ok((cols, rows))
 }
 
-// ============================================================================
-// PDF Security Commands (PythonBridge)
-// ============================================================================
+/// Print several pages per sheet (n-up), or with `booklet` reorder pages into
+/// 2-up printer spreads for saddle-stitch folding
+#[tauri::command]
+async fn pdf_impose(
+    app: AppHandle,
+    input: String,
+    output: Option<String>,
+    layout: String,
+    booklet: bool,
+) -> Result<ImposeResult, String> {
+    run_blocking(move || {
+        let grid = parse_impose_layout(&layout)?;
+        if booklet && grid != (2, 1) {
+            return Err("Booklet imposition uses a 2x1 layout".to_string());
+        }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct UnlockResult {
-    success: bool,
-    was_encrypted: bool,
-    had_restrictions: bool,
-    message: String,
-    #[serde(default)]
-    needs_password: bool,
-}
+        let output_path = output.unwrap_or_else(|| {
+            let cache_dir = app
+                .path()
+                .app_cache_dir()
+                .unwrap_or_else(|_| std::env::temp_dir());
+            cache_dir
+                .join("tlacuilo-imposed.pdf")
+                .to_string_lossy()
+                .to_string()
+        });
+
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+
+        let mut args: Vec<&str> = vec![
+            "impose",
+            "--input", &input,
+            "--output", &output_path,
+            "--layout", &layout,
+        ];
+        if booklet {
+            args.push("--booklet");
+        }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct SecurityCheckResult {
-    is_encrypted: bool,
-    needs_password: bool,
-    has_restrictions: bool,
-    #[serde(default)]
-    permissions: std::collections::HashMap<String, bool>,
-    #[serde(default)]
-    error: Option<String>,
+        let result = bridge
+            .run_script("pdf_pages.py", &args)
+            .map_err(|e| e.to_string())?;
+
+        let mapping: Vec<ImposedSheet> = serde_json::from_str(&result.stdout)
+            .map_err(|e| format!("Failed to parse result: {}", e))?;
+
+        Ok(ImposeResult {
+            output_path,
+            page_count: mapping.len(),
+            mapping,
+        })
+    })
+    .await
 }
 
-/// Check PDF security status
+/// Stamp one page of an overlay PDF (letterhead, form template) onto every base page
 #[tauri::command]
-fn pdf_check_security(app: AppHandle, input: String) -> Result<SecurityCheckResult, String> {
-    let bridge = PythonBridge::new(&app).map_err(|e| e.to_string())?;
+async fn pdf_overlay(
+    app: AppHandle,
+    base: String,
+    overlay: String,
+    output: Option<String>,
+    overlay_page: Option<u32>,
+    position: Option<String>,
+    layer: Option<String>,
+) -> Result<String, String> {
+    run_blocking(move || {
+        let output_path = output.unwrap_or_else(|| {
+            let cache_dir = app
+                .path()
+                .app_cache_dir()
+                .unwrap_or_else(|_| std::env::temp_dir());
+            cache_dir
+                .join("tlacuilo-overlay.pdf")
+                .to_string_lossy()
+                .to_string()
+        });
+
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+
+        let overlay_page_str = overlay_page.unwrap_or(1).to_string();
+        let position = position.unwrap_or_else(default_position);
+        let layer = layer.unwrap_or_else(|| "under".to_string());
+
+        let args: Vec<&str> = vec![
+            "overlay",
+            "--base", &base,
+            "--overlay", &overlay,
+            "--output", &output_path,
+            "--overlay-page", &overlay_page_str,
+            "--position", &position,
+            "--layer", &layer,
+        ];
+
+        bridge
+            .run_script("pdf_pages.py", &args)
+            .map_err(|e| e.to_string())?;
+
+        Ok(output_path)
+    })
+    .await
+}
 
-    let args: Vec<&str> = vec!["check", "--input", &input, "--json"];
+#[tauri::command]
+async fn split_pdf(
+    app: AppHandle,
+    input: String,
+    output_dir: Option<String>,
+    ranges: Option<Vec<String>>,
+) -> Result<Vec<String>, String> {
+    run_blocking(move || {
+        let out_dir = output_dir.unwrap_or_else(|| {
+            let cache_dir = app
+                .path()
+                .app_cache_dir()
+                .unwrap_or_else(|_| std::env::temp_dir());
+            cache_dir.join("tlacuilo-split").to_string_lossy().to_string()
+        });
+
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+
+        let mut args: Vec<&str> = vec!["split", "--input", &input, "--output-dir", &out_dir];
+
+        // Add ranges if provided
+        let range_refs: Vec<String> = ranges.as_ref().map(|r| r.clone()).unwrap_or_default();
+        if !range_refs.is_empty() {
+            args.push("--ranges");
+            for r in &range_refs {
+                args.push(r);
+            }
+        }
 
-    let result = bridge
-        .run_script("pdf_security.py", &args)
-        .map_err(|e| e.to_string())?;
+        bridge
+            .run_script("pdf_pages.py", &args)
+            .map_err(|e| e.to_string())?;
 
-    serde_json::from_str(&result.stdout)
-        .map_err(|e| format!("Failed to parse result: {}", e))
+        // Return the output directory and the number of files created based on ranges
+        let num_files = ranges.as_ref().map(|r| r.len()).unwrap_or(0);
+        let mut result = vec![out_dir.clone()];
+        for i in 1..=num_files.max(1) {
+            result.push(format!("{}/split_{}.pdf", out_dir, i));
+        }
+        Ok(result)
+    })
+    .await
 }
 
-/// Unlock/decrypt a PDF (remove restrictions)
+/// Extract page ranges (e.g. "3-7,10,15-20") into a single PDF, in the order given.
+/// Ranges are validated by `parse_ranges` in pdf_pages.py, the parser `split_pdf`
+/// uses too, so both commands accept the same syntax.
 #[tauri::command]
-fn pdf_unlock(
+async fn pdf_extract_range(
     app: AppHandle,
     input: String,
     output: Option<String>,
-    password: Option<String>,
-) -> Result<UnlockResult, String> {
-    let output_path = output.unwrap_or_else(|| {
-        let cache_dir = app
-            .path()
-            .app_cache_dir()
-            .unwrap_or_else(|_| std::env::temp_dir());
-        cache_dir.join("tlacuilo-unlocked.pdf").to_string_lossy().to_string()
-    });
+    ranges: Vec<String>,
+) -> Result<String, String> {
+    run_blocking(move || {
+        if ranges.iter().all(|r| r.trim().is_empty()) {
+            return Err("Provide at least one page range.".into());
+        }
 
-    let bridge = PythonBridge::new(&app).map_err(|e| e.to_string())?;
+        let output_path = output.unwrap_or_else(|| {
+            let cache_dir = app
+                .path()
+                .app_cache_dir()
+                .unwrap_or_else(|_| std::env::temp_dir());
+            cache_dir
+                .join("tlacuilo-extracted.pdf")
+                .to_string_lossy()
+                .to_string()
+        });
 
-    let mut args: Vec<String> = vec![
-        "unlock".to_string(),
-        "--input".to_string(),
-        input,
-        "--output".to_string(),
-        output_path,
-        "--json".to_string(),
-    ];
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
 
-    if let Some(pwd) = password {
-        args.push("--password".to_string());
-        args.push(pwd);
-    }
+        let mut args: Vec<&str> = vec!["extract", "--input", &input, "--output", &output_path, "--ranges"];
+        args.extend(ranges.iter().map(|r| r.as_str()));
 
-    let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+        bridge
+            .run_script("pdf_pages.py", &args)
+            .map_err(|e| e.to_string())?;
 
-    let result = bridge
-        .run_script("pdf_security.py", &args_refs)
-        .map_err(|e| e.to_string())?;
+        Ok(output_path)
+    })
+    .await
+}
 
-    serde_json::from_str(&result.stdout)
-        .map_err(|e| format!("Failed to parse result: {}", e))
+#[derive(Debug, Serialize, Deserialize)]
+struct SplitPart {
+    path: String,
+    pages: Vec<u32>,
 }
 
-/// Encrypt a PDF with password and permissions
+/// Split a PDF into consecutive files of `n` pages each (part_001.pdf, ...)
 #[tauri::command]
-fn pdf_encrypt(
+async fn split_every_n(
     app: AppHandle,
     input: String,
-    output: Option<String>,
-    user_password: Option<String>,
-    owner_password: Option<String>,
-    allow_printing: Option<bool>,
-    allow_copying: Option<bool>,
-    allow_modifying: Option<bool>,
-) -> Result<serde_json::Value, String> {
-    let output_path = output.unwrap_or_else(|| {
-        let cache_dir = app
-            .path()
-            .app_cache_dir()
-            .unwrap_or_else(|_| std::env::temp_dir());
-        cache_dir.join("tlacuilo-encrypted.pdf").to_string_lossy().to_string()
-    });
-
-    let bridge = PythonBridge::new(&app).map_err(|e| e.to_string())?;
-
-    let mut args: Vec<String> = vec![
-        "encrypt".to_string(),
-        "--input".to_string(),
-        input,
-        "--output".to_string(),
-        output_path,
-        "--json".to_string(),
-    ];
-
-    if let Some(pwd) = user_password {
-        args.push("--user-password".to_string());
-        args.push(pwd);
-    }
+    output_dir: String,
+    n: u32,
+) -> Result<Vec<SplitPart>, String> {
+    run_blocking(move || {
+        if n < 1 {
+            return Err("Pages per file must be at least 1.".into());
+        }
 
-    if let Some(pwd) = owner_password {
-        args.push("--owner-password".to_string());
-        args.push(pwd);
-    }
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
 
-    if allow_printing == Some(false) {
-        args.push("--no-print".to_string());
-    }
+        let n_str = n.to_string();
+        let args: Vec<&str> = vec![
+            "split-every",
+            "--input", &input,
+            "--n", &n_str,
+            "--output-dir", &output_dir,
+        ];
 
-    if allow_copying == Some(false) {
-        args.push("--no-copy".to_string());
-    }
+        let result = bridge
+            .run_script("pdf_pages.py", &args)
+            .map_err(|e| e.to_string())?;
 
-    if allow_modifying == Some(false) {
-        args.push("--no-modify".to_string());
-    }
+        serde_json::from_str(&result.stdout)
+            .map_err(|e| format!("Failed to parse result: {}", e))
+    })
+    .await
+}
 
-    let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+#[derive(Debug, Serialize, Deserialize)]
+struct BookmarkPart {
+    /// Bookmark title; bookmarks starting on the same page share a file
+    title: String,
+    path: String,
+    pages: Vec<u32>,
+}
 
-    let result = bridge
-        .run_script("pdf_security.py", &args_refs)
-        .map_err(|e| e.to_string())?;
+/// Split a PDF at its bookmarks `level` deep (default 1 = top level), one file
+/// per bookmark named after its title
+#[tauri::command]
+async fn pdf_split_by_bookmarks(
+    app: AppHandle,
+    input: String,
+    output_dir: Option<String>,
+    level: Option<u32>,
+) -> Result<Vec<BookmarkPart>, String> {
+    run_blocking(move || {
+        let level = level.unwrap_or(1);
+        if level < 1 {
+            return Err("Bookmark level must be at least 1.".into());
+        }
 
-    serde_json::from_str(&result.stdout)
-        .map_err(|e| format!("Failed to parse result: {}", e))
+        let out_dir = output_dir.unwrap_or_else(|| {
+            let cache_dir = app
+                .path()
+                .app_cache_dir()
+                .unwrap_or_else(|_| std::env::temp_dir());
+            cache_dir.join("tlacuilo-split").to_string_lossy().to_string()
+        });
+
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+
+        let level_str = level.to_string();
+        let args: Vec<&str> = vec![
+            "split-bookmarks",
+            "--input", &input,
+            "--output-dir", &out_dir,
+            "--level", &level_str,
+        ];
+
+        let result = bridge
+            .run_script("pdf_pages.py", &args)
+            .map_err(|e| e.to_string())?;
+
+        serde_json::from_str(&result.stdout)
+            .map_err(|e| format!("Failed to parse result: {}", e))
+    })
+    .await
 }
 
-// ============================================================================
-// PDF Graphical Signatures Commands (PythonBridge)
-// ============================================================================
+#[tauri::command]
+async fn rotate_pdf(
+    app: AppHandle,
+    input: String,
+    degrees: i32,
+    output: Option<String>,
+    rotations: Option<Vec<String>>,
+) -> Result<String, String> {
+    run_blocking(move || {
+        let out_path = output.unwrap_or_else(|| {
+            let cache_dir = app
+                .path()
+                .app_cache_dir()
+                .unwrap_or_else(|_| std::env::temp_dir());
+            cache_dir.join("tlacuilo-rotated.pdf").to_string_lossy().to_string()
+        });
+
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+
+        let degrees_str = degrees.to_string();
+        let mut args: Vec<&str> = vec!["rotate", "--input", &input, "--output", &out_path];
+
+        // Clone rotations to extend lifetime
+        let rotation_refs: Vec<String> = rotations.unwrap_or_default();
+        if !rotation_refs.is_empty() {
+            args.push("--rotation");
+            for r in &rotation_refs {
+                args.push(r);
+            }
+        } else {
+            args.push("--degrees");
+            args.push(&degrees_str);
+        }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct GraphicalSignatureResult {
-    success: bool,
-    message: String,
-    signature_type: String,
-    warning: Option<String>,
-    placement: Option<serde_json::Value>,
+        bridge
+            .run_script("pdf_pages.py", &args)
+            .map_err(|e| e.to_string())?;
+
+        Ok(out_path)
+    })
+    .await
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-struct SignatureCheckResult {
-    has_digital_signatures: bool,
-    signature_count: u32,
-    signature_fields: Vec<serde_json::Value>,
-    warning: Option<String>,
-    error: Option<String>,
+struct ImagesToPdfResult {
+    output: String,
+    /// Final page size in points, reported for single-page output
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    width: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    height: Option<f64>,
+    /// Bytes trimmed by downsampling images above `max_image_dpi`
+    #[serde(default)]
+    bytes_saved: u64,
 }
 
-/// Apply a graphical (visual) signature to a PDF
 #[tauri::command]
-fn apply_graphical_signature(
+async fn images_to_pdf(
     app: AppHandle,
-    input: String,
+    images: Vec<String>,
     output: Option<String>,
-    image_b64: String,
-    page: i32,
-    x: f64,
-    y: f64,
-    width: f64,
-    height: Option<f64>,
-    rotation: Option<f64>,
-    opacity: Option<f64>,
-    fit: Option<String>,
-) -> Result<GraphicalSignatureResult, String> {
-    let output_path = output.unwrap_or_else(|| {
-        let cache_dir = app
-            .path()
-            .app_cache_dir()
-            .unwrap_or_else(|_| std::env::temp_dir());
-        cache_dir
-            .join("tlacuilo-signed.pdf")
-            .to_string_lossy()
-            .to_string()
-    });
-
-    let bridge = PythonBridge::new(&app).map_err(|e| e.to_string())?;
-
-    let mut args: Vec<String> = vec![
-        "apply".to_string(),
-        "--input".to_string(),
-        input,
-        "--output".to_string(),
-        output_path,
-        "--image-b64".to_string(),
-        image_b64,
-        "--page".to_string(),
-        page.to_string(),
-        "--x".to_string(),
-        x.to_string(),
-        "--y".to_string(),
-        y.to_string(),
-        "--width".to_string(),
-        width.to_string(),
-        "--json".to_string(),
-    ];
+    page_size: Option<String>,
+    orientation: Option<String>,
+    margin: Option<f64>,
+    transforms: Option<Vec<ImageTransform>>,
+    append_to: Option<String>,
+    single_page: Option<bool>,
+    max_height: Option<f64>,
+    max_image_dpi: Option<i32>,
+) -> Result<ImagesToPdfResult, String> {
+    run_blocking(move || {
+        if images.is_empty() {
+            return Err("Provide at least one image path.".into());
+        }
+        if matches!(max_image_dpi, Some(dpi) if dpi <= 0) {
+            return Err("Maximum image DPI must be positive.".into());
+        }
+        let single_page = single_page.unwrap_or(false);
+        if single_page && append_to.is_some() {
+            return Err("A single-page PDF can't be appended to an existing document.".into());
+        }
 
-    if let Some(h) = height {
-        args.push("--height".to_string());
-        args.push(h.to_string());
-    }
+        let output_path = output.unwrap_or_else(|| {
+            let cache_dir = app
+                .path()
+                .app_cache_dir()
+                .unwrap_or_else(|_| std::env::temp_dir());
+            cache_dir.join("tlacuilo-images.pdf").to_string_lossy().to_string()
+        });
+
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+
+        // Phone photos store their rotation in EXIF, which the converter ignores
+        let transforms = resolve_image_transforms(&images, transforms, orientation.as_deref());
+
+        let mut args: Vec<String> = vec![
+            "images-to-pdf".to_string(),
+            "--output".to_string(),
+            output_path,
+            "--inputs".to_string(),
+        ];
+        args.extend(images);
+
+        if let Some(size) = page_size {
+            args.push("--page-size".to_string());
+            args.push(size);
+        }
+        if let Some(orient) = orientation {
+            args.push("--orientation".to_string());
+            args.push(orient);
+        }
+        if let Some(m) = margin {
+            args.push("--margin".to_string());
+            args.push(m.to_string());
+        }
 
-    if let Some(r) = rotation {
-        args.push("--rotation".to_string());
-        args.push(r.to_string());
-    }
+        let transforms_json = serde_json::to_string(&transforms)
+            .map_err(|e| format!("Failed to serialize transforms: {e}"))?;
+        args.push("--transforms".to_string());
+        args.push(transforms_json);
 
-    if let Some(o) = opacity {
-        args.push("--opacity".to_string());
-        args.push(o.to_string());
-    }
+        // Image pages go after the existing document's pages
+        if let Some(base) = append_to {
+            args.push("--append-to".to_string());
+            args.push(base);
+        }
 
-    if let Some(f) = fit {
-        args.push("--fit".to_string());
-        args.push(f);
-    }
+        // All images stacked on one tall page
+        if single_page {
+            args.push("--single-page".to_string());
+            if let Some(h) = max_height {
+                args.push("--max-height".to_string());
+                args.push(h.to_string());
+            }
+        }
 
-    let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+        if let Some(dpi) = max_image_dpi {
+            args.push("--max-image-dpi".to_string());
+            args.push(dpi.to_string());
+        }
 
-    let result = bridge
-        .run_script("pdf_signatures.py", &args_refs)
-        .map_err(|e| e.to_string())?;
+        let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+        let result = bridge
+            .run_script("pdf_convert.py", &args_refs)
+            .map_err(|e| e.to_string())?;
 
-    serde_json::from_str(&result.stdout)
-        .map_err(|e| format!("Failed to parse result: {}", e))
+        serde_json::from_str(&result.stdout).map_err(|e| format!("Failed to parse result: {}", e))
+    })
+    .await
 }
 
-/// Check if a PDF has existing digital signatures
 #[tauri::command]
-fn check_pdf_signatures(app: AppHandle, input: String) -> Result<SignatureCheckResult, String> {
-    let bridge = PythonBridge::new(&app).map_err(|e| e.to_string())?;
-
-    let args: Vec<&str> = vec!["check", "--input", &input, "--json"];
-
-    let result = bridge
-        .run_script("pdf_signatures.py", &args)
-        .map_err(|e| e.to_string())?;
+async fn pdf_to_images(
+    app: AppHandle,
+    input: String,
+    output_dir: Option<String>,
+    format: Option<String>,
+    dpi: Option<i32>,
+    pages: Option<String>,
+) -> Result<Vec<String>, String> {
+    run_blocking(move || {
+        let out_dir = output_dir.unwrap_or_else(|| {
+            let cache_dir = app
+                .path()
+                .app_cache_dir()
+                .unwrap_or_else(|_| std::env::temp_dir());
+            cache_dir
+                .join("tlacuilo-images")
+                .to_string_lossy()
+                .to_string()
+        });
+
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+
+        let mut args: Vec<String> = vec![
+            "pdf-to-images".to_string(),
+            "--input".to_string(),
+            input,
+            "--output-dir".to_string(),
+            out_dir.clone(),
+        ];
+
+        if let Some(fmt) = format {
+            args.push("--format".to_string());
+            args.push(fmt);
+        }
+        if let Some(d) = dpi {
+            args.push("--dpi".to_string());
+            args.push(d.to_string());
+        }
+        if let Some(p) = pages {
+            args.push("--pages".to_string());
+            args.push(p);
+        }
 
-    serde_json::from_str(&result.stdout)
-        .map_err(|e| format!("Failed to parse result: {}", e))
+        let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+        let output = bridge
+            .run_script_streamed(&app, "pdf_convert.py", &args_refs)
+            .map_err(|e| e.to_string())?;
+
+        // Parse output to get list of created files
+        let files: Vec<String> = output
+            .stdout
+            .lines()
+            .filter(|l| {
+                l.trim().starts_with(&out_dir)
+                    || l.trim().ends_with(".png")
+                    || l.trim().ends_with(".jpg")
+                    || l.trim().ends_with(".webp")
+                    || l.trim().ends_with(".tiff")
+            })
+            .map(|l| l.trim().to_string())
+            .collect();
+
+        if files.is_empty() {
+            // Return the output directory at minimum
+            Ok(vec![out_dir])
+        } else {
+            Ok(files)
+        }
+    })
+    .await
 }
 
-// ============================================================================
-// PDF Layers Commands (PythonBridge)
-// ============================================================================
-
 #[derive(Debug, Serialize, Deserialize)]
-struct LayerInfo {
-    xref: i32,
-    name: String,
-    on: bool,
-    #[serde(default)]
-    intent: Vec<String>,
+struct ExtractedImage {
+    /// 1-indexed page the image first appears on
+    page: u32,
+    /// 1-indexed position among the images extracted from that page
+    index: u32,
+    width: u32,
+    height: u32,
+    /// File extension of the stored format ("jpeg", "png", "jpx", ...)
+    format: String,
+    path: String,
+    /// Set when the image was written without its soft mask
     #[serde(default)]
-    usage: String,
+    warning: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct LayersResult {
-    has_layers: bool,
-    layers: Vec<LayerInfo>,
-    error: Option<String>,
+/// Save the images embedded in a PDF at their original format and resolution
+/// (no page rendering). Images smaller than `min_size` pixels on either side
+/// are skipped; `pages` is a range like "1-3,5".
+#[tauri::command]
+async fn pdf_extract_images(
+    app: AppHandle,
+    input: String,
+    output_dir: Option<String>,
+    pages: Option<String>,
+    min_size: Option<u32>,
+) -> Result<Vec<ExtractedImage>, String> {
+    run_blocking(move || {
+        let out_dir = output_dir.unwrap_or_else(|| {
+            let cache_dir = app
+                .path()
+                .app_cache_dir()
+                .unwrap_or_else(|_| std::env::temp_dir());
+            cache_dir
+                .join("tlacuilo-extracted-images")
+                .to_string_lossy()
+                .to_string()
+        });
+
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+
+        let min_size = min_size.unwrap_or(0).to_string();
+        let mut args: Vec<&str> = vec![
+            "extract-images",
+            "--input", &input,
+            "--output-dir", &out_dir,
+            "--min-size", &min_size,
+        ];
+        if let Some(p) = pages.as_deref() {
+            args.push("--pages");
+            args.push(p);
+        }
+
+        let result = bridge
+            .run_script_streamed(&app, "pdf_convert.py", &args)
+            .map_err(|e| e.to_string())?;
+
+        serde_json::from_str(&result.stdout)
+            .map_err(|e| format!("Failed to parse result: {}", e))
+    })
+    .await
 }
 
+/// TIFF compressions accepted by `pdf_convert.py pdf-to-tiff`
+const TIFF_COMPRESSIONS: &[&str] = &["lzw", "deflate", "ccitt"];
+
 #[derive(Debug, Serialize, Deserialize)]
-struct LayerToggleResult {
-    success: bool,
-    message: String,
-    #[serde(default)]
-    layer_name: String,
+struct MultipageTiffResult {
+    output_path: String,
+    pages: u32,
 }
 
-/// Get all layers from a PDF
+/// Export all pages as a single multipage TIFF ("ccitt" renders pages bitonal)
 #[tauri::command]
-fn pdf_get_layers(app: AppHandle, input: String) -> Result<LayersResult, String> {
-    let bridge = PythonBridge::new(&app).map_err(|e| e.to_string())?;
+async fn pdf_to_multipage_tiff(
+    app: AppHandle,
+    input: String,
+    output: String,
+    dpi: u32,
+    compression: String,
+) -> Result<MultipageTiffResult, String> {
+    run_blocking(move || {
+        let compression = compression.to_lowercase();
+        if !TIFF_COMPRESSIONS.contains(&compression.as_str()) {
+            return Err(format!(
+                "Unsupported TIFF compression '{}' (expected one of: {})",
+                compression,
+                TIFF_COMPRESSIONS.join(", ")
+            ));
+        }
+        if !(36..=1200).contains(&dpi) {
+            return Err(format!("DPI must be between 36 and 1200, got {}", dpi));
+        }
 
-    let args: Vec<&str> = vec!["list", "--input", &input, "--json"];
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+
+        let dpi_str = dpi.to_string();
+        let args: Vec<&str> = vec![
+            "pdf-to-tiff",
+            "--input", &input,
+            "--output", &output,
+            "--dpi", &dpi_str,
+            "--compression", &compression,
+        ];
+
+        let result = bridge
+            .run_script_streamed(&app, "pdf_convert.py", &args)
+            .map_err(|e| e.to_string())?;
+
+        // Output: "Created <n> pages: <path>"
+        let pages = result
+            .stdout
+            .lines()
+            .find_map(|l| l.trim().strip_prefix("Created "))
+            .and_then(|rest| rest.split_whitespace().next())
+            .and_then(|n| n.parse::<u32>().ok())
+            .ok_or_else(|| format!("Unexpected converter output: {}", result.stdout.trim()))?;
+
+        Ok(MultipageTiffResult {
+            output_path: output,
+            pages,
+        })
+    })
+    .await
+}
 
-    let result = bridge
-        .run_script("pdf_layers.py", &args)
-        .map_err(|e| e.to_string())?;
+/// Page image formats accepted by `pdf_convert.py flatten-to-images`
+const FLATTEN_FORMATS: &[&str] = &["png", "jpg"];
 
-    serde_json::from_str(&result.stdout)
-        .map_err(|e| format!("Failed to parse result: {}", e))
+#[derive(Debug, Serialize, Deserialize)]
+struct FlattenToImagesResult {
+    output_path: String,
+    pages: u32,
+    size_bytes: u64,
 }
 
-/// Toggle visibility of a layer
+/// Rebuild a PDF from rendered images of its pages, dropping all text and
+/// vector content. Page sizes are kept; the output size is returned so the
+/// DPI/format tradeoff can be judged.
 #[tauri::command]
-fn pdf_set_layer(
+async fn pdf_flatten_to_images(
     app: AppHandle,
     input: String,
     output: String,
-    layer_name: Option<String>,
-    layer_xref: Option<i32>,
-    visible: bool,
-) -> Result<LayerToggleResult, String> {
-    let bridge = PythonBridge::new(&app).map_err(|e| e.to_string())?;
+    dpi: Option<u32>,
+    format: Option<String>,
+) -> Result<FlattenToImagesResult, String> {
+    run_blocking(move || {
+        let dpi = dpi.unwrap_or(150);
+        if !(36..=1200).contains(&dpi) {
+            return Err(format!("DPI must be between 36 and 1200, got {}", dpi));
+        }
+        let format = match format.unwrap_or_else(|| "png".into()).to_lowercase().as_str() {
+            "jpeg" => "jpg".to_string(),
+            other => other.to_string(),
+        };
+        if !FLATTEN_FORMATS.contains(&format.as_str()) {
+            return Err(format!(
+                "Unsupported page image format '{}' (expected one of: {})",
+                format,
+                FLATTEN_FORMATS.join(", ")
+            ));
+        }
 
-    let mut args: Vec<String> = vec![
-        "toggle".to_string(),
-        "--input".to_string(),
-        input,
-        "--output".to_string(),
-        output,
-        "--visible".to_string(),
-        visible.to_string(),
-        "--json".to_string(),
-    ];
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+
+        let dpi_str = dpi.to_string();
+        let args: Vec<&str> = vec![
+            "flatten-to-images",
+            "--input", &input,
+            "--output", &output,
+            "--dpi", &dpi_str,
+            "--format", &format,
+        ];
+
+        let result = bridge
+            .run_script_streamed(&app, "pdf_convert.py", &args)
+            .map_err(|e| e.to_string())?;
+
+        // Output: "Created <n> pages: <path>"
+        let pages = result
+            .stdout
+            .lines()
+            .find_map(|l| l.trim().strip_prefix("Created "))
+            .and_then(|rest| rest.split_whitespace().next())
+            .and_then(|n| n.parse::<u32>().ok())
+            .ok_or_else(|| format!("Unexpected converter output: {}", result.stdout.trim()))?;
+
+        let size_bytes = std::fs::metadata(&output)
+            .map_err(|e| format!("Failed to read {}: {}", output, e))?
+            .len();
+
+        Ok(FlattenToImagesResult {
+            output_path: output,
+            pages,
+            size_bytes,
+        })
+    })
+    .await
+}
 
-    if let Some(name) = layer_name {
-        args.push("--layer".to_string());
-        args.push(name);
-    } else if let Some(xref) = layer_xref {
-        args.push("--xref".to_string());
-        args.push(xref.to_string());
-    }
+#[derive(Debug, Serialize, Deserialize)]
+struct ImagesZipResult {
+    zip_path: String,
+    count: u32,
+}
 
-    let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+/// Render pages to images and pack them into a single ZIP (entries named page_0001.png, ...)
+#[tauri::command]
+async fn pdf_to_images_zip(
+    app: AppHandle,
+    input: String,
+    output_zip: String,
+    format: Option<String>,
+    dpi: Option<i32>,
+    pages: Option<String>,
+) -> Result<ImagesZipResult, String> {
+    run_blocking(move || {
+        use std::fs::File;
+        use std::io::{BufWriter, Write};
 
-    let result = bridge
-        .run_script("pdf_layers.py", &args_refs)
-        .map_err(|e| e.to_string())?;
+        let cache_dir = app
+            .path()
+            .app_cache_dir()
+            .unwrap_or_else(|_| std::env::temp_dir());
+        let render_dir = cache_dir.join(format!("tlacuilo-zip-{}", uuid::Uuid::new_v4()));
+        let render_dir_str = render_dir.to_string_lossy().to_string();
+
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+
+        let mut args: Vec<String> = vec![
+            "pdf-to-images".to_string(),
+            "--input".to_string(),
+            input,
+            "--output-dir".to_string(),
+            render_dir_str,
+        ];
+
+        if let Some(fmt) = format {
+            args.push("--format".to_string());
+            args.push(fmt);
+        }
+        if let Some(d) = dpi {
+            args.push("--dpi".to_string());
+            args.push(d.to_string());
+        }
+        if let Some(p) = pages {
+            args.push("--pages".to_string());
+            args.push(p);
+        }
 
-    serde_json::from_str(&result.stdout)
-        .map_err(|e| format!("Failed to parse result: {}", e))
+        let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+
+        let packed = bridge
+            .run_script("pdf_convert.py", &args_refs)
+            .map_err(|e| e.to_string())
+            .and_then(|_| {
+                // pdf_convert names files page_0001.<ext>, so directory order is page order
+                let mut files: Vec<std::path::PathBuf> = std::fs::read_dir(&render_dir)
+                    .map_err(|e| format!("Failed to read rendered images: {}", e))?
+                    .filter_map(|entry| entry.ok().map(|e| e.path()))
+                    .filter(|path| path.is_file())
+                    .collect();
+                files.sort();
+
+                if files.is_empty() {
+                    return Err("No pages were rendered".to_string());
+                }
+
+                let zip_file = File::create(&output_zip)
+                    .map_err(|e| format!("Failed to create {}: {}", output_zip, e))?;
+                let mut zip = zip::ZipWriter::new(BufWriter::new(zip_file));
+                let options = zip::write::SimpleFileOptions::default()
+                    .compression_method(zip::CompressionMethod::Deflated);
+
+                for path in &files {
+                    let name = path
+                        .file_name()
+                        .map(|n| n.to_string_lossy().to_string())
+                        .unwrap_or_default();
+                    zip.start_file(name, options)
+                        .map_err(|e| format!("Failed to add zip entry: {}", e))?;
+                    let mut image = File::open(path)
+                        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+                    std::io::copy(&mut image, &mut zip)
+                        .map_err(|e| format!("Failed to write zip entry: {}", e))?;
+                }
+
+                let mut writer = zip
+                    .finish()
+                    .map_err(|e| format!("Failed to finish zip: {}", e))?;
+                writer
+                    .flush()
+                    .map_err(|e| format!("Failed to write zip: {}", e))?;
+
+                Ok(files.len() as u32)
+            });
+
+        // Rendered images are only scratch space for the archive
+        let _ = std::fs::remove_dir_all(&render_dir);
+
+        let count = packed?;
+
+        Ok(ImagesZipResult {
+            zip_path: output_zip,
+            count,
+        })
+    })
+    .await
 }
 
 // ============================================================================
-// PDF Redaction Commands (PythonBridge)
+// PDF Security Commands (PythonBridge)
 // ============================================================================
 
 #[derive(Debug, Serialize, Deserialize)]
-struct RedactionMarkResult {
+struct UnlockResult {
     success: bool,
+    was_encrypted: bool,
+    had_restrictions: bool,
     message: String,
-    page: i32,
-    rect: Vec<f64>,
+    #[serde(default)]
+    needs_password: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-struct RedactionApplyResult {
-    success: bool,
-    message: String,
-    pages_affected: i32,
-    redactions_applied: i32,
+struct SecurityCheckResult {
+    is_encrypted: bool,
+    needs_password: bool,
+    has_restrictions: bool,
+    #[serde(default)]
+    permissions: std::collections::HashMap<String, bool>,
+    #[serde(default)]
+    error: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct PendingRedaction {
-    page: i32,
-    x0: f64,
-    y0: f64,
-    x1: f64,
-    y1: f64,
-}
+/// Check PDF security status
+#[tauri::command]
+async fn pdf_check_security(app: AppHandle, input: String) -> Result<SecurityCheckResult, CommandError> {
+    run_blocking(move || {
+        let bridge = PythonBridge::shared(&app)?;
 
-#[derive(Debug, Serialize, Deserialize)]
-struct PendingRedactionsResult {
-    has_redactions: bool,
-    count: i32,
-    redactions: Vec<PendingRedaction>,
-    error: Option<String>,
-}
+        let args: Vec<&str> = vec!["check", "--input", &input, "--json"];
 
-#[derive(Debug, Serialize, Deserialize)]
-struct RedactionVerifyResult {
-    area_clear: bool,
-    text_found: String,
-    images_found: i32,
-    error: Option<String>,
+        let result = bridge.run_script("pdf_security.py", &args)?;
+
+        serde_json::from_str(&result.stdout).map_err(|e| CommandError::bad_output("result", e))
+    })
+    .await
 }
 
-/// Add a redaction mark to a PDF page
+/// Unlock/decrypt a PDF (remove restrictions)
 #[tauri::command]
-fn pdf_add_redaction(
+async fn pdf_unlock(
     app: AppHandle,
     input: String,
-    output: String,
-    page: i32,
-    x0: f64,
-    y0: f64,
-    x1: f64,
-    y1: f64,
-    text: Option<String>,
-) -> Result<RedactionMarkResult, String> {
-    let bridge = PythonBridge::new(&app).map_err(|e| e.to_string())?;
+    output: Option<String>,
+    password: Option<String>,
+) -> Result<UnlockResult, CommandError> {
+    run_blocking(move || {
+        let output_path = output.unwrap_or_else(|| {
+            let cache_dir = app
+                .path()
+                .app_cache_dir()
+                .unwrap_or_else(|_| std::env::temp_dir());
+            cache_dir.join("tlacuilo-unlocked.pdf").to_string_lossy().to_string()
+        });
+
+        let bridge = PythonBridge::shared(&app)?;
+
+        let mut args: Vec<String> = vec![
+            "unlock".to_string(),
+            "--input".to_string(),
+            input,
+            "--output".to_string(),
+            output_path,
+            "--json".to_string(),
+        ];
+
+        if let Some(pwd) = password {
+            args.push("--password".to_string());
+            args.push(pwd);
+        }
 
-    let mut args: Vec<String> = vec![
-        "mark".to_string(),
-        "--input".to_string(),
-        input,
-        "--output".to_string(),
-        output,
-        "--page".to_string(),
-        page.to_string(),
-        "--x0".to_string(),
-        x0.to_string(),
-        "--y0".to_string(),
-        y0.to_string(),
-        "--x1".to_string(),
-        x1.to_string(),
-        "--y1".to_string(),
-        y1.to_string(),
-        "--json".to_string(),
-    ];
+        let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
 
-    if let Some(t) = text {
-        args.push("--text".to_string());
-        args.push(t);
-    }
+        let result = bridge.run_script("pdf_security.py", &args_refs)?;
 
-    let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+        serde_json::from_str(&result.stdout).map_err(|e| CommandError::bad_output("result", e))
+    })
+    .await
+}
 
-    let result = bridge
-        .run_script("pdf_redaction.py", &args_refs)
-        .map_err(|e| e.to_string())?;
+/// Environment variable pdf_security.py reads the batch unlock passwords from
+const UNLOCK_PASSWORDS_ENV: &str = "TLACUILO_UNLOCK_PASSWORDS";
 
-    serde_json::from_str(&result.stdout)
-        .map_err(|e| format!("Failed to parse result: {}", e))
+#[derive(Debug, Serialize, Deserialize)]
+struct UnlockBatchEntry {
+    input: String,
+    output: Option<String>,
+    success: bool,
+    needs_password: bool,
+    /// Index into the supplied password list of the one that opened the file
+    password_index: Option<usize>,
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct UnlockBatchOutput {
+    results: Vec<UnlockBatchEntry>,
 }
 
-/// Apply all pending redactions (permanently remove content)
+/// Unlock many PDFs into `output_dir`, trying each password per file.
+/// Passwords are handed to Python through the environment, never as arguments,
+/// so they don't show up in process listings or logs.
 #[tauri::command]
-fn pdf_apply_redactions(
+async fn pdf_unlock_batch(
     app: AppHandle,
-    input: String,
-    output: String,
-    redact_images: bool,
-    redact_graphics: bool,
-) -> Result<RedactionApplyResult, String> {
-    let bridge = PythonBridge::new(&app).map_err(|e| e.to_string())?;
+    inputs: Vec<String>,
+    output_dir: String,
+    passwords: Vec<String>,
+) -> Result<Vec<UnlockBatchEntry>, CommandError> {
+    run_blocking(move || {
+        if inputs.is_empty() {
+            return Ok(Vec::new());
+        }
 
-    let mut args: Vec<&str> = vec![
-        "apply",
-        "--input",
-        &input,
-        "--output",
-        &output,
-        "--json",
+        let bridge = PythonBridge::shared(&app)?;
+
+        let mut args: Vec<&str> = vec!["unlock-batch", "--output-dir", &output_dir, "--json", "--inputs"];
+        args.extend(inputs.iter().map(|s| s.as_str()));
+
+        let passwords_json = serde_json::to_string(&passwords).map_err(|e| e.to_string())?;
+        let result = bridge.run_script_with_env(
+            "pdf_security.py",
+            &args,
+            &[(UNLOCK_PASSWORDS_ENV, passwords_json.as_str())],
+        )?;
+
+        let parsed: UnlockBatchOutput =
+            serde_json::from_str(&result.stdout).map_err(|e| CommandError::bad_output("result", e))?;
+        Ok(parsed.results)
+    })
+    .await
+}
+
+/// Ciphers understood by pdf_security.py; AES-256 unless asked otherwise
+fn parse_encryption_level(level: Option<String>) -> Result<String, String> {
+    let level = level.unwrap_or_else(|| "aes_256".into()).to_lowercase();
+    match level.as_str() {
+        "rc4_128" | "aes_128" | "aes_256" => Ok(level),
+        other => Err(format!("Unsupported encryption level: {}", other)),
+    }
+}
+
+/// Encrypt a PDF with password and permissions.
+/// Permissions left as `None` are allowed; form filling follows `allow_annotating`
+/// and assembly follows `allow_modifying`. The result reports the `cipher` used.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+async fn pdf_encrypt(
+    app: AppHandle,
+    input: String,
+    output: Option<String>,
+    user_password: Option<String>,
+    owner_password: Option<String>,
+    allow_printing: Option<bool>,
+    allow_copying: Option<bool>,
+    allow_modifying: Option<bool>,
+    allow_annotating: Option<bool>,
+    allow_filling_forms: Option<bool>,
+    allow_accessibility: Option<bool>,
+    allow_assembly: Option<bool>,
+    encryption_level: Option<String>,
+) -> Result<serde_json::Value, CommandError> {
+    run_blocking(move || {
+        pdf_encrypt_blocking(
+            app,
+            input,
+            output,
+            user_password,
+            owner_password,
+            allow_printing,
+            allow_copying,
+            allow_modifying,
+            allow_annotating,
+            allow_filling_forms,
+            allow_accessibility,
+            allow_assembly,
+            encryption_level,
+        )
+    })
+    .await
+}
+
+/// Blocking body of `pdf_encrypt`, also run by `batch_run`
+#[allow(clippy::too_many_arguments)]
+fn pdf_encrypt_blocking(
+    app: AppHandle,
+    input: String,
+    output: Option<String>,
+    user_password: Option<String>,
+    owner_password: Option<String>,
+    allow_printing: Option<bool>,
+    allow_copying: Option<bool>,
+    allow_modifying: Option<bool>,
+    allow_annotating: Option<bool>,
+    allow_filling_forms: Option<bool>,
+    allow_accessibility: Option<bool>,
+    allow_assembly: Option<bool>,
+    encryption_level: Option<String>,
+) -> Result<serde_json::Value, CommandError> {
+    let level = parse_encryption_level(encryption_level)
+        .map_err(|e| CommandError::new(command_error::codes::INVALID_ARGUMENT, e))?;
+
+    let output_path = output.unwrap_or_else(|| {
+        let cache_dir = app
+            .path()
+            .app_cache_dir()
+            .unwrap_or_else(|_| std::env::temp_dir());
+        cache_dir.join("tlacuilo-encrypted.pdf").to_string_lossy().to_string()
+    });
+
+    let bridge = PythonBridge::shared(&app)?;
+
+    let mut args: Vec<String> = vec![
+        "encrypt".to_string(),
+        "--input".to_string(),
+        input,
+        "--output".to_string(),
+        output_path,
+        "--encryption-level".to_string(),
+        level,
+        "--json".to_string(),
     ];
 
-    if !redact_images {
-        args.push("--no-images");
-    }
-    if !redact_graphics {
-        args.push("--no-graphics");
+    if let Some(pwd) = user_password {
+        args.push("--user-password".to_string());
+        args.push(pwd);
+    }
+
+    if let Some(pwd) = owner_password {
+        args.push("--owner-password".to_string());
+        args.push(pwd);
+    }
+
+    if allow_printing == Some(false) {
+        args.push("--no-print".to_string());
+    }
+
+    if allow_copying == Some(false) {
+        args.push("--no-copy".to_string());
+    }
+
+    if allow_modifying == Some(false) {
+        args.push("--no-modify".to_string());
+    }
+
+    if allow_annotating == Some(false) {
+        args.push("--no-annotate".to_string());
+    }
+
+    if allow_filling_forms == Some(false) {
+        args.push("--no-fill-forms".to_string());
+    }
+
+    if allow_accessibility == Some(false) {
+        args.push("--no-accessibility".to_string());
+    }
+
+    if allow_assembly == Some(false) {
+        args.push("--no-assemble".to_string());
+    }
+
+    let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+
+    let result = bridge.run_script("pdf_security.py", &args_refs)?;
+
+    serde_json::from_str(&result.stdout).map_err(|e| CommandError::bad_output("result", e))
+}
+
+/// Environment variables pdf_security.py reads set-permissions passwords from
+const OWNER_PASSWORD_ENV: &str = "TLACUILO_OWNER_PASSWORD";
+const USER_PASSWORD_ENV: &str = "TLACUILO_USER_PASSWORD";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SetPermissionsResult {
+    success: bool,
+    message: String,
+    /// Effective permissions of the saved file, keyed like the command's options
+    #[serde(default)]
+    permissions: std::collections::HashMap<String, bool>,
+}
+
+/// Change the permissions of an encrypted PDF without touching its passwords.
+/// Unset options keep their current value. `user_password` is only needed for
+/// AES-256 files, whose user password can't be recovered from the owner password.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+async fn pdf_set_permissions(
+    app: AppHandle,
+    input: String,
+    output: Option<String>,
+    owner_password: String,
+    user_password: Option<String>,
+    allow_printing: Option<bool>,
+    allow_copying: Option<bool>,
+    allow_modifying: Option<bool>,
+    allow_annotating: Option<bool>,
+    allow_filling_forms: Option<bool>,
+    allow_accessibility: Option<bool>,
+    allow_assembly: Option<bool>,
+) -> Result<SetPermissionsResult, CommandError> {
+    run_blocking(move || {
+        let output_path = output.unwrap_or_else(|| {
+            let cache_dir = app
+                .path()
+                .app_cache_dir()
+                .unwrap_or_else(|_| std::env::temp_dir());
+            cache_dir.join("tlacuilo-permissions.pdf").to_string_lossy().to_string()
+        });
+
+        let bridge = PythonBridge::shared(&app)?;
+
+        let mut args: Vec<String> = vec![
+            "set-permissions".to_string(),
+            "--input".to_string(),
+            input,
+            "--output".to_string(),
+            output_path,
+            "--json".to_string(),
+        ];
+
+        let options = [
+            ("--printing", allow_printing),
+            ("--copying", allow_copying),
+            ("--modifying", allow_modifying),
+            ("--annotating", allow_annotating),
+            ("--filling-forms", allow_filling_forms),
+            ("--accessibility", allow_accessibility),
+            ("--assembly", allow_assembly),
+        ];
+        for (flag, value) in options {
+            if let Some(allow) = value {
+                args.push(flag.to_string());
+                args.push(if allow { "allow" } else { "deny" }.to_string());
+            }
+        }
+
+        let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+
+        let mut env = vec![(OWNER_PASSWORD_ENV, owner_password.as_str())];
+        if let Some(pwd) = user_password.as_deref() {
+            env.push((USER_PASSWORD_ENV, pwd));
+        }
+
+        let result = bridge.run_script_with_env("pdf_security.py", &args_refs, &env)?;
+
+        serde_json::from_str(&result.stdout).map_err(|e| CommandError::bad_output("result", e))
+    })
+    .await
+}
+
+// ============================================================================
+// PDF Graphical and Digital Signatures Commands (PythonBridge)
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize)]
+struct GraphicalSignatureResult {
+    success: bool,
+    message: String,
+    signature_type: String,
+    warning: Option<String>,
+    placement: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct DigitalSignatureResult {
+    success: bool,
+    message: String,
+    signature_type: String,
+    #[serde(default)]
+    output_path: String,
+    field_name: Option<String>,
+    signer_subject: Option<String>,
+    /// ISO 8601 time the signer reported
+    signing_time: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SignatureCheckResult {
+    has_digital_signatures: bool,
+    signature_count: u32,
+    /// Per field: name, page, signed, plus valid/tampered/trusted/signer_subject/signing_time
+    /// when validation is available
+    signature_fields: Vec<serde_json::Value>,
+    /// Whether pyHanko could check signatures cryptographically
+    #[serde(default)]
+    validation_available: bool,
+    warning: Option<String>,
+    error: Option<String>,
+}
+
+/// Apply a graphical (visual) signature to a PDF
+#[tauri::command]
+async fn apply_graphical_signature(
+    app: AppHandle,
+    input: String,
+    output: Option<String>,
+    image_b64: String,
+    page: i32,
+    x: f64,
+    y: f64,
+    width: f64,
+    height: Option<f64>,
+    rotation: Option<f64>,
+    opacity: Option<f64>,
+    fit: Option<String>,
+) -> Result<GraphicalSignatureResult, String> {
+    run_blocking(move || {
+        let output_path = output.unwrap_or_else(|| {
+            let cache_dir = app
+                .path()
+                .app_cache_dir()
+                .unwrap_or_else(|_| std::env::temp_dir());
+            cache_dir
+                .join("tlacuilo-signed.pdf")
+                .to_string_lossy()
+                .to_string()
+        });
+
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+
+        let mut args: Vec<String> = vec![
+            "apply".to_string(),
+            "--input".to_string(),
+            input,
+            "--output".to_string(),
+            output_path,
+            "--image-b64".to_string(),
+            image_b64,
+            "--page".to_string(),
+            page.to_string(),
+            "--x".to_string(),
+            x.to_string(),
+            "--y".to_string(),
+            y.to_string(),
+            "--width".to_string(),
+            width.to_string(),
+            "--json".to_string(),
+        ];
+
+        if let Some(h) = height {
+            args.push("--height".to_string());
+            args.push(h.to_string());
+        }
+
+        if let Some(r) = rotation {
+            args.push("--rotation".to_string());
+            args.push(r.to_string());
+        }
+
+        if let Some(o) = opacity {
+            args.push("--opacity".to_string());
+            args.push(o.to_string());
+        }
+
+        if let Some(f) = fit {
+            args.push("--fit".to_string());
+            args.push(f);
+        }
+
+        let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+
+        let result = bridge
+            .run_script("pdf_signatures.py", &args_refs)
+            .map_err(|e| e.to_string())?;
+
+        serde_json::from_str(&result.stdout)
+            .map_err(|e| format!("Failed to parse result: {}", e))
+    })
+    .await
+}
+
+/// Apply a cryptographic PAdES signature with a PKCS#12 (.pfx/.p12) certificate
+#[tauri::command]
+async fn sign_pdf_digital(
+    app: AppHandle,
+    input: String,
+    output: Option<String>,
+    pfx_path: String,
+    pfx_password: Option<String>,
+    reason: Option<String>,
+    location: Option<String>,
+    field_name: Option<String>,
+) -> Result<DigitalSignatureResult, String> {
+    run_blocking(move || {
+        let output_path = output.unwrap_or_else(|| {
+            let cache_dir = app
+                .path()
+                .app_cache_dir()
+                .unwrap_or_else(|_| std::env::temp_dir());
+            cache_dir
+                .join("tlacuilo-digitally-signed.pdf")
+                .to_string_lossy()
+                .to_string()
+        });
+
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+
+        let mut args: Vec<String> = vec![
+            "sign".to_string(),
+            "--input".to_string(),
+            input,
+            "--output".to_string(),
+            output_path.clone(),
+            "--pfx".to_string(),
+            pfx_path,
+            "--json".to_string(),
+        ];
+
+        if let Some(pwd) = pfx_password {
+            args.push("--pfx-password".to_string());
+            args.push(pwd);
+        }
+
+        if let Some(r) = reason {
+            args.push("--reason".to_string());
+            args.push(r);
+        }
+
+        if let Some(l) = location {
+            args.push("--location".to_string());
+            args.push(l);
+        }
+
+        if let Some(f) = field_name {
+            args.push("--field".to_string());
+            args.push(f);
+        }
+
+        let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+
+        let result = bridge
+            .run_script("pdf_sign.py", &args_refs)
+            .map_err(|e| e.to_string())?;
+
+        let mut signed: DigitalSignatureResult = serde_json::from_str(&result.stdout)
+            .map_err(|e| format!("Failed to parse result: {}", e))?;
+        if !signed.success {
+            return Err(signed.message);
+        }
+        signed.output_path = output_path;
+        Ok(signed)
+    })
+    .await
+}
+
+/// Check if a PDF has existing digital signatures and whether they are valid
+#[tauri::command]
+async fn check_pdf_signatures(app: AppHandle, input: String) -> Result<SignatureCheckResult, String> {
+    run_blocking(move || {
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+
+        let args: Vec<&str> = vec!["check", "--input", &input, "--json"];
+
+        let result = bridge
+            .run_script("pdf_signatures.py", &args)
+            .map_err(|e| e.to_string())?;
+
+        serde_json::from_str(&result.stdout)
+            .map_err(|e| format!("Failed to parse result: {}", e))
+    })
+    .await
+}
+
+// ============================================================================
+// PDF Layers Commands (PythonBridge)
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize)]
+struct LayerInfo {
+    xref: i32,
+    name: String,
+    on: bool,
+    #[serde(default)]
+    intent: Vec<String>,
+    #[serde(default)]
+    usage: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct LayersResult {
+    has_layers: bool,
+    layers: Vec<LayerInfo>,
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct LayerToggleResult {
+    success: bool,
+    message: String,
+    #[serde(default)]
+    layer_name: String,
+}
+
+/// Get all layers from a PDF
+#[tauri::command]
+async fn pdf_get_layers(app: AppHandle, input: String) -> Result<LayersResult, String> {
+    run_blocking(move || {
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+
+        let args: Vec<&str> = vec!["list", "--input", &input, "--json"];
+
+        let result = bridge
+            .run_script("pdf_layers.py", &args)
+            .map_err(|e| e.to_string())?;
+
+        serde_json::from_str(&result.stdout)
+            .map_err(|e| format!("Failed to parse result: {}", e))
+    })
+    .await
+}
+
+/// Toggle visibility of a layer
+#[tauri::command]
+async fn pdf_set_layer(
+    app: AppHandle,
+    input: String,
+    output: String,
+    layer_name: Option<String>,
+    layer_xref: Option<i32>,
+    visible: bool,
+) -> Result<LayerToggleResult, String> {
+    run_blocking(move || {
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+
+        let mut args: Vec<String> = vec![
+            "toggle".to_string(),
+            "--input".to_string(),
+            input,
+            "--output".to_string(),
+            output,
+            "--visible".to_string(),
+            visible.to_string(),
+            "--json".to_string(),
+        ];
+
+        if let Some(name) = layer_name {
+            args.push("--layer".to_string());
+            args.push(name);
+        } else if let Some(xref) = layer_xref {
+            args.push("--xref".to_string());
+            args.push(xref.to_string());
+        }
+
+        let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+
+        let result = bridge
+            .run_script("pdf_layers.py", &args_refs)
+            .map_err(|e| e.to_string())?;
+
+        serde_json::from_str(&result.stdout)
+            .map_err(|e| format!("Failed to parse result: {}", e))
+    })
+    .await
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct LayerCleanResult {
+    success: bool,
+    message: String,
+    /// Names of the removed layers
+    removed: Vec<String>,
+}
+
+/// Remove layers listed in /OCProperties that no content, XObject or annotation uses
+#[tauri::command]
+async fn pdf_clean_orphan_layers(
+    app: AppHandle,
+    input: String,
+    output: String,
+) -> Result<LayerCleanResult, String> {
+    run_blocking(move || {
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+
+        let args: Vec<&str> = vec![
+            "clean-orphans",
+            "--input", &input,
+            "--output", &output,
+            "--json",
+        ];
+
+        let result = bridge
+            .run_script("pdf_layers.py", &args)
+            .map_err(|e| e.to_string())?;
+
+        let parsed: LayerCleanResult = serde_json::from_str(&result.stdout)
+            .map_err(|e| format!("Failed to parse result: {}", e))?;
+
+        if !parsed.success {
+            return Err(parsed.message);
+        }
+
+        Ok(parsed)
+    })
+    .await
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct LayerAssignment {
+    page: u32,
+    xref: i32,
+    /// Annotation id, when the annotation carries one
+    id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct LayerCreateResult {
+    success: bool,
+    message: String,
+    name: String,
+    xref: Option<i32>,
+    /// Annotations moved into the new layer
+    assigned: Vec<LayerAssignment>,
+}
+
+/// Create a new layer, optionally moving annotations (by id or by page) into it
+#[tauri::command]
+async fn pdf_create_layer(
+    app: AppHandle,
+    input: String,
+    output: String,
+    name: String,
+    from_pages: Option<String>,
+    annotation_ids: Option<Vec<String>>,
+    default_on: Option<bool>,
+) -> Result<LayerCreateResult, String> {
+    run_blocking(move || {
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+
+        let mut args: Vec<String> = vec![
+            "create".to_string(),
+            "--input".to_string(),
+            input,
+            "--output".to_string(),
+            output,
+            "--name".to_string(),
+            name,
+            "--default-on".to_string(),
+            default_on.unwrap_or(true).to_string(),
+            "--json".to_string(),
+        ];
+
+        if let Some(pages) = from_pages {
+            args.push("--from-pages".to_string());
+            args.push(pages);
+        }
+
+        if let Some(ids) = annotation_ids.filter(|ids| !ids.is_empty()) {
+            args.push("--annotations".to_string());
+            args.extend(ids);
+        }
+
+        let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+
+        let result = bridge
+            .run_script("pdf_layers.py", &args_refs)
+            .map_err(|e| e.to_string())?;
+
+        let parsed: LayerCreateResult = serde_json::from_str(&result.stdout)
+            .map_err(|e| format!("Failed to parse result: {}", e))?;
+
+        if !parsed.success {
+            return Err(parsed.message);
+        }
+
+        Ok(parsed)
+    })
+    .await
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct LayerDeleteResult {
+    success: bool,
+    message: String,
+    /// The deleted layer
+    xref: i32,
+    /// Content was kept and made permanent instead of removed
+    flattened: bool,
+    /// Marked-content sections removed or unwrapped
+    sections: u32,
+    /// XObjects emptied or made permanent
+    xobjects: u32,
+    /// Annotations deleted or made permanent
+    annotations: u32,
+    /// Whether the output still has any layers
+    has_layers: bool,
+}
+
+/// Delete a layer with its content, or with `flatten` keep the content and
+/// merge it permanently into the page
+#[tauri::command]
+async fn pdf_delete_layer(
+    app: AppHandle,
+    input: String,
+    output: String,
+    layer_xref: i32,
+    flatten: bool,
+) -> Result<LayerDeleteResult, String> {
+    run_blocking(move || {
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+
+        let mut args: Vec<String> = vec![
+            "delete".to_string(),
+            "--input".to_string(),
+            input,
+            "--output".to_string(),
+            output,
+            "--xref".to_string(),
+            layer_xref.to_string(),
+            "--json".to_string(),
+        ];
+
+        if flatten {
+            args.push("--flatten".to_string());
+        }
+
+        let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+
+        let result = bridge
+            .run_script("pdf_layers.py", &args_refs)
+            .map_err(|e| e.to_string())?;
+
+        let parsed: LayerDeleteResult = serde_json::from_str(&result.stdout)
+            .map_err(|e| format!("Failed to parse result: {}", e))?;
+
+        if !parsed.success {
+            return Err(parsed.message);
+        }
+
+        Ok(parsed)
+    })
+    .await
+}
+
+// ============================================================================
+// PDF Redaction Commands (PythonBridge)
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RedactionMarkResult {
+    success: bool,
+    message: String,
+    page: i32,
+    rect: Vec<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RedactionApplyResult {
+    success: bool,
+    message: String,
+    pages_affected: i32,
+    redactions_applied: i32,
+    #[serde(default)]
+    audit_output: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PendingRedaction {
+    page: i32,
+    x0: f64,
+    y0: f64,
+    x1: f64,
+    y1: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PendingRedactionsResult {
+    has_redactions: bool,
+    count: i32,
+    redactions: Vec<PendingRedaction>,
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PatternRedaction {
+    page: i32,
+    x0: f64,
+    y0: f64,
+    x1: f64,
+    y1: f64,
+    /// Text the pattern matched
+    text: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PatternRedactionResult {
+    success: bool,
+    message: String,
+    match_count: i32,
+    pages_affected: i32,
+    /// One entry per redacted rect (a match wrapping lines yields several)
+    redactions: Vec<PatternRedaction>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RedactionVerifyResult {
+    area_clear: bool,
+    text_found: String,
+    images_found: i32,
+    error: Option<String>,
+}
+
+/// Add a redaction mark to a PDF page
+#[tauri::command]
+async fn pdf_add_redaction(
+    app: AppHandle,
+    input: String,
+    output: String,
+    page: i32,
+    x0: f64,
+    y0: f64,
+    x1: f64,
+    y1: f64,
+    text: Option<String>,
+    coords_normalized: Option<bool>,
+) -> Result<RedactionMarkResult, String> {
+    run_blocking(move || {
+        let bounds = pdf_viewer::PageBounds::load(&input, page)?;
+        let (x0, y0, x1, y1) =
+            pdf_viewer::validate_rect(&bounds, x0, y0, x1, y1, coords_normalized.unwrap_or(false))?;
+
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+
+        let mut args: Vec<String> = vec![
+            "mark".to_string(),
+            "--input".to_string(),
+            input,
+            "--output".to_string(),
+            output,
+            "--page".to_string(),
+            page.to_string(),
+            "--x0".to_string(),
+            x0.to_string(),
+            "--y0".to_string(),
+            y0.to_string(),
+            "--x1".to_string(),
+            x1.to_string(),
+            "--y1".to_string(),
+            y1.to_string(),
+            "--json".to_string(),
+        ];
+
+        if let Some(t) = text {
+            args.push("--text".to_string());
+            args.push(t);
+        }
+
+        let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+
+        let result = bridge
+            .run_script("pdf_redaction.py", &args_refs)
+            .map_err(|e| e.to_string())?;
+
+        serde_json::from_str(&result.stdout)
+            .map_err(|e| format!("Failed to parse result: {}", e))
+    })
+    .await
+}
+
+/// Apply all pending redactions (permanently remove content).
+/// `audit_output` (.json or .csv) records each redaction and the text it removed.
+#[tauri::command]
+async fn pdf_apply_redactions(
+    app: AppHandle,
+    input: String,
+    output: String,
+    redact_images: bool,
+    redact_graphics: bool,
+    audit_output: Option<String>,
+) -> Result<RedactionApplyResult, String> {
+    run_blocking(move || {
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+
+        let mut args: Vec<&str> = vec![
+            "apply",
+            "--input",
+            &input,
+            "--output",
+            &output,
+            "--json",
+        ];
+
+        if !redact_images {
+            args.push("--no-images");
+        }
+        if !redact_graphics {
+            args.push("--no-graphics");
+        }
+        if let Some(audit) = &audit_output {
+            args.push("--audit-output");
+            args.push(audit);
+        }
+
+        let result = bridge
+            .run_script("pdf_redaction.py", &args)
+            .map_err(|e| e.to_string())?;
+
+        serde_json::from_str(&result.stdout)
+            .map_err(|e| format!("Failed to parse result: {}", e))
+    })
+    .await
+}
+
+/// Find every occurrence of a text pattern and permanently redact it in one pass
+#[tauri::command]
+async fn pdf_redact_pattern(
+    app: AppHandle,
+    input: String,
+    output: String,
+    pattern: String,
+    is_regex: Option<bool>,
+    case_sensitive: Option<bool>,
+    whole_word: Option<bool>,
+) -> Result<PatternRedactionResult, String> {
+    run_blocking(move || {
+        if pattern.is_empty() {
+            return Err("Redaction pattern must not be empty".to_string());
+        }
+
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+
+        // `--pattern=` form so a pattern starting with '-' isn't read as a flag
+        let pattern_arg = format!("--pattern={}", pattern);
+        let mut args: Vec<&str> = vec![
+            "pattern",
+            "--input",
+            &input,
+            "--output",
+            &output,
+            &pattern_arg,
+            "--json",
+        ];
+
+        if is_regex.unwrap_or(false) {
+            args.push("--regex");
+        }
+        if case_sensitive.unwrap_or(false) {
+            args.push("--case-sensitive");
+        }
+        if whole_word.unwrap_or(false) {
+            args.push("--whole-word");
+        }
+
+        let result = bridge
+            .run_script("pdf_redaction.py", &args)
+            .map_err(|e| e.to_string())?;
+
+        let parsed: PatternRedactionResult = serde_json::from_str(&result.stdout)
+            .map_err(|e| format!("Failed to parse result: {}", e))?;
+        if !parsed.success {
+            return Err(parsed.message);
+        }
+        Ok(parsed)
+    })
+    .await
+}
+
+/// Get pending redaction marks
+#[tauri::command]
+async fn pdf_get_pending_redactions(app: AppHandle, input: String) -> Result<PendingRedactionsResult, String> {
+    run_blocking(move || {
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+
+        let args: Vec<&str> = vec!["pending", "--input", &input, "--json"];
+
+        let result = bridge
+            .run_script("pdf_redaction.py", &args)
+            .map_err(|e| e.to_string())?;
+
+        serde_json::from_str(&result.stdout)
+            .map_err(|e| format!("Failed to parse result: {}", e))
+    })
+    .await
+}
+
+/// Verify redaction was successful
+#[tauri::command]
+async fn pdf_verify_redaction(
+    app: AppHandle,
+    input: String,
+    page: i32,
+    x0: f64,
+    y0: f64,
+    x1: f64,
+    y1: f64,
+) -> Result<RedactionVerifyResult, String> {
+    run_blocking(move || {
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+
+        let args: Vec<String> = vec![
+            "verify".to_string(),
+            "--input".to_string(),
+            input,
+            "--page".to_string(),
+            page.to_string(),
+            "--x0".to_string(),
+            x0.to_string(),
+            "--y0".to_string(),
+            y0.to_string(),
+            "--x1".to_string(),
+            x1.to_string(),
+            "--y1".to_string(),
+            y1.to_string(),
+            "--json".to_string(),
+        ];
+
+        let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+
+        let result = bridge
+            .run_script("pdf_redaction.py", &args_refs)
+            .map_err(|e| e.to_string())?;
+
+        serde_json::from_str(&result.stdout)
+            .map_err(|e| format!("Failed to parse result: {}", e))
+    })
+    .await
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RedactionLeak {
+    /// "text", "annotation", "metadata" or "unapplied_redaction"
+    kind: String,
+    page: Option<i32>,
+    #[serde(default)]
+    rect: Option<[f64; 4]>,
+    #[serde(default)]
+    field: Option<String>,
+    text: String,
+    #[serde(default)]
+    images: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RedactionScanResult {
+    passed: bool,
+    leaks: Vec<RedactionLeak>,
+    /// Images whose contents weren't checked (text in images needs OCR)
+    #[serde(default)]
+    unscanned_images: u32,
+    error: Option<String>,
+}
+
+/// Scan the whole document for sensitive terms still present after redaction,
+/// including redaction marks that were never applied. Text inside images is
+/// not scanned; `unscanned_images` says how many images went unchecked.
+#[tauri::command]
+async fn pdf_verify_all_redactions(
+    app: AppHandle,
+    input: String,
+    terms: Vec<String>,
+) -> Result<RedactionScanResult, String> {
+    run_blocking(move || {
+        if terms.iter().all(|t| t.trim().is_empty()) {
+            return Err("At least one term is required".to_string());
+        }
+
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+
+        let mut args: Vec<String> = vec![
+            "verify-all".to_string(),
+            "--input".to_string(),
+            input,
+            "--json".to_string(),
+        ];
+        // `--term=` form so terms starting with '-' aren't read as flags
+        args.extend(terms.iter().map(|t| format!("--term={}", t)));
+
+        let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+
+        let result = bridge
+            .run_script("pdf_redaction.py", &args_refs)
+            .map_err(|e| e.to_string())?;
+
+        let parsed: RedactionScanResult = serde_json::from_str(&result.stdout)
+            .map_err(|e| format!("Failed to parse result: {}", e))?;
+        if let Some(error) = &parsed.error {
+            return Err(error.clone());
+        }
+        Ok(parsed)
+    })
+    .await
+}
+
+// ============================================================================
+// PDF Sanitization Commands (PythonBridge)
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SanitizationInfo {
+    has_metadata: bool,
+    metadata_fields: Vec<String>,
+    has_javascript: bool,
+    javascript_count: i32,
+    has_embedded_files: bool,
+    embedded_files_count: i32,
+    has_links: bool,
+    links_count: i32,
+    error: Option<String>,
+    /// Only filled in by `pdf_sanitize_preview`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    details: Option<SanitizationDetails>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SanitizationDetails {
+    metadata: std::collections::HashMap<String, String>,
+    javascript: Vec<JavaScriptExcerpt>,
+    form_fields: Vec<SanitizedFormField>,
+    embedded_files: Vec<String>,
+    links: Vec<SanitizedLink>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct JavaScriptExcerpt {
+    xref: i32,
+    snippet: String,
+    truncated: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SanitizedFormField {
+    page: i32,
+    name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SanitizedLink {
+    page: i32,
+    /// "uri", "launch" or "remote"
+    kind: String,
+    target: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SanitizationRemoved {
+    metadata: bool,
+    javascript: i32,
+    embedded_files: i32,
+    links: i32,
+    annotations: i32,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct LinkCounts {
+    removed: i32,
+    kept: i32,
+}
+
+/// Links removed vs kept, split into in-document jumps and everything else
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SanitizationLinks {
+    internal: LinkCounts,
+    external: LinkCounts,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SanitizationResult {
+    success: bool,
+    message: String,
+    removed: SanitizationRemoved,
+    #[serde(default)]
+    links: SanitizationLinks,
+}
+
+/// "all", "external", "internal" or a domain pattern such as `example.com` or `*.example.*`
+fn parse_link_filter(filter: Option<String>) -> Result<Option<String>, String> {
+    let Some(filter) = filter.map(|f| f.trim().to_lowercase()) else {
+        return Ok(None);
+    };
+    let is_domain = !filter.is_empty()
+        && filter
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '*' | '?'));
+    if is_domain {
+        Ok(Some(filter))
+    } else {
+        Err(format!("Invalid link filter: {}", filter))
+    }
+}
+
+/// Get info about sanitizable content in a PDF
+#[tauri::command]
+async fn pdf_sanitization_info(app: AppHandle, input: String) -> Result<SanitizationInfo, String> {
+    run_blocking(move || {
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+
+        let args: Vec<&str> = vec!["info", "--input", &input, "--json"];
+
+        let result = bridge
+            .run_script("pdf_sanitize.py", &args)
+            .map_err(|e| e.to_string())?;
+
+        serde_json::from_str(&result.stdout)
+            .map_err(|e| format!("Failed to parse result: {}", e))
+    })
+    .await
+}
+
+/// List what sanitizing would remove (metadata values, script excerpts,
+/// attachment names, link targets) without changing the file
+#[tauri::command]
+async fn pdf_sanitize_preview(app: AppHandle, input: String) -> Result<SanitizationInfo, String> {
+    run_blocking(move || {
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+
+        let args: Vec<&str> = vec!["info", "--input", &input, "--details", "--json"];
+
+        let result = bridge
+            .run_script("pdf_sanitize.py", &args)
+            .map_err(|e| e.to_string())?;
+
+        serde_json::from_str(&result.stdout)
+            .map_err(|e| format!("Failed to parse result: {}", e))
+    })
+    .await
+}
+
+/// Sanitize a PDF by removing metadata, scripts, etc.
+/// `link_filter` narrows link removal (see `parse_link_filter`); `remove_links`
+/// alone removes every link.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+async fn pdf_sanitize(
+    app: AppHandle,
+    input: String,
+    output: String,
+    remove_metadata: bool,
+    remove_javascript: bool,
+    remove_embedded_files: bool,
+    remove_links: bool,
+    remove_annotations: bool,
+    link_filter: Option<String>,
+) -> Result<SanitizationResult, String> {
+    run_blocking(move || {
+        pdf_sanitize_blocking(
+            app,
+            input,
+            output,
+            remove_metadata,
+            remove_javascript,
+            remove_embedded_files,
+            remove_links,
+            remove_annotations,
+            link_filter,
+        )
+    })
+    .await
+}
+
+/// Blocking body of `pdf_sanitize`, also run by `batch_run`
+#[allow(clippy::too_many_arguments)]
+fn pdf_sanitize_blocking(
+    app: AppHandle,
+    input: String,
+    output: String,
+    remove_metadata: bool,
+    remove_javascript: bool,
+    remove_embedded_files: bool,
+    remove_links: bool,
+    remove_annotations: bool,
+    link_filter: Option<String>,
+) -> Result<SanitizationResult, String> {
+    let link_filter = parse_link_filter(link_filter)?;
+    let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+
+    let mut args: Vec<&str> = vec![
+        "clean",
+        "--input",
+        &input,
+        "--output",
+        &output,
+        "--json",
+    ];
+
+    if !remove_metadata {
+        args.push("--keep-metadata");
+    }
+    if !remove_javascript {
+        args.push("--keep-javascript");
+    }
+    if !remove_embedded_files {
+        args.push("--keep-embedded");
+    }
+    if remove_links {
+        args.push("--remove-links");
+    }
+    if remove_annotations {
+        args.push("--remove-annotations");
+    }
+    if let Some(filter) = &link_filter {
+        args.push("--link-filter");
+        args.push(filter);
+    }
+
+    let result = bridge
+        .run_script("pdf_sanitize.py", &args)
+        .map_err(|e| e.to_string())?;
+
+    serde_json::from_str(&result.stdout)
+        .map_err(|e| format!("Failed to parse result: {}", e))
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct AnonymizeIdsResult {
+    success: bool,
+    message: String,
+    #[serde(default)]
+    removed: Vec<String>,
+}
+
+/// Strip XMP document/instance IDs and the trailer /ID, keeping descriptive metadata
+#[tauri::command]
+async fn pdf_anonymize_ids(
+    app: AppHandle,
+    input: String,
+    output: String,
+) -> Result<AnonymizeIdsResult, String> {
+    run_blocking(move || {
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+
+        let args: Vec<&str> = vec![
+            "anonymize-ids",
+            "--input",
+            &input,
+            "--output",
+            &output,
+            "--json",
+        ];
+
+        let result = bridge
+            .run_script("pdf_sanitize.py", &args)
+            .map_err(|e| e.to_string())?;
+
+        let parsed: AnonymizeIdsResult = serde_json::from_str(&result.stdout)
+            .map_err(|e| format!("Failed to parse result: {}", e))?;
+
+        if !parsed.success {
+            return Err(parsed.message);
+        }
+
+        Ok(parsed)
+    })
+    .await
+}
+
+// ============================================================================
+// PDF Inspection Commands (PythonBridge)
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CompositionObject {
+    #[serde(rename = "type")]
+    object_type: String,
+    size: u64,
+    #[serde(default)]
+    page: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CompositionResult {
+    total: u64,
+    images: u64,
+    fonts: u64,
+    content_streams: u64,
+    metadata: u64,
+    other: u64,
+    #[serde(default)]
+    largest_objects: Vec<CompositionObject>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CompositionOutput {
+    success: bool,
+    #[serde(flatten)]
+    composition: CompositionResult,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+/// Break down a PDF's size by object category (images, fonts, content streams, metadata)
+#[tauri::command]
+async fn pdf_composition(app: AppHandle, input: String) -> Result<CompositionResult, String> {
+    run_blocking(move || {
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+
+        let args: Vec<&str> = vec!["composition", "--input", &input, "--json"];
+
+        let result = bridge
+            .run_script("pdf_inspect.py", &args)
+            .map_err(|e| e.to_string())?;
+
+        let parsed: CompositionOutput = serde_json::from_str(&result.stdout)
+            .map_err(|e| format!("Failed to parse result: {}", e))?;
+
+        if !parsed.success {
+            return Err(parsed
+                .error
+                .unwrap_or_else(|| "Composition analysis failed".to_string()));
+        }
+
+        Ok(parsed.composition)
+    })
+    .await
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct OversizedImage {
+    /// First page using the image (1-indexed)
+    page: u32,
+    #[serde(default)]
+    xref: u32,
+    width: u32,
+    height: u32,
+    megapixels: f32,
+    size_bytes: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct OversizedImagesOutput {
+    success: bool,
+    #[serde(default)]
+    images: Vec<OversizedImage>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+/// Flag embedded images above `max_megapixels` (or very large in bytes), largest first
+#[tauri::command]
+async fn pdf_find_oversized_images(
+    app: AppHandle,
+    input: String,
+    max_megapixels: f32,
+) -> Result<Vec<OversizedImage>, String> {
+    run_blocking(move || {
+        if !max_megapixels.is_finite() || max_megapixels <= 0.0 {
+            return Err(format!("max_megapixels must be positive, got {}", max_megapixels));
+        }
+
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+
+        let threshold = max_megapixels.to_string();
+        let args: Vec<&str> = vec![
+            "oversized-images",
+            "--input", &input,
+            "--max-megapixels", &threshold,
+            "--json",
+        ];
+
+        let result = bridge
+            .run_script("pdf_inspect.py", &args)
+            .map_err(|e| e.to_string())?;
+
+        let parsed: OversizedImagesOutput = serde_json::from_str(&result.stdout)
+            .map_err(|e| format!("Failed to parse result: {}", e))?;
+
+        if !parsed.success {
+            return Err(parsed
+                .error
+                .unwrap_or_else(|| "Image analysis failed".to_string()));
+        }
+
+        Ok(parsed.images)
+    })
+    .await
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ProblemFont {
+    font: String,
+    #[serde(default)]
+    reason: String,
+    /// Pages using the font (1-indexed)
+    pages: Vec<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TextExtractableResult {
+    fully_extractable: bool,
+    problem_fonts: Vec<ProblemFont>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TextExtractableOutput {
+    success: bool,
+    #[serde(flatten)]
+    report: TextExtractableResult,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+/// Find fonts without a usable ToUnicode map (text that copies or searches as garbage)
+#[tauri::command]
+async fn pdf_check_text_extractable(app: AppHandle, input: String) -> Result<TextExtractableResult, String> {
+    run_blocking(move || {
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+
+        let args: Vec<&str> = vec!["text-extractable", "--input", &input, "--json"];
+
+        let result = bridge
+            .run_script("pdf_inspect.py", &args)
+            .map_err(|e| e.to_string())?;
+
+        let parsed: TextExtractableOutput = serde_json::from_str(&result.stdout)
+            .map_err(|e| format!("Failed to parse result: {}", e))?;
+
+        if !parsed.success {
+            return Err(parsed
+                .error
+                .unwrap_or_else(|| "Font analysis failed".to_string()));
+        }
+
+        Ok(parsed.report)
+    })
+    .await
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ToUnicodeRepairResult {
+    success: bool,
+    output_path: String,
+    /// Fonts that received a synthesized ToUnicode map
+    repaired: Vec<String>,
+    /// Fonts whose glyphs carry no recoverable character information
+    #[serde(default)]
+    unrepaired: Vec<String>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+/// Synthesize ToUnicode maps from glyph names or embedded font cmaps
+#[tauri::command]
+async fn pdf_repair_tounicode(
+    app: AppHandle,
+    input: String,
+    output: String,
+) -> Result<ToUnicodeRepairResult, String> {
+    run_blocking(move || {
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+
+        let args: Vec<&str> = vec![
+            "repair-tounicode",
+            "--input", &input,
+            "--output", &output,
+            "--json",
+        ];
+
+        let result = bridge
+            .run_script("pdf_inspect.py", &args)
+            .map_err(|e| e.to_string())?;
+
+        let parsed: ToUnicodeRepairResult = serde_json::from_str(&result.stdout)
+            .map_err(|e| format!("Failed to parse result: {}", e))?;
+
+        if !parsed.success {
+            return Err(parsed
+                .error
+                .unwrap_or_else(|| "ToUnicode repair failed".to_string()));
+        }
+
+        Ok(parsed)
+    })
+    .await
+}
+
+// ============================================================================
+// PDF Watermark Commands (PythonBridge)
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize)]
+struct WatermarkResult {
+    success: bool,
+    message: String,
+    #[serde(default)]
+    pages_processed: u32,
+    /// Source file, set for batch results
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    input: Option<String>,
+    /// Written file, set for batch results
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    output: Option<String>,
+}
+
+/// Progress event payload for per-file batch operations
+#[derive(Debug, Clone, Serialize)]
+struct BatchProgress {
+    current: usize,
+    total: usize,
+    file: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct WatermarkTextOptions {
+    #[serde(default = "default_font_size")]
+    font_size: f32,
+    #[serde(default = "default_font_color")]
+    font_color: Vec<f32>,
+    #[serde(default = "default_opacity")]
+    opacity: f32,
+    #[serde(default = "default_rotation")]
+    rotation: f32,
+    #[serde(default = "default_position")]
+    position: String,
+    #[serde(default = "default_pages")]
+    pages: String,
+    #[serde(default = "default_layer")]
+    layer: String,
+    /// Repeat the watermark in a grid across the page
+    #[serde(default)]
+    tile: bool,
+    /// Gap between tiles in points
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    tile_spacing: Option<f32>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct WatermarkImageOptions {
+    #[serde(default = "default_opacity")]
+    opacity: f32,
+    #[serde(default = "default_scale")]
+    scale: f32,
+    #[serde(default = "default_zero_rotation")]
+    rotation: f32,
+    #[serde(default = "default_position")]
+    position: String,
+    #[serde(default = "default_pages")]
+    pages: String,
+    #[serde(default = "default_layer")]
+    layer: String,
+    /// Repeat the watermark in a grid across the page
+    #[serde(default)]
+    tile: bool,
+    /// Gap between tiles in points
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    tile_spacing: Option<f32>,
+}
+
+fn default_font_size() -> f32 { 48.0 }
+fn default_font_color() -> Vec<f32> { vec![0.5, 0.5, 0.5] }
+fn default_opacity() -> f32 { 0.3 }
+fn default_rotation() -> f32 { -45.0 }
+fn default_zero_rotation() -> f32 { 0.0 }
+fn default_position() -> String { "center".to_string() }
+fn default_pages() -> String { "all".to_string() }
+fn default_layer() -> String { "under".to_string() }
+fn default_scale() -> f32 { 0.5 }
+
+/// Add text watermark to PDF
+#[tauri::command]
+async fn pdf_watermark_text(
+    app: AppHandle,
+    input: String,
+    output: String,
+    text: String,
+    options: WatermarkTextOptions,
+) -> Result<WatermarkResult, String> {
+    run_blocking(move || pdf_watermark_text_blocking(app, input, output, text, options)).await
+}
+
+/// Blocking body of `pdf_watermark_text`, also run per file by `batch_run`,
+/// `pdf_watermark_batch` and the native watermark fallback
+fn pdf_watermark_text_blocking(
+    app: AppHandle,
+    input: String,
+    output: String,
+    text: String,
+    options: WatermarkTextOptions,
+) -> Result<WatermarkResult, String> {
+    let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+
+    let options_json = serde_json::to_string(&options)
+        .map_err(|e| format!("Failed to serialize options: {}", e))?;
+
+    let args: Vec<&str> = vec!["text", &input, &output, &text, &options_json];
+
+    let result = bridge
+        .run_script("pdf_watermark.py", &args)
+        .map_err(|e| e.to_string())?;
+
+    serde_json::from_str(&result.stdout)
+        .map_err(|e| format!("Failed to parse result: {}", e))
+}
+
+/// Add a text watermark with MuPDF directly, skipping the Python spawn.
+/// Text outside WinAnsi (e.g. CJK) still goes through `pdf_watermark_text`.
+#[tauri::command]
+async fn pdf_watermark_text_native(
+    app: AppHandle,
+    input: String,
+    output: String,
+    text: String,
+    options: WatermarkTextOptions,
+) -> Result<WatermarkResult, String> {
+    run_blocking(move || {
+        if !pdf_watermark::supports_native(&text) {
+            return pdf_watermark_text_blocking(app, input, output, text, options);
+        }
+        // The viewer may hold `output` open; it is replaced in place
+        app.state::<pdf_viewer::DocumentCache>().evict(&output);
+        pdf_watermark::watermark_text(&input, &output, &text, &options)
+    })
+    .await
+}
+
+/// Add image watermark to PDF
+#[tauri::command]
+async fn pdf_watermark_image(
+    app: AppHandle,
+    input: String,
+    output: String,
+    image_path: String,
+    options: WatermarkImageOptions,
+) -> Result<WatermarkResult, String> {
+    run_blocking(move || {
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+
+        let options_json = serde_json::to_string(&options)
+            .map_err(|e| format!("Failed to serialize options: {}", e))?;
+
+        let args: Vec<&str> = vec!["image", &input, &output, &image_path, &options_json];
+
+        let result = bridge
+            .run_script("pdf_watermark.py", &args)
+            .map_err(|e| e.to_string())?;
+
+        serde_json::from_str(&result.stdout)
+            .map_err(|e| format!("Failed to parse result: {}", e))
+    })
+    .await
+}
+
+/// Apply a text watermark to every PDF in `input_dir`, writing to `output_dir`
+/// (named by `batch_output_paths`, so no input is overwritten).
+/// Emits `watermark-batch-progress` per file and records failures instead of stopping.
+#[tauri::command]
+async fn pdf_watermark_batch(
+    app: AppHandle,
+    input_dir: String,
+    output_dir: String,
+    text: String,
+    options: WatermarkTextOptions,
+) -> Result<Vec<WatermarkResult>, String> {
+    run_blocking(move || {
+        let mut files: Vec<std::path::PathBuf> = std::fs::read_dir(&input_dir)
+            .map_err(|e| format!("Failed to read {}: {}", input_dir, e))?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| {
+                path.is_file()
+                    && path
+                        .extension()
+                        .map(|ext| ext.eq_ignore_ascii_case("pdf"))
+                        .unwrap_or(false)
+            })
+            .collect();
+        files.sort();
+
+        if files.is_empty() {
+            return Err(format!("No PDF files found in {}", input_dir));
+        }
+
+        std::fs::create_dir_all(&output_dir)
+            .map_err(|e| format!("Failed to create {}: {}", output_dir, e))?;
+
+        let inputs: Vec<String> = files.iter().map(|p| p.to_string_lossy().to_string()).collect();
+        let outputs = batch_output_paths(&inputs, &output_dir, "_watermark");
+
+        let total = inputs.len();
+        let mut results = Vec::with_capacity(total);
+
+        for (idx, (input, output)) in inputs.into_iter().zip(outputs).enumerate() {
+            let file_name = std::path::Path::new(&input)
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+
+            let _ = app.emit(
+                "watermark-batch-progress",
+                BatchProgress {
+                    current: idx + 1,
+                    total,
+                    file: file_name,
+                },
+            );
+
+            let mut file_result = pdf_watermark_text_blocking(
+                app.clone(),
+                input.clone(),
+                output.clone(),
+                text.clone(),
+                options.clone(),
+            )
+            .unwrap_or_else(|message| WatermarkResult {
+                success: false,
+                message,
+                pages_processed: 0,
+                input: None,
+                output: None,
+            });
+
+            file_result.input = Some(input);
+            file_result.output = Some(output);
+            results.push(file_result);
+        }
+
+        Ok(results)
+    })
+    .await
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BatesOptions {
+    prefix: String,
+    start_number: u64,
+    digits: u32,
+    position: String,
+    font_size: f32,
+    pages: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BatesResult {
+    success: bool,
+    message: String,
+    #[serde(default)]
+    pages_processed: u32,
+    #[serde(default)]
+    first_number: Option<String>,
+    #[serde(default)]
+    last_number: Option<String>,
+    /// Number the next page (or file, in a batch) would get
+    #[serde(default)]
+    next_number: Option<u64>,
+    /// Pages (1-indexed) where the stamp had to cover existing content
+    #[serde(default)]
+    overlapping_pages: Vec<u32>,
+    /// Source file, set for batch results
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    input: Option<String>,
+    /// Written file, set for batch results
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    output: Option<String>,
+}
+
+impl BatesOptions {
+    fn new(
+        prefix: Option<String>,
+        start_number: Option<u64>,
+        digits: Option<u32>,
+        position: Option<String>,
+        font_size: Option<f32>,
+        pages: Option<String>,
+    ) -> Self {
+        Self {
+            prefix: prefix.unwrap_or_default(),
+            start_number: start_number.unwrap_or(1),
+            digits: digits.unwrap_or(6),
+            position: position.unwrap_or_else(|| "bottom-right".to_string()),
+            font_size: font_size.unwrap_or(10.0),
+            pages: pages.unwrap_or_else(default_pages),
+        }
     }
-
-    let result = bridge
-        .run_script("pdf_redaction.py", &args)
-        .map_err(|e| e.to_string())?;
-
-    serde_json::from_str(&result.stdout)
-        .map_err(|e| format!("Failed to parse result: {}", e))
 }
 
-/// Get pending redaction marks
-#[tauri::command]
-fn pdf_get_pending_redactions(app: AppHandle, input: String) -> Result<PendingRedactionsResult, String> {
-    let bridge = PythonBridge::new(&app).map_err(|e| e.to_string())?;
+fn run_bates_stamp(
+    bridge: &PythonBridge,
+    input: &str,
+    output: &str,
+    options: &BatesOptions,
+) -> Result<BatesResult, String> {
+    let options_json = serde_json::to_string(options)
+        .map_err(|e| format!("Failed to serialize options: {}", e))?;
 
-    let args: Vec<&str> = vec!["pending", "--input", &input, "--json"];
+    let args: Vec<&str> = vec!["bates", input, output, &options_json];
 
     let result = bridge
-        .run_script("pdf_redaction.py", &args)
+        .run_script("pdf_watermark.py", &args)
         .map_err(|e| e.to_string())?;
 
     serde_json::from_str(&result.stdout)
         .map_err(|e| format!("Failed to parse result: {}", e))
 }
 
-/// Verify redaction was successful
+/// Stamp sequential Bates numbers (e.g. ABC000123) in a page corner
 #[tauri::command]
-fn pdf_verify_redaction(
+async fn pdf_bates_stamp(
     app: AppHandle,
     input: String,
-    page: i32,
-    x0: f64,
-    y0: f64,
-    x1: f64,
-    y1: f64,
-) -> Result<RedactionVerifyResult, String> {
-    let bridge = PythonBridge::new(&app).map_err(|e| e.to_string())?;
-
-    let args: Vec<String> = vec![
-        "verify".to_string(),
-        "--input".to_string(),
-        input,
-        "--page".to_string(),
-        page.to_string(),
-        "--x0".to_string(),
-        x0.to_string(),
-        "--y0".to_string(),
-        y0.to_string(),
-        "--x1".to_string(),
-        x1.to_string(),
-        "--y1".to_string(),
-        y1.to_string(),
-        "--json".to_string(),
-    ];
-
-    let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
-
-    let result = bridge
-        .run_script("pdf_redaction.py", &args_refs)
-        .map_err(|e| e.to_string())?;
-
-    serde_json::from_str(&result.stdout)
-        .map_err(|e| format!("Failed to parse result: {}", e))
+    output: String,
+    prefix: Option<String>,
+    start_number: Option<u64>,
+    digits: Option<u32>,
+    position: Option<String>,
+    font_size: Option<f32>,
+    pages: Option<String>,
+) -> Result<BatesResult, String> {
+    run_blocking(move || {
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+        let options = BatesOptions::new(prefix, start_number, digits, position, font_size, pages);
+        run_bates_stamp(&bridge, &input, &output, &options)
+    })
+    .await
 }
 
-// ============================================================================
-// PDF Sanitization Commands (PythonBridge)
-// ============================================================================
-
-#[derive(Debug, Serialize, Deserialize)]
-struct SanitizationInfo {
-    has_metadata: bool,
-    metadata_fields: Vec<String>,
-    has_javascript: bool,
-    javascript_count: i32,
-    has_embedded_files: bool,
-    embedded_files_count: i32,
-    has_links: bool,
-    links_count: i32,
-    error: Option<String>,
+/// Text per header/footer zone; may contain {page}, {pages}, {date} and {filename}
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct HeaderFooterZones {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    header_left: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    header_center: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    header_right: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    footer_left: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    footer_center: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    footer_right: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-struct SanitizationRemoved {
-    metadata: bool,
-    javascript: i32,
-    embedded_files: i32,
-    links: i32,
-    annotations: i32,
+struct HeaderFooterOptions {
+    zones: HeaderFooterZones,
+    /// "sans", "serif" or "mono"
+    font: String,
+    font_size: f32,
+    /// Distance from the page edge in points
+    margin: f32,
+    pages: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-struct SanitizationResult {
+struct HeaderFooterResult {
     success: bool,
     message: String,
-    removed: SanitizationRemoved,
+    #[serde(default)]
+    pages_processed: u32,
 }
 
-/// Get info about sanitizable content in a PDF
+/// Add running headers/footers in up to six zones, with per-page tokens
 #[tauri::command]
-fn pdf_sanitization_info(app: AppHandle, input: String) -> Result<SanitizationInfo, String> {
-    let bridge = PythonBridge::new(&app).map_err(|e| e.to_string())?;
-
-    let args: Vec<&str> = vec!["info", "--input", &input, "--json"];
+#[allow(clippy::too_many_arguments)]
+async fn pdf_add_header_footer(
+    app: AppHandle,
+    input: String,
+    output: String,
+    header_left: Option<String>,
+    header_center: Option<String>,
+    header_right: Option<String>,
+    footer_left: Option<String>,
+    footer_center: Option<String>,
+    footer_right: Option<String>,
+    font: Option<String>,
+    font_size: Option<f32>,
+    margin: Option<f32>,
+    pages: Option<String>,
+) -> Result<HeaderFooterResult, String> {
+    run_blocking(move || {
+        let options = HeaderFooterOptions {
+            zones: HeaderFooterZones {
+                header_left,
+                header_center,
+                header_right,
+                footer_left,
+                footer_center,
+                footer_right,
+            },
+            font: font.unwrap_or_else(|| "sans".to_string()),
+            font_size: font_size.unwrap_or(10.0),
+            margin: margin.unwrap_or(36.0),
+            pages: pages.unwrap_or_else(default_pages),
+        };
+
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+
+        let options_json = serde_json::to_string(&options)
+            .map_err(|e| format!("Failed to serialize options: {}", e))?;
+
+        let args: Vec<&str> = vec!["header-footer", &input, &output, &options_json];
+
+        let result = bridge
+            .run_script("pdf_watermark.py", &args)
+            .map_err(|e| e.to_string())?;
+
+        let parsed: HeaderFooterResult = serde_json::from_str(&result.stdout)
+            .map_err(|e| format!("Failed to parse result: {}", e))?;
+
+        if !parsed.success {
+            return Err(parsed.message);
+        }
 
-    let result = bridge
-        .run_script("pdf_sanitize.py", &args)
-        .map_err(|e| e.to_string())?;
+        Ok(parsed)
+    })
+    .await
+}
 
-    serde_json::from_str(&result.stdout)
-        .map_err(|e| format!("Failed to parse result: {}", e))
+/// Output path in `output_dir` for each batch input, named after the input.
+/// Inputs that live in `output_dir` itself get `suffix` appended to their stem,
+/// and a numeric suffix (`report_2.pdf`) keeps same-named inputs from
+/// overwriting each other, so no output ever replaces a batch input.
+fn batch_output_paths(inputs: &[String], output_dir: &str, suffix: &str) -> Vec<String> {
+    let canonical =
+        |path: &std::path::Path| std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let dir = canonical(std::path::Path::new(output_dir));
+    let sources: std::collections::HashSet<_> =
+        inputs.iter().map(|input| canonical(std::path::Path::new(input))).collect();
+    let mut taken = std::collections::HashSet::new();
+
+    inputs
+        .iter()
+        .map(|input| {
+            let path = std::path::Path::new(input);
+            let stem = path
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_else(|| "output".to_string());
+            let ext = path
+                .extension()
+                .map(|e| format!(".{}", e.to_string_lossy()))
+                .unwrap_or_default();
+            let in_place = path.parent().is_some_and(|parent| canonical(parent) == dir);
+            let base = if in_place { format!("{}{}", stem, suffix) } else { stem };
+
+            let mut n = 1;
+            loop {
+                let name = if n == 1 {
+                    format!("{}{}", base, ext)
+                } else {
+                    format!("{}_{}{}", base, n, ext)
+                };
+                let candidate = dir.join(&name);
+                if !sources.contains(&candidate) && taken.insert(candidate) {
+                    break std::path::Path::new(output_dir).join(name).to_string_lossy().to_string();
+                }
+                n += 1;
+            }
+        })
+        .collect()
 }
 
-/// Sanitize a PDF by removing metadata, scripts, etc.
+/// Bates-stamp several files in order, numbering continuously across them.
+/// Emits `bates-batch-progress` per file and records failures instead of stopping.
+/// Outputs are named by `batch_output_paths` (suffix `_bates`).
 #[tauri::command]
-fn pdf_sanitize(
+async fn pdf_bates_stamp_batch(
     app: AppHandle,
+    inputs: Vec<String>,
+    output_dir: String,
+    prefix: Option<String>,
+    start_number: Option<u64>,
+    digits: Option<u32>,
+    position: Option<String>,
+    font_size: Option<f32>,
+    pages: Option<String>,
+) -> Result<Vec<BatesResult>, String> {
+    run_blocking(move || {
+        if inputs.is_empty() {
+            return Err("Provide at least one PDF to stamp.".into());
+        }
+
+        std::fs::create_dir_all(&output_dir)
+            .map_err(|e| format!("Failed to create {}: {}", output_dir, e))?;
+
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+        let mut options = BatesOptions::new(prefix, start_number, digits, position, font_size, pages);
+
+        let total = inputs.len();
+        let mut results = Vec::with_capacity(total);
+        let outputs = batch_output_paths(&inputs, &output_dir, "_bates");
+
+        for (idx, (input, output)) in inputs.into_iter().zip(outputs).enumerate() {
+            let file_name = std::path::Path::new(&input)
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+
+            let _ = app.emit(
+                "bates-batch-progress",
+                BatchProgress {
+                    current: idx + 1,
+                    total,
+                    file: file_name,
+                },
+            );
+
+            let mut file_result = run_bates_stamp(&bridge, &input, &output, &options)
+                .unwrap_or_else(|message| BatesResult {
+                    success: false,
+                    message,
+                    pages_processed: 0,
+                    first_number: None,
+                    last_number: None,
+                    next_number: None,
+                    overlapping_pages: Vec::new(),
+                    input: None,
+                    output: None,
+                });
+
+            // A failed file consumes no numbers
+            if let Some(next) = file_result.next_number {
+                options.start_number = next;
+            }
+
+            file_result.input = Some(input);
+            file_result.output = Some(output);
+            results.push(file_result);
+        }
+
+        Ok(results)
+    })
+    .await
+}
+
+/// Operations `batch_run` can dispatch to
+const BATCH_OPERATIONS: &[&str] = &["compress", "ocr", "sanitize", "watermark-text", "encrypt"];
+
+#[derive(Debug, Serialize)]
+struct BatchFileResult {
     input: String,
     output: String,
-    remove_metadata: bool,
-    remove_javascript: bool,
-    remove_embedded_files: bool,
-    remove_links: bool,
-    remove_annotations: bool,
-) -> Result<SanitizationResult, String> {
-    let bridge = PythonBridge::new(&app).map_err(|e| e.to_string())?;
-
-    let mut args: Vec<&str> = vec![
-        "clean",
-        "--input",
-        &input,
-        "--output",
-        &output,
-        "--json",
-    ];
+    success: bool,
+    error: Option<String>,
+}
 
-    if !remove_metadata {
-        args.push("--keep-metadata");
-    }
-    if !remove_javascript {
-        args.push("--keep-javascript");
-    }
-    if !remove_embedded_files {
-        args.push("--keep-embedded");
-    }
-    if remove_links {
-        args.push("--remove-links");
+/// Turn a per-file command result into success/error. Commands that report
+/// failure in their payload (`success: false`) count as errors too.
+fn batch_outcome<T: Serialize, E: Into<String>>(result: Result<T, E>) -> Result<(), String> {
+    let value = serde_json::to_value(result.map_err(Into::into)?).map_err(|e| e.to_string())?;
+    if value["success"].as_bool() == Some(false) {
+        let message = value["error"]
+            .as_str()
+            .or_else(|| value["message"].as_str())
+            .unwrap_or("Operation failed");
+        return Err(message.to_string());
     }
-    if remove_annotations {
-        args.push("--remove-annotations");
+    Ok(())
+}
+
+/// Run one batch operation on a single file, reading its settings from `options`
+fn run_batch_operation(
+    app: &AppHandle,
+    operation: &str,
+    input: String,
+    output: String,
+    options: &serde_json::Value,
+) -> Result<(), String> {
+    let flag = |key: &str, default: bool| options[key].as_bool().unwrap_or(default);
+    let string = |key: &str| options[key].as_str().map(String::from);
+
+    match operation {
+        "compress" => batch_outcome(compress_pdf_blocking(
+            app.clone(),
+            input,
+            Some(output),
+            string("level"),
+            options["verify"].as_bool(),
+            options["target_bytes"].as_u64(),
+            None,
+        )),
+        "ocr" => {
+            let opts: pdf_ocr::OcrOptions = serde_json::from_value(options.clone())
+                .map_err(|e| format!("Invalid OCR options: {}", e))?;
+            batch_outcome(pdf_ocr::run_ocr(app, &input, &output, opts))
+        }
+        "sanitize" => batch_outcome(pdf_sanitize_blocking(
+            app.clone(),
+            input,
+            output,
+            flag("remove_metadata", true),
+            flag("remove_javascript", true),
+            flag("remove_embedded_files", true),
+            flag("remove_links", false),
+            flag("remove_annotations", false),
+            string("link_filter"),
+        )),
+        "watermark-text" => {
+            let text = string("text").ok_or("Watermark options need a text")?;
+            let opts: WatermarkTextOptions = serde_json::from_value(options.clone())
+                .map_err(|e| format!("Invalid watermark options: {}", e))?;
+            batch_outcome(pdf_watermark_text_blocking(app.clone(), input, output, text, opts))
+        }
+        "encrypt" => batch_outcome(pdf_encrypt_blocking(
+            app.clone(),
+            input,
+            Some(output),
+            string("user_password"),
+            string("owner_password"),
+            options["allow_printing"].as_bool(),
+            options["allow_copying"].as_bool(),
+            options["allow_modifying"].as_bool(),
+            options["allow_annotating"].as_bool(),
+            options["allow_filling_forms"].as_bool(),
+            options["allow_accessibility"].as_bool(),
+            options["allow_assembly"].as_bool(),
+            string("encryption_level"),
+        )),
+        other => Err(format!("Unsupported batch operation: {}", other)),
     }
+}
 
-    let result = bridge
-        .run_script("pdf_sanitize.py", &args)
-        .map_err(|e| e.to_string())?;
+/// Run one operation (compress, ocr, sanitize, watermark-text, encrypt) over many
+/// files sequentially, writing each result to `output_dir` under its original name
+/// (deduplicated, and suffixed with the operation for inputs already in `output_dir`).
+/// Emits `batch-progress` per file; a failing file is recorded and the batch goes on.
+#[tauri::command]
+async fn batch_run(
+    app: AppHandle,
+    operation: String,
+    inputs: Vec<String>,
+    output_dir: String,
+    options_json: String,
+) -> Result<Vec<BatchFileResult>, String> {
+    run_blocking(move || {
+        if !BATCH_OPERATIONS.contains(&operation.as_str()) {
+            return Err(format!(
+                "Unsupported batch operation: {} (expected one of: {})",
+                operation,
+                BATCH_OPERATIONS.join(", ")
+            ));
+        }
+        if inputs.is_empty() {
+            return Err("Provide at least one input file.".into());
+        }
 
-    serde_json::from_str(&result.stdout)
-        .map_err(|e| format!("Failed to parse result: {}", e))
+        let options: serde_json::Value = if options_json.trim().is_empty() {
+            serde_json::json!({})
+        } else {
+            serde_json::from_str(&options_json).map_err(|e| format!("Invalid options JSON: {}", e))?
+        };
+
+        std::fs::create_dir_all(&output_dir)
+            .map_err(|e| format!("Failed to create {}: {}", output_dir, e))?;
+
+        let total = inputs.len();
+        let mut results = Vec::with_capacity(total);
+        let suffix = format!("_{}", operation.replace('-', "_"));
+        let outputs = batch_output_paths(&inputs, &output_dir, &suffix);
+
+        for (idx, (input, output)) in inputs.into_iter().zip(outputs).enumerate() {
+            let file_name = std::path::Path::new(&input)
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+
+            let _ = app.emit(
+                "batch-progress",
+                BatchProgress {
+                    current: idx + 1,
+                    total,
+                    file: file_name,
+                },
+            );
+
+            let outcome = run_batch_operation(&app, &operation, input.clone(), output.clone(), &options);
+            results.push(BatchFileResult {
+                input,
+                output,
+                success: outcome.is_ok(),
+                error: outcome.err(),
+            });
+        }
+
+        Ok(results)
+    })
+    .await
 }
 
 // ============================================================================
-// PDF Watermark Commands (PythonBridge)
+// PDF Edit Commands (PythonBridge)
+// Note: pdf_get_text_blocks is already defined in pdf_viewer.rs using native MuPDF
 // ============================================================================
 
 #[derive(Debug, Serialize, Deserialize)]
-struct WatermarkResult {
+struct EditResult {
     success: bool,
     message: String,
-    #[serde(default)]
-    pages_processed: u32,
+}
+
+/// Insert text at a position
+#[tauri::command]
+async fn pdf_insert_text(
+    app: AppHandle,
+    input: String,
+    output: String,
+    page: i32,
+    x: f64,
+    y: f64,
+    text: String,
+    font: Option<String>,
+    size: Option<f64>,
+    coords_normalized: Option<bool>,
+) -> Result<EditResult, String> {
+    run_blocking(move || {
+        let bounds = pdf_viewer::PageBounds::load(&input, page)?;
+        let (x, y) = pdf_viewer::validate_point(&bounds, x, y, coords_normalized.unwrap_or(false))?;
+
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+
+        let page_str = page.to_string();
+        let x_str = x.to_string();
+        let y_str = y.to_string();
+        let font_val = font.unwrap_or_else(|| "helv".to_string());
+        let size_val = size.unwrap_or(12.0).to_string();
+
+        let args: Vec<&str> = vec![
+            "insert-text",
+            "--input", &input,
+            "--output", &output,
+            "--page", &page_str,
+            "--x", &x_str,
+            "--y", &y_str,
+            "--text", &text,
+            "--font", &font_val,
+            "--size", &size_val,
+            "--json",
+        ];
+
+        let result = bridge
+            .run_script("pdf_edit.py", &args)
+            .map_err(|e| e.to_string())?;
+
+        serde_json::from_str(&result.stdout)
+            .map_err(|e| format!("Failed to parse result: {}", e))
+    })
+    .await
+}
+
+/// Replace text in an area
+#[tauri::command]
+async fn pdf_replace_text(
+    app: AppHandle,
+    input: String,
+    output: String,
+    page: i32,
+    x0: f64,
+    y0: f64,
+    x1: f64,
+    y1: f64,
+    text: String,
+    coords_normalized: Option<bool>,
+) -> Result<EditResult, String> {
+    run_blocking(move || {
+        let bounds = pdf_viewer::PageBounds::load(&input, page)?;
+        let (x0, y0, x1, y1) =
+            pdf_viewer::validate_rect(&bounds, x0, y0, x1, y1, coords_normalized.unwrap_or(false))?;
+
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+
+        let page_str = page.to_string();
+        let x0_str = x0.to_string();
+        let y0_str = y0.to_string();
+        let x1_str = x1.to_string();
+        let y1_str = y1.to_string();
+
+        let args: Vec<&str> = vec![
+            "replace-text",
+            "--input", &input,
+            "--output", &output,
+            "--page", &page_str,
+            "--x0", &x0_str,
+            "--y0", &y0_str,
+            "--x1", &x1_str,
+            "--y1", &y1_str,
+            "--text", &text,
+            "--json",
+        ];
+
+        let result = bridge
+            .run_script("pdf_edit.py", &args)
+            .map_err(|e| e.to_string())?;
+
+        serde_json::from_str(&result.stdout)
+            .map_err(|e| format!("Failed to parse result: {}", e))
+    })
+    .await
+}
+
+/// Remove text and vector content inside an area without painting over it
+#[tauri::command]
+async fn pdf_delete_area(
+    app: AppHandle,
+    input: String,
+    output: String,
+    page: i32,
+    x0: f64,
+    y0: f64,
+    x1: f64,
+    y1: f64,
+    coords_normalized: Option<bool>,
+) -> Result<EditResult, String> {
+    run_blocking(move || {
+        let bounds = pdf_viewer::PageBounds::load(&input, page)?;
+        let (x0, y0, x1, y1) =
+            pdf_viewer::validate_rect(&bounds, x0, y0, x1, y1, coords_normalized.unwrap_or(false))?;
+
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+
+        let page_str = page.to_string();
+        let x0_str = x0.to_string();
+        let y0_str = y0.to_string();
+        let x1_str = x1.to_string();
+        let y1_str = y1.to_string();
+
+        let args: Vec<&str> = vec![
+            "delete-area",
+            "--input", &input,
+            "--output", &output,
+            "--page", &page_str,
+            "--x0", &x0_str,
+            "--y0", &y0_str,
+            "--x1", &x1_str,
+            "--y1", &y1_str,
+            "--json",
+        ];
+
+        let result = bridge
+            .run_script("pdf_edit.py", &args)
+            .map_err(|e| e.to_string())?;
+
+        serde_json::from_str(&result.stdout)
+            .map_err(|e| format!("Failed to parse result: {}", e))
+    })
+    .await
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-struct WatermarkTextOptions {
-    #[serde(default = "default_font_size")]
-    font_size: f32,
-    #[serde(default = "default_font_color")]
-    font_color: Vec<f32>,
-    #[serde(default = "default_opacity")]
-    opacity: f32,
-    #[serde(default = "default_rotation")]
-    rotation: f32,
-    #[serde(default = "default_position")]
-    position: String,
-    #[serde(default = "default_pages")]
-    pages: String,
-    #[serde(default = "default_layer")]
-    layer: String,
+struct TransitionsResult {
+    success: bool,
+    message: String,
+    #[serde(default)]
+    pages_set: u32,
+}
+
+/// Set per-page presentation transitions and auto-advance timing. The style
+/// and duration are validated by `pdf_edit.py transitions`.
+#[tauri::command]
+async fn pdf_set_transitions(
+    app: AppHandle,
+    input: String,
+    output: String,
+    transition: String,
+    duration: f32,
+    pages: Option<String>,
+) -> Result<TransitionsResult, String> {
+    run_blocking(move || {
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+
+        let duration_str = duration.to_string();
+        let mut args: Vec<&str> = vec![
+            "transitions",
+            "--input", &input,
+            "--output", &output,
+            "--transition", &transition,
+            "--duration", &duration_str,
+            "--json",
+        ];
+
+        if let Some(ref p) = pages {
+            args.push("--pages");
+            args.push(p);
+        }
+
+        let result = bridge
+            .run_script("pdf_edit.py", &args)
+            .map_err(|e| e.to_string())?;
+
+        let parsed: TransitionsResult = serde_json::from_str(&result.stdout)
+            .map_err(|e| format!("Failed to parse result: {}", e))?;
+
+        if !parsed.success {
+            return Err(parsed.message);
+        }
+
+        Ok(parsed)
+    })
+    .await
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-struct WatermarkImageOptions {
-    #[serde(default = "default_opacity")]
-    opacity: f32,
-    #[serde(default = "default_scale")]
-    scale: f32,
-    #[serde(default = "default_zero_rotation")]
-    rotation: f32,
-    #[serde(default = "default_position")]
-    position: String,
-    #[serde(default = "default_pages")]
-    pages: String,
-    #[serde(default = "default_layer")]
-    layer: String,
+struct PageOrientation {
+    page: u32,
+    /// Rotation declared by the page's /Rotate entry
+    rotate_attribute: i32,
+    /// Dominant text direction as displayed, degrees counter-clockwise
+    text_angle: f32,
+    #[serde(default)]
+    has_text: bool,
+    /// Text still reads at an angle after /Rotate, i.e. drawn rotated in the content stream
+    #[serde(default)]
+    baked_rotation: bool,
+    #[serde(default)]
+    corrected: bool,
 }
 
-fn default_font_size() -> f32 { 48.0 }
-fn default_font_color() -> Vec<f32> { vec![0.5, 0.5, 0.5] }
-fn default_opacity() -> f32 { 0.3 }
-fn default_rotation() -> f32 { -45.0 }
-fn default_zero_rotation() -> f32 { 0.0 }
-fn default_position() -> String { "center".to_string() }
-fn default_pages() -> String { "all".to_string() }
-fn default_layer() -> String { "under".to_string() }
-fn default_scale() -> f32 { 0.5 }
+#[derive(Debug, Serialize, Deserialize)]
+struct AutoRotateResult {
+    success: bool,
+    message: String,
+    #[serde(default)]
+    pages: Vec<PageOrientation>,
+}
 
-/// Add text watermark to PDF
+/// Detect text orientation per page; with `bake`, redraw rotated content upright into `output`
 #[tauri::command]
-fn pdf_watermark_text(
+async fn pdf_auto_rotate(
     app: AppHandle,
     input: String,
-    output: String,
-    text: String,
-    options: WatermarkTextOptions,
-) -> Result<WatermarkResult, String> {
-    let bridge = PythonBridge::new(&app).map_err(|e| e.to_string())?;
+    output: Option<String>,
+    bake: bool,
+    pages: Option<String>,
+) -> Result<AutoRotateResult, String> {
+    run_blocking(move || {
+        if bake && output.is_none() {
+            return Err("An output path is required when baking rotation".to_string());
+        }
 
-    let options_json = serde_json::to_string(&options)
-        .map_err(|e| format!("Failed to serialize options: {}", e))?;
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
 
-    let args: Vec<&str> = vec!["text", &input, &output, &text, &options_json];
+        let mut args: Vec<&str> = vec!["auto-rotate", "--input", &input, "--json"];
 
-    let result = bridge
-        .run_script("pdf_watermark.py", &args)
-        .map_err(|e| e.to_string())?;
+        if let Some(ref o) = output {
+            args.push("--output");
+            args.push(o);
+        }
+        if bake {
+            args.push("--bake");
+        }
+        if let Some(ref p) = pages {
+            args.push("--pages");
+            args.push(p);
+        }
 
-    serde_json::from_str(&result.stdout)
-        .map_err(|e| format!("Failed to parse result: {}", e))
+        let result = bridge
+            .run_script("pdf_edit.py", &args)
+            .map_err(|e| e.to_string())?;
+
+        let parsed: AutoRotateResult = serde_json::from_str(&result.stdout)
+            .map_err(|e| format!("Failed to parse result: {}", e))?;
+
+        if !parsed.success {
+            return Err(parsed.message);
+        }
+
+        Ok(parsed)
+    })
+    .await
 }
 
-/// Add image watermark to PDF
+/// Crop rectangle in points, top-left origin, as the page is displayed
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct CropBox {
+    x0: f64,
+    y0: f64,
+    x1: f64,
+    y1: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PageCrop {
+    page: u32,
+    /// Applied crop box; None when the page was left alone (blank page under auto trim)
+    #[serde(rename = "box")]
+    crop_box: Option<[f64; 4]>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CropPagesResult {
+    success: bool,
+    message: String,
+    #[serde(default)]
+    pages: Vec<PageCrop>,
+}
+
+/// Set page crop boxes to `crop_box`, or with `auto_trim` to each page's
+/// detected content plus `padding` points (for scans with heavy margins)
 #[tauri::command]
-fn pdf_watermark_image(
+async fn pdf_crop_pages(
     app: AppHandle,
     input: String,
     output: String,
-    image_path: String,
-    options: WatermarkImageOptions,
-) -> Result<WatermarkResult, String> {
-    let bridge = PythonBridge::new(&app).map_err(|e| e.to_string())?;
+    crop_box: Option<CropBox>,
+    pages: Option<String>,
+    auto_trim: bool,
+    padding: Option<f64>,
+) -> Result<CropPagesResult, String> {
+    run_blocking(move || {
+        let box_arg = match (crop_box, auto_trim) {
+            (Some(_), true) | (None, false) => {
+                return Err("Provide either a crop box or auto trim".to_string());
+            }
+            (Some(b), false) => {
+                if b.x1 <= b.x0 || b.y1 <= b.y0 {
+                    return Err("Crop box must have a positive width and height".to_string());
+                }
+                Some(format!("{},{},{},{}", b.x0, b.y0, b.x1, b.y1))
+            }
+            (None, true) => None,
+        };
+
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+
+        let mut args: Vec<String> = vec![
+            "crop".to_string(),
+            "--input".to_string(),
+            input,
+            "--output".to_string(),
+            output,
+            "--json".to_string(),
+        ];
+
+        if let Some(b) = box_arg {
+            args.push("--box".to_string());
+            args.push(b);
+        }
+        if auto_trim {
+            args.push("--auto-trim".to_string());
+        }
+        if let Some(p) = padding {
+            args.push("--padding".to_string());
+            args.push(p.to_string());
+        }
+        if let Some(p) = pages {
+            args.push("--pages".to_string());
+            args.push(p);
+        }
 
-    let options_json = serde_json::to_string(&options)
-        .map_err(|e| format!("Failed to serialize options: {}", e))?;
+        let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
 
-    let args: Vec<&str> = vec!["image", &input, &output, &image_path, &options_json];
+        let result = bridge
+            .run_script("pdf_edit.py", &args_refs)
+            .map_err(|e| e.to_string())?;
 
-    let result = bridge
-        .run_script("pdf_watermark.py", &args)
-        .map_err(|e| e.to_string())?;
+        let parsed: CropPagesResult = serde_json::from_str(&result.stdout)
+            .map_err(|e| format!("Failed to parse result: {}", e))?;
 
-    serde_json::from_str(&result.stdout)
-        .map_err(|e| format!("Failed to parse result: {}", e))
-}
+        if !parsed.success {
+            return Err(parsed.message);
+        }
 
-// ============================================================================
-// PDF Edit Commands (PythonBridge)
-// Note: pdf_get_text_blocks is already defined in pdf_viewer.rs using native MuPDF
-// ============================================================================
+        Ok(parsed)
+    })
+    .await
+}
 
 #[derive(Debug, Serialize, Deserialize)]
-struct EditResult {
+struct ContentOptimizeResult {
+    output_path: String,
+    /// Input size minus output size (negative if the file grew)
+    bytes_saved: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct ContentOptimizeOutput {
     success: bool,
     message: String,
+    #[serde(flatten)]
+    result: ContentOptimizeResult,
 }
 
-/// Insert text at a position
+/// Rebuild page content streams compactly without touching images or fonts
 #[tauri::command]
-fn pdf_insert_text(
+async fn pdf_optimize_content_streams(
     app: AppHandle,
     input: String,
     output: String,
-    page: i32,
-    x: f64,
-    y: f64,
-    text: String,
-    font: Option<String>,
-    size: Option<f64>,
-) -> Result<EditResult, String> {
-    let bridge = PythonBridge::new(&app).map_err(|e| e.to_string())?;
-
-    let page_str = page.to_string();
-    let x_str = x.to_string();
-    let y_str = y.to_string();
-    let font_val = font.unwrap_or_else(|| "helv".to_string());
-    let size_val = size.unwrap_or(12.0).to_string();
-
-    let args: Vec<&str> = vec![
-        "insert-text",
-        "--input", &input,
-        "--output", &output,
-        "--page", &page_str,
-        "--x", &x_str,
-        "--y", &y_str,
-        "--text", &text,
-        "--font", &font_val,
-        "--size", &size_val,
-        "--json",
-    ];
+) -> Result<ContentOptimizeResult, String> {
+    run_blocking(move || {
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+
+        let args: Vec<&str> = vec![
+            "optimize-content",
+            "--input", &input,
+            "--output", &output,
+            "--json",
+        ];
+
+        let result = bridge
+            .run_script("pdf_edit.py", &args)
+            .map_err(|e| e.to_string())?;
+
+        let parsed: ContentOptimizeOutput = serde_json::from_str(&result.stdout)
+            .map_err(|e| format!("Failed to parse result: {}", e))?;
+
+        if !parsed.success {
+            return Err(parsed.message);
+        }
 
-    let result = bridge
-        .run_script("pdf_edit.py", &args)
-        .map_err(|e| e.to_string())?;
+        Ok(parsed.result)
+    })
+    .await
+}
 
-    serde_json::from_str(&result.stdout)
-        .map_err(|e| format!("Failed to parse result: {}", e))
+/// Destination of a link annotation
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+enum LinkTarget {
+    Uri { uri: String },
+    /// Internal jump; page is 1-indexed, y is normalized (0-1) from the top
+    Page { page: u32, y: Option<f32> },
 }
 
-/// Replace text in an area
+/// URI schemes accepted by `pdf_edit.py add-link`
+const LINK_URI_SCHEMES: &[&str] = &["http", "https", "mailto", "ftp"];
+
+/// Add a link annotation over a normalized region of `page` (1-indexed)
 #[tauri::command]
-fn pdf_replace_text(
+async fn pdf_add_link(
     app: AppHandle,
     input: String,
     output: String,
-    page: i32,
-    x0: f64,
-    y0: f64,
-    x1: f64,
-    y1: f64,
-    text: String,
+    page: u32,
+    rect: pdf_viewer::NormalizedRect,
+    target: LinkTarget,
 ) -> Result<EditResult, String> {
-    let bridge = PythonBridge::new(&app).map_err(|e| e.to_string())?;
+    run_blocking(move || {
+        if page == 0 {
+            return Err("Page numbers start at 1".to_string());
+        }
 
-    let page_str = page.to_string();
-    let x0_str = x0.to_string();
-    let y0_str = y0.to_string();
-    let x1_str = x1.to_string();
-    let y1_str = y1.to_string();
+        let mut target_args: Vec<String> = Vec::new();
+        match &target {
+            LinkTarget::Uri { uri } => {
+                let scheme = uri.trim().split(':').next().unwrap_or("").to_lowercase();
+                if !uri.contains(':') || !LINK_URI_SCHEMES.contains(&scheme.as_str()) {
+                    return Err(format!(
+                        "Unsupported link URI '{}'. Expected one of: {}",
+                        uri,
+                        LINK_URI_SCHEMES.join(", ")
+                    ));
+                }
+                target_args.push("--uri".to_string());
+                target_args.push(uri.trim().to_string());
+            }
+            LinkTarget::Page { page: target_page, y } => {
+                if *target_page == 0 {
+                    return Err("Target page numbers start at 1".to_string());
+                }
+                target_args.push("--target-page".to_string());
+                target_args.push(target_page.to_string());
+                if let Some(y) = y {
+                    target_args.push("--target-y".to_string());
+                    target_args.push(y.clamp(0.0, 1.0).to_string());
+                }
+            }
+        }
 
-    let args: Vec<&str> = vec![
-        "replace-text",
-        "--input", &input,
-        "--output", &output,
-        "--page", &page_str,
-        "--x0", &x0_str,
-        "--y0", &y0_str,
-        "--x1", &x1_str,
-        "--y1", &y1_str,
-        "--text", &text,
-        "--json",
-    ];
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
 
-    let result = bridge
-        .run_script("pdf_edit.py", &args)
-        .map_err(|e| e.to_string())?;
+        let page_str = page.to_string();
+        let rect_str = format!("{},{},{},{}", rect.x, rect.y, rect.width, rect.height);
 
-    serde_json::from_str(&result.stdout)
-        .map_err(|e| format!("Failed to parse result: {}", e))
+        let mut args: Vec<&str> = vec![
+            "add-link",
+            "--input", &input,
+            "--output", &output,
+            "--page", &page_str,
+            "--rect", &rect_str,
+            "--json",
+        ];
+        args.extend(target_args.iter().map(|s| s.as_str()));
+
+        let result = bridge
+            .run_script("pdf_edit.py", &args)
+            .map_err(|e| e.to_string())?;
+
+        let parsed: EditResult = serde_json::from_str(&result.stdout)
+            .map_err(|e| format!("Failed to parse result: {}", e))?;
+
+        if !parsed.success {
+            return Err(parsed.message);
+        }
+
+        Ok(parsed)
+    })
+    .await
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -1580,13 +4994,23 @@ struct ApplyEditsResult {
 
 /// Apply multiple edit operations from JSON
 #[tauri::command]
-fn pdf_apply_edits(
+async fn pdf_apply_edits(
     app: AppHandle,
     input: String,
     output: String,
     edits_json: String,
 ) -> Result<ApplyEditsResult, String> {
-    let bridge = PythonBridge::new(&app).map_err(|e| e.to_string())?;
+    run_blocking(move || pdf_apply_edits_blocking(app, input, output, edits_json)).await
+}
+
+/// Blocking body of `pdf_apply_edits`, also run by `pdf_apply_edit_script`
+fn pdf_apply_edits_blocking(
+    app: AppHandle,
+    input: String,
+    output: String,
+    edits_json: String,
+) -> Result<ApplyEditsResult, String> {
+    let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
 
     let args: Vec<&str> = vec![
         "apply-edits",
@@ -1615,6 +5039,38 @@ fn pdf_apply_edits(
         .map_err(|e| format!("Failed to parse result: {}\nStdout was: {}", e, result.stdout))
 }
 
+/// Replay an exported edit-script onto another document, validated against its pages
+#[tauri::command]
+async fn pdf_apply_edit_script(
+    app: AppHandle,
+    input: String,
+    output: String,
+    script: String,
+) -> Result<ApplyEditsResult, String> {
+    run_blocking(move || {
+        let info = pdf_viewer::pdf_open(app.state(), input.clone(), None)?;
+        let ops = edit_session::validate_edit_script(&script, info.num_pages)?;
+
+        // Normalized rects are resolved against the target's own page sizes
+        let mut page_widths = serde_json::Map::new();
+        let mut page_heights = serde_json::Map::new();
+        for (i, size) in info.page_sizes.iter().enumerate() {
+            page_widths.insert(i.to_string(), serde_json::json!(size.width));
+            page_heights.insert(i.to_string(), serde_json::json!(size.height));
+        }
+
+        let edits_json = serde_json::json!({
+            "ops": ops,
+            "pageWidths": page_widths,
+            "pageHeights": page_heights,
+        })
+        .to_string();
+
+        pdf_apply_edits_blocking(app, input, output, edits_json)
+    })
+    .await
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct PreviewResult {
     success: bool,
@@ -1626,38 +5082,41 @@ struct PreviewResult {
 
 /// Render a page preview with edits applied (without saving)
 #[tauri::command]
-fn pdf_render_preview(
+async fn pdf_render_preview(
     app: AppHandle,
     input: String,
     page: i32,
     edits_json: String,
     dpi: Option<i32>,
 ) -> Result<PreviewResult, String> {
-    let bridge = PythonBridge::new(&app).map_err(|e| e.to_string())?;
-
-    let page_str = page.to_string();
-    let dpi_str = dpi.unwrap_or(150).to_string();
-
-    let args: Vec<&str> = vec![
-        "preview",
-        "--input", &input,
-        "--page", &page_str,
-        "--edits", &edits_json,
-        "--dpi", &dpi_str,
-        "--json",
-    ];
-
-    let result = bridge
-        .run_script("pdf_edit.py", &args)
-        .map_err(|e| e.to_string())?;
-
-    // Log stderr for debugging
-    if !result.stderr.is_empty() {
-        eprintln!("[pdf_render_preview] Python stderr:\n{}", result.stderr);
-    }
+    run_blocking(move || {
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+
+        let page_str = page.to_string();
+        let dpi_str = dpi.unwrap_or(150).to_string();
+
+        let args: Vec<&str> = vec![
+            "preview",
+            "--input", &input,
+            "--page", &page_str,
+            "--edits", &edits_json,
+            "--dpi", &dpi_str,
+            "--json",
+        ];
+
+        let result = bridge
+            .call("pdf_edit.py", &args)
+            .map_err(|e| e.to_string())?;
+
+        // Log stderr for debugging
+        if !result.stderr.is_empty() {
+            eprintln!("[pdf_render_preview] Python stderr:\n{}", result.stderr);
+        }
 
-    serde_json::from_str(&result.stdout)
-        .map_err(|e| format!("Failed to parse result: {}\nStdout was: {}", e, result.stdout))
+        serde_json::from_str(&result.stdout)
+            .map_err(|e| format!("Failed to parse result: {}\nStdout was: {}", e, result.stdout))
+    })
+    .await
 }
 
 // Normalized rect for font info (separate from pdf_viewer's version for f64 compatibility)
@@ -1735,33 +5194,36 @@ struct TextBlocksFontsResult {
 
 /// Get text blocks with detailed font information
 #[tauri::command]
-fn pdf_get_text_blocks_with_fonts(
+async fn pdf_get_text_blocks_with_fonts(
     app: AppHandle,
     input: String,
     page: i32,
 ) -> Result<TextBlocksFontsResult, String> {
-    let bridge = PythonBridge::new(&app).map_err(|e| e.to_string())?;
+    run_blocking(move || {
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
 
-    let page_str = page.to_string();
+        let page_str = page.to_string();
 
-    let args: Vec<&str> = vec![
-        "text-blocks-fonts",
-        "--input", &input,
-        "--page", &page_str,
-        "--json",
-    ];
+        let args: Vec<&str> = vec![
+            "text-blocks-fonts",
+            "--input", &input,
+            "--page", &page_str,
+            "--json",
+        ];
 
-    let result = bridge
-        .run_script("pdf_edit.py", &args)
-        .map_err(|e| e.to_string())?;
+        let result = bridge
+            .call("pdf_edit.py", &args)
+            .map_err(|e| e.to_string())?;
 
-    // Log stderr for debugging
-    if !result.stderr.is_empty() {
-        eprintln!("[pdf_get_text_blocks_with_fonts] Python stderr:\n{}", result.stderr);
-    }
+        // Log stderr for debugging
+        if !result.stderr.is_empty() {
+            eprintln!("[pdf_get_text_blocks_with_fonts] Python stderr:\n{}", result.stderr);
+        }
 
-    serde_json::from_str(&result.stdout)
-        .map_err(|e| format!("Failed to parse result: {}\nStdout was: {}", e, result.stdout))
+        serde_json::from_str(&result.stdout)
+            .map_err(|e| format!("Failed to parse result: {}\nStdout was: {}", e, result.stdout))
+    })
+    .await
 }
 
 // ============================================================================
@@ -1782,31 +5244,159 @@ struct AttachmentInfo {
 
 /// List all embedded files in a PDF
 #[tauri::command]
-fn attachments_list(app: AppHandle, input: String) -> Result<Vec<AttachmentInfo>, String> {
-    let bridge = PythonBridge::new(&app).map_err(|e| e.to_string())?;
+async fn attachments_list(app: AppHandle, input: String) -> Result<Vec<AttachmentInfo>, String> {
+    run_blocking(move || {
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+
+        let args: Vec<&str> = vec!["list", "--input", &input];
+
+        let result = bridge
+            .run_script("pdf_attachments.py", &args)
+            .map_err(|e| e.to_string())?;
+
+        parse_attachment_list(&result.stdout)
+    })
+    .await
+}
+
+/// Parse the attachment list printed by `pdf_attachments.py`
+fn parse_attachment_list(stdout: &str) -> Result<Vec<AttachmentInfo>, String> {
+    let attachments: Vec<serde_json::Value> = serde_json::from_str(stdout)
+        .map_err(|e| format!("Failed to parse result: {}", e))?;
+
+    Ok(attachments.iter().map(attachment_info).collect())
+}
+
+fn attachment_info(a: &serde_json::Value) -> AttachmentInfo {
+    AttachmentInfo {
+        index: a["index"].as_u64().unwrap_or(0) as u32,
+        name: a["name"].as_str().unwrap_or("").to_string(),
+        filename: a["filename"].as_str().unwrap_or("").to_string(),
+        size: a["size"].as_u64().unwrap_or(0),
+        length: a["length"].as_u64().unwrap_or(0),
+        created: a["created"].as_str().unwrap_or("").to_string(),
+        modified: a["modified"].as_str().unwrap_or("").to_string(),
+        description: a["description"].as_str().unwrap_or("").to_string(),
+    }
+}
+
+/// A file to embed with `attachments_add_many`
+#[derive(Debug, Serialize, Deserialize)]
+struct NewAttachment {
+    file_path: String,
+    /// Display name (defaults to the file name)
+    name: Option<String>,
+    description: Option<String>,
+}
+
+/// Embed a file as a PDF attachment. Returns the updated attachment list.
+#[tauri::command]
+async fn attachments_add(
+    app: AppHandle,
+    input: String,
+    output: String,
+    file_path: String,
+    name: Option<String>,
+    description: Option<String>,
+) -> Result<Vec<AttachmentInfo>, String> {
+    run_blocking(move || {
+        attachments_add_many_blocking(
+            app,
+            input,
+            output,
+            vec![NewAttachment {
+                file_path,
+                name,
+                description,
+            }],
+        )
+    })
+    .await
+}
+
+/// Embed several files in one pass. Returns the updated attachment list.
+#[tauri::command]
+async fn attachments_add_many(
+    app: AppHandle,
+    input: String,
+    output: String,
+    files: Vec<NewAttachment>,
+) -> Result<Vec<AttachmentInfo>, String> {
+    run_blocking(move || attachments_add_many_blocking(app, input, output, files)).await
+}
 
-    let args: Vec<&str> = vec!["list", "--input", &input];
+/// Blocking body of `attachments_add_many`, also run by `attachments_add`
+fn attachments_add_many_blocking(
+    app: AppHandle,
+    input: String,
+    output: String,
+    files: Vec<NewAttachment>,
+) -> Result<Vec<AttachmentInfo>, String> {
+    if files.is_empty() {
+        return Err("No files to attach".to_string());
+    }
+
+    let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+
+    let files_json = serde_json::to_string(&files)
+        .map_err(|e| format!("Failed to serialize files: {}", e))?;
+    let args: Vec<&str> = vec!["add-many", "--input", &input, "--output", &output, "--files-json", &files_json];
 
     let result = bridge
         .run_script("pdf_attachments.py", &args)
         .map_err(|e| e.to_string())?;
 
-    let attachments: Vec<serde_json::Value> = serde_json::from_str(&result.stdout)
+    parse_attachment_list(&result.stdout)
+}
+
+#[derive(Debug, Serialize)]
+struct AttachmentDeleteResult {
+    removed: u32,
+    /// Attachments left in the output
+    attachments: Vec<AttachmentInfo>,
+}
+
+/// Run a delete subcommand of `pdf_attachments.py` and parse its result
+fn run_attachment_delete(app: &AppHandle, args: &[&str]) -> Result<AttachmentDeleteResult, String> {
+    let bridge = PythonBridge::shared(app).map_err(|e| e.to_string())?;
+
+    let result = bridge
+        .run_script("pdf_attachments.py", args)
+        .map_err(|e| e.to_string())?;
+
+    let parsed: serde_json::Value = serde_json::from_str(&result.stdout)
         .map_err(|e| format!("Failed to parse result: {}", e))?;
 
-    Ok(attachments
-        .iter()
-        .map(|a| AttachmentInfo {
-            index: a["index"].as_u64().unwrap_or(0) as u32,
-            name: a["name"].as_str().unwrap_or("").to_string(),
-            filename: a["filename"].as_str().unwrap_or("").to_string(),
-            size: a["size"].as_u64().unwrap_or(0),
-            length: a["length"].as_u64().unwrap_or(0),
-            created: a["created"].as_str().unwrap_or("").to_string(),
-            modified: a["modified"].as_str().unwrap_or("").to_string(),
-            description: a["description"].as_str().unwrap_or("").to_string(),
-        })
-        .collect())
+    Ok(AttachmentDeleteResult {
+        removed: parsed["removed"].as_u64().unwrap_or(0) as u32,
+        attachments: parsed["attachments"]
+            .as_array()
+            .map(|arr| arr.iter().map(attachment_info).collect())
+            .unwrap_or_default(),
+    })
+}
+
+/// Delete an embedded file by name or index (indexes disambiguate duplicate names)
+#[tauri::command]
+async fn attachments_delete(
+    app: AppHandle,
+    input: String,
+    output: String,
+    name: String,
+) -> Result<AttachmentDeleteResult, String> {
+    run_blocking(move || {
+        run_attachment_delete(&app, &["delete", "--input", &input, "--output", &output, "--name", &name])
+    })
+    .await
+}
+
+/// Delete every embedded file
+#[tauri::command]
+async fn attachments_delete_all(app: AppHandle, input: String, output: String) -> Result<AttachmentDeleteResult, String> {
+    run_blocking(move || {
+        run_attachment_delete(&app, &["delete-all", "--input", &input, "--output", &output])
+    })
+    .await
 }
 
 #[derive(Debug, Serialize)]
@@ -1819,81 +5409,87 @@ struct AttachmentExtractResult {
 
 /// Extract a single embedded file
 #[tauri::command]
-fn attachments_extract(
+async fn attachments_extract(
     app: AppHandle,
     input: String,
     name: String,
-    output: Option<String>,
-) -> Result<AttachmentExtractResult, String> {
-    let output_path = output.unwrap_or_else(|| {
-        let cache_dir = app
-            .path()
-            .app_cache_dir()
-            .unwrap_or_else(|_| std::env::temp_dir());
-        cache_dir
-            .join("attachments")
-            .join(&name)
-            .to_string_lossy()
-            .to_string()
-    });
-
-    let bridge = PythonBridge::new(&app).map_err(|e| e.to_string())?;
-
-    let args: Vec<&str> = vec!["extract", "--input", &input, "--name", &name, "--output", &output_path];
-
-    let result = bridge
-        .run_script("pdf_attachments.py", &args)
-        .map_err(|e| e.to_string())?;
-
-    let parsed: serde_json::Value = serde_json::from_str(&result.stdout)
-        .map_err(|e| format!("Failed to parse result: {}", e))?;
-
-    Ok(AttachmentExtractResult {
-        success: parsed["success"].as_bool().unwrap_or(false),
-        path: parsed["path"].as_str().unwrap_or("").to_string(),
-        name: parsed["name"].as_str().unwrap_or("").to_string(),
-        size: parsed["size"].as_u64().unwrap_or(0),
+    output: Option<String>,
+) -> Result<AttachmentExtractResult, String> {
+    run_blocking(move || {
+        let output_path = output.unwrap_or_else(|| {
+            let cache_dir = app
+                .path()
+                .app_cache_dir()
+                .unwrap_or_else(|_| std::env::temp_dir());
+            cache_dir
+                .join("attachments")
+                .join(&name)
+                .to_string_lossy()
+                .to_string()
+        });
+
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+
+        let args: Vec<&str> = vec!["extract", "--input", &input, "--name", &name, "--output", &output_path];
+
+        let result = bridge
+            .run_script("pdf_attachments.py", &args)
+            .map_err(|e| e.to_string())?;
+
+        let parsed: serde_json::Value = serde_json::from_str(&result.stdout)
+            .map_err(|e| format!("Failed to parse result: {}", e))?;
+
+        Ok(AttachmentExtractResult {
+            success: parsed["success"].as_bool().unwrap_or(false),
+            path: parsed["path"].as_str().unwrap_or("").to_string(),
+            name: parsed["name"].as_str().unwrap_or("").to_string(),
+            size: parsed["size"].as_u64().unwrap_or(0),
+        })
     })
+    .await
 }
 
 /// Extract all embedded files to a directory
 #[tauri::command]
-fn attachments_extract_all(
+async fn attachments_extract_all(
     app: AppHandle,
     input: String,
     output_dir: Option<String>,
 ) -> Result<Vec<AttachmentExtractResult>, String> {
-    let out_dir = output_dir.unwrap_or_else(|| {
-        let cache_dir = app
-            .path()
-            .app_cache_dir()
-            .unwrap_or_else(|_| std::env::temp_dir());
-        cache_dir
-            .join("attachments")
-            .to_string_lossy()
-            .to_string()
-    });
-
-    let bridge = PythonBridge::new(&app).map_err(|e| e.to_string())?;
-
-    let args: Vec<&str> = vec!["extract-all", "--input", &input, "--output-dir", &out_dir];
-
-    let result = bridge
-        .run_script("pdf_attachments.py", &args)
-        .map_err(|e| e.to_string())?;
-
-    let parsed: Vec<serde_json::Value> = serde_json::from_str(&result.stdout)
-        .map_err(|e| format!("Failed to parse result: {}", e))?;
-
-    Ok(parsed
-        .iter()
-        .map(|a| AttachmentExtractResult {
-            success: a["success"].as_bool().unwrap_or(false),
-            path: a["path"].as_str().unwrap_or("").to_string(),
-            name: a["name"].as_str().unwrap_or("").to_string(),
-            size: a["size"].as_u64().unwrap_or(0),
-        })
-        .collect())
+    run_blocking(move || {
+        let out_dir = output_dir.unwrap_or_else(|| {
+            let cache_dir = app
+                .path()
+                .app_cache_dir()
+                .unwrap_or_else(|_| std::env::temp_dir());
+            cache_dir
+                .join("attachments")
+                .to_string_lossy()
+                .to_string()
+        });
+
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+
+        let args: Vec<&str> = vec!["extract-all", "--input", &input, "--output-dir", &out_dir];
+
+        let result = bridge
+            .run_script("pdf_attachments.py", &args)
+            .map_err(|e| e.to_string())?;
+
+        let parsed: Vec<serde_json::Value> = serde_json::from_str(&result.stdout)
+            .map_err(|e| format!("Failed to parse result: {}", e))?;
+
+        Ok(parsed
+            .iter()
+            .map(|a| AttachmentExtractResult {
+                success: a["success"].as_bool().unwrap_or(false),
+                path: a["path"].as_str().unwrap_or("").to_string(),
+                name: a["name"].as_str().unwrap_or("").to_string(),
+                size: a["size"].as_u64().unwrap_or(0),
+            })
+            .collect())
+    })
+    .await
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -1908,17 +5504,48 @@ struct AttachmentPreview {
 
 /// Get attachment content for preview (images as base64, text as string)
 #[tauri::command]
-fn attachments_preview(app: AppHandle, input: String, name: String) -> Result<AttachmentPreview, String> {
-    let bridge = PythonBridge::new(&app).map_err(|e| e.to_string())?;
+async fn attachments_preview(app: AppHandle, input: String, name: String) -> Result<AttachmentPreview, String> {
+    run_blocking(move || {
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
 
-    let args: Vec<&str> = vec!["preview", "--input", &input, "--name", &name];
+        let args: Vec<&str> = vec!["preview", "--input", &input, "--name", &name];
 
-    let result = bridge
-        .run_script("pdf_attachments.py", &args)
-        .map_err(|e| e.to_string())?;
+        let result = bridge
+            .run_script("pdf_attachments.py", &args)
+            .map_err(|e| e.to_string())?;
 
-    serde_json::from_str(&result.stdout)
-        .map_err(|e| format!("Failed to parse result: {}", e))
+        serde_json::from_str(&result.stdout)
+            .map_err(|e| format!("Failed to parse result: {}", e))
+    })
+    .await
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct AttachmentRisk {
+    name: String,
+    /// Type identified from magic bytes (exe, zip, pdf, text, ...)
+    detected_type: String,
+    /// "low", "medium" or "high"
+    risk: String,
+    reason: String,
+}
+
+/// Assess embedded files for executables, scripts, archives and disguised extensions
+#[tauri::command]
+async fn attachments_scan(app: AppHandle, input: String) -> Result<Vec<AttachmentRisk>, String> {
+    run_blocking(move || {
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+
+        let args: Vec<&str> = vec!["scan", "--input", &input];
+
+        let result = bridge
+            .run_script("pdf_attachments.py", &args)
+            .map_err(|e| e.to_string())?;
+
+        serde_json::from_str(&result.stdout)
+            .map_err(|e| format!("Failed to parse result: {}", e))
+    })
+    .await
 }
 
 // ============================================================================
@@ -1958,8 +5585,13 @@ struct FormFillResult {
 
 /// List all form fields in a PDF
 #[tauri::command]
-fn form_fields_list(app: AppHandle, input: String) -> Result<FormFieldsResult, String> {
-    let bridge = PythonBridge::new(&app).map_err(|e| e.to_string())?;
+async fn form_fields_list(app: AppHandle, input: String) -> Result<FormFieldsResult, String> {
+    run_blocking(move || form_fields_list_blocking(app, input)).await
+}
+
+/// Blocking body of `form_fields_list`, also run by `form_fields_validate`
+fn form_fields_list_blocking(app: AppHandle, input: String) -> Result<FormFieldsResult, String> {
+    let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
 
     let args: Vec<&str> = vec!["list", &input];
 
@@ -2013,40 +5645,459 @@ fn form_fields_list(app: AppHandle, input: String) -> Result<FormFieldsResult, S
 
 /// Fill form fields and save to output path
 #[tauri::command]
-fn form_fields_fill(
+async fn form_fields_fill(
     app: AppHandle,
     input: String,
     output: String,
     field_values: std::collections::HashMap<String, serde_json::Value>,
 ) -> Result<FormFillResult, String> {
-    let bridge = PythonBridge::new(&app).map_err(|e| e.to_string())?;
+    run_blocking(move || {
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
 
-    let values_json = serde_json::to_string(&field_values)
-        .map_err(|e| format!("Failed to serialize field values: {}", e))?;
+        let values_json = serde_json::to_string(&field_values)
+            .map_err(|e| format!("Failed to serialize field values: {}", e))?;
 
-    let args: Vec<&str> = vec!["fill", &input, &output, &values_json];
+        let args: Vec<&str> = vec!["fill", &input, &output, &values_json];
 
-    let result = bridge
-        .run_script("pdf_forms.py", &args)
-        .map_err(|e| e.to_string())?;
+        let result = bridge
+            .run_script("pdf_forms.py", &args)
+            .map_err(|e| e.to_string())?;
 
-    let parsed: serde_json::Value = serde_json::from_str(&result.stdout)
-        .map_err(|e| format!("Failed to parse result: {}", e))?;
+        let parsed: serde_json::Value = serde_json::from_str(&result.stdout)
+            .map_err(|e| format!("Failed to parse result: {}", e))?;
 
-    if let Some(error) = parsed.get("error") {
-        return Err(error.as_str().unwrap_or("Unknown error").to_string());
+        if let Some(error) = parsed.get("error") {
+            return Err(error.as_str().unwrap_or("Unknown error").to_string());
+        }
+
+        Ok(FormFillResult {
+            success: parsed["success"].as_bool().unwrap_or(false),
+            filled_count: parsed["filled_count"].as_u64().unwrap_or(0) as u32,
+            errors: parsed["errors"].as_array().map(|arr| {
+                arr.iter()
+                    .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                    .collect()
+            }),
+            output_path: parsed["output_path"].as_str().unwrap_or("").to_string(),
+        })
+    })
+    .await
+}
+
+/// Field data interchange formats understood by pdf_forms.py
+fn form_data_format(format: Option<String>) -> Result<String, String> {
+    let format = format.unwrap_or_else(|| "json".into()).to_lowercase();
+    match format.as_str() {
+        "json" | "fdf" => Ok(format),
+        other => Err(format!("Unsupported form data format: {}", other)),
+    }
+}
+
+fn string_list(value: &serde_json::Value) -> Vec<String> {
+    value
+        .as_array()
+        .map(|arr| arr.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+        .unwrap_or_default()
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+struct FormExportResult {
+    format: String,
+    /// Serialized field values (JSON envelope or FDF document)
+    data: String,
+    field_count: u32,
+    read_only: Vec<String>,
+}
+
+/// Export current field values as JSON (same map `form_fields_fill` takes) or FDF
+#[tauri::command]
+async fn form_fields_export(
+    app: AppHandle,
+    input: String,
+    format: Option<String>,
+) -> Result<FormExportResult, String> {
+    run_blocking(move || {
+        let format = form_data_format(format)?;
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+
+        let args: Vec<&str> = vec!["export", &input, &format];
+
+        let result = bridge
+            .run_script("pdf_forms.py", &args)
+            .map_err(|e| e.to_string())?;
+
+        let parsed: serde_json::Value = serde_json::from_str(&result.stdout)
+            .map_err(|e| format!("Failed to parse result: {}", e))?;
+
+        if let Some(error) = parsed.get("error") {
+            return Err(error.as_str().unwrap_or("Unknown error").to_string());
+        }
+
+        Ok(FormExportResult {
+            format,
+            data: parsed["data"].as_str().unwrap_or("").to_string(),
+            field_count: parsed["field_count"].as_u64().unwrap_or(0) as u32,
+            read_only: string_list(&parsed["read_only"]),
+        })
+    })
+    .await
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+struct FormImportResult {
+    success: bool,
+    filled_count: u32,
+    errors: Option<Vec<String>>,
+    skipped_read_only: Vec<String>,
+    unknown_fields: Vec<String>,
+    output_path: String,
+}
+
+/// Fill a form from data produced by `form_fields_export` (or any JSON map / FDF file).
+/// Read-only fields are skipped.
+#[tauri::command]
+async fn form_fields_import(
+    app: AppHandle,
+    input: String,
+    output: String,
+    data: String,
+    format: Option<String>,
+) -> Result<FormImportResult, String> {
+    run_blocking(move || {
+        let format = form_data_format(format)?;
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+
+        let args: Vec<&str> = vec!["import", &input, &output, &format, &data];
+
+        let result = bridge
+            .run_script("pdf_forms.py", &args)
+            .map_err(|e| e.to_string())?;
+
+        let parsed: serde_json::Value = serde_json::from_str(&result.stdout)
+            .map_err(|e| format!("Failed to parse result: {}", e))?;
+
+        if let Some(error) = parsed.get("error") {
+            return Err(error.as_str().unwrap_or("Unknown error").to_string());
+        }
+
+        Ok(FormImportResult {
+            success: parsed["success"].as_bool().unwrap_or(false),
+            filled_count: parsed["filled_count"].as_u64().unwrap_or(0) as u32,
+            errors: parsed["errors"].as_array().map(|_| string_list(&parsed["errors"])),
+            skipped_read_only: string_list(&parsed["skipped_read_only"]),
+            unknown_fields: string_list(&parsed["unknown_fields"]),
+            output_path: parsed["output_path"].as_str().unwrap_or("").to_string(),
+        })
+    })
+    .await
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct FormFieldSpec {
+    name: String,
+    field_type: String,
+    /// Page number (1-indexed)
+    page: u32,
+    /// Field rectangle in PDF points [x0, y0, x1, y1]
+    rect: Vec<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    choices: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+struct FormCreateResult {
+    success: bool,
+    created: u32,
+    names: Vec<String>,
+    output_path: String,
+}
+
+/// Create new AcroForm fields (text, checkbox, choice, signature) at the given rects
+#[tauri::command]
+async fn form_fields_create(
+    app: AppHandle,
+    input: String,
+    output: String,
+    fields: Vec<FormFieldSpec>,
+) -> Result<FormCreateResult, String> {
+    run_blocking(move || {
+        if fields.is_empty() {
+            return Err("Provide at least one field to create.".into());
+        }
+
+        let mut seen = std::collections::HashSet::new();
+        for field in &fields {
+            if !seen.insert(field.name.as_str()) {
+                return Err(format!("Duplicate field name: {}", field.name));
+            }
+            if field.rect.len() != 4 {
+                return Err(format!("Field '{}' rect must have 4 coordinates", field.name));
+            }
+        }
+
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+
+        let fields_json = serde_json::to_string(&fields)
+            .map_err(|e| format!("Failed to serialize fields: {}", e))?;
+
+        let args: Vec<&str> = vec!["create", &input, &output, &fields_json];
+
+        let result = bridge
+            .run_script("pdf_forms.py", &args)
+            .map_err(|e| e.to_string())?;
+
+        let parsed: serde_json::Value = serde_json::from_str(&result.stdout)
+            .map_err(|e| format!("Failed to parse result: {}", e))?;
+
+        if let Some(error) = parsed.get("error") {
+            return Err(error.as_str().unwrap_or("Unknown error").to_string());
+        }
+
+        Ok(FormCreateResult {
+            success: parsed["success"].as_bool().unwrap_or(false),
+            created: parsed["created"].as_u64().unwrap_or(0) as u32,
+            names: parsed["names"]
+                .as_array()
+                .map(|arr| arr.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+                .unwrap_or_default(),
+            output_path: parsed["output_path"].as_str().unwrap_or("").to_string(),
+        })
+    })
+    .await
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+struct FormFlattenResult {
+    success: bool,
+    flattened: Vec<String>,
+    flattened_count: u32,
+    /// Fields left interactive (empty when the whole form was flattened)
+    remaining: Vec<String>,
+    output_path: String,
+}
+
+/// Burn field appearances into the page content and remove the widgets,
+/// optionally only for the named fields
+#[tauri::command]
+async fn form_fields_flatten(
+    app: AppHandle,
+    input: String,
+    output: String,
+    fields: Option<Vec<String>>,
+) -> Result<FormFlattenResult, String> {
+    run_blocking(move || {
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+
+        let mut args: Vec<String> = vec!["flatten".into(), input, output];
+        if let Some(names) = fields.filter(|names| !names.is_empty()) {
+            args.push(
+                serde_json::to_string(&names)
+                    .map_err(|e| format!("Failed to serialize field names: {}", e))?,
+            );
+        }
+        let args_ref: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+
+        let result = bridge
+            .run_script("pdf_forms.py", &args_ref)
+            .map_err(|e| e.to_string())?;
+
+        let parsed: serde_json::Value = serde_json::from_str(&result.stdout)
+            .map_err(|e| format!("Failed to parse result: {}", e))?;
+
+        if let Some(error) = parsed.get("error") {
+            return Err(error.as_str().unwrap_or("Unknown error").to_string());
+        }
+
+        Ok(FormFlattenResult {
+            success: parsed["success"].as_bool().unwrap_or(false),
+            flattened: string_list(&parsed["flattened"]),
+            flattened_count: parsed["flattened_count"].as_u64().unwrap_or(0) as u32,
+            remaining: string_list(&parsed["remaining"]),
+            output_path: parsed["output_path"].as_str().unwrap_or("").to_string(),
+        })
+    })
+    .await
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SignatureSigner {
+    name: String,
+    role: String,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+struct SignaturePageResult {
+    output_path: String,
+    fields_created: Vec<String>,
+}
+
+/// Append a signature page: signature line, date line and printed name per signer,
+/// with matching AcroForm signature and date fields
+#[tauri::command]
+async fn pdf_add_signature_page(
+    app: AppHandle,
+    input: String,
+    output: String,
+    signers: Vec<SignatureSigner>,
+) -> Result<SignaturePageResult, String> {
+    run_blocking(move || {
+        if signers.is_empty() {
+            return Err("Provide at least one signer.".into());
+        }
+        if signers.iter().any(|s| s.name.trim().is_empty()) {
+            return Err("Every signer needs a name.".into());
+        }
+
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+
+        let signers_json = serde_json::to_string(&signers)
+            .map_err(|e| format!("Failed to serialize signers: {}", e))?;
+
+        let args: Vec<&str> = vec!["signature-page", &input, &output, &signers_json];
+
+        let result = bridge
+            .run_script("pdf_forms.py", &args)
+            .map_err(|e| e.to_string())?;
+
+        let parsed: serde_json::Value = serde_json::from_str(&result.stdout)
+            .map_err(|e| format!("Failed to parse result: {}", e))?;
+
+        if let Some(error) = parsed.get("error") {
+            return Err(error.as_str().unwrap_or("Unknown error").to_string());
+        }
+
+        Ok(SignaturePageResult {
+            output_path: parsed["output_path"].as_str().unwrap_or(&output).to_string(),
+            fields_created: parsed["fields_created"]
+                .as_array()
+                .map(|arr| arr.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+                .unwrap_or_default(),
+        })
+    })
+    .await
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+struct FormFieldIssue {
+    field: String,
+    reason: String,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+struct FormValidationResult {
+    complete: bool,
+    missing: Vec<String>,
+    invalid: Vec<FormFieldIssue>,
+}
+
+/// Whether a field currently holds no usable value
+fn form_field_is_empty(field: &FormField) -> bool {
+    if field.field_type == "checkbox" {
+        return field.checked != Some(true);
+    }
+    match &field.value {
+        serde_json::Value::Null => true,
+        serde_json::Value::Bool(b) => !b,
+        serde_json::Value::String(s) => s.trim().is_empty() || s == "Off",
+        serde_json::Value::Array(items) => items.is_empty(),
+        _ => false,
+    }
+}
+
+/// Check a field's current value against its own type, length and choice constraints
+fn form_field_violation(field: &FormField) -> Option<String> {
+    if form_field_is_empty(field) {
+        return None;
+    }
+
+    match field.field_type.as_str() {
+        "text" => {
+            let len = match &field.value {
+                serde_json::Value::String(s) => s.chars().count() as u32,
+                other => other.to_string().chars().count() as u32,
+            };
+            match field.max_length {
+                Some(max) if max > 0 && len > max => Some(format!(
+                    "Value has {} characters, maximum is {}",
+                    len, max
+                )),
+                _ => None,
+            }
+        }
+        "combobox" | "listbox" => {
+            let choices = field.choices.as_deref().unwrap_or(&[]);
+            if choices.is_empty() {
+                return None;
+            }
+            let values: Vec<String> = match &field.value {
+                serde_json::Value::Array(items) => items
+                    .iter()
+                    .map(|v| v.as_str().map(String::from).unwrap_or_else(|| v.to_string()))
+                    .collect(),
+                serde_json::Value::String(s) => vec![s.clone()],
+                other => vec![other.to_string()],
+            };
+            values
+                .iter()
+                .find(|v| !choices.contains(v))
+                .map(|v| format!("'{}' is not one of the allowed choices", v))
+        }
+        _ => None,
+    }
+}
+
+fn validate_form_fields(fields: &[FormField], required: &[String]) -> FormValidationResult {
+    let mut missing = Vec::new();
+    let mut invalid = Vec::new();
+
+    for name in required {
+        // Radio groups and multi-widget fields appear once per widget; any filled widget counts
+        let widgets: Vec<&FormField> = fields.iter().filter(|f| &f.name == name).collect();
+        if widgets.is_empty() {
+            invalid.push(FormFieldIssue {
+                field: name.clone(),
+                reason: "Field does not exist in the form".to_string(),
+            });
+        } else if widgets.iter().all(|f| form_field_is_empty(f)) {
+            missing.push(name.clone());
+        }
+    }
+
+    for field in fields {
+        if let Some(reason) = form_field_violation(field) {
+            if !invalid.iter().any(|i: &FormFieldIssue| i.field == field.name) {
+                invalid.push(FormFieldIssue {
+                    field: field.name.clone(),
+                    reason,
+                });
+            }
+        }
     }
 
-    Ok(FormFillResult {
-        success: parsed["success"].as_bool().unwrap_or(false),
-        filled_count: parsed["filled_count"].as_u64().unwrap_or(0) as u32,
-        errors: parsed["errors"].as_array().map(|arr| {
-            arr.iter()
-                .filter_map(|v| v.as_str().map(|s| s.to_string()))
-                .collect()
-        }),
-        output_path: parsed["output_path"].as_str().unwrap_or("").to_string(),
+    FormValidationResult {
+        complete: missing.is_empty() && invalid.is_empty(),
+        missing,
+        invalid,
+    }
+}
+
+/// Check that required fields are filled and that values respect field constraints
+#[tauri::command]
+async fn form_fields_validate(
+    app: AppHandle,
+    input: String,
+    required: Vec<String>,
+) -> Result<FormValidationResult, String> {
+    run_blocking(move || {
+        let form = form_fields_list_blocking(app, input)?;
+
+        if !form.is_form {
+            return Err("PDF does not contain form fields".into());
+        }
+
+        Ok(validate_form_fields(&form.fields, &required))
     })
+    .await
 }
 
 // ============================================================================
@@ -2080,6 +6131,11 @@ struct FontInfo {
     #[serde(rename = "bestMatchScore")]
     best_match_score: i32,
     status: String,
+    /// Used characters the embedded font has no glyph for (coverage check only)
+    #[serde(rename = "missingGlyphs", default)]
+    missing_glyphs: Vec<String>,
+    #[serde(rename = "coveragePercent", default)]
+    coverage_percent: Option<f32>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -2098,24 +6154,41 @@ struct FontAnalysisResult {
     error: Option<String>,
 }
 
-/// Analyze fonts in a PDF document
+/// Analyze fonts in a PDF document.
+/// `check_coverage` also cross-checks used characters against embedded glyphs (slower).
 #[tauri::command]
-fn pdf_analyze_fonts(app: AppHandle, input: String) -> Result<FontAnalysisResult, String> {
-    let bridge = PythonBridge::new(&app).map_err(|e| e.to_string())?;
-
-    let args: Vec<&str> = vec!["analyze-fonts", "--input", &input, "--json"];
+async fn pdf_analyze_fonts(
+    app: AppHandle,
+    input: String,
+    check_coverage: Option<bool>,
+) -> Result<FontAnalysisResult, String> {
+    run_blocking(move || {
+        let bridge = PythonBridge::shared(&app).map_err(|e| e.to_string())?;
+
+        let mut args: Vec<&str> = vec!["analyze-fonts", "--input", &input, "--json"];
+        if check_coverage.unwrap_or(false) {
+            args.push("--coverage");
+        }
 
-    let result = bridge
-        .run_script("pdf_edit.py", &args)
-        .map_err(|e| e.to_string())?;
+        let result = bridge
+            .run_script("pdf_edit.py", &args)
+            .map_err(|e| e.to_string())?;
 
-    serde_json::from_str(&result.stdout)
-        .map_err(|e| format!("Failed to parse font analysis result: {}\nStdout: {}", e, result.stdout))
+        serde_json::from_str(&result.stdout)
+            .map_err(|e| format!("Failed to parse font analysis result: {}\nStdout: {}", e, result.stdout))
+    })
+    .await
 }
 
-/// Replace a file with another file (atomic rename for in-place save)
+/// Replace a file with another file (atomic rename for in-place save).
+/// With `backup`, the current destination is first copied to `{name}.bak`
+/// (overwriting the previous backup) and the backup path is returned.
 #[tauri::command]
-fn replace_file(from: String, to: String) -> Result<(), String> {
+fn replace_file(
+    from: String,
+    to: String,
+    backup: Option<bool>,
+) -> Result<Option<String>, String> {
     use std::fs;
     use std::path::Path;
 
@@ -2127,6 +6200,16 @@ fn replace_file(from: String, to: String) -> Result<(), String> {
         return Err(format!("Source file does not exist: {}", from));
     }
 
+    let mut backup_path = None;
+    if backup.unwrap_or(false) && to_path.exists() {
+        let file_name = to_path
+            .file_name()
+            .ok_or_else(|| format!("Invalid destination path: {}", to))?;
+        let bak = to_path.with_file_name(format!("{}.bak", file_name.to_string_lossy()));
+        fs::copy(to_path, &bak).map_err(|e| format!("Failed to back up original file: {}", e))?;
+        backup_path = Some(bak.to_string_lossy().to_string());
+    }
+
     // Remove destination if it exists
     if to_path.exists() {
         fs::remove_file(to_path).map_err(|e| format!("Failed to remove original file: {}", e))?;
@@ -2135,7 +6218,7 @@ fn replace_file(from: String, to: String) -> Result<(), String> {
     // Rename temp file to destination
     fs::rename(from_path, to_path).map_err(|e| format!("Failed to rename file: {}", e))?;
 
-    Ok(())
+    Ok(backup_path)
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -2168,6 +6251,9 @@ pub fn run() {
     .plugin(tauri_plugin_fs::init())
     .plugin(tauri_plugin_dialog::init())
     .plugin(tauri_plugin_store::Builder::new().build())
+    .manage(python_bridge::PythonBridgeCache::default())
+    .manage(pdf_viewer::DocumentCache::default())
+    .manage(edit_session::EditSessionStore::default())
     .menu(|app| {
       let file = SubmenuBuilder::new(app, "File")
         .item(
@@ -2281,6 +6367,13 @@ pub fn run() {
       }
     })
     .setup(|app| {
+      app.manage(python_bridge::ScriptLimiter::for_app(app.handle()));
+      let handle = app.handle().clone();
+      std::thread::spawn(move || {
+        if let Err(e) = PythonBridge::shared(&handle).and_then(|bridge| bridge.start_worker()) {
+          log::warn!("Python worker not started: {}", e);
+        }
+      });
       if cfg!(debug_assertions) {
         app.handle().plugin(
           tauri_plugin_log::Builder::default()
@@ -2294,9 +6387,13 @@ pub fn run() {
       // Python bridge
       python_check,
       python_check_packages,
+      python_queue_status,
+      python_set_max_concurrent,
       python_install_package,
+      python_set_interpreter,
       // PDF compression (MuPDF)
       compress_pdf,
+      preview_compression,
       estimate_compression,
       // OCR (Python/OCRmyPDF)
       ocr_check_dependencies,
@@ -2304,27 +6401,54 @@ pub fn run() {
       ocr_run,
       ocr_run_editable,
       ocr_get_metrics,
+      ocr_detect_language,
+      ocr_list_languages,
+      ocr_install_language,
+      pdf_detect_scanned_form,
       // PDF operations (PythonBridge)
       merge_pdfs,
       merge_pages,
+      merge_interleave,
+      pdf_impose,
+      pdf_overlay,
       split_pdf,
+      split_every_n,
+      pdf_split_by_bookmarks,
+      pdf_extract_range,
       rotate_pdf,
       images_to_pdf,
       pdf_to_images,
+      pdf_extract_images,
+      pdf_to_multipage_tiff,
+      pdf_flatten_to_images,
+      pdf_to_images_zip,
       // PDF viewer
       pdf_viewer::pdf_open,
+      pdf_viewer::pdf_page_size_report,
       pdf_viewer::pdf_render_page,
+      pdf_viewer::pdf_render_pages,
       pdf_viewer::pdf_render_thumbnail,
       pdf_viewer::pdf_render_thumbnails,
+      thumbnail_cache::thumbnails_clear_cache,
       pdf_viewer::pdf_close,
       pdf_viewer::pdf_get_text_blocks,
+      pdf_viewer::pdf_get_links,
+      pdf_viewer::pdf_get_annotations,
+      pdf_viewer::pdf_extract_text,
       pdf_viewer::pdf_search_text,
       pdf_viewer::pdf_get_outlines,
+      pdf_viewer::pdf_set_outline,
+      pdf_viewer::pdf_text_stats,
+      pdf_viewer::pdf_section_stats,
+      pdf_viewer::pdf_list_substituted_fonts,
       pdf_viewer::pdf_get_metadata,
+      pdf_viewer::pdf_set_metadata,
+      pdf_viewer::pdf_diff,
       // Annotations (JSON file-based)
       annotations::annotations_save,
       annotations::annotations_load,
       annotations::annotations_delete,
+      annotations::annotations_render_previews,
       // Annotations (PDF embedded)
       annotations_embed_in_pdf,
       annotations_read_from_pdf,
@@ -2333,38 +6457,87 @@ pub fn run() {
       // Print commands
       print_prepare_pdf,
       print_pdf,
+      print_pdf_direct,
+      list_printers,
       // Attachments
       attachments_list,
       attachments_extract,
       attachments_extract_all,
       attachments_preview,
+      attachments_scan,
+      attachments_add,
+      attachments_add_many,
+      attachments_delete,
+      attachments_delete_all,
       form_fields_list,
       form_fields_fill,
+      form_fields_create,
+      form_fields_flatten,
+      form_fields_export,
+      form_fields_import,
+      form_fields_validate,
+      pdf_add_signature_page,
       // PDF Security
       pdf_check_security,
       pdf_unlock,
+      pdf_unlock_batch,
+      pdf_set_permissions,
       pdf_encrypt,
       // Graphical Signatures
       apply_graphical_signature,
       check_pdf_signatures,
+      sign_pdf_digital,
       // Layers
       pdf_get_layers,
       pdf_set_layer,
+      pdf_clean_orphan_layers,
+      pdf_create_layer,
+      pdf_delete_layer,
       // Redaction
       pdf_add_redaction,
       pdf_apply_redactions,
+      pdf_redact_pattern,
       pdf_get_pending_redactions,
       pdf_verify_redaction,
+      pdf_verify_all_redactions,
       // Sanitization
       pdf_sanitization_info,
+      pdf_sanitize_preview,
       pdf_sanitize,
+      pdf_anonymize_ids,
+      // Inspection
+      pdf_composition,
+      pdf_find_oversized_images,
+      pdf_check_text_extractable,
+      pdf_repair_tounicode,
       // Watermark
       pdf_watermark_text,
+      pdf_watermark_text_native,
       pdf_watermark_image,
+      pdf_watermark_batch,
+      pdf_bates_stamp,
+      pdf_bates_stamp_batch,
+      pdf_add_header_footer,
+      batch_run,
       // PDF Edit (pdf_get_text_blocks is in pdf_viewer)
       pdf_insert_text,
       pdf_replace_text,
+      pdf_delete_area,
+      pdf_set_transitions,
+      pdf_auto_rotate,
+      pdf_crop_pages,
+      pdf_add_link,
+      pdf_optimize_content_streams,
       pdf_apply_edits,
+      pdf_apply_edit_script,
+      // Edit sessions
+      edit_session::edit_session_begin,
+      edit_session::edit_session_apply,
+      edit_session::edit_session_undo,
+      edit_session::edit_session_redo,
+      edit_session::edit_session_export,
+      edit_session::edit_session_commit,
+      edit_session::edit_session_close,
       pdf_render_preview,
       pdf_get_text_blocks_with_fonts,
       pdf_analyze_fonts,
@@ -2374,3 +6547,204 @@ pub fn run() {
     .run(tauri::generate_context!())
     .expect("error while running tauri application");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(name: &str, field_type: &str, value: serde_json::Value) -> FormField {
+        FormField {
+            name: name.to_string(),
+            field_type: field_type.to_string(),
+            type_id: 0,
+            value,
+            page: 0,
+            rect: vec![0.0, 0.0, 10.0, 10.0],
+            read_only: false,
+            choices: None,
+            checked: None,
+            on_state: None,
+            max_length: None,
+            multiline: None,
+        }
+    }
+
+    #[test]
+    fn test_validate_form_fields() {
+        let mut zip = field("zip", "text", serde_json::json!("123456"));
+        zip.max_length = Some(5);
+        let mut country = field("country", "combobox", serde_json::json!("Atlantis"));
+        country.choices = Some(vec!["MX".to_string(), "US".to_string()]);
+        let fields = vec![
+            field("name", "text", serde_json::json!("Ana")),
+            field("email", "text", serde_json::json!("  ")),
+            zip,
+            country,
+        ];
+
+        let required = vec!["name".to_string(), "email".to_string(), "phone".to_string()];
+        let result = validate_form_fields(&fields, &required);
+
+        assert!(!result.complete);
+        assert_eq!(result.missing, vec!["email".to_string()]);
+        let invalid: Vec<&str> = result.invalid.iter().map(|i| i.field.as_str()).collect();
+        assert_eq!(invalid, vec!["phone", "zip", "country"]);
+    }
+
+    #[test]
+    fn test_parse_encryption_level() {
+        assert_eq!(parse_encryption_level(None).unwrap(), "aes_256");
+        assert_eq!(parse_encryption_level(Some("AES_128".into())).unwrap(), "aes_128");
+        assert!(parse_encryption_level(Some("des".into())).is_err());
+    }
+
+    #[test]
+    fn test_parse_link_filter() {
+        assert_eq!(parse_link_filter(None).unwrap(), None);
+        assert_eq!(parse_link_filter(Some("External".into())).unwrap().as_deref(), Some("external"));
+        assert_eq!(parse_link_filter(Some("*.example.com".into())).unwrap().as_deref(), Some("*.example.com"));
+        assert!(parse_link_filter(Some("".into())).is_err());
+        assert!(parse_link_filter(Some("http://x/".into())).is_err());
+    }
+
+    #[test]
+    fn test_parse_impose_layout() {
+        assert_eq!(parse_impose_layout("2x1").unwrap(), (2, 1));
+        assert_eq!(parse_impose_layout("2X2").unwrap(), (2, 2));
+        assert!(parse_impose_layout("1x1").is_err());
+        assert!(parse_impose_layout("two").is_err());
+        assert!(parse_impose_layout("2x").is_err());
+    }
+
+    #[test]
+    fn test_form_data_format() {
+        assert_eq!(form_data_format(None).unwrap(), "json");
+        assert_eq!(form_data_format(Some("FDF".into())).unwrap(), "fdf");
+        assert!(form_data_format(Some("xfdf".into())).is_err());
+    }
+
+    #[test]
+    fn test_exif_orientation_transform() {
+        use image::{ImageEncoder, Rgb, RgbImage};
+
+        // Little-endian TIFF block holding a single Orientation = 6 (rotate 90° CW) entry
+        let exif: Vec<u8> = vec![
+            b'I', b'I', 42, 0, 8, 0, 0, 0, // header, IFD at offset 8
+            1, 0, // one entry
+            0x12, 0x01, 3, 0, 1, 0, 0, 0, 6, 0, 0, 0, // Orientation, SHORT, 1 value: 6
+            0, 0, 0, 0, // no next IFD
+        ];
+        let pixels = RgbImage::from_pixel(4, 2, Rgb([200, 40, 40]));
+        let path = std::env::temp_dir().join(format!("tlacuilo-exif-{}.jpg", std::process::id()));
+        let mut file = std::fs::File::create(&path).unwrap();
+        let mut encoder = image::codecs::jpeg::JpegEncoder::new(&mut file);
+        encoder.set_exif_metadata(exif).unwrap();
+        encoder
+            .write_image(pixels.as_raw(), 4, 2, image::ExtendedColorType::Rgb8)
+            .unwrap();
+        drop(file);
+        let path_str = path.to_string_lossy().to_string();
+
+        assert_eq!(exif_orientation_transform(&path_str), Some((90, false, false)));
+
+        let images = vec![path_str.clone(), path_str];
+        let explicit = ImageTransform {
+            rotation: Some(180),
+            ..Default::default()
+        };
+        let resolved = resolve_image_transforms(
+            &images,
+            Some(vec![ImageTransform::default(), explicit]),
+            None,
+        );
+        assert_eq!(resolved[0].rotation, Some(90));
+        assert_eq!(resolved[1].rotation, Some(180));
+        assert_eq!(resolve_image_transforms(&images, None, Some("auto"))[1].rotation, Some(90));
+
+        // EXIF is only honoured when the orientation is "auto"
+        assert_eq!(resolve_image_transforms(&images, None, Some("portrait"))[0].rotation, None);
+        let per_image = ImageTransform {
+            orientation: Some("landscape".into()),
+            ..Default::default()
+        };
+        let resolved = resolve_image_transforms(&images, Some(vec![per_image]), Some("auto"));
+        assert_eq!(resolved[0].rotation, None);
+        assert_eq!(resolved[1].rotation, Some(90));
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_parse_lp_job_id() {
+        assert_eq!(
+            parse_lp_job_id("request id is Office-42 (1 file(s))\n").as_deref(),
+            Some("Office-42")
+        );
+        assert_eq!(parse_lp_job_id(""), None);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_parse_lpstat_printers() {
+        let printers = "printer Office is idle.  enabled since Tue 01 Oct 2024\n\
+            \tForm mounts:\n\
+            \tLocation: 2nd floor\n\
+            printer Lab now printing Lab-12.  enabled since Tue 01 Oct 2024\n\
+            \tLocation: \n\
+            printer Old disabled since Mon 30 Sep 2024 -\n\
+            \treason unknown\n";
+        let parsed = parse_lpstat_printers(printers, "system default destination: Lab\n");
+        assert_eq!(parsed.len(), 3);
+        assert_eq!(parsed[0].name, "Office");
+        assert_eq!(parsed[0].status, "idle");
+        assert_eq!(parsed[0].location.as_deref(), Some("2nd floor"));
+        assert!(!parsed[0].is_default);
+        assert_eq!(parsed[1].status, "printing");
+        assert_eq!(parsed[1].location, None);
+        assert!(parsed[1].is_default);
+        assert_eq!(parsed[2].status, "disabled");
+
+        assert!(parse_lpstat_printers("", "no system default destination\n").is_empty());
+    }
+
+    #[test]
+    fn test_batch_output_paths() {
+        let root = std::env::temp_dir().join(format!("tlacuilo-batch-{}", std::process::id()));
+        let (a, b, out) = (root.join("a"), root.join("b"), root.join("out"));
+        for dir in [&a, &b, &out] {
+            std::fs::create_dir_all(dir).unwrap();
+        }
+        let path = |dir: &std::path::Path, name: &str| dir.join(name).to_string_lossy().to_string();
+        let sources = [
+            (&a, "report.pdf"),
+            (&a, "memo.pdf"),
+            (&b, "report.pdf"),
+            (&out, "report.pdf"),
+            (&out, "report_bates.pdf"),
+        ];
+        for (dir, name) in sources {
+            std::fs::write(dir.join(name), b"%PDF").unwrap();
+        }
+        let out_dir = out.to_string_lossy().to_string();
+
+        let inputs = vec![path(&a, "report.pdf"), path(&b, "report.pdf"), path(&a, "memo.pdf")];
+        assert_eq!(
+            batch_output_paths(&inputs, &out_dir, "_bates"),
+            vec![path(&out, "report.pdf"), path(&out, "report_2.pdf"), path(&out, "memo.pdf")]
+        );
+
+        // Inputs already in the output folder are suffixed, never overwritten
+        let inputs = vec![path(&out, "report.pdf"), path(&out, "report_bates.pdf"), path(&a, "report.pdf")];
+        assert_eq!(
+            batch_output_paths(&inputs, &out_dir, "_bates"),
+            vec![
+                path(&out, "report_bates_2.pdf"),
+                path(&out, "report_bates_bates.pdf"),
+                path(&out, "report_2.pdf"),
+            ]
+        );
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
@@ -4,8 +4,13 @@
 //! - Low: Just garbage collection (remove unused objects)
 //! - Medium: Garbage + basic compression
 //! - High: Full compression + optimization + linearization
+//!
+//! Optionally verifies the output by rendering pages from both files and
+//! comparing them, and reports how image and font stream bytes changed.
 
-use mupdf::pdf::{PdfDocument, PdfWriteOptions};
+use crate::pdf_viewer::{page_similarity, render_page, RenderFormat, RenderedPage, DEFAULT_RENDER_QUALITY};
+use mupdf::pdf::{PdfDocument, PdfObject, PdfWriteOptions};
+use mupdf::Document;
 use serde::{Deserialize, Serialize};
 use std::fs;
 
@@ -21,6 +26,19 @@ pub enum CompressionLevel {
     High,
 }
 
+/// Documents up to this many pages are verified in full; longer ones are sampled
+const VERIFY_ALL_PAGES_MAX: u32 = 20;
+/// Pages compared when sampling a long document
+const VERIFY_SAMPLE_PAGES: u32 = 10;
+/// Longer side (pixels) of the renders compared during verification
+const VERIFY_RENDER_SIZE: u32 = 400;
+/// Mean per-channel difference (0-1) above which a page is reported as visibly changed
+pub const VISUAL_DIFF_WARNING_THRESHOLD: f32 = 0.02;
+/// Resolution of the before/after renders in a compression preview
+const PREVIEW_DPI: u32 = 96;
+/// Image caps tried in order when compressing to a target size: (max DPI, JPEG quality)
+const TARGET_SIZE_STEPS: &[(u32, u8)] = &[(200, 80), (150, 70), (120, 60), (96, 50), (72, 40)];
+
 impl Default for CompressionLevel {
     fn default() -> Self {
         Self::Medium
@@ -42,32 +60,157 @@ pub struct CompressionResult {
     pub bytes_saved: i64,
     /// Percentage reduction
     pub percent_saved: f64,
+    /// Largest per-page visual difference (0-1), set when verification ran
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_visual_diff: Option<f32>,
+    /// Visual difference of each verified page
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub page_diffs: Vec<PageVisualDiff>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+    /// Compression passes attempted, set in target-size mode
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub passes: Option<u32>,
+    /// Whether the output reached the requested size, set in target-size mode
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_met: Option<bool>,
+    /// Encoded image stream bytes before compression, set in detailed mode
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub images_before: Option<u64>,
+    /// Encoded image stream bytes after compression, set in detailed mode
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub images_after: Option<u64>,
+    /// Embedded font program bytes before compression, set in detailed mode
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fonts_before: Option<u64>,
+    /// Embedded font program bytes after compression, set in detailed mode
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fonts_after: Option<u64>,
+    /// Streams stored unfiltered in the original that gained a filter, set in detailed mode
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub streams_recompressed: Option<u32>,
 }
 
-/// Compress a PDF file
-///
-/// # Arguments
-/// * `input` - Path to input PDF file
-/// * `output` - Path to output PDF file (can be same as input for in-place)
-/// * `level` - Compression level
-///
-/// # Returns
-/// Result containing compression statistics
-pub fn compress_pdf(
-    input: &str,
-    output: &str,
-    level: CompressionLevel,
-) -> Result<CompressionResult, String> {
-    // Get original file size
-    let original_size = fs::metadata(input)
-        .map_err(|e| format!("Failed to read input file metadata: {}", e))?
-        .len();
+/// Stream bytes of a document grouped by what the stream holds
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+struct StreamBreakdown {
+    images: u64,
+    fonts: u64,
+    unfiltered_streams: u32,
+}
 
-    // Open the PDF document
-    let doc = PdfDocument::open(input)
-        .map_err(|e| format!("Failed to open PDF: {:?}", e))?;
+/// Visual difference between original and compressed rendering of a page
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PageVisualDiff {
+    /// Page number (1-indexed)
+    pub page: u32,
+    /// Mean per-channel difference (0 = identical, 1 = inverted)
+    pub diff: f32,
+}
+
+/// Pages (1-indexed) to compare: all of a short document, else an even spread including first and last
+fn verification_pages(page_count: u32) -> Vec<u32> {
+    if page_count <= VERIFY_ALL_PAGES_MAX {
+        return (1..=page_count).collect();
+    }
+
+    let steps = VERIFY_SAMPLE_PAGES - 1;
+    let mut pages: Vec<u32> = (0..=steps)
+        .map(|i| 1 + i * (page_count - 1) / steps)
+        .collect();
+    pages.dedup();
+    pages
+}
+
+/// Whether a stream dictionary holds an embedded font program (/FontFile,
+/// /FontFile2 carry /Length1; /FontFile3 carries a font /Subtype)
+fn is_font_stream(dict: &PdfObject, subtype: Option<&str>) -> Result<bool, mupdf::Error> {
+    if matches!(subtype, Some("Type1C" | "CIDFontType0C" | "OpenType")) {
+        return Ok(true);
+    }
+    Ok(dict.get_dict("Length1")?.is_some())
+}
+
+/// Walk every xref object and sum encoded stream lengths by subtype
+fn stream_breakdown(doc: &PdfDocument) -> Result<StreamBreakdown, String> {
+    let walk = || -> Result<StreamBreakdown, mupdf::Error> {
+        let mut breakdown = StreamBreakdown::default();
+
+        for num in 1..doc.count_objects()? as i32 {
+            let obj = doc.new_indirect(num, 0)?;
+            if !obj.is_stream()? {
+                continue;
+            }
+
+            let subtype = obj
+                .get_dict("Subtype")?
+                .and_then(|o| o.as_name().ok().map(|n| String::from_utf8_lossy(n).to_string()));
+            let length = match obj.get_dict("Length")? {
+                Some(len) => len.as_int()?.max(0) as u64,
+                None => obj.read_raw_stream()?.len() as u64,
+            };
+
+            if subtype.as_deref() == Some("Image") {
+                breakdown.images += length;
+            } else if is_font_stream(&obj, subtype.as_deref())? {
+                breakdown.fonts += length;
+            }
+            if obj.get_dict("Filter")?.is_none() {
+                breakdown.unfiltered_streams += 1;
+            }
+        }
+
+        Ok(breakdown)
+    };
+
+    walk().map_err(|e| format!("Failed to inspect PDF streams: {:?}", e))
+}
+
+/// Breakdown of the file at `path`
+fn stream_breakdown_of(path: &str) -> Result<StreamBreakdown, String> {
+    let doc = PdfDocument::open(path)
+        .map_err(|e| format!("Failed to open PDF for stream breakdown: {:?}", e))?;
+    stream_breakdown(&doc)
+}
+
+/// Copy a before/after breakdown into the result
+fn apply_breakdown(result: &mut CompressionResult, before: StreamBreakdown, after: StreamBreakdown) {
+    result.images_before = Some(before.images);
+    result.images_after = Some(after.images);
+    result.fonts_before = Some(before.fonts);
+    result.fonts_after = Some(after.fonts);
+    result.streams_recompressed = Some(
+        before
+            .unfiltered_streams
+            .saturating_sub(after.unfiltered_streams),
+    );
+}
+
+/// Render sampled pages from both files and measure how much they differ
+fn verify_visual_fidelity(original: &str, compressed: &str) -> Result<Vec<PageVisualDiff>, String> {
+    let before = Document::open(original)
+        .map_err(|e| format!("Failed to open original for verification: {:?}", e))?;
+    let after = Document::open(compressed)
+        .map_err(|e| format!("Failed to open compressed output for verification: {:?}", e))?;
 
-    // Configure write options based on compression level
+    let page_count = before
+        .page_count()
+        .map_err(|e| format!("Failed to get page count: {:?}", e))? as u32;
+
+    verification_pages(page_count)
+        .into_iter()
+        .map(|page| {
+            let similarity = page_similarity(&before, &after, page, VERIFY_RENDER_SIZE)?;
+            Ok(PageVisualDiff {
+                page,
+                diff: 1.0 - similarity,
+            })
+        })
+        .collect()
+}
+
+/// Write options for a compression level
+fn write_options(level: CompressionLevel) -> PdfWriteOptions {
     let mut options = PdfWriteOptions::default();
 
     match level {
@@ -96,6 +239,44 @@ pub fn compress_pdf(
         }
     }
 
+    options
+}
+
+/// Compress a PDF file
+///
+/// # Arguments
+/// * `input` - Path to input PDF file
+/// * `output` - Path to output PDF file (can be same as input for in-place)
+/// * `level` - Compression level
+/// * `verify` - Compare renders of the original and compressed pages
+/// * `detailed` - Break down image and font bytes before and after (extra pass)
+///
+/// # Returns
+/// Result containing compression statistics
+pub fn compress_pdf(
+    input: &str,
+    output: &str,
+    level: CompressionLevel,
+    verify: bool,
+    detailed: bool,
+) -> Result<CompressionResult, String> {
+    // Get original file size
+    let original_size = fs::metadata(input)
+        .map_err(|e| format!("Failed to read input file metadata: {}", e))?
+        .len();
+
+    // Open the PDF document
+    let doc = PdfDocument::open(input)
+        .map_err(|e| format!("Failed to open PDF: {:?}", e))?;
+
+    let before = if detailed {
+        Some(stream_breakdown(&doc)?)
+    } else {
+        None
+    };
+
+    let options = write_options(level);
+
     // Handle in-place compression by using a temp file
     let is_in_place = input == output;
     let temp_output = if is_in_place {
@@ -108,12 +289,38 @@ pub fn compress_pdf(
     doc.save_with_options(&temp_output, options)
         .map_err(|e| format!("Failed to save compressed PDF: {:?}", e))?;
 
+    // Verify before an in-place rename replaces the original
+    let page_diffs = if verify {
+        Some(verify_visual_fidelity(input, &temp_output).inspect_err(|_| {
+            if is_in_place {
+                let _ = fs::remove_file(&temp_output);
+            }
+        })?)
+    } else {
+        None
+    };
+
     // If in-place, replace original with temp file
     if is_in_place {
         fs::rename(&temp_output, output)
             .map_err(|e| format!("Failed to replace original file: {}", e))?;
     }
 
+    let mut result = compression_result(output, original_size, page_diffs)?;
+    if let Some(before) = before {
+        apply_breakdown(&mut result, before, stream_breakdown_of(output)?);
+    }
+
+    Ok(result)
+}
+
+/// Build statistics for a finished compression. `page_diffs` is set when
+/// verification ran.
+fn compression_result(
+    output: &str,
+    original_size: u64,
+    page_diffs: Option<Vec<PageVisualDiff>>,
+) -> Result<CompressionResult, String> {
     // Get compressed file size
     let compressed_size = fs::metadata(output)
         .map_err(|e| format!("Failed to read output file metadata: {}", e))?
@@ -128,6 +335,16 @@ pub fn compress_pdf(
         0.0
     };
 
+    let max_visual_diff = page_diffs
+        .as_ref()
+        .map(|diffs| diffs.iter().map(|d| d.diff).fold(0.0, f32::max));
+    let page_diffs = page_diffs.unwrap_or_default();
+    let warnings = page_diffs
+        .iter()
+        .filter(|d| d.diff > VISUAL_DIFF_WARNING_THRESHOLD)
+        .map(|d| format!("Page {} looks visibly different after compression ({:.1}% difference)", d.page, d.diff * 100.0))
+        .collect();
+
     Ok(CompressionResult {
         output_path: output.to_string(),
         original_size,
@@ -135,9 +352,145 @@ pub fn compress_pdf(
         ratio,
         bytes_saved,
         percent_saved,
+        max_visual_diff,
+        page_diffs,
+        warnings,
+        passes: None,
+        target_met: None,
+        images_before: None,
+        images_after: None,
+        fonts_before: None,
+        fonts_after: None,
+        streams_recompressed: None,
     })
 }
 
+/// Removes its files when dropped, so temporaries go away on every exit path
+struct TempFiles(Vec<String>);
+
+impl Drop for TempFiles {
+    fn drop(&mut self) {
+        for path in &self.0 {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+/// Walk `steps` until the best size is within `target_bytes`. `attempt(max_dpi,
+/// quality)` writes a candidate and returns its size; `keep()` makes that
+/// candidate the new best. A failed attempt ends the search with the best
+/// result so far. Returns the best size and the number of passes, counting the
+/// initial compression.
+fn search_target_size(
+    mut best_size: u64,
+    target_bytes: u64,
+    steps: &[(u32, u8)],
+    mut attempt: impl FnMut(u32, u8) -> Result<u64, String>,
+    mut keep: impl FnMut() -> Result<(), String>,
+) -> Result<(u64, u32), String> {
+    let mut passes = 1;
+    for &(max_dpi, quality) in steps {
+        if best_size <= target_bytes {
+            break;
+        }
+        passes += 1;
+
+        match attempt(max_dpi, quality) {
+            Ok(size) if size < best_size => {
+                keep()?;
+                best_size = size;
+            }
+            Ok(_) => {}
+            Err(e) => {
+                log::warn!("Compression pass at {} DPI failed, keeping the best result: {}", max_dpi, e);
+                break;
+            }
+        }
+    }
+    Ok((best_size, passes))
+}
+
+/// Compress toward a size target. The first pass uses `level`; while the output
+/// is still too large, images are downsampled with `downsample(input, output,
+/// max_dpi, jpeg_quality)` at each step of `TARGET_SIZE_STEPS` and the result is
+/// recompressed at High. The smallest output is kept even if the target is
+/// missed or a later pass fails.
+pub fn compress_to_target(
+    input: &str,
+    output: &str,
+    level: CompressionLevel,
+    target_bytes: u64,
+    verify: bool,
+    detailed: bool,
+    mut downsample: impl FnMut(&str, &str, u32, u8) -> Result<(), String>,
+) -> Result<CompressionResult, String> {
+    let original_size = fs::metadata(input)
+        .map_err(|e| format!("Failed to read input file metadata: {}", e))?
+        .len();
+
+    // Keep the original readable for every pass, even when compressing in place
+    let is_in_place = input == output;
+    let work_output = if is_in_place {
+        format!("{}.target.pdf", output)
+    } else {
+        output.to_string()
+    };
+    let staged = format!("{}.downsampled.pdf", work_output);
+    let candidate = format!("{}.candidate.pdf", work_output);
+
+    let mut temp_files = vec![staged.clone(), candidate.clone()];
+    if is_in_place {
+        // Renamed over the original on success; any earlier exit must remove it
+        temp_files.push(work_output.clone());
+    }
+    let _temp_files = TempFiles(temp_files);
+
+    let before = if detailed {
+        Some(stream_breakdown_of(input)?)
+    } else {
+        None
+    };
+
+    let first_size = compress_pdf(input, &work_output, level, false, false)?.compressed_size;
+    let (_, passes) = search_target_size(
+        first_size,
+        target_bytes,
+        TARGET_SIZE_STEPS,
+        |max_dpi, quality| {
+            downsample(input, &staged, max_dpi, quality)?;
+            Ok(compress_pdf(&staged, &candidate, CompressionLevel::High, false, false)?.compressed_size)
+        },
+        || fs::rename(&candidate, &work_output).map_err(|e| format!("Failed to keep smaller output: {}", e)),
+    )?;
+
+    let page_diffs = if verify {
+        Some(verify_visual_fidelity(input, &work_output)?)
+    } else {
+        None
+    };
+
+    if is_in_place {
+        fs::rename(&work_output, output)
+            .map_err(|e| format!("Failed to replace original file: {}", e))?;
+    }
+
+    let mut result = compression_result(output, original_size, page_diffs)?;
+    result.passes = Some(passes);
+    result.target_met = Some(result.compressed_size <= target_bytes);
+    if result.target_met == Some(false) {
+        result.warnings.push(format!(
+            "Could not reach {}; smallest achievable size is {}",
+            format_size(target_bytes),
+            format_size(result.compressed_size)
+        ));
+    }
+    if let Some(before) = before {
+        apply_breakdown(&mut result, before, stream_breakdown_of(output)?);
+    }
+
+    Ok(result)
+}
+
 /// Get estimated compression ratio without actually compressing
 /// (based on analyzing the PDF structure)
 pub fn estimate_compression(input: &str) -> Result<EstimationResult, String> {
@@ -176,6 +529,66 @@ pub fn estimate_compression(input: &str) -> Result<EstimationResult, String> {
     })
 }
 
+/// Before/after rendering of one page for a dry-run compression
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CompressionPreview {
+    /// Page rendered from the original file
+    pub original: RenderedPage,
+    /// Same page rendered from the compressed file
+    pub compressed: RenderedPage,
+    /// Original file size in bytes
+    pub original_size: u64,
+    /// Size the whole document would have at this level
+    pub projected_size: u64,
+    /// Mean per-channel difference (0-1) between the two renders
+    pub visual_diff: f32,
+}
+
+/// Compress to a scratch file and render `page` (1-indexed) from both versions,
+/// so quality loss can be judged before writing the real output. `scratch` is
+/// removed before returning.
+pub fn preview_compression(
+    input: &str,
+    scratch: &str,
+    level: CompressionLevel,
+    page: u32,
+) -> Result<CompressionPreview, String> {
+    let original_size = fs::metadata(input)
+        .map_err(|e| format!("Failed to read input file metadata: {}", e))?
+        .len();
+
+    let doc = PdfDocument::open(input)
+        .map_err(|e| format!("Failed to open PDF: {:?}", e))?;
+    doc.save_with_options(scratch, write_options(level))
+        .map_err(|e| format!("Failed to save compressed preview: {:?}", e))?;
+
+    let preview = (|| -> Result<CompressionPreview, String> {
+        let projected_size = fs::metadata(scratch)
+            .map_err(|e| format!("Failed to read preview file metadata: {}", e))?
+            .len();
+
+        let before = Document::open(input)
+            .map_err(|e| format!("Failed to open original for preview: {:?}", e))?;
+        let after = Document::open(scratch)
+            .map_err(|e| format!("Failed to open compressed preview: {:?}", e))?;
+
+        let render = |document: &Document| {
+            render_page(document, page, Some(PREVIEW_DPI), None, None, None, RenderFormat::Png, DEFAULT_RENDER_QUALITY)
+        };
+
+        Ok(CompressionPreview {
+            original: render(&before)?,
+            compressed: render(&after)?,
+            original_size,
+            projected_size,
+            visual_diff: 1.0 - page_similarity(&before, &after, page, VERIFY_RENDER_SIZE)?,
+        })
+    })();
+
+    let _ = fs::remove_file(scratch);
+    preview
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct EstimationResult {
     pub original_size: u64,
@@ -215,8 +628,41 @@ mod tests {
         assert_eq!(format_size(1073741824), "1.00 GB");
     }
 
+    #[test]
+    fn test_verification_pages() {
+        assert_eq!(verification_pages(3), vec![1, 2, 3]);
+
+        let sampled = verification_pages(100);
+        assert_eq!(sampled.len(), VERIFY_SAMPLE_PAGES as usize);
+        assert_eq!(sampled.first(), Some(&1));
+        assert_eq!(sampled.last(), Some(&100));
+    }
+
     #[test]
     fn test_compression_level_default() {
         assert_eq!(CompressionLevel::default(), CompressionLevel::Medium);
     }
+
+    #[test]
+    fn test_search_target_size() {
+        let steps = [(200, 80), (150, 70), (120, 60)];
+
+        // Stops as soon as the target is met, keeping only smaller candidates
+        let mut sizes = vec![900, 1200, 400].into_iter();
+        let mut kept = 0;
+        let result = search_target_size(1000, 500, &steps, |_, _| Ok(sizes.next().unwrap()), || {
+            kept += 1;
+            Ok(())
+        });
+        assert_eq!(result, Ok((400, 4)));
+        assert_eq!(kept, 2);
+
+        // Already small enough: no extra passes
+        assert_eq!(search_target_size(400, 500, &steps, |_, _| unreachable!(), || Ok(())), Ok((400, 1)));
+
+        // A failing pass ends the search with the best result so far
+        let mut sizes = vec![Ok(800), Err("downsampling failed".to_string())].into_iter();
+        let result = search_target_size(1000, 500, &steps, |_, _| sizes.next().unwrap(), || Ok(()));
+        assert_eq!(result, Ok((800, 3)));
+    }
 }
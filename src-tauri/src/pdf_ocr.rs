@@ -2,9 +2,10 @@
 //!
 //! Provides OCR functionality for scanned PDFs through the Python backend.
 
+use crate::command_error::{codes, ensure_exists, CommandError, CommandResult};
+use crate::python_bridge::{PythonBridge, PythonError};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
-use std::process::Command;
 use tauri::AppHandle;
 
 /// OCR dependency check result
@@ -37,6 +38,12 @@ pub struct OcrResult {
     pub exit_code: i32,
     pub message: Option<String>,
     pub error: Option<String>,
+    /// Plain-text sidecar written alongside the PDF, when requested
+    #[serde(default)]
+    pub sidecar_path: Option<String>,
+    /// Non-whitespace characters recognized, counted from the sidecar
+    #[serde(default)]
+    pub char_count: Option<u64>,
 }
 
 /// OCR options (searchable mode - invisible text layer)
@@ -69,6 +76,9 @@ pub struct OcrOptions {
     /// Optimization level (0-3)
     #[serde(default = "default_optimize")]
     pub optimize: i32,
+    /// Also write the recognized text to this .txt path (OCRmyPDF `--sidecar`)
+    #[serde(default)]
+    pub sidecar: Option<String>,
 }
 
 /// Editable OCR options (real text objects with visual metrics)
@@ -113,6 +123,88 @@ pub struct OcrMetricsResult {
     pub error: Option<String>,
 }
 
+/// Empty field region detected on a scanned page
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DetectedField {
+    /// Page number (1-indexed)
+    pub page: u32,
+    /// Field rectangle in PDF points [x0, y0, x1, y1]
+    pub rect: Vec<f64>,
+    /// Nearby OCR text that likely labels the field
+    pub label_guess: Option<String>,
+}
+
+/// Scanned form detection result
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ScannedFormDetection {
+    pub success: bool,
+    #[serde(default)]
+    pub likely_form: bool,
+    #[serde(default)]
+    pub detected_fields: Vec<DetectedField>,
+    /// 1-based pages left out because they already have a text layer
+    #[serde(default)]
+    pub skipped_pages: Vec<u32>,
+    pub error: Option<String>,
+}
+
+/// A candidate document language
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DetectedLanguage {
+    /// Tesseract language code, e.g. "spa"
+    pub code: String,
+    /// Relative confidence (0-1)
+    pub confidence: f64,
+    /// Whether the Tesseract language pack is installed
+    pub installed: bool,
+}
+
+/// Language detection result, most likely language first
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LanguageDetection {
+    pub success: bool,
+    /// Script reported by Tesseract OSD for scanned pages (e.g. "Latin")
+    pub script: Option<String>,
+    /// Pages (1-indexed) the detection sampled
+    #[serde(default)]
+    pub sampled_pages: Vec<u32>,
+    #[serde(default)]
+    pub languages: Vec<DetectedLanguage>,
+    /// Detected languages without an installed language pack
+    #[serde(default)]
+    pub missing_languages: Vec<String>,
+    pub error: Option<String>,
+}
+
+/// An installed Tesseract language pack
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LanguagePack {
+    /// Tesseract language code, e.g. "spa"
+    pub code: String,
+    /// Display name, e.g. "Spanish" (the code when unknown)
+    pub name: String,
+}
+
+/// Installed Tesseract languages
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LanguageList {
+    pub success: bool,
+    /// Directory Tesseract loads language packs from
+    pub tessdata_dir: Option<String>,
+    #[serde(default)]
+    pub languages: Vec<LanguagePack>,
+}
+
+/// Language pack installation result
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LanguageInstallResult {
+    pub success: bool,
+    pub code: String,
+    pub message: String,
+    /// Package manager command to run when the packs are system-managed
+    pub install_command: Option<String>,
+}
+
 fn default_language() -> String {
     "eng".to_string()
 }
@@ -133,247 +225,196 @@ fn default_optimize() -> i32 {
     1
 }
 
-/// Resolve the OCR Python script path
-fn resolve_ocr_script(app: &AppHandle) -> Option<PathBuf> {
-    use tauri::Manager;
+/// Run a `pdf_ocr.py` subcommand through the shared bridge (configured
+/// interpreter, concurrency limit) and parse its JSON output. `action` names
+/// the step in error messages.
+fn run_ocr_script<T: DeserializeOwned>(app: &AppHandle, args: &[&str], action: &str) -> CommandResult<T> {
+    let bridge = PythonBridge::shared(app)?;
+    let result = bridge
+        .run_script("pdf_ocr.py", args)
+        .map_err(|e| CommandError::from(e).context(&format!("{} failed", action)))?;
 
-    // Try relative to executable (dev mode)
-    if let Ok(mut exe) = std::env::current_exe() {
-        for _ in 0..4 {
-            exe.pop();
-        }
-        let script = exe.join("backend/pdf_ocr.py");
-        if script.exists() {
-            return Some(script);
-        }
-    }
-
-    // Try app resource directory (bundled mode)
-    if let Ok(resource) = app
-        .path()
-        .resolve("backend/pdf_ocr.py", tauri::path::BaseDirectory::Resource)
-    {
-        if resource.exists() {
-            return Some(resource);
-        }
-    }
-
-    // Fallback to current directory
-    let cwd = PathBuf::from("backend/pdf_ocr.py");
-    if cwd.exists() {
-        return Some(cwd);
-    }
-
-    None
+    serde_json::from_str(&result.stdout).map_err(|e| CommandError::bad_output(&format!("{} result", action), e))
 }
 
-/// Resolve Python binary
-fn resolve_python_bin() -> String {
-    if let Ok(p) = std::env::var("APP_PYTHON_BIN") {
-        return p;
-    }
-
-    let mut root = std::env::current_exe().unwrap_or_else(|_| PathBuf::from("."));
-    for _ in 0..4 {
-        root.pop();
-    }
+/// Check OCR dependencies
+pub fn check_dependencies(app: &AppHandle) -> CommandResult<OcrDependencies> {
+    run_ocr_script(app, &["check"], "OCR check")
+}
 
-    let venv = root.join("backend/venv/bin/python3");
-    if venv.exists() {
-        return venv.to_string_lossy().to_string();
-    }
+/// Analyze PDF for OCR needs
+pub fn analyze_pdf(app: &AppHandle, input: &str) -> CommandResult<OcrAnalysis> {
+    ensure_exists(input)?;
+    run_ocr_script(app, &["analyze", "--input", input], "PDF analysis")
+}
 
-    "python3".to_string()
+/// List installed Tesseract language packs with display names
+pub fn list_languages(app: &AppHandle) -> CommandResult<LanguageList> {
+    run_ocr_script(app, &["list-languages"], "Listing OCR languages")
 }
 
-/// Check OCR dependencies
-pub fn check_dependencies(app: &AppHandle) -> Result<OcrDependencies, String> {
-    let script = resolve_ocr_script(app)
-        .ok_or_else(|| "OCR script not found (backend/pdf_ocr.py)".to_string())?;
+/// Download a Tesseract language pack, or report the system command to run
+/// when language packs are managed by the OS
+pub fn install_language(app: &AppHandle, code: &str) -> CommandResult<LanguageInstallResult> {
+    let bridge = PythonBridge::shared(app)?;
 
-    let python = resolve_python_bin();
+    let args: Vec<&str> = vec!["install-language", "--code", code];
 
-    let output = Command::new(&python)
-        .arg(&script)
-        .arg("check")
-        .output()
-        .map_err(|e| format!("Failed to run OCR check: {}", e))?;
+    let result = bridge
+        .run_script("pdf_ocr.py", &args)
+        .map_err(|e| CommandError::from(e).context("Language pack installation failed"))?;
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("OCR check failed: {}", stderr));
-    }
+    serde_json::from_str(&result.stdout).map_err(|e| CommandError::bad_output("installation result", e))
+}
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    serde_json::from_str(&stdout)
-        .map_err(|e| format!("Failed to parse OCR check result: {}", e))
+/// Language packs in `language` ("eng+spa") missing from `installed`
+fn missing_languages<'a>(language: &'a str, installed: &[String]) -> Vec<&'a str> {
+    language
+        .split('+')
+        .map(str::trim)
+        .filter(|code| !code.is_empty() && !installed.iter().any(|i| i == code))
+        .collect()
 }
 
-/// Analyze PDF for OCR needs
-pub fn analyze_pdf(app: &AppHandle, input: &str) -> Result<OcrAnalysis, String> {
-    let script = resolve_ocr_script(app)
-        .ok_or_else(|| "OCR script not found (backend/pdf_ocr.py)".to_string())?;
-
-    let python = resolve_python_bin();
-
-    let output = Command::new(&python)
-        .arg(&script)
-        .arg("analyze")
-        .arg("--input")
-        .arg(input)
-        .output()
-        .map_err(|e| format!("Failed to analyze PDF: {}", e))?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("PDF analysis failed: {}", stderr));
+/// Fail with `missing_dependency` naming the first language pack that isn't
+/// installed. Skipped when the installed set can't be determined.
+fn ensure_languages_installed(app: &AppHandle, language: &str) -> CommandResult<()> {
+    let installed: Vec<String> = match list_languages(app) {
+        Ok(list) => list.languages.into_iter().map(|l| l.code).collect(),
+        Err(_) => return Ok(()),
+    };
+    if installed.is_empty() {
+        return Ok(());
     }
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    serde_json::from_str(&stdout)
-        .map_err(|e| format!("Failed to parse analysis result: {}", e))
+    match missing_languages(language, &installed).first() {
+        Some(code) => Err(PythonError::missing_language_pack(*code).into()),
+        None => Ok(()),
+    }
 }
 
-/// Run OCR on a PDF
+/// Run OCR on a PDF, streaming progress as `python-progress` events
 pub fn run_ocr(
     app: &AppHandle,
     input: &str,
     output: &str,
     options: OcrOptions,
-) -> Result<OcrResult, String> {
-    let script = resolve_ocr_script(app)
-        .ok_or_else(|| "OCR script not found (backend/pdf_ocr.py)".to_string())?;
-
-    let python = resolve_python_bin();
-
-    let mut cmd = Command::new(&python);
-    cmd.arg(&script)
-        .arg("ocr")
-        .arg("--input")
-        .arg(input)
-        .arg("--output")
-        .arg(output)
-        .arg("--language")
-        .arg(&options.language)
-        .arg("--optimize")
-        .arg(options.optimize.to_string());
+) -> CommandResult<OcrResult> {
+    ensure_exists(input)?;
+    ensure_languages_installed(app, &options.language)?;
+
+    let bridge = PythonBridge::shared(app)?;
+
+    let optimize = options.optimize.to_string();
+    let mut args: Vec<&str> = vec![
+        "ocr",
+        "--input", input,
+        "--output", output,
+        "--language", &options.language,
+        "--optimize", &optimize,
+    ];
 
     if options.deskew {
-        cmd.arg("--deskew");
+        args.push("--deskew");
     }
     if options.rotate_pages {
-        cmd.arg("--rotate-pages");
+        args.push("--rotate-pages");
     }
     if options.remove_background {
-        cmd.arg("--remove-background");
+        args.push("--remove-background");
     }
     if options.clean {
-        cmd.arg("--clean");
+        args.push("--clean");
     }
     if options.skip_text {
-        cmd.arg("--skip-text");
+        args.push("--skip-text");
     }
     if options.force_ocr {
-        cmd.arg("--force-ocr");
+        args.push("--force-ocr");
     }
     if options.redo_ocr {
-        cmd.arg("--redo-ocr");
+        args.push("--redo-ocr");
     }
-
-    let output_result = cmd
-        .output()
-        .map_err(|e| format!("Failed to run OCR: {}", e))?;
-
-    if !output_result.status.success() {
-        let stderr = String::from_utf8_lossy(&output_result.stderr);
-        return Err(format!("OCR failed: {}", stderr));
+    if let Some(sidecar) = &options.sidecar {
+        args.push("--sidecar");
+        args.push(sidecar);
     }
 
-    let stdout = String::from_utf8_lossy(&output_result.stdout);
-    serde_json::from_str(&stdout)
-        .map_err(|e| format!("Failed to parse OCR result: {}", e))
+    let result = bridge
+        .run_script_streamed(app, "pdf_ocr.py", &args)
+        .map_err(|e| CommandError::from(e).context("OCR failed"))?;
+
+    serde_json::from_str(&result.stdout).map_err(|e| CommandError::bad_output("OCR result", e))
 }
 
-/// Run editable OCR on a PDF (creates real text objects with visual metrics)
+/// Run editable OCR on a PDF (creates real text objects with visual metrics),
+/// streaming per-page progress as `python-progress` events
 pub fn run_editable_ocr(
     app: &AppHandle,
     input: &str,
     output: &str,
     options: EditableOcrOptions,
-) -> Result<EditableOcrResult, String> {
-    let script = resolve_ocr_script(app)
-        .ok_or_else(|| "OCR script not found (backend/pdf_ocr.py)".to_string())?;
-
-    let python = resolve_python_bin();
-
-    let mut cmd = Command::new(&python);
-    cmd.arg(&script)
-        .arg("ocr-editable")
-        .arg("--input")
-        .arg(input)
-        .arg("--output")
-        .arg(output)
-        .arg("--language")
-        .arg(&options.language)
-        .arg("--dpi")
-        .arg(options.dpi.to_string())
-        .arg("--font-family")
-        .arg(&options.font_family);
+) -> CommandResult<EditableOcrResult> {
+    ensure_exists(input)?;
+    ensure_languages_installed(app, &options.language)?;
+
+    let bridge = PythonBridge::shared(app)?;
+
+    let dpi = options.dpi.to_string();
+    let mut args: Vec<&str> = vec![
+        "ocr-editable",
+        "--input", input,
+        "--output", output,
+        "--language", &options.language,
+        "--dpi", &dpi,
+        "--font-family", &options.font_family,
+    ];
 
     if options.preserve_images {
-        cmd.arg("--preserve-images");
+        args.push("--preserve-images");
     }
     if options.embed_metrics {
-        cmd.arg("--embed-metrics");
-    }
-
-    eprintln!("[run_editable_ocr] Running: {:?}", cmd);
-
-    let output_result = cmd
-        .output()
-        .map_err(|e| format!("Failed to run editable OCR: {}", e))?;
-
-    // Log stderr for debugging
-    let stderr = String::from_utf8_lossy(&output_result.stderr);
-    if !stderr.is_empty() {
-        eprintln!("[run_editable_ocr] stderr: {}", stderr);
+        args.push("--embed-metrics");
     }
 
-    if !output_result.status.success() {
-        return Err(format!("Editable OCR failed: {}", stderr));
-    }
+    let result = bridge
+        .run_script_streamed(app, "pdf_ocr.py", &args)
+        .map_err(|e| CommandError::from(e).context("Editable OCR failed"))?;
 
-    let stdout = String::from_utf8_lossy(&output_result.stdout);
-    serde_json::from_str(&stdout)
-        .map_err(|e| format!("Failed to parse editable OCR result: {} (stdout: {})", e, stdout))
+    serde_json::from_str(&result.stdout).map_err(|e| {
+        CommandError::bad_output("editable OCR result", e).with_detail(result.stdout.clone())
+    })
 }
 
 /// Get embedded OCR metrics from a PDF
 pub fn get_ocr_metrics(
     app: &AppHandle,
     input: &str,
-) -> Result<OcrMetricsResult, String> {
-    let script = resolve_ocr_script(app)
-        .ok_or_else(|| "OCR script not found (backend/pdf_ocr.py)".to_string())?;
-
-    let python = resolve_python_bin();
-
-    let output = Command::new(&python)
-        .arg(&script)
-        .arg("get-metrics")
-        .arg("--input")
-        .arg(input)
-        .output()
-        .map_err(|e| format!("Failed to get OCR metrics: {}", e))?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("Get metrics failed: {}", stderr));
+) -> CommandResult<OcrMetricsResult> {
+    ensure_exists(input)?;
+    run_ocr_script(app, &["get-metrics", "--input", input], "Get metrics")
+}
+
+/// Detect empty form fields (boxes and underlines) on scanned pages. Pages
+/// with a text layer are skipped unless `force_ocr` is set.
+pub fn detect_scanned_form(
+    app: &AppHandle,
+    input: &str,
+    language: &str,
+    force_ocr: bool,
+) -> CommandResult<ScannedFormDetection> {
+    ensure_exists(input)?;
+    let mut args = vec!["detect-form", "--input", input, "--language", language];
+    if force_ocr {
+        args.push("--force-ocr");
     }
+    run_ocr_script(app, &args, "Form detection")
+}
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    serde_json::from_str(&stdout)
-        .map_err(|e| format!("Failed to parse metrics result: {}", e))
+/// Rank the languages a PDF is likely written in, to pre-fill the OCR language
+pub fn detect_language(app: &AppHandle, input: &str) -> CommandResult<LanguageDetection> {
+    ensure_exists(input)?;
+    run_ocr_script(app, &["detect-language", "--input", input], "Language detection")
 }
 
 #[cfg(test)]
@@ -387,4 +428,12 @@ mod tests {
         assert_eq!(opts.optimize, 1);
         assert!(!opts.deskew);
     }
+
+    #[test]
+    fn test_missing_languages() {
+        let installed = vec!["eng".to_string(), "spa".to_string(), "osd".to_string()];
+        assert!(missing_languages("eng+spa", &installed).is_empty());
+        assert_eq!(missing_languages("eng+fra+deu", &installed), vec!["fra", "deu"]);
+        assert_eq!(missing_languages("chi_sim", &[]), vec!["chi_sim"]);
+    }
 }
@@ -3,14 +3,186 @@
 //! This module provides Tauri commands for:
 //! - Loading PDF documents
 //! - Rendering pages at various DPI/quality levels
-//! - Getting document metadata
+//! - Getting and editing document metadata
 //! - Extracting text with positions for text selection
+//!
+//! Opened documents are kept in a small LRU cache (managed state) so repeated
+//! renders of the same file don't reparse it. Thumbnails are additionally
+//! persisted to disk (see `thumbnail_cache`) so reopening a document is fast.
 
+use crate::command_error::{codes, ensure_exists, CommandError};
+use crate::pdf_watermark::invert;
+use crate::thumbnail_cache::{png_dimensions, ThumbnailCache};
 use base64::Engine;
+use mupdf::pdf::{PdfDocument, PdfObject, PdfPage};
 use mupdf::text_page::TextPageOptions;
-use mupdf::{Colorspace, Document, Matrix, MetadataName, Outline as MuOutline};
+use mupdf::{
+    ColorParams, Colorspace, Device, Document, IRect, Matrix, MetadataName, NativeDevice, Outline as MuOutline, Page,
+    Pixmap, Size, Text,
+};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
 use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
 use std::io::Cursor;
+use std::rc::Rc;
+use std::sync::Mutex;
+use std::time::SystemTime;
+use tauri::{AppHandle, Manager, State};
+
+/// Documents kept open by default
+pub const DOCUMENT_CACHE_CAPACITY: usize = 8;
+
+/// Most-recently-used list of values keyed by path, each stamped with the
+/// file's modification time so changed files are reopened
+struct StampedLru<T> {
+    capacity: usize,
+    /// Most recently used first
+    entries: VecDeque<(String, Option<SystemTime>, T)>,
+}
+
+impl<T> StampedLru<T> {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            entries: VecDeque::new(),
+        }
+    }
+
+    /// Return the entry for `key`, calling `open` if it is missing or its stamp changed
+    fn get_or_open(
+        &mut self,
+        key: &str,
+        stamp: Option<SystemTime>,
+        open: impl FnOnce() -> Result<T, String>,
+    ) -> Result<&mut T, String> {
+        if let Some(pos) = self.entries.iter().position(|(k, _, _)| k == key) {
+            let entry = self.entries.remove(pos).expect("position is in range");
+            if entry.1 == stamp && stamp.is_some() {
+                self.entries.push_front(entry);
+                return Ok(&mut self.entries[0].2);
+            }
+        }
+
+        let value = open()?;
+        self.entries.push_front((key.to_string(), stamp, value));
+        self.entries.truncate(self.capacity);
+        Ok(&mut self.entries[0].2)
+    }
+
+    fn remove(&mut self, key: &str) {
+        self.entries.retain(|(k, _, _)| k != key);
+    }
+}
+
+/// Error returned when an encrypted document is opened without a password
+pub const PASSWORD_REQUIRED: &str = "PASSWORD_REQUIRED";
+/// Error returned when the supplied password doesn't unlock the document
+pub const PASSWORD_INCORRECT: &str = "PASSWORD_INCORRECT";
+
+/// A cached document and whether it still needs a password
+struct OpenDocument {
+    document: Document,
+    /// Needed a password when opened (stays true after unlocking)
+    encrypted: bool,
+    /// Unlocked state is tracked here because MuPDF's `needs_password` re-tries
+    /// the empty password and can't tell us whether we already authenticated
+    locked: bool,
+}
+
+// SAFETY: `mupdf::Document` is a raw MuPDF handle and so isn't `Send`. MuPDF
+// allows a document to be used from several threads as long as each thread
+// has its own context (mupdf-rs clones one per thread from a shared base with
+// shared locks) and only one thread uses the document at a time. The cache
+// guarantees the latter: documents are only reached through `with_document`,
+// which holds the cache mutex for the whole call, and the `&Document` it hands
+// out can't outlive that closure.
+unsafe impl Send for OpenDocument {}
+
+/// LRU cache of opened MuPDF documents, keyed by path and reopened when the
+/// file's modification time changes. Unlocked encrypted documents stay
+/// unlocked until evicted (`pdf_close`) so the password is only sent once.
+pub struct DocumentCache(Mutex<StampedLru<OpenDocument>>);
+
+impl Default for DocumentCache {
+    fn default() -> Self {
+        Self::with_capacity(DOCUMENT_CACHE_CAPACITY)
+    }
+}
+
+impl DocumentCache {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self(Mutex::new(StampedLru::new(capacity)))
+    }
+
+    /// Run `f` with the cached document for `path`, opening it if needed.
+    /// Encrypted documents are unlocked with `password`; without one (or with a
+    /// wrong one) this fails with `password_required` / `password_incorrect`
+    /// (messages `PASSWORD_REQUIRED` / `PASSWORD_INCORRECT`).
+    pub fn with_document<T>(
+        &self,
+        path: &str,
+        password: Option<&str>,
+        f: impl FnOnce(&Document) -> Result<T, String>,
+    ) -> Result<T, CommandError> {
+        self.with_document_state(path, password, |document, _encrypted| f(document))
+    }
+
+    /// Like `with_document`, also telling `f` whether the document is
+    /// password-protected (so its content must not be persisted unencrypted)
+    pub fn with_document_state<T>(
+        &self,
+        path: &str,
+        password: Option<&str>,
+        f: impl FnOnce(&Document, bool) -> Result<T, String>,
+    ) -> Result<T, CommandError> {
+        let modified = std::fs::metadata(path).and_then(|m| m.modified()).ok();
+        if modified.is_none() {
+            ensure_exists(path)?;
+        }
+        let mut cache = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        let entry = cache
+            .get_or_open(path, modified, || {
+                let document = Document::open(path).map_err(|e| format!("Failed to load PDF: {:?}", e))?;
+                let locked = document
+                    .needs_password()
+                    .map_err(|e| format!("Failed to check encryption: {:?}", e))?;
+                Ok(OpenDocument {
+                    document,
+                    encrypted: locked,
+                    locked,
+                })
+            })
+            .map_err(|e| CommandError::new(codes::PDF_ERROR, e))?;
+
+        if entry.locked {
+            let password = password.ok_or_else(|| PASSWORD_REQUIRED.to_string())?;
+            let unlocked = entry
+                .document
+                .authenticate(password)
+                .map_err(|e| CommandError::pdf("Failed to authenticate", e))?;
+            if !unlocked {
+                return Err(PASSWORD_INCORRECT.into());
+            }
+            entry.locked = false;
+        }
+
+        Ok(f(&entry.document, entry.encrypted)?)
+    }
+
+    /// Drop the cached document for `path`
+    pub fn evict(&self, path: &str) {
+        let mut cache = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        cache.remove(path);
+    }
+}
+
+/// Open `path` outside the cache, failing with `file_not_found` / `pdf_error`
+fn open_document(path: &str) -> Result<Document, CommandError> {
+    ensure_exists(path)?;
+    Document::open(path).map_err(|e| CommandError::pdf("Failed to load PDF", e))
+}
 
 /// PDF document info
 #[derive(Debug, Serialize, Deserialize)]
@@ -20,11 +192,30 @@ pub struct PdfInfo {
     pub page_sizes: Vec<PageSize>,
 }
 
-/// Page size in points (1/72 inch)
+/// Page size in points (1/72 inch), as displayed (after /Rotate)
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct PageSize {
     pub width: f32,
     pub height: f32,
+    /// Page /Rotate in degrees: 0, 90, 180 or 270
+    #[serde(default)]
+    pub rotation: i32,
+    /// MediaBox, for PDF pages
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub media_box: Option<PageBox>,
+    /// CropBox (the visible area), for PDF pages. Defaults to the MediaBox;
+    /// its offset from the MediaBox origin shifts overlay coordinates.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub crop_box: Option<PageBox>,
+}
+
+/// Page boundary box in unrotated PDF user space (points, origin bottom-left)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct PageBox {
+    pub x0: f32,
+    pub y0: f32,
+    pub x1: f32,
+    pub y1: f32,
 }
 
 /// Rendered page result
@@ -38,14 +229,102 @@ pub struct RenderedPage {
     pub height: u32,
     /// Page number (1-indexed)
     pub page: u32,
+    /// Image encoding of `data`: "png", "jpeg" or "webp"
+    pub format: String,
+}
+
+/// Default quality for lossy page encodings
+pub(crate) const DEFAULT_RENDER_QUALITY: u8 = 85;
+
+/// Image encoding for rendered pages
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RenderFormat {
+    Png,
+    Jpeg,
+    Webp,
+}
+
+impl RenderFormat {
+    /// Parse a format name, defaulting to PNG
+    pub fn parse(format: Option<&str>) -> Result<Self, String> {
+        match format.map(|f| f.to_ascii_lowercase()).as_deref() {
+            None | Some("png") => Ok(Self::Png),
+            Some("jpeg") | Some("jpg") => Ok(Self::Jpeg),
+            Some("webp") => Ok(Self::Webp),
+            Some(other) => Err(format!("Unsupported render format '{}' (expected png, jpeg or webp)", other)),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            Self::Jpeg => "jpeg",
+            Self::Webp => "webp",
+        }
+    }
+
+    /// Lossy formats have no alpha channel, so pages are rendered opaque
+    fn has_alpha(self) -> bool {
+        self == Self::Png
+    }
+}
+
+/// Encode a rendered pixmap. JPEG and WebP expect an RGB pixmap without alpha.
+fn encode_pixmap(pixmap: &Pixmap, format: RenderFormat, quality: u8) -> Result<Vec<u8>, String> {
+    if format == RenderFormat::Png {
+        let mut png_data = Vec::new();
+        pixmap
+            .write_to(&mut Cursor::new(&mut png_data), mupdf::ImageFormat::PNG)
+            .map_err(|e| format!("Failed to encode PNG: {:?}", e))?;
+        return Ok(png_data);
+    }
+
+    let width = pixmap.width() as usize;
+    let height = pixmap.height() as usize;
+    let samples = pixmap.samples();
+    let stride = samples.len() / height.max(1);
+
+    // Pack rows tightly in case the pixmap pads its stride
+    let mut rgb = Vec::with_capacity(width * height * 3);
+    for row in samples.chunks(stride.max(1)).take(height) {
+        rgb.extend_from_slice(&row[..width * 3]);
+    }
+
+    let quality = quality.clamp(1, 100);
+    match format {
+        RenderFormat::Jpeg => {
+            let mut jpeg_data = Vec::new();
+            image::codecs::jpeg::JpegEncoder::new_with_quality(&mut jpeg_data, quality)
+                .encode(&rgb, width as u32, height as u32, image::ExtendedColorType::Rgb8)
+                .map_err(|e| format!("Failed to encode JPEG: {}", e))?;
+            Ok(jpeg_data)
+        }
+        RenderFormat::Webp => Ok(webp::Encoder::from_rgb(&rgb, width as u32, height as u32)
+            .encode(quality as f32)
+            .to_vec()),
+        RenderFormat::Png => unreachable!("handled above"),
+    }
 }
 
 /// Load a PDF and return its info
 #[tauri::command]
-pub fn pdf_open(path: String) -> Result<PdfInfo, String> {
-    let document = Document::open(&path)
-        .map_err(|e| format!("Failed to load PDF: {:?}", e))?;
+pub fn pdf_open(
+    cache: State<'_, DocumentCache>,
+    path: String,
+    password: Option<String>,
+) -> Result<PdfInfo, CommandError> {
+    let page_sizes = cache.with_document(&path, password.as_deref(), collect_page_sizes)?;
+    let num_pages = page_sizes.len() as u32;
+
+    Ok(PdfInfo {
+        path,
+        num_pages,
+        page_sizes,
+    })
+}
 
+/// Collect the size of every page, falling back to Letter for pages that fail to load
+fn collect_page_sizes(document: &Document) -> Result<Vec<PageSize>, String> {
     let num_pages = document
         .page_count()
         .map_err(|e| format!("Failed to get page count: {:?}", e))? as u32;
@@ -56,9 +335,13 @@ pub fn pdf_open(path: String) -> Result<PdfInfo, String> {
         match document.load_page(i as i32) {
             Ok(page) => {
                 let bounds = page.bounds().map_err(|e| format!("Failed to get page bounds: {:?}", e))?;
+                let (rotation, media_box, crop_box) = page_geometry(page);
                 page_sizes.push(PageSize {
                     width: bounds.width(),
                     height: bounds.height(),
+                    rotation,
+                    media_box,
+                    crop_box,
                 });
             }
             Err(e) => {
@@ -66,35 +349,276 @@ pub fn pdf_open(path: String) -> Result<PdfInfo, String> {
                 page_sizes.push(PageSize {
                     width: 612.0, // Default letter width
                     height: 792.0, // Default letter height
+                    rotation: 0,
+                    media_box: None,
+                    crop_box: None,
                 });
             }
         }
     }
 
-    Ok(PdfInfo {
-        path,
-        num_pages,
-        page_sizes,
+    Ok(page_sizes)
+}
+
+/// Rotation, MediaBox and CropBox of a PDF page; non-PDF pages report no rotation or boxes
+fn page_geometry(page: Page) -> (i32, Option<PageBox>, Option<PageBox>) {
+    let Ok(pdf_page) = PdfPage::try_from(page) else {
+        return (0, None, None);
+    };
+    let rotation = pdf_page.rotation().map(normalize_rotation).unwrap_or(0);
+    let object = pdf_page.object();
+    let media_box = page_box(&object, "MediaBox");
+    let crop_box = match (page_box(&object, "CropBox"), media_box) {
+        (Some(crop), Some(media)) => Some(PageBox {
+            x0: crop.x0.max(media.x0),
+            y0: crop.y0.max(media.y0),
+            x1: crop.x1.min(media.x1),
+            y1: crop.y1.min(media.y1),
+        }),
+        (crop, media) => crop.or(media),
+    };
+    (rotation, media_box, crop_box)
+}
+
+/// Snap /Rotate to 0, 90, 180 or 270 the way MuPDF does when rendering
+fn normalize_rotation(degrees: i32) -> i32 {
+    (90 * ((degrees.rem_euclid(360) + 45) / 90)) % 360
+}
+
+/// A boundary box (inheritable) from the page dictionary, with corners ordered
+fn page_box(page: &PdfObject, key: &str) -> Option<PageBox> {
+    let array = page.get_dict_inheritable(key).ok()??;
+    let mut corners = [0.0f32; 4];
+    for (i, corner) in corners.iter_mut().enumerate() {
+        *corner = array.get_array(i as i32).ok()??.as_float().ok()?;
+    }
+    let [ax, ay, bx, by] = corners;
+    Some(PageBox {
+        x0: ax.min(bx),
+        y0: ay.min(by),
+        x1: ax.max(bx),
+        y1: ay.max(by),
+    })
+}
+
+/// Size of a single page, with its standard paper name when recognized
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PageSizeEntry {
+    /// Page number (1-indexed)
+    pub page: u32,
+    pub width: f32,
+    pub height: f32,
+    /// Standard paper name (e.g. "A4", "Letter"), if the size matches one
+    pub name: Option<String>,
+}
+
+/// Page size report for the whole document
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PageSizeReport {
+    pub sizes: Vec<PageSizeEntry>,
+    /// True when every page has the same size (within tolerance)
+    pub uniform: bool,
+    /// Number of distinct page sizes found
+    pub distinct_sizes: u32,
+}
+
+/// Standard paper sizes in points (portrait width, height)
+const NAMED_PAGE_SIZES: &[(&str, f32, f32)] = &[
+    ("A3", 841.89, 1190.55),
+    ("A4", 595.28, 841.89),
+    ("A5", 419.53, 595.28),
+    ("B4", 708.66, 1000.63),
+    ("B5", 498.90, 708.66),
+    ("Letter", 612.0, 792.0),
+    ("Legal", 612.0, 1008.0),
+    ("Tabloid", 792.0, 1224.0),
+    ("Executive", 522.0, 756.0),
+];
+
+/// Tolerance in points when matching or comparing page sizes (~1 mm)
+const PAGE_SIZE_TOLERANCE: f32 = 3.0;
+
+/// Map a page size to a standard paper name, in either orientation
+fn named_page_size(width: f32, height: f32) -> Option<&'static str> {
+    let (short, long) = if width <= height { (width, height) } else { (height, width) };
+    NAMED_PAGE_SIZES
+        .iter()
+        .find(|(_, w, h)| {
+            (short - w).abs() <= PAGE_SIZE_TOLERANCE && (long - h).abs() <= PAGE_SIZE_TOLERANCE
+        })
+        .map(|(name, _, _)| *name)
+}
+
+/// Report each page's size and whether the document mixes page sizes
+#[tauri::command]
+pub fn pdf_page_size_report(path: String) -> Result<PageSizeReport, CommandError> {
+    let document = open_document(&path)?;
+
+    let page_sizes = collect_page_sizes(&document)?;
+
+    let mut distinct: Vec<(f32, f32)> = Vec::new();
+    let mut sizes = Vec::with_capacity(page_sizes.len());
+
+    for (i, size) in page_sizes.iter().enumerate() {
+        let seen = distinct.iter().any(|(w, h)| {
+            (size.width - w).abs() <= PAGE_SIZE_TOLERANCE
+                && (size.height - h).abs() <= PAGE_SIZE_TOLERANCE
+        });
+        if !seen {
+            distinct.push((size.width, size.height));
+        }
+
+        sizes.push(PageSizeEntry {
+            page: i as u32 + 1,
+            width: size.width,
+            height: size.height,
+            name: named_page_size(size.width, size.height).map(String::from),
+        });
+    }
+
+    Ok(PageSizeReport {
+        sizes,
+        uniform: distinct.len() <= 1,
+        distinct_sizes: distinct.len() as u32,
     })
 }
 
+/// Page size used to validate edit coordinates
+#[derive(Debug, Clone)]
+pub(crate) struct PageBounds {
+    /// Page index (0-indexed, as used by the edit commands)
+    pub page_index: i32,
+    pub width: f64,
+    pub height: f64,
+}
+
+/// Slack in points for coordinates that land exactly on the page edge after rounding
+const COORD_TOLERANCE: f64 = 0.5;
+
+impl PageBounds {
+    /// Load the bounds of a page (0-indexed) with MuPDF
+    pub fn load(path: &str, page_index: i32) -> Result<Self, CommandError> {
+        let document = Document::open(path)
+            .map_err(|e| format!("Failed to load PDF: {:?}", e))?;
+        let page_count = document
+            .page_count()
+            .map_err(|e| format!("Failed to get page count: {:?}", e))?;
+        if page_index < 0 || page_index >= page_count {
+            return Err(format!(
+                "Page {} does not exist (document has {} pages)",
+                page_index + 1,
+                page_count
+            ));
+        }
+        let bounds = document
+            .load_page(page_index)
+            .and_then(|p| p.bounds())
+            .map_err(|e| format!("Failed to get page bounds: {:?}", e))?;
+
+        Ok(Self {
+            page_index,
+            width: bounds.width() as f64,
+            height: bounds.height() as f64,
+        })
+    }
+
+    fn outside_error(&self) -> String {
+        format!(
+            "coordinates outside page {} (0..{:.0}, 0..{:.0})",
+            self.page_index + 1,
+            self.width,
+            self.height
+        )
+    }
+
+    fn to_points(&self, x: f64, y: f64, normalized: bool) -> (f64, f64) {
+        if normalized {
+            (x * self.width, y * self.height)
+        } else {
+            (x, y)
+        }
+    }
+
+    fn contains(&self, x: f64, y: f64) -> bool {
+        x.is_finite()
+            && y.is_finite()
+            && (-COORD_TOLERANCE..=self.width + COORD_TOLERANCE).contains(&x)
+            && (-COORD_TOLERANCE..=self.height + COORD_TOLERANCE).contains(&y)
+    }
+}
+
+/// Check a point lies on the page, returning it in points.
+/// With `normalized`, x and y are fractions (0-1) of the page size.
+pub(crate) fn validate_point(bounds: &PageBounds, x: f64, y: f64, normalized: bool) -> Result<(f64, f64), String> {
+    let (px, py) = bounds.to_points(x, y, normalized);
+    if !bounds.contains(px, py) {
+        return Err(bounds.outside_error());
+    }
+    Ok((px.clamp(0.0, bounds.width), py.clamp(0.0, bounds.height)))
+}
+
+/// Check a rect lies on the page and is not empty, returning it in points
+pub(crate) fn validate_rect(
+    bounds: &PageBounds,
+    x0: f64,
+    y0: f64,
+    x1: f64,
+    y1: f64,
+    normalized: bool,
+) -> Result<(f64, f64, f64, f64), String> {
+    let (x0, y0) = validate_point(bounds, x0, y0, normalized)?;
+    let (x1, y1) = validate_point(bounds, x1, y1, normalized)?;
+    if x1 <= x0 || y1 <= y0 {
+        return Err(format!(
+            "Empty or inverted rect on page {}: ({}, {}) to ({}, {})",
+            bounds.page_index + 1,
+            x0,
+            y0,
+            x1,
+            y1
+        ));
+    }
+    Ok((x0, y0, x1, y1))
+}
+
 /// Render a single page at the specified DPI
 #[tauri::command]
 pub fn pdf_render_page(
+    cache: State<'_, DocumentCache>,
     path: String,
     page: u32,
     dpi: Option<u32>,
     max_width: Option<u32>,
     max_height: Option<u32>,
     hide_annotations: Option<bool>,
+    password: Option<String>,
+    format: Option<String>,
+    quality: Option<u8>,
+) -> Result<RenderedPage, CommandError> {
+    let format = RenderFormat::parse(format.as_deref()).map_err(|e| CommandError::new(codes::INVALID_ARGUMENT, e))?;
+    let quality = quality.unwrap_or(DEFAULT_RENDER_QUALITY);
+    cache.with_document(&path, password.as_deref(), |document| {
+        render_page(document, page, dpi, max_width, max_height, hide_annotations, format, quality)
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn render_page(
+    document: &Document,
+    page: u32,
+    dpi: Option<u32>,
+    max_width: Option<u32>,
+    max_height: Option<u32>,
+    hide_annotations: Option<bool>,
+    format: RenderFormat,
+    quality: u8,
 ) -> Result<RenderedPage, String> {
     let dpi = dpi.unwrap_or(150);
     let show_annots = !hide_annotations.unwrap_or(false);
 
-    let document = Document::open(&path)
-        .map_err(|e| format!("Failed to load PDF: {:?}", e))?;
-
-    let page_index = (page - 1) as i32;
+    let page_index = page
+        .checked_sub(1)
+        .ok_or_else(|| "Page numbers start at 1".to_string())? as i32;
     let pdf_page = document
         .load_page(page_index)
         .map_err(|e| format!("Failed to get page {}: {:?}", page, e))?;
@@ -132,119 +656,293 @@ pub fn pdf_render_page(
     // Create transformation matrix for scaling
     let matrix = Matrix::new_scale(scale, scale);
 
-    // Render the page to a pixmap (RGB with alpha)
+    // Render the page to a pixmap (RGB, with alpha only for PNG)
     // show_annots controls whether PDF annotations are rendered
     let pixmap = pdf_page
-        .to_pixmap(&matrix, &Colorspace::device_rgb(), true, show_annots)
+        .to_pixmap(&matrix, &Colorspace::device_rgb(), format.has_alpha(), show_annots)
         .map_err(|e| format!("Failed to render page: {:?}", e))?;
 
     // Get actual rendered dimensions
     let actual_width = pixmap.width() as u32;
     let actual_height = pixmap.height() as u32;
 
-    // Write pixmap to PNG
-    let mut png_data = Vec::new();
-    let mut cursor = Cursor::new(&mut png_data);
-    pixmap
-        .write_to(&mut cursor, mupdf::ImageFormat::PNG)
-        .map_err(|e| format!("Failed to encode PNG: {:?}", e))?;
+    let image_data = encode_pixmap(&pixmap, format, quality)?;
 
     // Encode as base64
-    let base64_data = base64::engine::general_purpose::STANDARD.encode(&png_data);
+    let base64_data = base64::engine::general_purpose::STANDARD.encode(&image_data);
 
     Ok(RenderedPage {
         data: base64_data,
         width: actual_width,
         height: actual_height,
         page,
+        format: format.name().to_string(),
+    })
+}
+
+/// Render a normalized region of a page (1-indexed) so its longer side is `size` pixels.
+/// Returns an RGB pixmap without alpha, sized to the region.
+pub(crate) fn render_page_region(
+    document: &Document,
+    page: u32,
+    region: &NormalizedRect,
+    size: u32,
+) -> Result<Pixmap, String> {
+    let pdf_page = document
+        .load_page(page as i32 - 1)
+        .map_err(|e| format!("Failed to get page {}: {:?}", page, e))?;
+    let bounds = pdf_page
+        .bounds()
+        .map_err(|e| format!("Failed to get page bounds: {:?}", e))?;
+
+    let region_w = (region.width * bounds.width()).max(1.0);
+    let region_h = (region.height * bounds.height()).max(1.0);
+    let scale = size as f32 / region_w.max(region_h);
+
+    // Allocate only the region, in the scaled page's device space, and draw
+    // the page into it; MuPDF skips everything outside the pixmap
+    let x0 = ((bounds.x0 + region.x * bounds.width()) * scale).floor() as i32;
+    let y0 = ((bounds.y0 + region.y * bounds.height()) * scale).floor() as i32;
+    let clip = IRect {
+        x0,
+        y0,
+        x1: x0 + ((region_w * scale).round() as i32).max(1),
+        y1: y0 + ((region_h * scale).round() as i32).max(1),
+    };
+    let mut pixmap = Pixmap::new_with_rect(&Colorspace::device_rgb(), clip, false)
+        .map_err(|e| format!("Failed to allocate pixmap: {:?}", e))?;
+    pixmap
+        .clear_with(0xff)
+        .map_err(|e| format!("Failed to clear pixmap: {:?}", e))?;
+
+    let device = Device::from_pixmap(&pixmap).map_err(|e| format!("Failed to create device: {:?}", e))?;
+    pdf_page
+        .run(&device, &Matrix::new_scale(scale, scale))
+        .map_err(|e| format!("Failed to render page: {:?}", e))?;
+    drop(device);
+
+    Ok(pixmap)
+}
+
+/// Visual similarity of the same page (1-indexed) in two documents, rendered so the
+/// longer side is `size` pixels. 1.0 means pixel-identical; pages whose rendered
+/// dimensions differ score 0.0.
+pub(crate) fn page_similarity(a: &Document, b: &Document, page: u32, size: u32) -> Result<f32, String> {
+    let full = NormalizedRect {
+        x: 0.0,
+        y: 0.0,
+        width: 1.0,
+        height: 1.0,
+    };
+    let left = render_page_region(a, page, &full, size)?;
+    let right = render_page_region(b, page, &full, size)?;
+
+    if left.width() != right.width() || left.height() != right.height() {
+        return Ok(0.0);
+    }
+
+    let (left, right) = (left.samples(), right.samples());
+    if left.is_empty() {
+        return Ok(1.0);
+    }
+
+    let total: u64 = left
+        .iter()
+        .zip(right)
+        .map(|(&l, &r)| l.abs_diff(r) as u64)
+        .sum();
+
+    Ok(1.0 - total as f32 / (left.len() as f32 * 255.0))
+}
+
+/// Render several pages at full quality with a single document open.
+/// Pages that fail to render are skipped with a warning.
+#[tauri::command]
+pub async fn pdf_render_pages(
+    app: AppHandle,
+    path: String,
+    pages: Vec<u32>,
+    dpi: Option<u32>,
+    max_width: Option<u32>,
+    max_height: Option<u32>,
+) -> Result<Vec<RenderedPage>, CommandError> {
+    // Rasterizes every requested page: keep it off the main thread
+    crate::run_blocking(move || {
+        let cache = app.state::<DocumentCache>();
+        cache.with_document(&path, None, |document| {
+            let mut results = Vec::with_capacity(pages.len());
+
+            for page in pages {
+                match render_page(
+                    document,
+                    page,
+                    dpi,
+                    max_width,
+                    max_height,
+                    None,
+                    RenderFormat::Png,
+                    DEFAULT_RENDER_QUALITY,
+                ) {
+                    Ok(rendered) => results.push(rendered),
+                    Err(e) => log::warn!("Failed to render page {}: {}", page, e),
+                }
+            }
+
+            Ok(results)
+        })
     })
+    .await
 }
 
 /// Render a thumbnail (low-res) for a page
 #[tauri::command]
 pub fn pdf_render_thumbnail(
+    cache: State<'_, DocumentCache>,
     path: String,
     page: u32,
     max_size: Option<u32>,
-) -> Result<RenderedPage, String> {
+    format: Option<String>,
+    quality: Option<u8>,
+) -> Result<RenderedPage, CommandError> {
     let max_size = max_size.unwrap_or(200);
-    pdf_render_page(path, page, Some(72), Some(max_size), Some(max_size), None)
+    pdf_render_page(cache, path, page, Some(72), Some(max_size), Some(max_size), None, None, format, quality)
+}
+
+/// Normalize a preview rotation to 0, 90, 180 or 270 degrees clockwise
+fn preview_rotation(degrees: i32) -> Result<i32, String> {
+    if degrees % 90 != 0 {
+        return Err(format!("Rotation must be a multiple of 90 degrees, got {}", degrees));
+    }
+    Ok(degrees.rem_euclid(360))
 }
 
-/// Batch render multiple thumbnails
+/// Batch render multiple thumbnails. Thumbnails are served from the on-disk
+/// cache when the document is unchanged; the document is only opened for misses.
+///
+/// `rotations` (page -> degrees) adds a render-time rotation on top of the
+/// page's own /Rotate, for previewing a rotation before it's applied. Rotated
+/// thumbnails bypass the disk cache and the document is left untouched.
 #[tauri::command]
 pub fn pdf_render_thumbnails(
+    app: AppHandle,
+    cache: State<'_, DocumentCache>,
     path: String,
     pages: Vec<u32>,
     max_size: Option<u32>,
-) -> Result<Vec<RenderedPage>, String> {
+    rotations: Option<HashMap<u32, i32>>,
+) -> Result<Vec<RenderedPage>, CommandError> {
     let max_size = max_size.unwrap_or(200);
+    let disk = ThumbnailCache::for_app(&app);
 
-    let document = Document::open(&path)
-        .map_err(|e| format!("Failed to load PDF: {:?}", e))?;
+    let mut preview: HashMap<u32, i32> = HashMap::new();
+    for (page, degrees) in rotations.unwrap_or_default() {
+        let degrees = preview_rotation(degrees).map_err(|e| CommandError::new(codes::INVALID_ARGUMENT, e))?;
+        if degrees != 0 {
+            preview.insert(page, degrees);
+        }
+    }
 
-    let mut results = Vec::with_capacity(pages.len());
-
-    for page_num in pages {
-        let page_index = (page_num - 1) as i32;
-
-        match document.load_page(page_index) {
-            Ok(pdf_page) => {
-                match pdf_page.bounds() {
-                    Ok(bounds) => {
-                        let width_points = bounds.width();
-                        let height_points = bounds.height();
-
-                        // Calculate thumbnail scale maintaining aspect ratio
-                        let aspect = width_points / height_points;
-                        let thumb_width = if aspect > 1.0 {
-                            max_size as f32
-                        } else {
-                            max_size as f32 * aspect
-                        };
-
-                        // Calculate scale to achieve thumbnail size
-                        let scale = thumb_width / width_points;
-                        let matrix = Matrix::new_scale(scale, scale);
-
-                        match pdf_page.to_pixmap(&matrix, &Colorspace::device_rgb(), true, false) {
-                            Ok(pixmap) => {
-                                let mut png_data = Vec::new();
-                                let mut cursor = Cursor::new(&mut png_data);
-
-                                if pixmap.write_to(&mut cursor, mupdf::ImageFormat::PNG).is_ok() {
-                                    let base64_data =
-                                        base64::engine::general_purpose::STANDARD.encode(&png_data);
-                                    results.push(RenderedPage {
-                                        data: base64_data,
-                                        width: pixmap.width() as u32,
-                                        height: pixmap.height() as u32,
-                                        page: page_num,
-                                    });
-                                }
-                            }
-                            Err(e) => {
-                                log::warn!("Failed to render thumbnail for page {}: {:?}", page_num, e);
+    let mut cached: HashMap<u32, (Vec<u8>, u32, u32)> = HashMap::new();
+    for &page in &pages {
+        if preview.contains_key(&page) {
+            continue;
+        }
+        if let Some(png) = disk.get(&path, page, max_size) {
+            if let Some((width, height)) = png_dimensions(&png) {
+                cached.insert(page, (png, width, height));
+            }
+        }
+    }
+
+    let missing: Vec<u32> = pages.iter().copied().filter(|p| !cached.contains_key(p)).collect();
+    if !missing.is_empty() {
+        cache.with_document_state(&path, None, |document, encrypted| {
+            for page in missing {
+                let rotation = preview.get(&page).copied().unwrap_or(0);
+                match render_thumbnail_png(document, page, max_size, rotation) {
+                    Ok(rendered) => {
+                        // Never write page images of protected documents to disk
+                        if rotation == 0 && !encrypted {
+                            if let Err(e) = disk.put(&path, page, max_size, &rendered.0) {
+                                log::warn!("Failed to cache thumbnail for page {}: {}", page, e);
                             }
                         }
+                        cached.insert(page, rendered);
                     }
-                    Err(e) => {
-                        log::warn!("Failed to get bounds for page {}: {:?}", page_num, e);
-                    }
+                    Err(e) => log::warn!("{}", e),
                 }
             }
-            Err(e) => {
-                log::warn!("Failed to get page {}: {:?}", page_num, e);
-            }
-        }
+            Ok(())
+        })?;
+        disk.evict();
     }
 
-    Ok(results)
+    Ok(pages
+        .into_iter()
+        .filter_map(|page| {
+            cached.remove(&page).map(|(png, width, height)| RenderedPage {
+                data: base64::engine::general_purpose::STANDARD.encode(&png),
+                width,
+                height,
+                page,
+                format: RenderFormat::Png.name().to_string(),
+            })
+        })
+        .collect())
+}
+
+/// Render one page as a PNG whose longer side is `max_size` pixels, turned
+/// clockwise by an extra `rotation` degrees.
+/// Returns the PNG bytes with its width and height.
+fn render_thumbnail_png(
+    document: &Document,
+    page_num: u32,
+    max_size: u32,
+    rotation: i32,
+) -> Result<(Vec<u8>, u32, u32), String> {
+    let page_index = page_num as i32 - 1;
+    let pdf_page = document
+        .load_page(page_index)
+        .map_err(|e| format!("Failed to get page {}: {:?}", page_num, e))?;
+    let bounds = pdf_page
+        .bounds()
+        .map_err(|e| format!("Failed to get bounds for page {}: {:?}", page_num, e))?;
+
+    let width_points = bounds.width();
+    let height_points = bounds.height();
+
+    // Calculate thumbnail scale maintaining aspect ratio
+    let aspect = width_points / height_points;
+    let thumb_width = if aspect > 1.0 {
+        max_size as f32
+    } else {
+        max_size as f32 * aspect
+    };
+
+    // Calculate scale to achieve thumbnail size
+    let scale = thumb_width / width_points;
+    let mut matrix = Matrix::new_scale(scale, scale);
+    if rotation != 0 {
+        matrix.concat(Matrix::new_rotate(rotation as f32));
+    }
+
+    let pixmap = pdf_page
+        .to_pixmap(&matrix, &Colorspace::device_rgb(), true, false)
+        .map_err(|e| format!("Failed to render thumbnail for page {}: {:?}", page_num, e))?;
+
+    let mut png_data = Vec::new();
+    let mut cursor = Cursor::new(&mut png_data);
+    pixmap
+        .write_to(&mut cursor, mupdf::ImageFormat::PNG)
+        .map_err(|e| format!("Failed to encode thumbnail for page {}: {:?}", page_num, e))?;
+
+    Ok((png_data, pixmap.width() as u32, pixmap.height() as u32))
 }
 
-/// Close a document (no-op since MuPDF handles cleanup automatically)
+/// Close a document, dropping it from the document cache
 #[tauri::command]
-pub fn pdf_close(_path: String) -> Result<(), String> {
+pub fn pdf_close(cache: State<'_, DocumentCache>, path: String) -> Result<(), CommandError> {
+    cache.evict(&path);
     Ok(())
 }
 
@@ -257,116 +955,345 @@ pub struct NormalizedRect {
     pub height: f32,
 }
 
-/// A single character with its bounding box
+/// A single character with its bounding box and style
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct TextCharInfo {
     pub char: String,
     pub quad: [f32; 8], // 4 corners: [x0,y0, x1,y1, x2,y2, x3,y3]
+    /// Font name without the subset prefix (e.g. "Arial-BoldMT")
+    #[serde(default)]
+    pub font: Option<String>,
+    /// Font size in points
+    #[serde(default)]
+    pub size: f32,
+    /// Fill color as "#rrggbb"
+    #[serde(default)]
+    pub color: Option<String>,
+    #[serde(default)]
+    pub bold: bool,
+    #[serde(default)]
+    pub italic: bool,
 }
 
-/// A line of text with its bounding box and characters
+/// A line of text with its bounding box and characters. The style fields are
+/// those of the most common style among the line's characters.
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct TextLineInfo {
     pub text: String,
     pub rect: NormalizedRect,
     pub chars: Vec<TextCharInfo>,
+    #[serde(default)]
+    pub font: Option<String>,
+    #[serde(default)]
+    pub size: f32,
+    #[serde(default)]
+    pub color: Option<String>,
+    #[serde(default)]
+    pub bold: bool,
+    #[serde(default)]
+    pub italic: bool,
 }
 
-/// A block of text (paragraph) with its lines
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct TextBlockInfo {
-    pub rect: NormalizedRect,
-    pub lines: Vec<TextLineInfo>,
+/// Font, size and color of one character
+#[derive(Debug, Clone, Default, PartialEq)]
+struct CharStyle {
+    font: Option<String>,
+    size: f32,
+    color: Option<String>,
+    bold: bool,
+    italic: bool,
 }
 
-/// Text content of a page
-#[derive(Debug, Serialize, Deserialize)]
-pub struct PageTextContent {
-    pub page: u32,
-    pub blocks: Vec<TextBlockInfo>,
+/// `FZ_STEXT_BOLD` in a structured-text character's flags. MuPDF sets it from
+/// the loaded font's bold flag, or when it detects overprinted (fake) bold.
+const STEXT_CHAR_BOLD: u32 = 8;
+/// Italic bit of a PDF font descriptor's /Flags
+const FONT_DESCRIPTOR_ITALIC: i32 = 1 << 6;
+
+/// A text block from MuPDF's structured-text XML, in page points
+#[derive(Debug, Default)]
+struct StextBlock {
+    bbox: [f32; 4],
+    lines: Vec<StextLine>,
 }
 
-/// Extract text blocks with positions from a page
-#[tauri::command]
-pub fn pdf_get_text_blocks(path: String, page: u32) -> Result<PageTextContent, String> {
-    let document = Document::open(&path)
-        .map_err(|e| format!("Failed to load PDF: {:?}", e))?;
-
-    let page_index = (page - 1) as i32;
-    let pdf_page = document
-        .load_page(page_index)
-        .map_err(|e| format!("Failed to get page {}: {:?}", page, e))?;
-
-    // Get page dimensions for normalization
-    let bounds = pdf_page.bounds()
-        .map_err(|e| format!("Failed to get page bounds: {:?}", e))?;
-    let page_width = bounds.width();
-    let page_height = bounds.height();
+#[derive(Debug, Default)]
+struct StextLine {
+    bbox: [f32; 4],
+    chars: Vec<StextChar>,
+}
 
-    // Extract text page
-    let text_page = pdf_page
-        .to_text_page(TextPageOptions::empty())
-        .map_err(|e| format!("Failed to extract text: {:?}", e))?;
+#[derive(Debug)]
+struct StextChar {
+    c: char,
+    /// Corners as MuPDF writes them: ul, ur, ll, lr
+    quad: [f32; 8],
+    style: CharStyle,
+}
 
-    let mut blocks = Vec::new();
+/// Unescaped value of an attribute of a structured-text XML element
+fn xml_attr(element: &BytesStart, name: &str) -> Option<String> {
+    let attribute = element.try_get_attribute(name).ok()??;
+    attribute.unescape_value().ok().map(|value| value.into_owned())
+}
 
-    for block in text_page.blocks() {
-        // Skip image blocks
-        if block.lines().next().is_none() {
-            continue;
-        }
+/// Space-separated numbers of an XML attribute (bbox, quad)
+fn xml_numbers<const N: usize>(element: &BytesStart, name: &str) -> Option<[f32; N]> {
+    let mut values = [0.0; N];
+    let value = xml_attr(element, name)?;
+    let mut parts = value.split_whitespace();
+    for value in values.iter_mut() {
+        *value = parts.next()?.parse().ok()?;
+    }
+    Some(values)
+}
 
-        let block_bounds = block.bounds();
-        let block_rect = NormalizedRect {
-            x: block_bounds.x0 / page_width,
-            y: block_bounds.y0 / page_height,
-            width: (block_bounds.x1 - block_bounds.x0) / page_width,
-            height: (block_bounds.y1 - block_bounds.y0) / page_height,
+/// Text blocks of a page from MuPDF's structured-text XML (`<block>` >
+/// `<line>` > `<font>` > `<char>`). Characters in `italic_fonts` are marked
+/// italic; bold comes from the character flags.
+fn parse_stext_xml(xml: &str, italic_fonts: &[String]) -> Vec<StextBlock> {
+    let mut reader = Reader::from_str(xml);
+    let mut blocks: Vec<StextBlock> = Vec::new();
+    let (mut font, mut size) = (String::new(), 0.0);
+    loop {
+        let element = match reader.read_event() {
+            Ok(Event::Start(element)) | Ok(Event::Empty(element)) => element,
+            Ok(Event::Eof) => break,
+            Ok(_) => continue,
+            Err(e) => {
+                log::warn!("Malformed structured text at {}: {}", reader.error_position(), e);
+                break;
+            }
         };
-
-        let mut lines = Vec::new();
-
-        for line in block.lines() {
-            let line_bounds = line.bounds();
+        match element.name().as_ref() {
+            b"block" => blocks.push(StextBlock {
+                bbox: xml_numbers(&element, "bbox").unwrap_or_default(),
+                lines: Vec::new(),
+            }),
+            b"line" => {
+                if let Some(block) = blocks.last_mut() {
+                    block.lines.push(StextLine {
+                        bbox: xml_numbers(&element, "bbox").unwrap_or_default(),
+                        chars: Vec::new(),
+                    });
+                }
+            }
+            b"font" => {
+                font = xml_attr(&element, "name").unwrap_or_default();
+                size = xml_attr(&element, "size").and_then(|s| s.parse().ok()).unwrap_or(0.0);
+            }
+            b"char" => {
+                let c = xml_attr(&element, "c").and_then(|c| c.chars().next());
+                let (Some(c), Some(quad)) = (c, xml_numbers(&element, "quad")) else {
+                    continue;
+                };
+                let flags: u32 = xml_attr(&element, "flags").and_then(|f| f.parse().ok()).unwrap_or(0);
+                let style = CharStyle {
+                    font: Some(font.clone()).filter(|f| !f.is_empty()),
+                    size,
+                    color: xml_attr(&element, "color"),
+                    bold: flags & STEXT_CHAR_BOLD != 0,
+                    italic: italic_fonts.contains(&font),
+                };
+                if let Some(text_line) = blocks.last_mut().and_then(|b| b.lines.last_mut()) {
+                    text_line.chars.push(StextChar { c, quad, style });
+                }
+            }
+            _ => {}
+        }
+    }
+    blocks
+}
+
+/// Names (without subset prefix, as MuPDF reports them) of the fonts a page
+/// uses whose descriptor declares them italic, by flag or slant angle
+fn italic_font_names(pdf_page: &PdfPage) -> Vec<String> {
+    let mut fonts = Vec::new();
+    let walked = pdf_page
+        .object()
+        .get_dict_inheritable("Resources")
+        .and_then(|resources| match resources {
+            Some(resources) => collect_fonts(&resources, &mut Vec::new(), &mut fonts),
+            None => Ok(()),
+        });
+    if let Err(e) = walked {
+        log::warn!("Failed to read page fonts: {:?}", e);
+    }
+
+    let mut names = Vec::new();
+    for font in fonts {
+        // Type0 fonts keep their descriptor on the descendant font
+        let descendant = font
+            .get_dict("DescendantFonts")
+            .ok()
+            .flatten()
+            .and_then(|d| d.get_array(0).ok().flatten());
+        let Some(descriptor) = descendant
+            .as_ref()
+            .unwrap_or(&font)
+            .get_dict("FontDescriptor")
+            .ok()
+            .flatten()
+        else {
+            continue;
+        };
+        let flags = descriptor
+            .get_dict("Flags")
+            .ok()
+            .flatten()
+            .and_then(|f| f.as_int().ok())
+            .unwrap_or(0);
+        let angle = descriptor
+            .get_dict("ItalicAngle")
+            .ok()
+            .flatten()
+            .and_then(|a| a.as_float().ok())
+            .unwrap_or(0.0);
+        if flags & FONT_DESCRIPTOR_ITALIC == 0 && angle == 0.0 {
+            continue;
+        }
+        for source in [Some(&font), descendant.as_ref()].into_iter().flatten() {
+            if let Some(base_font) = pdf_name(source.get_dict("BaseFont").ok().flatten()) {
+                let name = base_font.split_once('+').map_or(base_font.as_str(), |(_, n)| n).to_string();
+                if !names.contains(&name) {
+                    names.push(name);
+                }
+            }
+        }
+    }
+    names
+}
+
+/// Most common style in a line, by character count
+fn dominant_style<'a>(styles: impl Iterator<Item = &'a CharStyle>) -> CharStyle {
+    let mut counts: Vec<(&CharStyle, usize)> = Vec::new();
+    for style in styles {
+        match counts.iter_mut().find(|(s, _)| *s == style) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((style, 1)),
+        }
+    }
+    counts
+        .into_iter()
+        .max_by_key(|&(_, count)| count)
+        .map(|(style, _)| style.clone())
+        .unwrap_or_default()
+}
+
+/// A block of text (paragraph) with its lines
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TextBlockInfo {
+    pub rect: NormalizedRect,
+    pub lines: Vec<TextLineInfo>,
+}
+
+/// Text content of a page
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PageTextContent {
+    pub page: u32,
+    pub blocks: Vec<TextBlockInfo>,
+}
+
+/// Extract text blocks with positions from a page
+#[tauri::command]
+pub fn pdf_get_text_blocks(
+    cache: State<'_, DocumentCache>,
+    path: String,
+    page: u32,
+    password: Option<String>,
+) -> Result<PageTextContent, CommandError> {
+    cache.with_document(&path, password.as_deref(), |document| page_text_blocks(document, page))
+}
+
+fn page_text_blocks(document: &Document, page: u32) -> Result<PageTextContent, String> {
+    let page_index = (page - 1) as i32;
+    let pdf_page = document
+        .load_page(page_index)
+        .map_err(|e| format!("Failed to get page {}: {:?}", page, e))?;
+
+    // Get page dimensions for normalization
+    let bounds = pdf_page.bounds()
+        .map_err(|e| format!("Failed to get page bounds: {:?}", e))?;
+    let page_width = bounds.width();
+    let page_height = bounds.height();
+
+    let italic_fonts = match PdfPage::try_from(pdf_page.clone()) {
+        Ok(pdf) => italic_font_names(&pdf),
+        Err(_) => Vec::new(),
+    };
+
+    // One structured-text pass: the XML export carries the same blocks, lines
+    // and character quads as `TextPage`, plus each character's font and flags
+    let xml = pdf_page
+        .to_xml()
+        .map_err(|e| format!("Failed to extract text: {:?}", e))?;
+
+    let mut blocks = Vec::new();
+
+    for block in parse_stext_xml(&xml, &italic_fonts) {
+        let [x0, y0, x1, y1] = block.bbox;
+        let block_rect = NormalizedRect {
+            x: x0 / page_width,
+            y: y0 / page_height,
+            width: (x1 - x0) / page_width,
+            height: (y1 - y0) / page_height,
+        };
+
+        let mut lines = Vec::new();
+
+        for line in block.lines {
+            let [x0, y0, x1, y1] = line.bbox;
             let line_rect = NormalizedRect {
-                x: line_bounds.x0 / page_width,
-                y: line_bounds.y0 / page_height,
-                width: (line_bounds.x1 - line_bounds.x0) / page_width,
-                height: (line_bounds.y1 - line_bounds.y0) / page_height,
+                x: x0 / page_width,
+                y: y0 / page_height,
+                width: (x1 - x0) / page_width,
+                height: (y1 - y0) / page_height,
             };
 
             let mut chars = Vec::new();
+            let mut line_styles = Vec::new();
             let mut line_text = String::new();
 
-            for char_info in line.chars() {
-                if let Some(c) = char_info.char() {
-                    line_text.push(c);
-
-                    let quad = char_info.quad();
-                    // Normalize quad coordinates
-                    let normalized_quad = [
-                        quad.ul.x / page_width,
-                        quad.ul.y / page_height,
-                        quad.ur.x / page_width,
-                        quad.ur.y / page_height,
-                        quad.lr.x / page_width,
-                        quad.lr.y / page_height,
-                        quad.ll.x / page_width,
-                        quad.ll.y / page_height,
-                    ];
-
-                    chars.push(TextCharInfo {
-                        char: c.to_string(),
-                        quad: normalized_quad,
-                    });
-                }
+            for char_info in line.chars {
+                line_text.push(char_info.c);
+
+                let [ul_x, ul_y, ur_x, ur_y, ll_x, ll_y, lr_x, lr_y] = char_info.quad;
+                // Normalize quad coordinates
+                let normalized_quad = [
+                    ul_x / page_width,
+                    ul_y / page_height,
+                    ur_x / page_width,
+                    ur_y / page_height,
+                    lr_x / page_width,
+                    lr_y / page_height,
+                    ll_x / page_width,
+                    ll_y / page_height,
+                ];
+
+                let style = char_info.style;
+                chars.push(TextCharInfo {
+                    char: char_info.c.to_string(),
+                    quad: normalized_quad,
+                    font: style.font.clone(),
+                    size: style.size,
+                    color: style.color.clone(),
+                    bold: style.bold,
+                    italic: style.italic,
+                });
+                line_styles.push(style);
             }
 
             if !line_text.is_empty() {
+                let style = dominant_style(line_styles.iter());
                 lines.push(TextLineInfo {
                     text: line_text,
                     rect: line_rect,
                     chars,
+                    font: style.font,
+                    size: style.size,
+                    color: style.color,
+                    bold: style.bold,
+                    italic: style.italic,
                 });
             }
         }
@@ -382,9 +1309,363 @@ pub fn pdf_get_text_blocks(path: String, page: u32) -> Result<PageTextContent, S
     Ok(PageTextContent { page, blocks })
 }
 
+/// Where a link points
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum LinkTarget {
+    /// A page in this document (1-indexed), with the target position (0-1)
+    /// when the destination specifies one. `page` is None if it can't be resolved.
+    Internal { page: Option<u32>, y: Option<f32> },
+    /// A URI outside the document (web, mailto, other files)
+    External { uri: String },
+}
+
+/// A clickable link on a page
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PageLink {
+    pub rect: NormalizedRect,
+    pub target: LinkTarget,
+}
+
+/// An annotation stored in the PDF itself (not the JSON sidecar)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PageAnnotation {
+    /// PDF subtype, e.g. "Highlight", "Text", "FreeText", "Ink"
+    #[serde(rename = "type")]
+    pub annot_type: String,
+    pub rect: NormalizedRect,
+    /// Annotation color (/C) as "#rrggbb"
+    pub color: Option<String>,
+    pub contents: Option<String>,
+    pub author: Option<String>,
+    /// Unique name (/NM), when present
+    pub name: Option<String>,
+}
+
+/// Annotation subtypes left out of `pdf_get_annotations`: links have their own
+/// command, popups belong to their parent and widgets are form fields
+const SKIPPED_ANNOTATION_TYPES: &[&str] = &["Link", "Popup", "Widget"];
+
+fn normalized_rect(rect: &mupdf::Rect, page_width: f32, page_height: f32) -> NormalizedRect {
+    NormalizedRect {
+        x: rect.x0 / page_width,
+        y: rect.y0 / page_height,
+        width: (rect.x1 - rect.x0) / page_width,
+        height: (rect.y1 - rect.y0) / page_height,
+    }
+}
+
+/// Target y (page space, points) encoded in a MuPDF internal link URI such as
+/// "#page=3&zoom=100,72,540", "#page=3&view=FitH,540" or "#page=3&viewrect=72,540,200,100"
+fn link_target_y(uri: &str) -> Option<f32> {
+    let fragment = uri.split_once('#')?.1;
+    fragment.split('&').find_map(|param| {
+        let (key, value) = param.split_once('=')?;
+        let mut values = value.split(',');
+        let y = match key {
+            "zoom" => values.nth(2)?,
+            "viewrect" => values.nth(1)?,
+            "view" if matches!(values.next()?, "FitH" | "FitBH") => values.next()?,
+            _ => return None,
+        };
+        y.parse::<f32>().ok().filter(|y| y.is_finite())
+    })
+}
+
+/// Get the links on a page with normalized rects and their destinations
+#[tauri::command]
+pub fn pdf_get_links(
+    cache: State<'_, DocumentCache>,
+    path: String,
+    page: u32,
+    password: Option<String>,
+) -> Result<Vec<PageLink>, CommandError> {
+    cache.with_document(&path, password.as_deref(), |document| page_links(document, page))
+}
+
+fn page_links(document: &Document, page: u32) -> Result<Vec<PageLink>, String> {
+    let pdf_page = document
+        .load_page(page as i32 - 1)
+        .map_err(|e| format!("Failed to get page {}: {:?}", page, e))?;
+    let bounds = pdf_page
+        .bounds()
+        .map_err(|e| format!("Failed to get page bounds: {:?}", e))?;
+    let page_count = document
+        .page_count()
+        .map_err(|e| format!("Failed to get page count: {:?}", e))? as u32;
+    let links = pdf_page
+        .links()
+        .map_err(|e| format!("Failed to get links: {:?}", e))?;
+
+    Ok(links
+        .map(|link| {
+            let target = if link.uri.starts_with('#') {
+                // Unresolvable destinations come back as page -1
+                let target_page = (link.page < page_count).then_some(link.page);
+                let y = target_page.zip(link_target_y(&link.uri)).and_then(|(p, y)| {
+                    let height = document.load_page(p as i32).ok()?.bounds().ok()?.height();
+                    Some((y / height).clamp(0.0, 1.0))
+                });
+                LinkTarget::Internal {
+                    page: target_page.map(|p| p + 1),
+                    y,
+                }
+            } else {
+                LinkTarget::External { uri: link.uri }
+            };
+            PageLink {
+                rect: normalized_rect(&link.bounds, bounds.width(), bounds.height()),
+                target,
+            }
+        })
+        .collect())
+}
+
+/// Get the annotations stored in the PDF on a page (type, rect, color, contents).
+/// Links, popups and form widgets are skipped. Non-PDF documents have none.
+#[tauri::command]
+pub fn pdf_get_annotations(
+    cache: State<'_, DocumentCache>,
+    path: String,
+    page: u32,
+    password: Option<String>,
+) -> Result<Vec<PageAnnotation>, CommandError> {
+    cache.with_document(&path, password.as_deref(), |document| {
+        let pdf_page = document
+            .load_page(page as i32 - 1)
+            .map_err(|e| format!("Failed to get page {}: {:?}", page, e))?;
+        let bounds = pdf_page
+            .bounds()
+            .map_err(|e| format!("Failed to get page bounds: {:?}", e))?;
+        let Ok(pdf_page) = PdfPage::try_from(pdf_page) else {
+            return Ok(Vec::new());
+        };
+        page_annotations(&pdf_page, bounds.width(), bounds.height())
+            .map_err(|e| format!("Failed to read annotations: {:?}", e))
+    })
+}
+
+fn page_annotations(
+    pdf_page: &PdfPage,
+    page_width: f32,
+    page_height: f32,
+) -> Result<Vec<PageAnnotation>, mupdf::Error> {
+    let Some(annots) = pdf_page.object().get_dict("Annots")? else {
+        return Ok(Vec::new());
+    };
+    let ctm = pdf_page.ctm()?;
+    let text = |annot: &PdfObject, key: &str| -> Result<Option<String>, mupdf::Error> {
+        Ok(annot
+            .get_dict(key)?
+            .and_then(|v| v.as_string().ok().map(str::to_string))
+            .filter(|v| !v.is_empty()))
+    };
+
+    let mut annotations = Vec::new();
+    for i in 0..annots.len()? as i32 {
+        let Some(annot) = annots.get_array(i)? else {
+            continue;
+        };
+        let annot_type = pdf_name(annot.get_dict("Subtype")?).unwrap_or_default();
+        if annot_type.is_empty() || SKIPPED_ANNOTATION_TYPES.contains(&annot_type.as_str()) {
+            continue;
+        }
+        let Some(rect) = annot.get_dict("Rect")? else {
+            continue;
+        };
+
+        let mut corners = [0.0f32; 4];
+        for (j, corner) in corners.iter_mut().enumerate() {
+            *corner = rect.get_array(j as i32)?.map(|v| v.as_float()).transpose()?.unwrap_or(0.0);
+        }
+        let [x0, y0, x1, y1] = corners;
+        let points = [(x0, y0), (x1, y0), (x0, y1), (x1, y1)]
+            .map(|(x, y)| mupdf::Point::new(x, y).transform(&ctm));
+        let page_rect = mupdf::Rect::new(
+            points.iter().map(|p| p.x).fold(f32::INFINITY, f32::min),
+            points.iter().map(|p| p.y).fold(f32::INFINITY, f32::min),
+            points.iter().map(|p| p.x).fold(f32::NEG_INFINITY, f32::max),
+            points.iter().map(|p| p.y).fold(f32::NEG_INFINITY, f32::max),
+        );
+
+        let mut components = Vec::new();
+        if let Some(color) = annot.get_dict("C")? {
+            for j in 0..color.len()? as i32 {
+                if let Some(value) = color.get_array(j)? {
+                    components.push(value.as_float()?);
+                }
+            }
+        }
+
+        annotations.push(PageAnnotation {
+            annot_type,
+            rect: normalized_rect(&page_rect, page_width, page_height),
+            color: color_hex(&components),
+            contents: text(&annot, "Contents")?,
+            author: text(&annot, "T")?,
+            name: text(&annot, "NM")?,
+        });
+    }
+
+    Ok(annotations)
+}
+
+/// "#rrggbb" for a gray, RGB or CMYK color array; None when empty (transparent)
+fn color_hex(components: &[f32]) -> Option<String> {
+    let (r, g, b) = match *components {
+        [gray] => (gray, gray, gray),
+        [r, g, b] => (r, g, b),
+        [c, m, y, k] => ((1.0 - c) * (1.0 - k), (1.0 - m) * (1.0 - k), (1.0 - y) * (1.0 - k)),
+        _ => return None,
+    };
+    let byte = |v: f32| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+    Some(format!("#{:02x}{:02x}{:02x}", byte(r), byte(g), byte(b)))
+}
+
+/// Separator placed between pages of extracted text
+const PAGE_TEXT_SEPARATOR: &str = "\n\n";
+
+/// A positioned character for layout-preserving extraction
+#[derive(Debug, Clone, Copy)]
+struct LayoutChar {
+    c: char,
+    /// Left edge in points
+    x: f32,
+    /// Baseline in points
+    baseline: f32,
+    width: f32,
+    size: f32,
+}
+
+/// Lay characters out on a monospace grid so columns and indentation survive.
+/// Characters sharing a baseline (within half a line) form a row; each is placed
+/// at the column matching its x position, and large vertical gaps become blank lines.
+fn layout_text(mut chars: Vec<LayoutChar>) -> String {
+    chars.retain(|ch| !ch.c.is_whitespace());
+    if chars.is_empty() {
+        return String::new();
+    }
+
+    let mut widths: Vec<f32> = chars.iter().map(|ch| ch.width).filter(|w| *w > 0.0).collect();
+    widths.sort_by(|a, b| a.total_cmp(b));
+    let char_width = widths.get(widths.len() / 2).copied().unwrap_or(5.0).max(1.0);
+    let mut sizes: Vec<f32> = chars.iter().map(|ch| ch.size).collect();
+    sizes.sort_by(|a, b| a.total_cmp(b));
+    let line_height = sizes[sizes.len() / 2].max(1.0);
+    let left = chars.iter().map(|ch| ch.x).fold(f32::MAX, f32::min);
+
+    chars.sort_by(|a, b| a.baseline.total_cmp(&b.baseline));
+    let mut rows: Vec<(f32, Vec<LayoutChar>)> = Vec::new();
+    for ch in chars {
+        match rows.last_mut() {
+            Some((baseline, row)) if (ch.baseline - *baseline).abs() < line_height / 2.0 => row.push(ch),
+            _ => rows.push((ch.baseline, vec![ch])),
+        }
+    }
+
+    let mut out = String::new();
+    let mut previous: Option<f32> = None;
+    for (baseline, mut row) in rows {
+        if let Some(prev) = previous {
+            out.push('\n');
+            // Keep paragraph gaps, capped so big whitespace doesn't explode the output
+            let blank_lines = (((baseline - prev) / (line_height * 1.2)).round() as usize).saturating_sub(1);
+            out.extend(std::iter::repeat('\n').take(blank_lines.min(2)));
+        }
+        previous = Some(baseline);
+
+        row.sort_by(|a, b| a.x.total_cmp(&b.x));
+        let mut line = String::new();
+        let mut column = 0usize;
+        for ch in row {
+            let target = ((ch.x - left) / char_width).round() as usize;
+            while column < target {
+                line.push(' ');
+                column += 1;
+            }
+            line.push(ch.c);
+            column += 1;
+        }
+        out.push_str(line.trim_end());
+    }
+    out
+}
+
+/// Text of one page, in MuPDF's reading order or laid out on a grid
+fn page_plain_text(document: &Document, page: u32, layout: bool) -> Result<String, String> {
+    let pdf_page = document
+        .load_page(page as i32 - 1)
+        .map_err(|e| format!("Failed to load page {}: {:?}", page, e))?;
+    let text_page = pdf_page
+        .to_text_page(TextPageOptions::empty())
+        .map_err(|e| format!("Failed to extract text from page {}: {:?}", page, e))?;
+
+    if !layout {
+        let text = text_page
+            .to_text()
+            .map_err(|e| format!("Failed to extract text from page {}: {:?}", page, e))?;
+        return Ok(text.trim_end().to_string());
+    }
+
+    let mut chars = Vec::new();
+    for block in text_page.blocks() {
+        for line in block.lines() {
+            for char_info in line.chars() {
+                if let Some(c) = char_info.char() {
+                    let quad = char_info.quad();
+                    chars.push(LayoutChar {
+                        c,
+                        x: quad.ul.x.min(quad.ll.x),
+                        baseline: char_info.origin().y,
+                        width: quad.ur.x.max(quad.lr.x) - quad.ul.x.min(quad.ll.x),
+                        size: char_info.size(),
+                    });
+                }
+            }
+        }
+    }
+    Ok(layout_text(chars))
+}
+
+/// Extract the text of the whole document (or selected 1-indexed `pages`) as one
+/// string, pages separated by a blank line. With `layout`, spacing is preserved so
+/// columns and tables stay aligned; otherwise text follows reading order.
+#[tauri::command]
+pub async fn pdf_extract_text(
+    app: AppHandle,
+    path: String,
+    pages: Option<Vec<u32>>,
+    layout: Option<bool>,
+    password: Option<String>,
+) -> Result<String, CommandError> {
+    let layout = layout.unwrap_or(false);
+    // Extracts every selected page's text: keep it off the main thread
+    crate::run_blocking(move || {
+        let cache = app.state::<DocumentCache>();
+        cache.with_document(&path, password.as_deref(), |document| {
+            let page_count = document
+                .page_count()
+                .map_err(|e| format!("Failed to get page count: {:?}", e))? as u32;
+            let pages = pages.unwrap_or_else(|| (1..=page_count).collect());
+
+            let mut texts = Vec::with_capacity(pages.len());
+            for page in pages {
+                if page == 0 || page > page_count {
+                    return Err(format!("Page {} is out of range (1-{})", page, page_count));
+                }
+                texts.push(page_plain_text(document, page, layout)?);
+            }
+            Ok(texts.join(PAGE_TEXT_SEPARATOR))
+        })
+    })
+    .await
+}
+
 /// Search result with page and position info
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SearchResult {
+    /// Position of the match in document order (0-based), for next/previous navigation
+    pub index: u32,
     /// Page number (1-indexed)
     pub page: u32,
     /// Normalized Y position of the match (0-1)
@@ -395,6 +1676,14 @@ pub struct SearchResult {
     pub context: String,
 }
 
+/// Matches found on one page
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PageSearchResults {
+    /// Page number (1-indexed)
+    pub page: u32,
+    pub matches: Vec<SearchResult>,
+}
+
 /// Search results for the entire document
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SearchResults {
@@ -402,61 +1691,340 @@ pub struct SearchResults {
     pub query: String,
     /// Total number of matches
     pub total: u32,
-    /// List of results
+    /// List of results (empty when grouped by page)
     pub results: Vec<SearchResult>,
+    /// Results grouped by page, in page order (only with `group_by_page`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pages: Option<Vec<PageSearchResults>>,
 }
 
-/// Search for text across all pages of a PDF
-/// Uses MuPDF's native search which is much faster than JavaScript iteration
-/// Runs in a blocking thread to avoid freezing the UI
-#[tauri::command]
-pub async fn pdf_search_text(path: String, query: String, max_results: Option<u32>) -> Result<SearchResults, String> {
-    let max_results = max_results.unwrap_or(1000);
+/// How the search query is interpreted
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum SearchMode {
+    /// Substring search through MuPDF's accelerated path
+    #[default]
+    Literal,
+    /// `regex` crate pattern
+    Regex,
+    /// Comma-separated terms, any of which matches
+    AnyOf,
+}
 
-    if query.is_empty() {
-        return Ok(SearchResults {
-            query,
-            total: 0,
-            results: Vec::new(),
-        });
+impl SearchMode {
+    /// Parse a mode name, defaulting to literal
+    pub fn parse(mode: Option<&str>) -> Result<Self, String> {
+        match mode.map(|m| m.to_ascii_lowercase()).as_deref() {
+            None | Some("literal") => Ok(Self::Literal),
+            Some("regex") => Ok(Self::Regex),
+            Some("any_of") => Ok(Self::AnyOf),
+            Some(other) => Err(format!("Unsupported search mode '{}' (expected literal, regex or any_of)", other)),
+        }
     }
+}
 
-    // Run the heavy search in a blocking thread to not freeze UI
-    let query_clone = query.clone();
-    let results = tauri::async_runtime::spawn_blocking(move || {
-        search_text_blocking(&path, &query_clone, max_results)
-    })
-    .await
-    .map_err(|e| format!("Search task failed: {:?}", e))??;
+/// Matching options. For literal mode these are applied on top of MuPDF's
+/// case-insensitive substring search.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SearchOptions {
+    pub mode: SearchMode,
+    pub case_sensitive: bool,
+    pub whole_word: bool,
+}
 
-    Ok(SearchResults {
-        query,
-        total: results.len() as u32,
-        results,
-    })
+/// Compile the pattern for regex / any_of searches (None for literal mode)
+fn search_pattern(query: &str, options: SearchOptions) -> Result<Option<regex::Regex>, String> {
+    let pattern = match options.mode {
+        SearchMode::Literal => return Ok(None),
+        SearchMode::Regex => query.to_string(),
+        SearchMode::AnyOf => {
+            let terms: Vec<String> = query
+                .split(',')
+                .map(str::trim)
+                .filter(|t| !t.is_empty())
+                .map(regex::escape)
+                .collect();
+            if terms.is_empty() {
+                return Err("No search terms given".to_string());
+            }
+            terms.join("|")
+        }
+    };
+    let pattern = if options.whole_word {
+        format!(r"\b(?:{})\b", pattern)
+    } else {
+        pattern
+    };
+
+    regex::RegexBuilder::new(&pattern)
+        .case_insensitive(!options.case_sensitive)
+        .build()
+        .map(Some)
+        .map_err(|e| format!("Invalid search pattern: {}", e))
 }
 
-/// Internal blocking search function
-fn search_text_blocking(path: &str, query: &str, max_results: u32) -> Result<Vec<SearchResult>, String> {
-    use std::time::Instant;
+/// Characters of a page in reading order with their rects `[x0, y0, x1, y1]`;
+/// line breaks are `'\n'` without a rect
+fn page_chars(text_page: &mupdf::TextPage) -> Vec<(char, Option<[f32; 4]>)> {
+    let mut chars = Vec::new();
+    for block in text_page.blocks() {
+        for line in block.lines() {
+            for char_info in line.chars() {
+                if let Some(c) = char_info.char() {
+                    let q = char_info.quad();
+                    chars.push((
+                        c,
+                        Some([
+                            q.ul.x.min(q.ll.x),
+                            q.ul.y.min(q.ur.y),
+                            q.ur.x.max(q.lr.x),
+                            q.ll.y.max(q.lr.y),
+                        ]),
+                    ));
+                }
+            }
+            chars.push(('\n', None));
+        }
+    }
+    chars
+}
 
-    let total_start = Instant::now();
+/// Run `pattern` over a page's characters, returning one rect per line a
+/// match covers (like MuPDF's literal search, a match that wraps onto the next
+/// line gives one entry per line) with that line's text as context. At most
+/// `limit` entries.
+fn pattern_matches(
+    chars: &[(char, Option<[f32; 4]>)],
+    pattern: &regex::Regex,
+    limit: usize,
+) -> Vec<([f32; 4], String)> {
+    let text: String = chars.iter().map(|&(c, _)| c).collect();
+    // Byte offset <-> char index
+    let char_starts: Vec<usize> = text.char_indices().map(|(i, _)| i).collect();
+    let char_index = |byte: usize| char_starts.partition_point(|&start| start < byte);
+
+    let mut matches = Vec::new();
+    for found in pattern.find_iter(&text) {
+        let (start, end) = (char_index(found.start()), char_index(found.end()));
+
+        // Line breaks have no rect, so they split the match into its lines
+        let mut chunk_start = start;
+        for chunk in chars[start..end].split(|&(_, rect)| rect.is_none()) {
+            let first = chunk_start;
+            chunk_start += chunk.len() + 1;
+            let Some(rect) = chunk
+                .iter()
+                .filter_map(|&(_, rect)| rect)
+                .reduce(|a, b| [a[0].min(b[0]), a[1].min(b[1]), a[2].max(b[2]), a[3].max(b[3])])
+            else {
+                continue;
+            };
+            if matches.len() >= limit {
+                return matches;
+            }
 
-    let open_start = Instant::now();
-    let document = Document::open(path)
-        .map_err(|e| format!("Failed to load PDF: {:?}", e))?;
-    log::info!("[Search] Document open: {:?}", open_start.elapsed());
+            let at = char_starts[first];
+            let line_start = text[..at].rfind('\n').map_or(0, |i| i + 1);
+            let line_end = text[at..].find('\n').map_or(text.len(), |i| at + i);
+            let line = text[line_start..line_end].trim();
+            let context = if line.chars().count() > 100 {
+                format!("{}...", line.chars().take(100).collect::<String>())
+            } else {
+                line.to_string()
+            };
 
-    let num_pages = document
-        .page_count()
-        .map_err(|e| format!("Failed to get page count: {:?}", e))? as u32;
+            matches.push((rect, context));
+        }
+    }
+    matches
+}
 
-    let mut results = Vec::new();
-    let mut total_found: u32 = 0;
+impl SearchOptions {
+    fn needs_match_text(self) -> bool {
+        self.case_sensitive || self.whole_word
+    }
+}
 
-    // Track seen positions for deduplication: (page, y_bucket)
-    let mut seen_positions: std::collections::HashSet<(u32, i32)> = std::collections::HashSet::new();
-    const Y_BUCKET_SIZE: f32 = 0.015;
+/// Group results by page, keeping document order
+fn group_results_by_page(results: Vec<SearchResult>) -> Vec<PageSearchResults> {
+    let mut groups: Vec<PageSearchResults> = Vec::new();
+    for result in results {
+        match groups.last_mut() {
+            Some(group) if group.page == result.page => group.matches.push(result),
+            _ => groups.push(PageSearchResults {
+                page: result.page,
+                matches: vec![result],
+            }),
+        }
+    }
+    groups
+}
+
+/// Points two hit rectangles may differ by and still be the same match
+/// (MuPDF reports text drawn twice, e.g. fake bold, as separate hits)
+const SAME_MATCH_TOLERANCE: f32 = 1.0;
+
+/// Whether two hit rectangles `[x0, y0, x1, y1]` cover the same text
+fn same_match_rect(a: [f32; 4], b: [f32; 4]) -> bool {
+    a.iter().zip(b.iter()).all(|(p, q)| (p - q).abs() < SAME_MATCH_TOLERANCE)
+}
+
+/// Text covered by a hit rectangle plus the characters just before and after it on the line
+fn text_in_rect(text_page: &mupdf::TextPage, rect: [f32; 4]) -> Option<(String, Option<char>, Option<char>)> {
+    let [x0, y0, x1, y1] = rect;
+    let center_y = (y0 + y1) / 2.0;
+
+    for block in text_page.blocks() {
+        for line in block.lines() {
+            let bounds = line.bounds();
+            if center_y < bounds.y0 || center_y > bounds.y1 {
+                continue;
+            }
+
+            let chars: Vec<(char, bool)> = line
+                .chars()
+                .filter_map(|char_info| {
+                    let c = char_info.char()?;
+                    let quad = char_info.quad();
+                    let cx = (quad.ul.x + quad.ur.x + quad.ll.x + quad.lr.x) / 4.0;
+                    let cy = (quad.ul.y + quad.ur.y + quad.ll.y + quad.lr.y) / 4.0;
+                    Some((c, cx >= x0 && cx <= x1 && cy >= y0 && cy <= y1))
+                })
+                .collect();
+
+            let first = chars.iter().position(|&(_, inside)| inside);
+            let last = chars.iter().rposition(|&(_, inside)| inside);
+            if let (Some(first), Some(last)) = (first, last) {
+                let text = chars[first..=last].iter().map(|&(c, _)| c).collect();
+                let before = first.checked_sub(1).map(|i| chars[i].0);
+                let after = chars.get(last + 1).map(|&(c, _)| c);
+                return Some((text, before, after));
+            }
+        }
+    }
+
+    None
+}
+
+/// Check a hit's text against the case/whole-word options.
+/// Matches split across lines only see part of the query, so the word
+/// boundary is only checked on the side where the query actually starts/ends.
+fn hit_matches(query: &str, text: &str, before: Option<char>, after: Option<char>, options: SearchOptions) -> bool {
+    let (query, text) = if options.case_sensitive {
+        (query.to_string(), text.to_string())
+    } else {
+        (query.to_lowercase(), text.to_lowercase())
+    };
+    let text = text.trim();
+    if text.is_empty() || !query.contains(text) {
+        return false;
+    }
+
+    if options.whole_word {
+        let is_word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+        if query.starts_with(text) && is_word(before) {
+            return false;
+        }
+        if query.ends_with(text) && is_word(after) {
+            return false;
+        }
+    }
+
+    true
+}
+
+/// Search for text across all pages of a PDF
+/// Uses MuPDF's native search which is much faster than JavaScript iteration
+/// Runs in a blocking thread to avoid freezing the UI. Opens its own handle rather
+/// than using the document cache so a long search never blocks page rendering.
+///
+/// `mode` "regex" and "any_of" can't use MuPDF's accelerated search: every page's
+/// full text is extracted and scanned, which is noticeably slower on large documents.
+#[tauri::command]
+pub async fn pdf_search_text(
+    path: String,
+    query: String,
+    max_results: Option<u32>,
+    group_by_page: Option<bool>,
+    whole_word: Option<bool>,
+    case_sensitive: Option<bool>,
+    mode: Option<String>,
+) -> Result<SearchResults, CommandError> {
+    let max_results = max_results.unwrap_or(1000);
+    let group_by_page = group_by_page.unwrap_or(false);
+    let options = SearchOptions {
+        mode: SearchMode::parse(mode.as_deref()).map_err(|e| CommandError::new(codes::INVALID_ARGUMENT, e))?,
+        case_sensitive: case_sensitive.unwrap_or(false),
+        whole_word: whole_word.unwrap_or(false),
+    };
+
+    if query.is_empty() {
+        return Ok(SearchResults {
+            query,
+            total: 0,
+            results: Vec::new(),
+            pages: group_by_page.then(Vec::new),
+        });
+    }
+
+    ensure_exists(&path)?;
+
+    // Run the heavy search in a blocking thread to not freeze UI
+    let query_clone = query.clone();
+    let results = tauri::async_runtime::spawn_blocking(move || {
+        search_text_blocking(&path, &query_clone, max_results, options)
+    })
+    .await
+    .map_err(|e| format!("Search task failed: {:?}", e))??;
+
+    let total = results.len() as u32;
+    if group_by_page {
+        return Ok(SearchResults {
+            query,
+            total,
+            results: Vec::new(),
+            pages: Some(group_results_by_page(results)),
+        });
+    }
+
+    Ok(SearchResults {
+        query,
+        total,
+        results,
+        pages: None,
+    })
+}
+
+/// Internal blocking search function
+fn search_text_blocking(
+    path: &str,
+    query: &str,
+    max_results: u32,
+    options: SearchOptions,
+) -> Result<Vec<SearchResult>, String> {
+    use std::time::Instant;
+
+    let total_start = Instant::now();
+
+    let open_start = Instant::now();
+    let document = Document::open(path)
+        .map_err(|e| format!("Failed to load PDF: {:?}", e))?;
+    log::info!("[Search] Document open: {:?}", open_start.elapsed());
+
+    let num_pages = document
+        .page_count()
+        .map_err(|e| format!("Failed to get page count: {:?}", e))? as u32;
+
+    if let Some(pattern) = search_pattern(query, options)? {
+        let results = search_pattern_pages(&document, num_pages, &pattern, max_results);
+        log::info!(
+            "[Search] Pattern search complete: {} pages, {} results in {:?}",
+            num_pages, results.len(), total_start.elapsed()
+        );
+        return Ok(results);
+    }
+
+    let mut results = Vec::new();
+    let mut total_found: u32 = 0;
 
     // Timing accumulators
     let mut load_page_time = std::time::Duration::ZERO;
@@ -484,9 +2052,14 @@ fn search_text_blocking(path: &str, query: &str, max_results: u32) -> Result<Vec
         let page_width = bounds.width();
         let page_height = bounds.height();
 
-        // Use MuPDF's native search
+        // Use MuPDF's native search. Case/whole-word filtering drops hits
+        // afterwards, so ask for the full page's worth in that case.
         let search_start = Instant::now();
-        let hits_remaining = (max_results - total_found).min(100);
+        let hits_remaining = if options.needs_match_text() {
+            500
+        } else {
+            (max_results - total_found).min(100)
+        };
         let search_results = match pdf_page.search(query, hits_remaining) {
             Ok(r) => r,
             Err(_) => continue,
@@ -503,25 +2076,33 @@ fn search_text_blocking(path: &str, query: &str, max_results: u32) -> Result<Vec
             None
         };
 
+        // Rectangles already reported on this page, for deduplication
+        let mut seen_rects: Vec<[f32; 4]> = Vec::new();
+
         for quad in search_results.iter() {
             // Calculate bounding box from quad
             let x0 = quad.ul.x.min(quad.ll.x);
             let y0 = quad.ul.y.min(quad.ur.y);
             let x1 = quad.ur.x.max(quad.lr.x);
             let y1 = quad.ll.y.max(quad.lr.y);
+            let hit_rect = [x0, y0, x1, y1];
 
-            let normalized_y = y0 / page_height;
-            let current_page = page_num + 1;
-
-            // Bucket the Y position for deduplication
-            let y_bucket = (normalized_y / Y_BUCKET_SIZE) as i32;
-            let position_key = (current_page, y_bucket);
-
-            // Deduplicate: skip if we've already seen this position
-            if seen_positions.contains(&position_key) {
+            // Deduplicate: skip hits covering the same text as an earlier one
+            if seen_rects.iter().any(|&seen| same_match_rect(seen, hit_rect)) {
                 continue;
             }
-            seen_positions.insert(position_key);
+            seen_rects.push(hit_rect);
+
+            if options.needs_match_text() {
+                let matched = text_page.as_ref().and_then(|tp| text_in_rect(tp, hit_rect));
+                match matched {
+                    Some((text, before, after)) if hit_matches(query, &text, before, after, options) => {}
+                    _ => continue,
+                }
+            }
+
+            let normalized_y = y0 / page_height;
+            let current_page = page_num + 1;
 
             let rect = NormalizedRect {
                 x: x0 / page_width,
@@ -540,6 +2121,7 @@ fn search_text_blocking(path: &str, query: &str, max_results: u32) -> Result<Vec
             context_time += ctx_start.elapsed();
 
             results.push(SearchResult {
+                index: total_found,
                 page: current_page, // 1-indexed
                 y: normalized_y,
                 rect,
@@ -554,114 +2136,914 @@ fn search_text_blocking(path: &str, query: &str, max_results: u32) -> Result<Vec
         }
     }
 
-    log::info!(
-        "[Search] Complete: {} pages, {} results in {:?} | load_page: {:?}, search: {:?}, text_page: {:?}, context: {:?}",
-        num_pages, results.len(), total_start.elapsed(),
-        load_page_time, search_time, text_page_time, context_time
-    );
+    log::info!(
+        "[Search] Complete: {} pages, {} results in {:?} | load_page: {:?}, search: {:?}, text_page: {:?}, context: {:?}",
+        num_pages, results.len(), total_start.elapsed(),
+        load_page_time, search_time, text_page_time, context_time
+    );
+
+    Ok(results)
+}
+
+/// Regex / any_of search: scan each page's extracted text with `pattern`
+fn search_pattern_pages(
+    document: &Document,
+    num_pages: u32,
+    pattern: &regex::Regex,
+    max_results: u32,
+) -> Vec<SearchResult> {
+    let mut results = Vec::new();
+
+    for page_num in 0..num_pages {
+        let remaining = max_results as usize - results.len();
+        if remaining == 0 {
+            break;
+        }
+
+        let Ok(pdf_page) = document.load_page(page_num as i32) else { continue };
+        let Ok(bounds) = pdf_page.bounds() else { continue };
+        let Ok(text_page) = pdf_page.to_text_page(TextPageOptions::empty()) else { continue };
+
+        let page_width = bounds.width();
+        let page_height = bounds.height();
+
+        for ([x0, y0, x1, y1], context) in pattern_matches(&page_chars(&text_page), pattern, remaining) {
+            results.push(SearchResult {
+                index: results.len() as u32,
+                page: page_num + 1,
+                y: y0 / page_height,
+                rect: NormalizedRect {
+                    x: x0 / page_width,
+                    y: y0 / page_height,
+                    width: (x1 - x0) / page_width,
+                    height: (y1 - y0) / page_height,
+                },
+                context,
+            });
+        }
+    }
+
+    results
+}
+
+/// Extract context text around a match position
+fn extract_context_around_match(text_page: &mupdf::TextPage, query: &str, match_y: f32, page_height: f32) -> String {
+    let query_lower = query.to_lowercase();
+
+    for block in text_page.blocks() {
+        for line in block.lines() {
+            let line_bounds = line.bounds();
+            // Check if this line is near the match position
+            if (line_bounds.y0 - match_y).abs() < 5.0 ||
+               (line_bounds.y1 - match_y).abs() < 5.0 ||
+               (match_y >= line_bounds.y0 && match_y <= line_bounds.y1) {
+
+                let mut line_text = String::new();
+                for char_info in line.chars() {
+                    if let Some(c) = char_info.char() {
+                        line_text.push(c);
+                    }
+                }
+
+                // Check if this line contains the query
+                if line_text.to_lowercase().contains(&query_lower) {
+                    // Return a trimmed context (UTF-8 safe)
+                    let trimmed = line_text.trim();
+                    if trimmed.chars().count() > 100 {
+                        let truncated: String = trimmed.chars().take(100).collect();
+                        return format!("{}...", truncated);
+                    }
+                    return trimmed.to_string();
+                }
+            }
+        }
+    }
+
+    query.to_string()
+}
+
+/// PDF outline (table of contents) entry
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OutlineEntry {
+    /// Title of the outline entry
+    pub title: String,
+    /// Target page number (1-indexed), if available
+    pub page: Option<u32>,
+    /// Y position on the page (normalized 0-1)
+    pub y: Option<f32>,
+    /// Child entries (sub-sections)
+    #[serde(default)]
+    pub children: Vec<OutlineEntry>,
+    /// Whether the entry starts expanded, for PDF entries with children
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub open: Option<bool>,
+}
+
+/// Convert MuPDF Outline to our OutlineEntry, fetching page dimensions for normalization
+fn convert_outline(outline: &MuOutline, document: &Document) -> OutlineEntry {
+    let (page, normalized_y) = if let Some(p) = outline.page {
+        // Page is 0-indexed in the outline
+        let page_num = p + 1; // Convert to 1-indexed
+
+        // Normalize Y coordinate if we have a valid page
+        let norm_y = if outline.y > 0.0 {
+            if let Ok(pdf_page) = document.load_page(p as i32) {
+                if let Ok(bounds) = pdf_page.bounds() {
+                    Some(outline.y / bounds.height())
+                } else {
+                    None
+                }
+            } else {
+                None
+            }
+        } else {
+            None
+        };
+
+        (Some(page_num), norm_y)
+    } else {
+        (None, None)
+    };
+
+    OutlineEntry {
+        title: outline.title.clone(),
+        page,
+        y: normalized_y,
+        children: outline.down.iter().map(|c| convert_outline(c, document)).collect(),
+        open: None,
+    }
+}
+
+/// Get PDF outline (table of contents)
+#[tauri::command]
+pub fn pdf_get_outlines(path: String) -> Result<Vec<OutlineEntry>, CommandError> {
+    let document = open_document(&path)?;
+
+    let outlines = document
+        .outlines()
+        .map_err(|e| format!("Failed to get outlines: {:?}", e))?;
+
+    let mut entries: Vec<OutlineEntry> = outlines
+        .iter()
+        .map(|o| convert_outline(o, &document))
+        .collect();
+
+    // MuPDF's outline doesn't say which entries start expanded; read the /Count signs
+    if let Ok(pdf) = PdfDocument::try_from(document) {
+        let first = pdf
+            .catalog()
+            .and_then(|catalog| catalog.get_dict("Outlines"))
+            .ok()
+            .flatten()
+            .and_then(|outlines| outlines.get_dict("First").ok().flatten());
+        if let Some(first) = first {
+            apply_outline_open_states(&mut entries, first);
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Set `open` on entries with children from the /Count of the matching outline
+/// items, walking both trees in the same order
+fn apply_outline_open_states(entries: &mut [OutlineEntry], first: PdfObject) {
+    let mut item = Some(first);
+    for entry in entries.iter_mut() {
+        let Some(current) = item.take() else {
+            break;
+        };
+        if !entry.children.is_empty() {
+            let count = current.get_dict("Count").ok().flatten().and_then(|c| c.as_int().ok());
+            entry.open = Some(count.is_some_and(|c| c > 0));
+            if let Ok(Some(child)) = current.get_dict("First") {
+                apply_outline_open_states(&mut entry.children, child);
+            }
+        }
+        item = current.get_dict("Next").ok().flatten();
+    }
+}
+
+/// Entries shown when their parent is expanded: /Count of an open parent
+fn visible_outline_count(entries: &[OutlineEntry]) -> i32 {
+    entries
+        .iter()
+        .map(|e| 1 + if e.open.unwrap_or(true) { visible_outline_count(&e.children) } else { 0 })
+        .sum()
+}
+
+/// Fail with `invalid_argument` if any entry targets a page outside 1..=page_count
+fn check_outline_pages(entries: &[OutlineEntry], page_count: u32) -> Result<(), CommandError> {
+    for entry in entries {
+        if let Some(page) = entry.page.filter(|&p| p == 0 || p > page_count) {
+            return Err(CommandError::new(
+                codes::INVALID_ARGUMENT,
+                format!("Bookmark \"{}\" targets page {} (document has {} pages)", entry.title, page, page_count),
+            ));
+        }
+        check_outline_pages(&entry.children, page_count)?;
+    }
+    Ok(())
+}
+
+/// Destination for a 1-indexed page: /XYZ at the normalized `y` (from the top
+/// of the displayed page), or /Fit for the whole page
+fn outline_destination(doc: &PdfDocument, page: u32, y: Option<f32>) -> Result<PdfObject, mupdf::Error> {
+    let index = page as i32 - 1;
+    let page_obj = doc.find_page(index)?;
+    let mut dest = doc.new_array()?;
+    dest.array_push(page_obj.try_clone()?)?;
+    match y {
+        Some(y) => {
+            let bounds = doc.load_page(index)?.bounds()?;
+            let to_pdf = invert(&page_obj.page_ctm()?).unwrap_or(Matrix::IDENTITY);
+            let top = mupdf::Point::new(bounds.x0, bounds.y0 + y.clamp(0.0, 1.0) * bounds.height()).transform(&to_pdf);
+            dest.array_push(PdfObject::new_name("XYZ")?)?;
+            dest.array_push(PdfObject::new_null())?;
+            dest.array_push(PdfObject::new_real(top.y)?)?;
+            dest.array_push(PdfObject::new_null())?;
+        }
+        None => dest.array_push(PdfObject::new_name("Fit")?)?,
+    }
+    Ok(dest)
+}
+
+/// Create linked outline items for `entries` under `parent` and point the
+/// parent's /First and /Last at them
+fn add_outline_items(
+    doc: &mut PdfDocument,
+    entries: &[OutlineEntry],
+    parent: &mut PdfObject,
+) -> Result<(), mupdf::Error> {
+    let mut items: Vec<PdfObject> = Vec::with_capacity(entries.len());
+    for entry in entries {
+        let dict = doc.new_dict()?;
+        let mut item = doc.add_object(&dict)?;
+        let title = if entry.title.is_ascii() {
+            PdfObject::new_string(&entry.title)?
+        } else {
+            doc.new_object_from_str(&utf16_hex_string(&entry.title))?
+        };
+        item.dict_put("Title", title)?;
+        item.dict_put("Parent", parent.try_clone()?)?;
+        if let Some(page) = entry.page {
+            item.dict_put("Dest", outline_destination(doc, page, entry.y)?)?;
+        }
+        if !entry.children.is_empty() {
+            add_outline_items(doc, &entry.children, &mut item)?;
+            let count = visible_outline_count(&entry.children);
+            let count = if entry.open.unwrap_or(true) { count } else { -count };
+            item.dict_put("Count", PdfObject::new_int(count)?)?;
+        }
+        if let Some(prev) = items.last_mut() {
+            prev.dict_put("Next", item.try_clone()?)?;
+            item.dict_put("Prev", prev.try_clone()?)?;
+        }
+        items.push(item);
+    }
+
+    if let (Some(first), Some(last)) = (items.first(), items.last()) {
+        parent.dict_put("First", first.try_clone()?)?;
+        parent.dict_put("Last", last.try_clone()?)?;
+    }
+    Ok(())
+}
+
+/// Save `doc` to `output`, which may be the file it was opened from. The save
+/// goes to a sibling temp file that then replaces `output`, so a failed save
+/// can't truncate the original; `doc` is closed first because Windows can't
+/// replace an open file. Callers evict `output` from the `DocumentCache` first.
+pub(crate) fn save_atomically(doc: PdfDocument, output: &str) -> Result<(), CommandError> {
+    let temp_output = format!("{}.tmp", output);
+    let saved = doc.save(&temp_output);
+    drop(doc);
+    if let Err(e) = saved {
+        let _ = std::fs::remove_file(&temp_output);
+        return Err(CommandError::pdf("Failed to save PDF", e));
+    }
+    std::fs::rename(&temp_output, output).map_err(|e| {
+        let _ = std::fs::remove_file(&temp_output);
+        CommandError::new(codes::ERROR, format!("Failed to write output file: {}", e))
+    })
+}
+
+/// Replace the outline with `entries` (none removes it) and save to `output`
+/// (may equal `input`). `cache` drops its copy of `output` before the file is
+/// replaced.
+fn write_outline(
+    cache: &DocumentCache,
+    input: &str,
+    output: &str,
+    entries: &[OutlineEntry],
+) -> Result<(), CommandError> {
+    ensure_exists(input)?;
+    let mut doc = PdfDocument::open(input).map_err(|e| CommandError::pdf("Failed to open PDF", e))?;
+    let page_count = doc
+        .page_count()
+        .map_err(|e| CommandError::pdf("Failed to get page count", e))? as u32;
+    check_outline_pages(entries, page_count)?;
+
+    let mut edit = || -> Result<(), mupdf::Error> {
+        let mut catalog = doc.catalog()?;
+        catalog.dict_delete("Outlines")?;
+        if entries.is_empty() {
+            return Ok(());
+        }
+
+        let dict = doc.new_dict()?;
+        let mut root = doc.add_object(&dict)?;
+        root.dict_put("Type", PdfObject::new_name("Outlines")?)?;
+        add_outline_items(&mut doc, entries, &mut root)?;
+        root.dict_put("Count", PdfObject::new_int(visible_outline_count(entries))?)?;
+        catalog.dict_put("Outlines", root)?;
+        Ok(())
+    };
+    edit().map_err(|e| CommandError::pdf("Failed to update outline", e))?;
+
+    cache.evict(output);
+    save_atomically(doc, output)
+}
+
+/// Replace the bookmark tree with `entries` (an empty list removes it) and
+/// save to `output` (defaults to `input`). Returns the outline read back from
+/// the saved file.
+#[tauri::command]
+pub fn pdf_set_outline(
+    cache: State<'_, DocumentCache>,
+    input: String,
+    output: Option<String>,
+    entries: Vec<OutlineEntry>,
+) -> Result<Vec<OutlineEntry>, CommandError> {
+    let output = output.unwrap_or_else(|| input.clone());
+    write_outline(&cache, &input, &output, &entries)?;
+
+    pdf_get_outlines(output)
+}
+
+/// Average silent reading speed used for reading-time estimates
+const READING_WORDS_PER_MINUTE: u32 = 200;
+
+/// Word count and reading time for one outline section
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SectionStats {
+    pub title: String,
+    /// First page of the section (1-indexed)
+    pub start_page: u32,
+    /// Last page of the section (1-indexed, inclusive)
+    pub end_page: u32,
+    pub word_count: u32,
+    pub reading_minutes: u32,
+}
+
+/// Count whitespace-separated words on a page
+fn page_word_count(document: &Document, page_index: i32) -> Result<u32, CommandError> {
+    let page = document
+        .load_page(page_index)
+        .map_err(|e| format!("Failed to get page {}: {:?}", page_index + 1, e))?;
+    let text_page = page
+        .to_text_page(TextPageOptions::empty())
+        .map_err(|e| format!("Failed to extract text: {:?}", e))?;
+
+    let mut words = 0;
+    for block in text_page.blocks() {
+        for line in block.lines() {
+            let text: String = line.chars().filter_map(|c| c.char()).collect();
+            words += text.split_whitespace().count() as u32;
+        }
+    }
+
+    Ok(words)
+}
+
+/// Split top-level outline entries into (title, start, end) page ranges (1-indexed, inclusive)
+fn outline_sections(outlines: &[OutlineEntry], page_count: u32) -> Vec<(String, u32, u32)> {
+    let mut starts: Vec<(String, u32)> = outlines
+        .iter()
+        .filter_map(|o| o.page.map(|p| (o.title.clone(), p)))
+        .filter(|(_, p)| *p >= 1 && *p <= page_count)
+        .collect();
+    starts.sort_by_key(|(_, p)| *p);
+
+    starts
+        .iter()
+        .enumerate()
+        .map(|(i, (title, start))| {
+            let end = starts
+                .get(i + 1)
+                .map(|(_, next)| next.saturating_sub(1).max(*start))
+                .unwrap_or(page_count);
+            (title.clone(), *start, end)
+        })
+        .collect()
+}
+
+/// Per-section word counts and reading time, using top-level bookmarks as sections.
+/// Documents without bookmarks are reported as a single section.
+#[tauri::command]
+pub async fn pdf_section_stats(input: String) -> Result<Vec<SectionStats>, CommandError> {
+    // Extracts the text of every page: keep it off the main thread
+    crate::run_blocking(move || pdf_section_stats_blocking(input)).await
+}
+
+fn pdf_section_stats_blocking(input: String) -> Result<Vec<SectionStats>, CommandError> {
+    let outlines = pdf_get_outlines(input.clone())?;
+
+    let document = open_document(&input)?;
+    let page_count = document
+        .page_count()
+        .map_err(|e| format!("Failed to get page count: {:?}", e))? as u32;
+
+    let mut sections = outline_sections(&outlines, page_count);
+    if sections.is_empty() && page_count > 0 {
+        let title = document
+            .metadata(MetadataName::Title)
+            .ok()
+            .filter(|s| !s.is_empty())
+            .unwrap_or_else(|| "Document".to_string());
+        sections.push((title, 1, page_count));
+    }
+
+    let mut page_words: Vec<Option<u32>> = vec![None; page_count as usize];
+    let mut stats = Vec::with_capacity(sections.len());
+
+    for (title, start, end) in sections {
+        let mut word_count = 0;
+        for page in start..=end {
+            let slot = &mut page_words[(page - 1) as usize];
+            if slot.is_none() {
+                *slot = Some(page_word_count(&document, (page - 1) as i32)?);
+            }
+            word_count += slot.unwrap_or(0);
+        }
+
+        stats.push(SectionStats {
+            title,
+            start_page: start,
+            end_page: end,
+            word_count,
+            reading_minutes: word_count.div_ceil(READING_WORDS_PER_MINUTE),
+        });
+    }
+
+    Ok(stats)
+}
+
+/// Image coverage (0-1) from which a page with little text looks like a scan
+const SCAN_IMAGE_COVERAGE: f32 = 0.5;
+/// Fewer non-whitespace characters than this count as "little text" on a page
+const SCAN_MAX_CHARS: u32 = 50;
+
+/// Text statistics for one page
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PageTextStats {
+    /// Page number (1-indexed)
+    pub page: u32,
+    pub word_count: u32,
+    /// Non-whitespace characters
+    pub char_count: u32,
+    /// Fraction of the page covered by images (0-1)
+    pub image_coverage: f32,
+}
+
+/// Document-wide text statistics
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TextStats {
+    pub page_count: u32,
+    pub word_count: u32,
+    pub char_count: u32,
+    pub pages: Vec<PageTextStats>,
+    /// Pages that are mostly image with (almost) no text
+    pub scanned_pages: u32,
+    /// True when at least half the pages look scanned, so OCR is worth suggesting
+    pub likely_scanned: bool,
+}
+
+fn page_text_stats(document: &Document, page_index: i32) -> Result<PageTextStats, CommandError> {
+    let page = document
+        .load_page(page_index)
+        .map_err(|e| format!("Failed to get page {}: {:?}", page_index + 1, e))?;
+    let bounds = page
+        .bounds()
+        .map_err(|e| format!("Failed to get page bounds: {:?}", e))?;
+    let text_page = page
+        .to_text_page(TextPageOptions::PRESERVE_IMAGES)
+        .map_err(|e| format!("Failed to extract text: {:?}", e))?;
+
+    let (mut word_count, mut char_count, mut image_area) = (0, 0, 0.0);
+    for block in text_page.blocks() {
+        if block.r#type() == mupdf::text_page::TextBlockType::Image {
+            let b = block.bounds();
+            // Only the part of the image on the page counts
+            let width = (b.x1.min(bounds.x1) - b.x0.max(bounds.x0)).max(0.0);
+            let height = (b.y1.min(bounds.y1) - b.y0.max(bounds.y0)).max(0.0);
+            image_area += width * height;
+            continue;
+        }
+        for line in block.lines() {
+            let text: String = line.chars().filter_map(|c| c.char()).collect();
+            word_count += text.split_whitespace().count() as u32;
+            char_count += text.chars().filter(|c| !c.is_whitespace()).count() as u32;
+        }
+    }
+
+    let page_area = bounds.width() * bounds.height();
+    let image_coverage = if page_area > 0.0 {
+        (image_area / page_area).min(1.0)
+    } else {
+        0.0
+    };
+
+    Ok(PageTextStats {
+        page: page_index as u32 + 1,
+        word_count,
+        char_count,
+        image_coverage,
+    })
+}
+
+/// Whether a page is mostly image with (almost) no text
+fn looks_scanned(page: &PageTextStats) -> bool {
+    page.image_coverage >= SCAN_IMAGE_COVERAGE && page.char_count < SCAN_MAX_CHARS
+}
+
+/// Word, character and page counts, per-page word counts, and whether the
+/// document appears to be a scan (natively, unlike `ocr_analyze_pdf`)
+#[tauri::command]
+pub async fn pdf_text_stats(
+    app: AppHandle,
+    path: String,
+    password: Option<String>,
+) -> Result<TextStats, CommandError> {
+    // Extracts every page's text: keep it off the main thread
+    crate::run_blocking(move || {
+        let cache = app.state::<DocumentCache>();
+        cache.with_document(&path, password.as_deref(), |document| {
+            let page_count = document
+                .page_count()
+                .map_err(|e| format!("Failed to get page count: {:?}", e))? as u32;
+            let pages = (0..page_count as i32)
+                .map(|i| page_text_stats(document, i))
+                .collect::<Result<Vec<_>, String>>()?;
+            Ok(text_stats(pages))
+        })
+    })
+    .await
+}
+
+fn text_stats(pages: Vec<PageTextStats>) -> TextStats {
+    let scanned_pages = pages.iter().filter(|p| looks_scanned(p)).count() as u32;
+    let page_count = pages.len() as u32;
+    TextStats {
+        page_count,
+        word_count: pages.iter().map(|p| p.word_count).sum(),
+        char_count: pages.iter().map(|p| p.char_count).sum(),
+        pages,
+        scanned_pages,
+        likely_scanned: page_count > 0 && scanned_pages * 2 >= page_count,
+    }
+}
+
+/// A font that is not embedded and is drawn with a substitute at render time
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SubstitutedFont {
+    /// Font name requested by the PDF (subset prefix removed)
+    pub requested: String,
+    /// Font MuPDF draws instead: a system font's family name or one of its
+    /// built-in fallbacks. Empty when `substituted` is false
+    pub substitute: String,
+    /// False when MuPDF could not load the font or any stand-in, so text
+    /// set in it does not render at all
+    pub substituted: bool,
+    /// Pages using the font (1-indexed)
+    pub pages: Vec<u32>,
+}
+
+/// CID orderings MuPDF serves from its CJK fallback font
+const CJK_ORDERINGS: &[&str] = &["GB1", "CNS1", "Japan1", "Korea1"];
+
+/// A font as MuPDF loaded it for drawing: its name and the style flags it
+/// was picked by
+#[derive(Debug, Clone, PartialEq)]
+struct LoadedFont {
+    name: String,
+    bold: bool,
+    italic: bool,
+    serif: bool,
+    monospaced: bool,
+}
+
+/// Device that keeps the first font MuPDF draws text with
+struct FontProbe(Rc<RefCell<Option<LoadedFont>>>);
+
+impl FontProbe {
+    fn record(&mut self, text: &Text) {
+        let mut slot = self.0.borrow_mut();
+        if slot.is_some() {
+            return;
+        }
+        if let Some(span) = text.spans().next() {
+            let font = span.font();
+            *slot = Some(LoadedFont {
+                name: font.name().to_string(),
+                bold: font.is_bold(),
+                italic: font.is_italic(),
+                serif: font.is_serif(),
+                monospaced: font.is_monospaced(),
+            });
+        }
+    }
+}
+
+impl NativeDevice for FontProbe {
+    fn fill_text(
+        &mut self,
+        text: &Text,
+        _cmt: Matrix,
+        _color_space: &Colorspace,
+        _color: &[f32],
+        _alpha: f32,
+        _cp: ColorParams,
+    ) {
+        self.record(text);
+    }
+
+    fn ignore_text(&mut self, text: &Text, _cmt: Matrix) {
+        self.record(text);
+    }
+}
+
+/// Let MuPDF load a font dictionary the way a page would and report what it
+/// actually draws with. The font is copied into a one-page scratch document
+/// whose content shows a code in it. None when no text comes out, which
+/// means MuPDF could not load the font or any stand-in for it.
+fn resolve_loaded_font(font: &PdfObject) -> Result<Option<LoadedFont>, mupdf::Error> {
+    let mut scratch = PdfDocument::new();
+    let copy = scratch.graft_object(font)?;
+    let page = scratch.new_page(Size::new(100.0, 100.0))?;
+    let page_obj = page.object();
+
+    let mut fonts = scratch.new_dict()?;
+    fonts.dict_put("F0", copy)?;
+    if let Some(mut resources) = page_obj.get_dict("Resources")? {
+        resources.dict_put("Font", fonts)?;
+    }
+    if let Some(mut contents) = page_obj.get_dict("Contents")? {
+        // One code for two-byte CMaps, two for simple fonts: either way a span
+        contents.write_stream_string("BT /F0 12 Tf 10 10 Td <0041> Tj ET")?;
+    }
+
+    let loaded = Rc::new(RefCell::new(None));
+    let device = Device::from_native(FontProbe(Rc::clone(&loaded)))?;
+    page.run(&device, &Matrix::IDENTITY)?;
+    drop(device);
+
+    let result = loaded.borrow_mut().take();
+    Ok(result)
+}
+
+/// Base-14 family MuPDF serves a font name from directly, after the same
+/// Arial/TimesNewRoman aliasing it applies
+fn base14_family(name: &str) -> Option<&'static str> {
+    let lower = name.to_lowercase();
+    if lower.starts_with("courier") {
+        Some("NimbusMonoPS")
+    } else if lower.starts_with("helvetica") || lower.starts_with("arial") {
+        Some("NimbusSans")
+    } else if lower.starts_with("times") {
+        Some("NimbusRoman")
+    } else if lower == "symbol" || lower.starts_with("symbol,") {
+        Some("StandardSymbolsPS")
+    } else if lower.starts_with("zapfdingbats") {
+        Some("Dingbats")
+    } else {
+        None
+    }
+}
+
+/// Name the stand-in MuPDF drew a non-embedded font with. A system font
+/// keeps its own family name. MuPDF's built-in fallbacks are loaded under
+/// the requested name, so they are named from the family and style flags
+/// MuPDF chose the fallback face by.
+fn substitute_font_name(requested: &str, base_font: &str, loaded: &LoadedFont, cjk: bool) -> String {
+    let builtin = loaded.name == requested || loaded.name == base_font || base14_family(&loaded.name).is_some();
+    if !builtin {
+        return loaded.name.clone();
+    }
+
+    if cjk {
+        return "DroidSansFallback".to_string();
+    }
+
+    let family = match base14_family(requested) {
+        Some(family @ ("StandardSymbolsPS" | "Dingbats")) => return family.to_string(),
+        Some(family) => family,
+        None if loaded.monospaced => "NimbusMonoPS",
+        None if loaded.serif => "NimbusRoman",
+        None => "NimbusSans",
+    };
+
+    let style = match (loaded.bold, loaded.italic) {
+        (false, false) => "Regular",
+        (true, false) => "Bold",
+        (false, true) => "Italic",
+        (true, true) => "BoldItalic",
+    };
+
+    format!("{}-{}", family, style)
+}
 
-    Ok(results)
+fn pdf_name(obj: Option<PdfObject>) -> Option<String> {
+    obj.and_then(|o| o.as_name().ok().map(|n| String::from_utf8_lossy(n).to_string()))
 }
 
-/// Extract context text around a match position
-fn extract_context_around_match(text_page: &mupdf::TextPage, query: &str, match_y: f32, page_height: f32) -> String {
-    let query_lower = query.to_lowercase();
+/// The page's /Resources dictionary, following /Parent for inherited resources
+fn page_resources(document: &PdfDocument, page_index: i32) -> Result<Option<PdfObject>, mupdf::Error> {
+    let mut node = document.find_page(page_index)?;
+    loop {
+        if let Some(resources) = node.get_dict("Resources")? {
+            return Ok(Some(resources));
+        }
+        match node.get_dict("Parent")? {
+            Some(parent) => node = parent,
+            None => return Ok(None),
+        }
+    }
+}
 
-    for block in text_page.blocks() {
-        for line in block.lines() {
-            let line_bounds = line.bounds();
-            // Check if this line is near the match position
-            if (line_bounds.y0 - match_y).abs() < 5.0 ||
-               (line_bounds.y1 - match_y).abs() < 5.0 ||
-               (match_y >= line_bounds.y0 && match_y <= line_bounds.y1) {
+/// Every font dictionary reachable from a resource dictionary: its own /Font
+/// entries plus those of the Form XObjects, patterns and Type3 glyph
+/// procedures it uses, recursively. `seen` holds the object numbers already
+/// walked so shared or self-referencing resources are visited once.
+fn collect_fonts(resources: &PdfObject, seen: &mut Vec<i32>, fonts: &mut Vec<PdfObject>) -> Result<(), mupdf::Error> {
+    let mut nested = Vec::new();
 
-                let mut line_text = String::new();
-                for char_info in line.chars() {
-                    if let Some(c) = char_info.char() {
-                        line_text.push(c);
-                    }
+    if let Some(font_dict) = resources.get_dict("Font")? {
+        for i in 0..font_dict.dict_len()? as i32 {
+            let Some(font) = font_dict.get_dict_val(i)? else {
+                continue;
+            };
+            let num = font.as_indirect().unwrap_or(0);
+            if num != 0 {
+                if seen.contains(&num) {
+                    continue;
                 }
+                seen.push(num);
+            }
+            if let Some(type3_resources) = font.get_dict("Resources")? {
+                nested.push(type3_resources);
+            }
+            fonts.push(font);
+        }
+    }
 
-                // Check if this line contains the query
-                if line_text.to_lowercase().contains(&query_lower) {
-                    // Return a trimmed context (UTF-8 safe)
-                    let trimmed = line_text.trim();
-                    if trimmed.chars().count() > 100 {
-                        let truncated: String = trimmed.chars().take(100).collect();
-                        return format!("{}...", truncated);
-                    }
-                    return trimmed.to_string();
+    for key in ["XObject", "Pattern"] {
+        let Some(dict) = resources.get_dict(key)? else {
+            continue;
+        };
+        for i in 0..dict.dict_len()? as i32 {
+            let Some(child) = dict.get_dict_val(i)? else {
+                continue;
+            };
+            let num = child.as_indirect().unwrap_or(0);
+            if num != 0 {
+                if seen.contains(&num) {
+                    continue;
                 }
+                seen.push(num);
+            }
+            if let Some(child_resources) = child.get_dict("Resources")? {
+                nested.push(child_resources);
             }
         }
     }
 
-    query.to_string()
+    for child_resources in nested {
+        collect_fonts(&child_resources, seen, fonts)?;
+    }
+    Ok(())
 }
 
-/// PDF outline (table of contents) entry
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct OutlineEntry {
-    /// Title of the outline entry
-    pub title: String,
-    /// Target page number (1-indexed), if available
-    pub page: Option<u32>,
-    /// Y position on the page (normalized 0-1)
-    pub y: Option<f32>,
-    /// Child entries (sub-sections)
-    pub children: Vec<OutlineEntry>,
-}
+/// Requested name, raw /BaseFont and whether MuPDF would serve it from its
+/// CJK fallback, or None if the font is embedded or Type3
+fn non_embedded_font(font: &PdfObject) -> Result<Option<(String, String, bool)>, mupdf::Error> {
+    let subtype = pdf_name(font.get_dict("Subtype")?).unwrap_or_default();
+    if subtype == "Type3" {
+        return Ok(None);
+    }
 
-/// Convert MuPDF Outline to our OutlineEntry, fetching page dimensions for normalization
-fn convert_outline(outline: &MuOutline, document: &Document) -> OutlineEntry {
-    let (page, normalized_y) = if let Some(p) = outline.page {
-        // Page is 0-indexed in the outline
-        let page_num = p + 1; // Convert to 1-indexed
+    let base_font = pdf_name(font.get_dict("BaseFont")?).unwrap_or_else(|| "Unnamed".to_string());
+    let requested = match base_font.split_once('+') {
+        Some((prefix, rest)) if prefix.len() == 6 => rest.to_string(),
+        _ => base_font.clone(),
+    };
 
-        // Normalize Y coordinate if we have a valid page
-        let norm_y = if outline.y > 0.0 {
-            if let Ok(pdf_page) = document.load_page(p as i32) {
-                if let Ok(bounds) = pdf_page.bounds() {
-                    Some(outline.y / bounds.height())
-                } else {
-                    None
+    let mut cjk = false;
+    let descriptor = if subtype == "Type0" {
+        let descendant = match font.get_dict("DescendantFonts")? {
+            Some(array) => array.get_array(0)?,
+            None => None,
+        };
+        match descendant {
+            Some(d) => {
+                if let Some(info) = d.get_dict("CIDSystemInfo")? {
+                    if let Some(ordering) = info.get_dict("Ordering")? {
+                        let ordering = ordering.as_string().unwrap_or_default();
+                        cjk = CJK_ORDERINGS.contains(&ordering);
+                    }
                 }
-            } else {
-                None
+                d.get_dict("FontDescriptor")?
             }
-        } else {
-            None
-        };
-
-        (Some(page_num), norm_y)
+            None => None,
+        }
     } else {
-        (None, None)
+        font.get_dict("FontDescriptor")?
     };
 
-    OutlineEntry {
-        title: outline.title.clone(),
-        page,
-        y: normalized_y,
-        children: outline.down.iter().map(|c| convert_outline(c, document)).collect(),
+    if let Some(desc) = &descriptor {
+        for key in ["FontFile", "FontFile2", "FontFile3"] {
+            if desc.get_dict(key)?.is_some() {
+                return Ok(None);
+            }
+        }
     }
+
+    Ok(Some((requested, base_font, cjk)))
 }
 
-/// Get PDF outline (table of contents)
+/// List non-embedded fonts and the font MuPDF actually draws each with,
+/// including fonts used only inside Form XObjects. These render here but
+/// may look different in other viewers. Fonts MuPDF found no stand-in for
+/// are listed with `substituted: false`.
 #[tauri::command]
-pub fn pdf_get_outlines(path: String) -> Result<Vec<OutlineEntry>, String> {
-    let document = Document::open(&path)
-        .map_err(|e| format!("Failed to load PDF: {:?}", e))?;
+pub async fn pdf_list_substituted_fonts(path: String) -> Result<Vec<SubstitutedFont>, CommandError> {
+    // Walks every page's resources and resolves system fonts: keep it off the main thread
+    crate::run_blocking(move || pdf_list_substituted_fonts_blocking(&path)).await
+}
 
-    let outlines = document
-        .outlines()
-        .map_err(|e| format!("Failed to get outlines: {:?}", e))?;
+fn pdf_list_substituted_fonts_blocking(path: &str) -> Result<Vec<SubstitutedFont>, CommandError> {
+    ensure_exists(path)?;
+    let document = PdfDocument::open(path).map_err(|e| CommandError::pdf("Failed to load PDF", e))?;
+    let page_count = document
+        .page_count()
+        .map_err(|e| format!("Failed to get page count: {:?}", e))?;
+
+    let mut found: Vec<SubstitutedFont> = Vec::new();
+    // Shared fonts are resolved once, by object number
+    let mut resolved: HashMap<i32, Option<String>> = HashMap::new();
+
+    for page_index in 0..page_count {
+        let mut fonts = Vec::new();
+        let walked = page_resources(&document, page_index)
+            .and_then(|resources| match resources {
+                Some(resources) => collect_fonts(&resources, &mut Vec::new(), &mut fonts),
+                None => Ok(()),
+            });
+        if let Err(e) = walked {
+            log::warn!("Failed to read fonts on page {}: {:?}", page_index + 1, e);
+        }
 
-    let entries: Vec<OutlineEntry> = outlines
-        .iter()
-        .map(|o| convert_outline(o, &document))
-        .collect();
+        for font in fonts {
+            let Ok(Some((requested, base_font, cjk))) = non_embedded_font(&font) else {
+                continue;
+            };
 
-    Ok(entries)
+            let num = font.as_indirect().unwrap_or(0);
+            let substitute = match resolved.get(&num) {
+                Some(substitute) if num != 0 => substitute.clone(),
+                _ => {
+                    let substitute = match resolve_loaded_font(&font) {
+                        Ok(loaded) => loaded.map(|l| substitute_font_name(&requested, &base_font, &l, cjk)),
+                        Err(e) => {
+                            log::warn!("Failed to resolve substitute for {}: {:?}", requested, e);
+                            None
+                        }
+                    };
+                    if num != 0 {
+                        resolved.insert(num, substitute.clone());
+                    }
+                    substitute
+                }
+            };
+
+            let substituted = substitute.is_some();
+            let substitute = substitute.unwrap_or_default();
+            let page = page_index as u32 + 1;
+            match found.iter_mut().find(|f| f.requested == requested && f.substitute == substitute) {
+                Some(entry) => {
+                    if !entry.pages.contains(&page) {
+                        entry.pages.push(page);
+                    }
+                }
+                None => found.push(SubstitutedFont {
+                    requested,
+                    substitute,
+                    substituted,
+                    pages: vec![page],
+                }),
+            }
+        }
+    }
+
+    Ok(found)
 }
 
 /// PDF document metadata
@@ -695,10 +3077,15 @@ pub struct PdfMetadata {
 
 /// Get PDF metadata
 #[tauri::command]
-pub fn pdf_get_metadata(path: String) -> Result<PdfMetadata, String> {
-    let document = Document::open(&path)
-        .map_err(|e| format!("Failed to load PDF: {:?}", e))?;
+pub fn pdf_get_metadata(
+    cache: State<'_, DocumentCache>,
+    path: String,
+    password: Option<String>,
+) -> Result<PdfMetadata, CommandError> {
+    cache.with_document(&path, password.as_deref(), |document| document_metadata(document, &path))
+}
 
+fn document_metadata(document: &Document, path: &str) -> Result<PdfMetadata, String> {
     let page_count = document
         .page_count()
         .map_err(|e| format!("Failed to get page count: {:?}", e))? as u32;
@@ -709,7 +3096,7 @@ pub fn pdf_get_metadata(path: String) -> Result<PdfMetadata, String> {
     };
 
     // Get file size
-    let file_size = std::fs::metadata(&path)
+    let file_size = std::fs::metadata(path)
         .map(|m| m.len())
         .unwrap_or(0);
 
@@ -728,3 +3115,662 @@ pub fn pdf_get_metadata(path: String) -> Result<PdfMetadata, String> {
         file_size,
     })
 }
+
+/// Editable /Info dictionary keys
+const INFO_KEYS: &[&str] = &["Title", "Author", "Subject", "Keywords", "Creator", "Producer"];
+
+/// The /Info key for a field name, matched case-insensitively
+fn info_key(field: &str) -> Result<&'static str, String> {
+    INFO_KEYS
+        .iter()
+        .find(|key| key.eq_ignore_ascii_case(field))
+        .copied()
+        .ok_or_else(|| format!("Unsupported metadata field '{}' (expected one of: {})", field, INFO_KEYS.join(", ")))
+}
+
+/// A PDF hex string holding `value` as UTF-16BE with a byte order mark, the
+/// text string encoding for values PDFDocEncoding can't represent
+fn utf16_hex_string(value: &str) -> String {
+    let hex: String = value
+        .encode_utf16()
+        .map(|unit| format!("{:04X}", unit))
+        .collect();
+    format!("<FEFF{}>", hex)
+}
+
+/// Set, delete or clear /Info entries and save to `output` (may equal `input`).
+/// A `None` field value deletes that entry; `clear_all` empties the dictionary
+/// before `fields` are applied. `cache` drops its copy of `output` before the
+/// file is replaced.
+fn write_metadata(
+    cache: &DocumentCache,
+    input: &str,
+    output: &str,
+    fields: &HashMap<String, Option<String>>,
+    clear_all: bool,
+) -> Result<(), CommandError> {
+    // Reject unknown keys before touching the file
+    let updates = fields
+        .iter()
+        .map(|(field, value)| Ok((info_key(field)?, value.as_deref())))
+        .collect::<Result<Vec<_>, String>>()
+        .map_err(|e| CommandError::new(codes::INVALID_ARGUMENT, e))?;
+
+    ensure_exists(input)?;
+    let mut doc = PdfDocument::open(input).map_err(|e| CommandError::pdf("Failed to open PDF", e))?;
+
+    let mut edit = || -> Result<(), mupdf::Error> {
+        let mut trailer = doc.trailer()?;
+        let existing = trailer.get_dict("Info")?;
+        let mut info = match existing {
+            Some(info) if !clear_all => info,
+            _ => {
+                let dict = doc.new_dict()?;
+                let info = doc.add_object(&dict)?;
+                trailer.dict_put("Info", info.try_clone()?)?;
+                info
+            }
+        };
+
+        for &(key, value) in &updates {
+            match value {
+                Some(text) if text.is_ascii() => info.dict_put(key, PdfObject::new_string(text)?)?,
+                Some(text) => info.dict_put(key, doc.new_object_from_str(&utf16_hex_string(text))?)?,
+                None => info.dict_delete(key)?,
+            }
+        }
+        Ok(())
+    };
+    edit().map_err(|e| CommandError::pdf("Failed to update metadata", e))?;
+
+    cache.evict(output);
+    save_atomically(doc, output)
+}
+
+/// Edit Title/Author/Subject/Keywords/Creator/Producer and save to `output`
+/// (defaults to `input`). Returns the metadata read back from the saved file.
+#[tauri::command]
+pub fn pdf_set_metadata(
+    cache: State<'_, DocumentCache>,
+    input: String,
+    output: Option<String>,
+    fields: HashMap<String, Option<String>>,
+    clear_all: Option<bool>,
+) -> Result<PdfMetadata, CommandError> {
+    let output = output.unwrap_or_else(|| input.clone());
+    write_metadata(&cache, &input, &output, &fields, clear_all.unwrap_or(false))?;
+
+    let document = Document::open(&output).map_err(|e| CommandError::pdf("Failed to reopen saved PDF", e))?;
+    Ok(document_metadata(&document, &output)?)
+}
+
+/// Channel difference above which a pixel counts as changed (rendering noise stays below)
+const DIFF_PIXEL_THRESHOLD: u8 = 32;
+
+/// Longer side, in pixels, of the renders used to decide whether a page changed
+const DIFF_SCAN_SIZE: u32 = 256;
+
+/// Document-level change counts
+#[derive(Debug, Serialize)]
+pub struct DiffSummary {
+    /// Pages that render differently, counting pages only one document has
+    pub pages_changed: u32,
+    /// Lines on the compared page present only in `b`
+    pub text_added: u32,
+    /// Lines on the compared page present only in `a`
+    pub text_removed: u32,
+}
+
+/// Visual and text differences between the same page of two documents
+#[derive(Debug, Serialize)]
+pub struct PdfDiffResult {
+    /// Page of `b`, faded, with changed pixels in red (PNG)
+    pub overlay: RenderedPage,
+    /// Fraction of pixels that changed on this page
+    pub changed_ratio: f32,
+    pub text_added: Vec<String>,
+    pub text_removed: Vec<String>,
+    pub summary: DiffSummary,
+}
+
+/// Lines only in `b` (added) and only in `a` (removed), keeping document order.
+/// Repeated lines are matched by count.
+fn diff_lines(a: &[String], b: &[String]) -> (Vec<String>, Vec<String>) {
+    let mut counts: HashMap<&str, i64> = HashMap::new();
+    for line in a {
+        *counts.entry(line.as_str()).or_default() += 1;
+    }
+
+    let mut added = Vec::new();
+    for line in b {
+        let count = counts.entry(line.as_str()).or_default();
+        if *count > 0 {
+            *count -= 1;
+        } else {
+            added.push(line.clone());
+        }
+    }
+
+    let mut removed = Vec::new();
+    for line in a {
+        if let Some(count) = counts.get_mut(line.as_str()) {
+            if *count > 0 {
+                *count -= 1;
+                removed.push(line.clone());
+            }
+        }
+    }
+
+    (added, removed)
+}
+
+/// Build an RGB overlay of two RGB renders: `right` faded toward white, with pixels
+/// that differ by more than the threshold painted red. Areas covered by only one
+/// render count as changed. Returns (width, height, samples, changed pixel count).
+fn diff_overlay(left: &Pixmap, right: &Pixmap) -> (usize, usize, Vec<u8>, usize) {
+    let geometry = |p: &Pixmap| {
+        let (w, h) = (p.width() as usize, p.height() as usize);
+        let stride = p.samples().len() / h.max(1);
+        (w, h, stride, stride / w.max(1))
+    };
+    let (lw, lh, ls, ln) = geometry(left);
+    let (rw, rh, rs, rn) = geometry(right);
+    let (width, height) = (lw.max(rw), lh.max(rh));
+    let (lsamples, rsamples) = (left.samples(), right.samples());
+
+    let mut out = Vec::with_capacity(width * height * 3);
+    let mut changed = 0;
+    for y in 0..height {
+        for x in 0..width {
+            let l = (x < lw && y < lh).then(|| &lsamples[y * ls + x * ln..][..3]);
+            let r = (x < rw && y < rh).then(|| &rsamples[y * rs + x * rn..][..3]);
+            let differs = match (l, r) {
+                (Some(l), Some(r)) => l.iter().zip(r).any(|(a, b)| a.abs_diff(*b) > DIFF_PIXEL_THRESHOLD),
+                _ => true,
+            };
+            if differs {
+                changed += 1;
+                out.extend_from_slice(&[255, 0, 0]);
+            } else {
+                let base = r.or(l).unwrap_or(&[255, 255, 255]);
+                out.extend(base.iter().map(|&c| 255 - (255 - c) / 4));
+            }
+        }
+    }
+
+    (width, height, out, changed)
+}
+
+fn page_lines(document: &Document, page: u32) -> Result<Vec<String>, String> {
+    Ok(page_text_blocks(document, page)?
+        .blocks
+        .into_iter()
+        .flat_map(|block| block.lines)
+        .map(|line| line.text.trim().to_string())
+        .filter(|text| !text.is_empty())
+        .collect())
+}
+
+/// Compare two versions of a document: render `page` (1-indexed) from both and
+/// highlight changed pixels, diff the page's text lines, and count how many
+/// pages differ across the documents
+#[tauri::command]
+pub async fn pdf_diff(a: String, b: String, page: u32, size: Option<u32>) -> Result<PdfDiffResult, CommandError> {
+    // Renders both documents and scans every page: keep it off the main thread
+    crate::run_blocking(move || pdf_diff_blocking(&a, &b, page, size)).await
+}
+
+fn pdf_diff_blocking(a: &str, b: &str, page: u32, size: Option<u32>) -> Result<PdfDiffResult, CommandError> {
+    let doc_a = open_document(a)?;
+    let doc_b = open_document(b)?;
+    let count_a = doc_a.page_count().map_err(|e| format!("Failed to count pages: {:?}", e))? as u32;
+    let count_b = doc_b.page_count().map_err(|e| format!("Failed to count pages: {:?}", e))? as u32;
+
+    if page < 1 || page > count_a.min(count_b) {
+        return Err(CommandError::new(
+            codes::INVALID_ARGUMENT,
+            format!("Page {} is not in both documents ({} and {} pages)", page, count_a, count_b),
+        ));
+    }
+
+    let full = NormalizedRect {
+        x: 0.0,
+        y: 0.0,
+        width: 1.0,
+        height: 1.0,
+    };
+    let size = size.unwrap_or(1200);
+    let left = render_page_region(&doc_a, page, &full, size)?;
+    let right = render_page_region(&doc_b, page, &full, size)?;
+    let (width, height, samples, changed) = diff_overlay(&left, &right);
+
+    let mut pixmap = Pixmap::new_with_w_h(&Colorspace::device_rgb(), width as i32, height as i32, false)
+        .map_err(|e| format!("Failed to allocate pixmap: {:?}", e))?;
+    let out = pixmap.samples_mut();
+    let out_stride = out.len() / height.max(1);
+    for (row, src) in samples.chunks(width * 3).enumerate() {
+        out[row * out_stride..row * out_stride + src.len()].copy_from_slice(src);
+    }
+    let png = encode_pixmap(&pixmap, RenderFormat::Png, DEFAULT_RENDER_QUALITY)?;
+
+    let (text_added, text_removed) = diff_lines(&page_lines(&doc_a, page)?, &page_lines(&doc_b, page)?);
+
+    let mut pages_changed = count_a.abs_diff(count_b);
+    for p in 1..=count_a.min(count_b) {
+        if page_similarity(&doc_a, &doc_b, p, DIFF_SCAN_SIZE)? < 1.0 - 1e-3 {
+            pages_changed += 1;
+        }
+    }
+
+    Ok(PdfDiffResult {
+        overlay: RenderedPage {
+            data: base64::engine::general_purpose::STANDARD.encode(&png),
+            width: width as u32,
+            height: height as u32,
+            page,
+            format: RenderFormat::Png.name().to_string(),
+        },
+        changed_ratio: changed as f32 / (width * height).max(1) as f32,
+        summary: DiffSummary {
+            pages_changed,
+            text_added: text_added.len() as u32,
+            text_removed: text_removed.len() as u32,
+        },
+        text_added,
+        text_removed,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_outline_round_trip() {
+        let input = std::env::temp_dir().join(format!("tlacuilo-outline-{}.pdf", std::process::id()));
+        let input = input.to_str().unwrap().to_string();
+        let mut doc = PdfDocument::new();
+        for _ in 0..3 {
+            doc.new_page(mupdf::Size::LETTER).unwrap();
+        }
+        doc.save(&input).unwrap();
+
+        let entry = |title: &str, page, children, open| OutlineEntry {
+            title: title.to_string(),
+            page: Some(page),
+            y: None,
+            children,
+            open,
+        };
+        let entries = vec![
+            entry("Chapter 1", 1, vec![entry("Section 1.1", 2, Vec::new(), None)], Some(false)),
+            entry("Capítulo 2", 3, Vec::new(), None),
+        ];
+        // Keep the input open in the cache, as the viewer would
+        let cache = DocumentCache::default();
+        cache.with_document(&input, None, |_| Ok(())).unwrap();
+        write_outline(&cache, &input, &input, &entries).unwrap();
+
+        let outline = pdf_get_outlines(input.clone()).unwrap();
+        assert_eq!(outline.len(), 2);
+        assert_eq!(outline[0].children[0].title, "Section 1.1");
+        assert_eq!(outline[0].children[0].page, Some(2));
+        assert_eq!(outline[0].open, Some(false));
+        assert_eq!(outline[1].title, "Capítulo 2");
+
+        let err = write_outline(&cache, &input, &input, &[entry("Missing", 9, Vec::new(), None)]).unwrap_err();
+        assert_eq!(err.code, codes::INVALID_ARGUMENT);
+
+        write_outline(&cache, &input, &input, &[]).unwrap();
+        assert!(pdf_get_outlines(input.clone()).unwrap().is_empty());
+
+        let _ = std::fs::remove_file(input);
+    }
+
+    #[test]
+    fn test_text_stats_likely_scanned() {
+        let page = |page, char_count, image_coverage| PageTextStats {
+            page,
+            word_count: char_count / 5,
+            char_count,
+            image_coverage,
+        };
+
+        let stats = text_stats(vec![page(1, 0, 0.98), page(2, 12, 0.9), page(3, 2400, 0.1)]);
+        assert_eq!(stats.page_count, 3);
+        assert_eq!(stats.char_count, 2412);
+        assert_eq!(stats.scanned_pages, 2);
+        assert!(stats.likely_scanned);
+
+        // An OCR'd scan keeps its page images but has a text layer
+        let stats = text_stats(vec![page(1, 1800, 1.0), page(2, 1500, 1.0)]);
+        assert!(!stats.likely_scanned);
+        assert!(!text_stats(Vec::new()).likely_scanned);
+    }
+
+    #[test]
+    fn test_link_target_y() {
+        assert_eq!(link_target_y("#page=3&zoom=100,72,540"), Some(540.0));
+        assert_eq!(link_target_y("#page=3&zoom=nan,nan,120"), Some(120.0));
+        assert_eq!(link_target_y("#page=3&view=FitH,200"), Some(200.0));
+        assert_eq!(link_target_y("#page=3&viewrect=72,300,200,100"), Some(300.0));
+        assert_eq!(link_target_y("#page=3&view=FitV,72"), None);
+        assert_eq!(link_target_y("#page=3"), None);
+        assert_eq!(link_target_y("#page=3&zoom=100,72,nan"), None);
+    }
+
+    #[test]
+    fn test_color_hex() {
+        assert_eq!(color_hex(&[1.0, 0.0, 0.0]).as_deref(), Some("#ff0000"));
+        assert_eq!(color_hex(&[0.5]).as_deref(), Some("#808080"));
+        assert_eq!(color_hex(&[0.0, 0.0, 0.0, 1.0]).as_deref(), Some("#000000"));
+        assert_eq!(color_hex(&[]), None);
+    }
+
+    #[test]
+    fn test_parse_stext_xml() {
+        let xml = r##"<page id="page1" width="612" height="792">
+<image bbox="0 0 50 50" />
+<block bbox="72 60 200 80">
+<line bbox="72 60 200 80" wmode="0" dir="1 0" text="Hi&amp;">
+<font name="Helvetica-Bold" size="12">
+<char quad="72 60 80 60 72 80 80 80" x="72" y="76" bidi="0" color="#ff0000" alpha="#ff" flags="24" c="H"/>
+</font>
+<font name="Times-Italic" size="10.5">
+<char quad="80 60 85 60 80 80 85 80" x="80" y="76" bidi="0" color="#000000" alpha="#ff" flags="16" c="i"/>
+<char quad="85 60 90 60 85 80 90 80" x="85" y="76" bidi="0" color="#000000" alpha="#ff" flags="16" c="&amp;"/>
+<char quad="90 60 95 60 90 80 95 80" x="90" y="76" bidi="0" color="#000000" alpha="#ff" flags="16" c="&#x2014;"/>
+</font>
+</line>
+</block>
+</page>"##;
+        let blocks = parse_stext_xml(xml, &["Times-Italic".to_string()]);
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].bbox, [72.0, 60.0, 200.0, 80.0]);
+        let chars = &blocks[0].lines[0].chars;
+        let text: String = chars.iter().map(|c| c.c).collect();
+        assert_eq!(text, "Hi&\u{2014}");
+        assert_eq!(chars[0].quad, [72.0, 60.0, 80.0, 60.0, 72.0, 80.0, 80.0, 80.0]);
+
+        let styles: Vec<CharStyle> = chars.iter().map(|c| c.style.clone()).collect();
+        assert_eq!(styles[0].font.as_deref(), Some("Helvetica-Bold"));
+        assert_eq!(styles[0].color.as_deref(), Some("#ff0000"));
+        // Bold and italic come from the flags, not the font name
+        assert!(styles[0].bold && !styles[0].italic);
+        assert_eq!(styles[1].size, 10.5);
+        assert!(styles[1].italic && !styles[1].bold);
+
+        let line = [styles[1].clone(), styles[0].clone(), styles[1].clone()];
+        assert_eq!(dominant_style(line.iter()), styles[1]);
+    }
+
+
+    #[test]
+    fn test_normalize_rotation() {
+        assert_eq!(normalize_rotation(0), 0);
+        assert_eq!(normalize_rotation(-90), 270);
+        assert_eq!(normalize_rotation(450), 90);
+        assert_eq!(normalize_rotation(100), 90);
+        assert_eq!(normalize_rotation(350), 0);
+    }
+
+    #[test]
+    fn test_preview_rotation() {
+        assert_eq!(preview_rotation(90).unwrap(), 90);
+        assert_eq!(preview_rotation(-90).unwrap(), 270);
+        assert_eq!(preview_rotation(450).unwrap(), 90);
+        assert_eq!(preview_rotation(360).unwrap(), 0);
+        assert!(preview_rotation(45).is_err());
+    }
+
+    #[test]
+    fn test_layout_text() {
+        let row = |text: &str, x0: f32, baseline: f32| {
+            text.chars()
+                .enumerate()
+                .map(|(i, c)| LayoutChar { c, x: x0 + i as f32 * 6.0, baseline, width: 6.0, size: 10.0 })
+                .collect::<Vec<_>>()
+        };
+
+        // Two columns on the same rows, then a paragraph gap
+        let mut chars = row("Name", 0.0, 10.0);
+        chars.extend(row("Qty", 60.0, 10.5));
+        chars.extend(row("Pen", 0.0, 22.0));
+        chars.extend(row("2", 60.0, 22.0));
+        chars.extend(row("Total", 0.0, 58.0));
+
+        assert_eq!(layout_text(chars), "Name      Qty\nPen       2\n\n\nTotal");
+        assert_eq!(layout_text(Vec::new()), "");
+    }
+
+    #[test]
+    fn test_same_match_rect() {
+        let a = [10.0, 20.0, 40.0, 30.0];
+        assert!(same_match_rect(a, [10.4, 20.2, 40.3, 30.0]));
+        // "the the": second match on the same line is distinct
+        assert!(!same_match_rect(a, [45.0, 20.0, 75.0, 30.0]));
+    }
+
+    #[test]
+    fn test_hit_matches() {
+        let plain = SearchOptions::default();
+        let cased = SearchOptions { case_sensitive: true, ..Default::default() };
+        let word = SearchOptions { whole_word: true, ..Default::default() };
+
+        assert!(hit_matches("the", "The", None, Some(' '), plain));
+        assert!(!hit_matches("the", "The", None, Some(' '), cased));
+        assert!(hit_matches("the", "the", Some(' '), Some(' '), word));
+        assert!(!hit_matches("the", "the", Some(' '), Some('m'), word));
+        assert!(!hit_matches("the", "the", Some('o'), None, word));
+        // Second half of a match split across lines: only the end is checked
+        assert!(hit_matches("annual report", "report", Some('x'), Some('.'), word));
+    }
+
+    #[test]
+    fn test_pattern_matches() {
+        // Each char is 10pt wide on a 12pt line
+        let mut chars = Vec::new();
+        for (line, text) in ["Invoice #123 due", "see invoice #7"].iter().enumerate() {
+            let y = line as f32 * 12.0;
+            for (i, c) in text.chars().enumerate() {
+                let x = i as f32 * 10.0;
+                chars.push((c, Some([x, y, x + 10.0, y + 12.0])));
+            }
+            chars.push(('\n', None));
+        }
+
+        let regex = |query: &str, mode| {
+            search_pattern(query, SearchOptions { mode, ..Default::default() }).unwrap().unwrap()
+        };
+
+        let found = pattern_matches(&chars, &regex(r"invoice #\d+", SearchMode::Regex), 10);
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].0, [0.0, 0.0, 120.0, 12.0]);
+        assert_eq!(found[0].1, "Invoice #123 due");
+        assert_eq!(found[1].0, [40.0, 12.0, 140.0, 24.0]);
+
+        // A match wrapping onto the next line gives one rect per line
+        let found = pattern_matches(&chars, &regex(r"due\s+see", SearchMode::Regex), 10);
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0], ([130.0, 0.0, 160.0, 12.0], "Invoice #123 due".to_string()));
+        assert_eq!(found[1], ([0.0, 12.0, 30.0, 24.0], "see invoice #7".to_string()));
+        assert_eq!(pattern_matches(&chars, &regex(r"due\s+see", SearchMode::Regex), 1).len(), 1);
+
+        let found = pattern_matches(&chars, &regex("due, see", SearchMode::AnyOf), 10);
+        assert_eq!(found.len(), 2);
+        assert_eq!(pattern_matches(&chars, &regex("due, see", SearchMode::AnyOf), 1).len(), 1);
+
+        assert!(search_pattern("(", SearchOptions { mode: SearchMode::Regex, ..Default::default() }).is_err());
+        assert!(search_pattern("x", SearchOptions::default()).unwrap().is_none());
+        assert!(SearchMode::parse(Some("fuzzy")).is_err());
+    }
+
+    #[test]
+    fn test_group_results_by_page() {
+        let result = |index, page| SearchResult {
+            index,
+            page,
+            y: 0.0,
+            rect: NormalizedRect { x: 0.0, y: 0.0, width: 0.0, height: 0.0 },
+            context: String::new(),
+        };
+        let groups = group_results_by_page(vec![result(0, 1), result(1, 1), result(2, 3)]);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].page, 1);
+        assert_eq!(groups[0].matches.iter().map(|m| m.index).collect::<Vec<_>>(), vec![0, 1]);
+        assert_eq!(groups[1].page, 3);
+    }
+
+    #[test]
+    fn test_diff_lines() {
+        let lines = |v: &[&str]| v.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let a = lines(&["Title", "Total: 10", "Footer", "Footer"]);
+        let b = lines(&["Title", "Total: 12", "Footer", "Signed"]);
+
+        let (added, removed) = diff_lines(&a, &b);
+        assert_eq!(added, lines(&["Total: 12", "Signed"]));
+        assert_eq!(removed, lines(&["Total: 10", "Footer"]));
+    }
+
+    #[test]
+    fn test_render_format_parse() {
+        assert_eq!(RenderFormat::parse(None).unwrap(), RenderFormat::Png);
+        assert_eq!(RenderFormat::parse(Some("JPG")).unwrap(), RenderFormat::Jpeg);
+        assert_eq!(RenderFormat::parse(Some("webp")).unwrap().name(), "webp");
+        assert!(RenderFormat::parse(Some("gif")).is_err());
+    }
+
+    #[test]
+    fn test_stamped_lru() {
+        let mut cache = StampedLru::new(2);
+        let t0 = Some(SystemTime::UNIX_EPOCH);
+        let t1 = t0.map(|t| t + std::time::Duration::from_secs(1));
+
+        assert_eq!(*cache.get_or_open("a", t0, || Ok("a0".to_string())).unwrap(), "a0");
+        // Unchanged stamp: served from cache
+        assert_eq!(*cache.get_or_open("a", t0, || Err("reopened".into())).unwrap(), "a0");
+        // Changed mtime: reopened
+        assert_eq!(*cache.get_or_open("a", t1, || Ok("a1".to_string())).unwrap(), "a1");
+
+        // Capacity 2: "a" is least recently used once "b" and "c" are opened
+        cache.get_or_open("b", t0, || Ok("b".to_string())).unwrap();
+        cache.get_or_open("c", t0, || Ok("c".to_string())).unwrap();
+        assert_eq!(*cache.get_or_open("a", t1, || Ok("a2".to_string())).unwrap(), "a2");
+
+        cache.remove("a");
+        assert!(cache.entries.iter().all(|(k, _, _)| k != "a"));
+    }
+
+    #[test]
+    fn test_document_cache_is_managed_state() {
+        // tauri::Manager::manage needs Send + Sync + 'static
+        fn assert_state<T: Send + Sync + 'static>() {}
+        assert_state::<DocumentCache>();
+    }
+
+    #[test]
+    fn test_named_page_size() {
+        assert_eq!(named_page_size(595.0, 842.0), Some("A4"));
+        assert_eq!(named_page_size(842.0, 595.0), Some("A4"));
+        assert_eq!(named_page_size(612.0, 792.0), Some("Letter"));
+        assert_eq!(named_page_size(500.0, 500.0), None);
+    }
+
+    fn loaded(name: &str, bold: bool, italic: bool, serif: bool, monospaced: bool) -> LoadedFont {
+        LoadedFont { name: name.to_string(), bold, italic, serif, monospaced }
+    }
+
+    #[test]
+    fn test_substitute_font_name() {
+        // Built-in fallbacks carry the requested name; the face follows MuPDF's flags
+        let garamond = loaded("Garamond-Italic", false, true, true, false);
+        assert_eq!(substitute_font_name("Garamond-Italic", "Garamond-Italic", &garamond, false), "NimbusRoman-Italic");
+        let consolas = loaded("Consolas", false, false, false, true);
+        assert_eq!(substitute_font_name("Consolas", "ABCDEF+Consolas", &consolas, false), "NimbusMonoPS-Regular");
+        let unknown = loaded("Frutiger", true, false, false, false);
+        assert_eq!(substitute_font_name("Frutiger", "Frutiger", &unknown, false), "NimbusSans-Bold");
+
+        // Base-14 names and their aliases are served by name
+        let arial = loaded("Helvetica-Bold", true, false, true, false);
+        assert_eq!(substitute_font_name("Arial,Bold", "Arial,Bold", &arial, false), "NimbusSans-Bold");
+        let symbol = loaded("Symbol", false, false, true, false);
+        assert_eq!(substitute_font_name("Symbol", "Symbol", &symbol, false), "StandardSymbolsPS");
+
+        let mincho = loaded("MS-Mincho", false, false, true, false);
+        assert_eq!(substitute_font_name("MS-Mincho", "MS-Mincho", &mincho, true), "DroidSansFallback");
+
+        // A system font is reported by its own family name
+        let system = loaded("Liberation Sans", false, false, false, false);
+        assert_eq!(substitute_font_name("Arial", "Arial", &system, false), "Liberation Sans");
+        let system_cjk = loaded("Noto Sans CJK JP", false, false, false, false);
+        assert_eq!(substitute_font_name("MS-Gothic", "MS-Gothic", &system_cjk, true), "Noto Sans CJK JP");
+    }
+
+    #[test]
+    fn test_base14_family() {
+        assert_eq!(base14_family("Courier-BoldOblique"), Some("NimbusMonoPS"));
+        assert_eq!(base14_family("ArialMT"), Some("NimbusSans"));
+        assert_eq!(base14_family("TimesNewRomanPS-BoldMT"), Some("NimbusRoman"));
+        assert_eq!(base14_family("ZapfDingbats"), Some("Dingbats"));
+        assert_eq!(base14_family("SymbolMT"), None);
+        assert_eq!(base14_family("Garamond"), None);
+    }
+
+    #[test]
+    fn test_validate_coordinates() {
+        let bounds = PageBounds { page_index: 1, width: 612.0, height: 792.0 };
+
+        assert_eq!(validate_point(&bounds, 100.0, 200.0, false), Ok((100.0, 200.0)));
+        assert_eq!(validate_point(&bounds, 0.5, 0.5, true), Ok((306.0, 396.0)));
+
+        let err = validate_point(&bounds, -20.0, 10.0, false).unwrap_err();
+        assert_eq!(err, "coordinates outside page 2 (0..612, 0..792)");
+        assert!(validate_point(&bounds, 1.5, 0.5, true).is_err());
+
+        assert_eq!(
+            validate_rect(&bounds, 0.0, 0.0, 0.5, 0.25, true),
+            Ok((0.0, 0.0, 306.0, 198.0))
+        );
+        assert!(validate_rect(&bounds, 100.0, 100.0, 50.0, 200.0, false).is_err());
+    }
+
+    #[test]
+    fn test_outline_sections() {
+        let entry = |title: &str, page: Option<u32>| OutlineEntry {
+            title: title.to_string(),
+            page,
+            y: None,
+            children: vec![],
+            open: None,
+        };
+        let outlines = vec![
+            entry("Two", Some(4)),
+            entry("One", Some(1)),
+            entry("Dangling", None),
+            entry("Three", Some(4)),
+        ];
+
+        let sections = outline_sections(&outlines, 10);
+        assert_eq!(
+            sections,
+            vec![
+                ("One".to_string(), 1, 3),
+                ("Two".to_string(), 4, 4),
+                ("Three".to_string(), 4, 10),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_info_key() {
+        assert_eq!(info_key("author"), Ok("Author"));
+        assert_eq!(info_key("Title"), Ok("Title"));
+        assert!(info_key("ModDate").is_err());
+    }
+
+    #[test]
+    fn test_utf16_hex_string() {
+        assert_eq!(utf16_hex_string("Añ"), "<FEFF004100F1>");
+        assert_eq!(utf16_hex_string(""), "<FEFF>");
+    }
+}
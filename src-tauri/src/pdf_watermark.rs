@@ -0,0 +1,392 @@
+//! Native text watermarking using MuPDF.
+//!
+//! Writes a diagonal (or tiled) Helvetica text stamp straight into each
+//! page's content stream, avoiding the Python round-trip for the common case.
+//! Text that can't be encoded in WinAnsi (the standard fonts' encoding) is
+//! left to `pdf_watermark.py`, as are image watermarks.
+
+use crate::pdf_viewer::save_atomically;
+use crate::{WatermarkResult, WatermarkTextOptions};
+use mupdf::pdf::{PdfDocument, PdfObject};
+use mupdf::{Font, Matrix};
+
+/// Resource names for the stamp's font and transparency state
+const FONT_RESOURCE: &str = "TlacuiloWmFont";
+const GSTATE_RESOURCE: &str = "TlacuiloWmGS";
+/// Distance from the page edges for corner positions, as in pdf_watermark.py
+const CORNER_MARGIN: f32 = 50.0;
+
+/// Whether `text` can be stamped natively (every character exists in WinAnsi)
+pub fn supports_native(text: &str) -> bool {
+    !text.is_empty() && winansi_bytes(text).is_some()
+}
+
+/// WinAnsi bytes for printable ASCII and Latin-1 text
+fn winansi_bytes(text: &str) -> Option<Vec<u8>> {
+    text.chars()
+        .map(|c| match c as u32 {
+            code @ (0x20..=0x7E | 0xA0..=0xFF) => Some(code as u8),
+            _ => None,
+        })
+        .collect()
+}
+
+/// 0-based page indices for a selection like "all", "1,3,5" or "2-4".
+/// Out-of-range pages are skipped, matching `_parse_pages` in pdf_watermark.py.
+fn selected_pages(pages: &str, total: i32) -> Result<Vec<i32>, String> {
+    if pages.trim() == "all" {
+        return Ok((0..total).collect());
+    }
+
+    let number = |s: &str| -> Result<i32, String> {
+        s.trim()
+            .parse()
+            .map_err(|_| format!("Invalid page selection '{}'", pages))
+    };
+
+    let mut indices = Vec::new();
+    for part in pages.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let (start, end) = match part.split_once('-') {
+            Some((start, end)) => (number(start)?, number(end)?),
+            None => {
+                let page = number(part)?;
+                (page, page)
+            }
+        };
+        indices.extend((start.max(1)..=end.min(total)).map(|p| p - 1));
+    }
+    indices.sort_unstable();
+    indices.dedup();
+    Ok(indices)
+}
+
+/// Baseline start points (top-left page space) for the stamp copies
+fn stamp_positions(
+    width: f32,
+    height: f32,
+    text_width: f32,
+    font_size: f32,
+    options: &WatermarkTextOptions,
+) -> Vec<(f32, f32)> {
+    if options.tile || options.position == "tile" {
+        let gap = options.tile_spacing.unwrap_or((font_size * 2.0).max(60.0));
+        let (step_x, step_y) = (text_width + gap, font_size + gap);
+        let mut positions = Vec::new();
+        let mut y = -step_y / 2.0;
+        while y < height + step_y {
+            let mut x = -step_x / 2.0;
+            while x < width + step_x {
+                positions.push((x - text_width / 2.0, y + font_size / 3.0));
+                x += step_x;
+            }
+            y += step_y;
+        }
+        return positions;
+    }
+
+    let position = match options.position.as_str() {
+        "center" => (width / 2.0 - text_width / 2.0, height / 2.0),
+        "top-left" => (CORNER_MARGIN, CORNER_MARGIN + font_size),
+        "top-right" => (width - text_width - CORNER_MARGIN, CORNER_MARGIN + font_size),
+        "bottom-left" => (CORNER_MARGIN, height - CORNER_MARGIN),
+        "bottom-right" => (width - text_width - CORNER_MARGIN, height - CORNER_MARGIN),
+        _ => return Vec::new(),
+    };
+    vec![position]
+}
+
+/// Inverse of an affine matrix (page space back to PDF user space)
+pub(crate) fn invert(m: &Matrix) -> Option<Matrix> {
+    let det = m.a * m.d - m.b * m.c;
+    if det.abs() < f32::EPSILON {
+        return None;
+    }
+    let (a, b, c, d) = (m.d / det, -m.b / det, -m.c / det, m.a / det);
+    Some(Matrix::new(a, b, c, d, -m.e * a - m.f * c, -m.e * b - m.f * d))
+}
+
+/// Text matrix placing a copy at `origin` (top-left page space) rotated about
+/// its own center. Like pdf_watermark.py, a negative rotation rises to the right.
+fn text_matrix(origin: (f32, f32), text_width: f32, font_size: f32, rotation: f32, to_pdf: &Matrix) -> Matrix {
+    let (sin, cos) = (-rotation).to_radians().sin_cos();
+    // Rotate counterclockwise on screen (y grows downward)
+    let rotate = |dx: f32, dy: f32| (dx * cos + dy * sin, -dx * sin + dy * cos);
+
+    let pivot = (origin.0 + text_width / 2.0, origin.1 - font_size / 3.0);
+    let offset = rotate(origin.0 - pivot.0, origin.1 - pivot.1);
+    let start = (pivot.0 + offset.0, pivot.1 + offset.1);
+    let (ux, uy) = rotate(1.0, 0.0);
+    let (vx, vy) = rotate(0.0, -1.0);
+
+    let vector = |x: f32, y: f32| (x * to_pdf.a + y * to_pdf.c, x * to_pdf.b + y * to_pdf.d);
+    let (ux, uy) = vector(ux, uy);
+    let (vx, vy) = vector(vx, vy);
+    let e = start.0 * to_pdf.a + start.1 * to_pdf.c + to_pdf.e;
+    let f = start.0 * to_pdf.b + start.1 * to_pdf.d + to_pdf.f;
+    Matrix::new(ux, uy, vx, vy, e, f)
+}
+
+/// Content stream drawing every copy of the stamp
+fn stamp_content(encoded: &[u8], font_size: f32, color: [f32; 3], matrices: &[Matrix]) -> String {
+    let hex: String = encoded.iter().map(|b| format!("{:02X}", b)).collect();
+    let mut content = format!(
+        "q\n/{} gs\n{:.3} {:.3} {:.3} rg\nBT\n/{} {:.2} Tf\n",
+        GSTATE_RESOURCE, color[0], color[1], color[2], FONT_RESOURCE, font_size
+    );
+    for m in matrices {
+        content.push_str(&format!(
+            "{:.5} {:.5} {:.5} {:.5} {:.3} {:.3} Tm <{}> Tj\n",
+            m.a, m.b, m.c, m.d, m.e, m.f, hex
+        ));
+    }
+    content.push_str("ET\nQ\n");
+    content
+}
+
+/// Subdictionary `key` of `parent`, created if missing
+fn sub_dict(doc: &PdfDocument, parent: &mut PdfObject, key: &str) -> Result<PdfObject, mupdf::Error> {
+    if let Some(dict) = parent.get_dict(key)? {
+        return Ok(dict);
+    }
+    let dict = doc.new_dict()?;
+    parent.dict_put(key, dict.try_clone()?)?;
+    Ok(dict)
+}
+
+/// New content stream object holding `content`
+fn content_stream(doc: &mut PdfDocument, content: &str) -> Result<PdfObject, mupdf::Error> {
+    let dict = doc.new_dict()?;
+    let mut stream = doc.add_object(&dict)?;
+    stream.write_stream_string(content)?;
+    Ok(stream)
+}
+
+/// Add the stamp to one page, under or over its existing content
+fn stamp_page(
+    doc: &mut PdfDocument,
+    page_index: i32,
+    content: &str,
+    font: &PdfObject,
+    gstate: &PdfObject,
+    over: bool,
+) -> Result<(), mupdf::Error> {
+    let mut page = doc.find_page(page_index)?;
+
+    // Inherited resources are attached to the page so the names resolve here
+    let mut resources = match page.get_dict_inheritable("Resources")? {
+        Some(resources) => resources,
+        None => doc.new_dict()?,
+    };
+    page.dict_put("Resources", resources.try_clone()?)?;
+    sub_dict(doc, &mut resources, "Font")?.dict_put(FONT_RESOURCE, font.try_clone()?)?;
+    sub_dict(doc, &mut resources, "ExtGState")?.dict_put(GSTATE_RESOURCE, gstate.try_clone()?)?;
+
+    let mut contents = doc.new_array()?;
+    let existing = page.get_dict("Contents")?;
+    if over {
+        // Isolate the page's graphics state so it can't distort the stamp
+        contents.array_push(content_stream(doc, "q\n")?)?;
+    } else {
+        contents.array_push(content_stream(doc, content)?)?;
+    }
+    match existing {
+        Some(existing) if existing.is_array()? => {
+            for i in 0..existing.len()? as i32 {
+                if let Some(part) = existing.get_array(i)? {
+                    contents.array_push(part)?;
+                }
+            }
+        }
+        Some(existing) => contents.array_push(existing)?,
+        None => {}
+    }
+    if over {
+        contents.array_push(content_stream(doc, &format!("Q\n{}", content))?)?;
+    }
+    page.dict_put("Contents", contents)?;
+    Ok(())
+}
+
+/// Stamp `text` on the selected pages of `input` and save to `output`.
+/// Callers should check `supports_native` first; unsupported text is an error.
+pub fn watermark_text(
+    input: &str,
+    output: &str,
+    text: &str,
+    options: &WatermarkTextOptions,
+) -> Result<WatermarkResult, String> {
+    let encoded = winansi_bytes(text)
+        .filter(|bytes| !bytes.is_empty())
+        .ok_or_else(|| "Text can't be encoded with the standard fonts".to_string())?;
+    let color = match options.font_color.as_slice() {
+        &[r, g, b] => [r, g, b],
+        _ => return Err("font_color needs three components".to_string()),
+    };
+    let opacity = options.opacity.clamp(0.0, 1.0);
+
+    let helvetica = Font::new("Helvetica").map_err(|e| format!("Failed to load font: {:?}", e))?;
+    let mut text_width = 0.0;
+    for c in text.chars() {
+        let glyph = helvetica
+            .encode_character(c as i32)
+            .and_then(|g| helvetica.advance_glyph(g))
+            .map_err(|e| format!("Failed to measure text: {:?}", e))?;
+        text_width += glyph * options.font_size;
+    }
+
+    let mut doc = PdfDocument::open(input).map_err(|e| format!("Failed to open PDF: {:?}", e))?;
+    let page_count = doc
+        .page_count()
+        .map_err(|e| format!("Failed to count pages: {:?}", e))?;
+    let pages = selected_pages(&options.pages, page_count)?;
+
+    let mut stamp = || -> Result<(), mupdf::Error> {
+        let font = doc.new_object_from_str(
+            "<</Type/Font/Subtype/Type1/BaseFont/Helvetica/Encoding/WinAnsiEncoding>>",
+        )?;
+        let font = doc.add_object(&font)?;
+        let gstate = doc.new_object_from_str(&format!("<</CA {0:.3} /ca {0:.3}>>", opacity))?;
+        let gstate = doc.add_object(&gstate)?;
+
+        for &index in &pages {
+            let page = doc.load_page(index)?;
+            let bounds = page.bounds()?;
+            let to_pdf = invert(&doc.find_page(index)?.page_ctm()?).unwrap_or(Matrix::IDENTITY);
+
+            let matrices: Vec<Matrix> = stamp_positions(
+                bounds.x1 - bounds.x0,
+                bounds.y1 - bounds.y0,
+                text_width,
+                options.font_size,
+                options,
+            )
+            .into_iter()
+            .map(|origin| text_matrix(origin, text_width, options.font_size, options.rotation, &to_pdf))
+            .collect();
+            let content = stamp_content(&encoded, options.font_size, color, &matrices);
+            stamp_page(&mut doc, index, &content, &font, &gstate, options.layer == "over")?;
+        }
+        Ok(())
+    };
+    stamp().map_err(|e| format!("Failed to add watermark: {:?}", e))?;
+
+    save_atomically(doc, output)?;
+
+    Ok(WatermarkResult {
+        success: true,
+        message: format!("Watermark added to {} pages", pages.len()),
+        pages_processed: pages.len() as u32,
+        input: None,
+        output: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use std::process::Command;
+    use std::time::Instant;
+
+    fn options() -> WatermarkTextOptions {
+        serde_json::from_str("{}").unwrap()
+    }
+
+    fn sample_pdf(name: &str, pages: usize) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("tlacuilo-wm-{}-{}.pdf", name, std::process::id()));
+        let mut doc = PdfDocument::new();
+        for _ in 0..pages {
+            doc.new_page(mupdf::Size::LETTER).unwrap();
+        }
+        doc.save(path.to_str().unwrap()).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_supports_native() {
+        assert!(supports_native("CONFIDENTIAL"));
+        assert!(supports_native("Brouillon ébauche"));
+        assert!(!supports_native("机密"));
+        assert!(!supports_native(""));
+    }
+
+    #[test]
+    fn test_selected_pages() {
+        assert_eq!(selected_pages("all", 3).unwrap(), vec![0, 1, 2]);
+        assert_eq!(selected_pages("1,3-4,9", 5).unwrap(), vec![0, 2, 3]);
+        assert_eq!(selected_pages("4-10", 5).unwrap(), vec![3, 4]);
+        assert!(selected_pages("x", 5).is_err());
+    }
+
+    #[test]
+    fn test_text_matrix_rotates_about_center() {
+        let flip = Matrix::new(1.0, 0.0, 0.0, -1.0, 0.0, 792.0);
+        let m = text_matrix((100.0, 400.0), 200.0, 48.0, -45.0, &flip);
+        // Text rises to the right in PDF space
+        assert!(m.a > 0.0 && m.b > 0.0);
+        // Unrotated copies keep their baseline start
+        let m = text_matrix((100.0, 400.0), 200.0, 48.0, 0.0, &flip);
+        assert!((m.e - 100.0).abs() < 1e-3 && (m.f - 392.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_watermark_text_native() {
+        let input = sample_pdf("native", 2);
+        let output = input.with_extension("out.pdf");
+        let mut opts = options();
+        opts.pages = "2".to_string();
+
+        let result = watermark_text(input.to_str().unwrap(), output.to_str().unwrap(), "DRAFT", &opts).unwrap();
+        assert_eq!(result.pages_processed, 1);
+
+        let doc = mupdf::Document::open(output.to_str().unwrap()).unwrap();
+        let text = |index| {
+            doc.load_page(index)
+                .and_then(|page| page.to_text())
+                .unwrap_or_default()
+        };
+        assert!(!text(0).contains("DRAFT"));
+        assert!(text(1).contains("DRAFT"));
+
+        let _ = std::fs::remove_file(input);
+        let _ = std::fs::remove_file(output);
+    }
+
+    /// The native path must beat pdf_watermark.py and stamp the same pages;
+    /// run with `cargo test -- --ignored`
+    #[test]
+    #[ignore = "needs python3 with PyMuPDF"]
+    fn bench_native_vs_python() {
+        let input = sample_pdf("bench", 50);
+        let native_out = input.with_extension("native.pdf");
+        let python_out = input.with_extension("python.pdf");
+        let opts = options();
+
+        let started = Instant::now();
+        watermark_text(input.to_str().unwrap(), native_out.to_str().unwrap(), "CONFIDENTIAL", &opts).unwrap();
+        let native = started.elapsed();
+
+        let script = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../backend/pdf_watermark.py");
+        let started = Instant::now();
+        let status = Command::new("python3")
+            .arg(script)
+            .args(["text", input.to_str().unwrap(), python_out.to_str().unwrap(), "CONFIDENTIAL"])
+            .arg(serde_json::to_string(&opts).unwrap())
+            .status()
+            .unwrap();
+        let python = started.elapsed();
+        assert!(status.success());
+
+        assert!(native < python, "native {:?} is not faster than python {:?}", native, python);
+        for output in [&native_out, &python_out] {
+            let doc = mupdf::Document::open(output.to_str().unwrap()).unwrap();
+            assert_eq!(doc.page_count().unwrap(), 50);
+            let last = doc.load_page(49).and_then(|page| page.to_text()).unwrap_or_default();
+            assert!(last.contains("CONFIDENTIAL"), "{} has no watermark", output.display());
+        }
+
+        for path in [input, native_out, python_out] {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
@@ -2,14 +2,44 @@
 //!
 //! Provides a centralized, reusable interface for executing Python scripts
 //! from Tauri. Handles virtual environment discovery, dependency checking,
-//! and structured error handling.
+//! and structured error handling. A user-chosen interpreter can be persisted
+//! in the settings store and takes precedence over auto-detection. Quick
+//! interactive requests can go through a long-lived worker process (`call`)
+//! that keeps imports warm.
 
 use std::collections::HashMap;
-use std::path::PathBuf;
-use std::process::{Command, Output, Stdio};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, Command, Output, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{Arc, Condvar, Mutex, TryLockError};
+use std::thread;
+use std::time::{Duration, Instant};
 
 use serde::{Deserialize, Serialize};
-use tauri::{AppHandle, Manager};
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_store::StoreExt;
+
+/// Event emitted for each progress line a streamed script prints
+pub const PROGRESS_EVENT: &str = "python-progress";
+
+/// Stdout prefix marking a progress line: `PROGRESS {"phase": ..., "current": ..., "total": ...}`
+const PROGRESS_PREFIX: &str = "PROGRESS ";
+
+/// Store file shared with the frontend settings
+const SETTINGS_STORE: &str = "settings.json";
+
+/// Settings key holding the user-chosen interpreter path
+const PYTHON_INTERPRETER_KEY: &str = "pythonInterpreter";
+
+/// Settings key holding how many scripts may run at once
+const MAX_CONCURRENT_KEY: &str = "maxConcurrentPython";
+
+/// Long-lived script worker answering `call` requests
+const WORKER_SCRIPT: &str = "worker.py";
+
+/// How long a `call` waits for the worker when no timeout is configured
+const WORKER_TIMEOUT: Duration = Duration::from_secs(60);
 
 /// Result type for Python bridge operations
 pub type PythonResult<T> = Result<T, PythonError>;
@@ -22,6 +52,9 @@ pub struct PythonError {
     pub stdout: Option<String>,
     pub stderr: Option<String>,
     pub exit_code: Option<i32>,
+    /// Machine-readable code from a script's error envelope (see `parse_error_envelope`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
@@ -44,6 +77,10 @@ pub enum PythonErrorKind {
 
 impl std::fmt::Display for PythonError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Scripts that reported a coded error already gave a user-facing message
+        if let Some(ref code) = self.code {
+            return write!(f, "{} [{}]: {}", self.kind_str(), code, self.message);
+        }
         if let Some(ref stderr) = self.stderr {
             if !stderr.is_empty() {
                 return write!(f, "{}: {} (stderr: {})", self.kind_str(), self.message, stderr);
@@ -78,6 +115,7 @@ impl PythonError {
             stdout: None,
             stderr: None,
             exit_code: None,
+            code: None,
         }
     }
 
@@ -88,6 +126,7 @@ impl PythonError {
             stdout: None,
             stderr: None,
             exit_code: None,
+            code: None,
         }
     }
 
@@ -98,6 +137,7 @@ impl PythonError {
             stdout: None,
             stderr: None,
             exit_code: None,
+            code: None,
         }
     }
 
@@ -113,6 +153,51 @@ impl PythonError {
             stdout,
             stderr,
             exit_code,
+            code: None,
+        }
+    }
+
+    pub fn timeout(
+        message: impl Into<String>,
+        stdout: Option<String>,
+        stderr: Option<String>,
+    ) -> Self {
+        Self {
+            kind: PythonErrorKind::Timeout,
+            message: message.into(),
+            stdout,
+            stderr,
+            exit_code: None,
+            code: None,
+        }
+    }
+
+    /// Script failure described by an error envelope on stdout
+    pub fn script_error(
+        code: impl Into<String>,
+        message: impl Into<String>,
+        stdout: Option<String>,
+        stderr: Option<String>,
+        exit_code: Option<i32>,
+    ) -> Self {
+        Self {
+            kind: PythonErrorKind::ExecutionFailed,
+            message: message.into(),
+            stdout,
+            stderr,
+            exit_code,
+            code: Some(code.into()),
+        }
+    }
+
+    pub fn missing_language_pack(code: impl Into<String>) -> Self {
+        Self {
+            kind: PythonErrorKind::MissingDependency,
+            message: format!("Missing Tesseract language pack: {}", code.into()),
+            stdout: None,
+            stderr: None,
+            exit_code: None,
+            code: None,
         }
     }
 
@@ -123,6 +208,7 @@ impl PythonError {
             stdout: None,
             stderr: None,
             exit_code: None,
+            code: None,
         }
     }
 }
@@ -130,7 +216,9 @@ impl PythonError {
 // Enable conversion to String for Tauri command compatibility
 impl From<PythonError> for String {
     fn from(err: PythonError) -> Self {
-        if let Some(stderr) = &err.stderr {
+        if let Some(code) = &err.code {
+            format!("{} [{}]: {}", err.kind_str(), code, err.message)
+        } else if let Some(stderr) = &err.stderr {
             format!("{}: {} (stderr: {})", err.kind_str(), err.message, stderr)
         } else {
             format!("{}: {}", err.kind_str(), err.message)
@@ -149,6 +237,8 @@ pub struct PythonConfig {
     pub working_dir: Option<PathBuf>,
     /// Environment variables to set
     pub env_vars: HashMap<String, String>,
+    /// Kill scripts that run longer than this (None = wait indefinitely)
+    pub timeout: Option<Duration>,
 }
 
 impl Default for PythonConfig {
@@ -158,6 +248,7 @@ impl Default for PythonConfig {
             venv_path: None,
             working_dir: None,
             env_vars: HashMap::new(),
+            timeout: None,
         }
     }
 }
@@ -167,12 +258,165 @@ pub struct PythonBridge {
     config: PythonConfig,
     python_path: PathBuf,
     scripts_dir: PathBuf,
+    limiter: Option<ScriptLimiter>,
+    worker: Mutex<Option<PythonWorker>>,
+}
+
+/// Lazily resolved bridge shared by all commands, kept in Tauri managed state
+#[derive(Default)]
+pub struct PythonBridgeCache(Mutex<Option<Arc<PythonBridge>>>);
+
+/// Scripts running and queued behind the concurrency limit
+#[derive(Debug, Clone, Copy, Default, Serialize, PartialEq)]
+pub struct QueueStatus {
+    pub running: usize,
+    pub waiting: usize,
+    pub max_concurrent: usize,
+}
+
+/// Bounded semaphore for script runs, kept in Tauri managed state so heavy
+/// operations (OCR, compression, conversion) queue instead of all running at
+/// once. Interpreter checks and package installs don't take a slot.
+#[derive(Clone)]
+pub struct ScriptLimiter(Arc<(Mutex<QueueStatus>, Condvar)>);
+
+/// A running script's slot; released when dropped
+pub struct ScriptPermit(ScriptLimiter);
+
+impl ScriptLimiter {
+    pub fn new(max_concurrent: usize) -> Self {
+        let status = QueueStatus {
+            max_concurrent: max_concurrent.max(1),
+            ..QueueStatus::default()
+        };
+        Self(Arc::new((Mutex::new(status), Condvar::new())))
+    }
+
+    /// Limiter using the stored `maxConcurrentPython` setting, or half the CPUs
+    pub fn for_app(app: &AppHandle) -> Self {
+        let stored = app
+            .store(SETTINGS_STORE)
+            .ok()
+            .and_then(|store| store.get(MAX_CONCURRENT_KEY)?.as_u64());
+        Self::new(stored.map(|n| n as usize).unwrap_or_else(default_max_concurrent))
+    }
+
+    /// Block until a slot is free. Commands wait here through `run_blocking`,
+    /// off the main thread.
+    pub fn acquire(&self) -> ScriptPermit {
+        let (lock, freed) = &*self.0;
+        let mut status = lock.lock().unwrap_or_else(|e| e.into_inner());
+        status.waiting += 1;
+        while status.running >= status.max_concurrent {
+            status = freed.wait(status).unwrap_or_else(|e| e.into_inner());
+        }
+        status.waiting -= 1;
+        status.running += 1;
+        ScriptPermit(self.clone())
+    }
+
+    pub fn status(&self) -> QueueStatus {
+        let (lock, _) = &*self.0;
+        *lock.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Change the limit; queued scripts start right away if it went up
+    pub fn set_max_concurrent(&self, max_concurrent: usize) {
+        let (lock, freed) = &*self.0;
+        lock.lock().unwrap_or_else(|e| e.into_inner()).max_concurrent = max_concurrent.max(1);
+        freed.notify_all();
+    }
+
+    /// Persist a new limit in the settings store and apply it
+    pub fn configure(&self, app: &AppHandle, max_concurrent: usize) -> PythonResult<()> {
+        let store = app
+            .store(SETTINGS_STORE)
+            .map_err(|e| PythonError::spawn_failed(format!("Failed to open settings store: {}", e)))?;
+        store.set(MAX_CONCURRENT_KEY, max_concurrent.max(1));
+        store
+            .save()
+            .map_err(|e| PythonError::spawn_failed(format!("Failed to save settings: {}", e)))?;
+        self.set_max_concurrent(max_concurrent);
+        Ok(())
+    }
+}
+
+impl Drop for ScriptPermit {
+    fn drop(&mut self) {
+        let (lock, freed) = &*(self.0).0;
+        let mut status = lock.lock().unwrap_or_else(|e| e.into_inner());
+        status.running -= 1;
+        freed.notify_one();
+    }
+}
+
+/// Half the available CPUs, at least one
+fn default_max_concurrent() -> usize {
+    thread::available_parallelism().map(|n| n.get() / 2).unwrap_or(1).max(1)
 }
 
 impl PythonBridge {
-    /// Create a new PythonBridge with auto-detected settings
+    /// Create a new PythonBridge, preferring the interpreter stored with
+    /// `set_interpreter` over auto-detected settings
     pub fn new(app: &AppHandle) -> PythonResult<Self> {
-        Self::with_config(app, PythonConfig::default())
+        let config = PythonConfig {
+            python_bin: stored_python_bin(app),
+            ..PythonConfig::default()
+        };
+        Self::with_config(app, config)
+    }
+
+    /// Validate `path` (an interpreter or an environment directory), persist it
+    /// as the interpreter for all bridges and drop the cached shared bridge.
+    /// Returns the version string the interpreter reports.
+    pub fn set_interpreter(app: &AppHandle, path: &str) -> PythonResult<String> {
+        let python_bin = interpreter_path(Path::new(path));
+        let version = probe_interpreter(&python_bin)?;
+
+        let store = app
+            .store(SETTINGS_STORE)
+            .map_err(|e| PythonError::spawn_failed(format!("Failed to open settings store: {}", e)))?;
+        store.set(PYTHON_INTERPRETER_KEY, python_bin.to_string_lossy().to_string());
+        store
+            .save()
+            .map_err(|e| PythonError::spawn_failed(format!("Failed to save settings: {}", e)))?;
+
+        if let Some(cache) = app.try_state::<PythonBridgeCache>() {
+            *cache.0.lock().unwrap_or_else(|e| e.into_inner()) = None;
+        }
+
+        Ok(version)
+    }
+
+    /// Get the shared bridge from managed state, resolving the interpreter and
+    /// scripts directory on first use. Re-resolves if the cached paths disappear.
+    pub fn shared(app: &AppHandle) -> PythonResult<Arc<Self>> {
+        let Some(cache) = app.try_state::<PythonBridgeCache>() else {
+            return Self::new(app).map(Arc::new);
+        };
+
+        let mut slot = cache.0.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(bridge) = slot.as_ref() {
+            if bridge.paths_exist() {
+                return Ok(Arc::clone(bridge));
+            }
+            log::warn!(
+                "Cached Python paths no longer exist ({:?}, {:?}); re-resolving",
+                bridge.python_path,
+                bridge.scripts_dir
+            );
+        }
+
+        let bridge = Arc::new(Self::new(app)?);
+        *slot = Some(Arc::clone(&bridge));
+        Ok(bridge)
+    }
+
+    /// Whether the resolved interpreter and scripts directory are still on disk.
+    /// Bare command names (e.g. "python3") are looked up on PATH and not checked.
+    fn paths_exist(&self) -> bool {
+        let interpreter_ok = self.python_path.components().count() <= 1 || self.python_path.exists();
+        interpreter_ok && self.scripts_dir.exists()
     }
 
     /// Create a new PythonBridge with custom configuration
@@ -183,14 +427,22 @@ impl PythonBridge {
             .unwrap_or_else(|| PathBuf::from(resolve_python_bin()));
 
         let scripts_dir = resolve_scripts_dir(app);
+        let limiter = app.try_state::<ScriptLimiter>().map(|l| l.inner().clone());
 
         Ok(Self {
             config,
             python_path,
             scripts_dir,
+            limiter,
+            worker: Mutex::new(None),
         })
     }
 
+    /// Wait for a script slot, if a limiter is managed
+    fn acquire_slot(&self) -> Option<ScriptPermit> {
+        self.limiter.as_ref().map(ScriptLimiter::acquire)
+    }
+
     /// Get the resolved Python interpreter path
     pub fn python_path(&self) -> &PathBuf {
         &self.python_path
@@ -242,8 +494,142 @@ impl PythonBridge {
         Ok(())
     }
 
-    /// Run a Python script with arguments
+    /// Run a Python script with arguments, using the configured timeout (if any)
     pub fn run_script(&self, script_name: &str, args: &[&str]) -> PythonResult<ScriptOutput> {
+        let script_path = self.script_path(script_name)?;
+        self.run_script_path(&script_path, args)
+    }
+
+    /// Run a Python script, killing it if it runs longer than `timeout`
+    pub fn run_script_with_timeout(
+        &self,
+        script_name: &str,
+        args: &[&str],
+        timeout: Duration,
+    ) -> PythonResult<ScriptOutput> {
+        let script_path = self.script_path(script_name)?;
+        let cmd = self.script_command(&script_path, args);
+        let _permit = self.acquire_slot();
+        self.output_with_deadline(cmd, timeout)
+    }
+
+    /// Start the long-lived worker if it isn't running yet, so the first
+    /// `call` doesn't pay for interpreter startup and imports
+    pub fn start_worker(&self) -> PythonResult<()> {
+        let mut slot = self.worker.lock().unwrap_or_else(|e| e.into_inner());
+        if slot.as_mut().is_some_and(PythonWorker::is_alive) {
+            return Ok(());
+        }
+        *slot = Some(self.spawn_worker()?);
+        Ok(())
+    }
+
+    /// Run `method` (a script name, e.g. "pdf_edit.py") with `params` as its
+    /// arguments in the long-lived worker, so imports stay warm between calls.
+    /// A request takes a limiter slot and is killed (and the worker respawned)
+    /// after the configured timeout, or `WORKER_TIMEOUT` without one. Falls
+    /// back to spawning the script if the worker is busy with another request,
+    /// can't be (re)started or dies mid-request.
+    pub fn call(&self, method: &str, params: &[&str]) -> PythonResult<ScriptOutput> {
+        let mut slot = match self.worker.try_lock() {
+            Ok(slot) => slot,
+            Err(TryLockError::Poisoned(e)) => e.into_inner(),
+            Err(TryLockError::WouldBlock) => return self.run_script(method, params),
+        };
+        if !slot.as_mut().is_some_and(PythonWorker::is_alive) {
+            *slot = match self.spawn_worker() {
+                Ok(worker) => Some(worker),
+                Err(e) => {
+                    log::warn!("Python worker unavailable ({}); spawning {}", e, method);
+                    None
+                }
+            };
+        }
+
+        let Some(worker) = slot.as_mut() else {
+            drop(slot);
+            return self.run_script(method, params);
+        };
+
+        let timeout = self.config.timeout.unwrap_or(WORKER_TIMEOUT);
+        let outcome = {
+            let _permit = self.acquire_slot();
+            worker.request(method, params, timeout)
+        };
+
+        match outcome {
+            Ok(response) => {
+                drop(slot);
+                response_output(response)
+            }
+            Err(WorkerFailure::TimedOut) => {
+                // The old worker is still busy with the request; replace it so
+                // the next call doesn't queue behind it
+                *slot = None;
+                *slot = self.spawn_worker().ok();
+                Err(PythonError::timeout(
+                    format!("{} timed out after {}s", method, timeout.as_secs_f32()),
+                    None,
+                    None,
+                ))
+            }
+            Err(WorkerFailure::Broken(e)) => {
+                log::warn!("Python worker failed ({}); spawning {}", e, method);
+                *slot = None;
+                drop(slot);
+                self.run_script(method, params)
+            }
+        }
+    }
+
+    fn spawn_worker(&self) -> PythonResult<PythonWorker> {
+        let script_path = self.script_path(WORKER_SCRIPT)?;
+        PythonWorker::spawn(self.script_command(&script_path, &[]))
+    }
+
+    /// Run a Python script from a specific path
+    pub fn run_script_path(&self, script_path: &PathBuf, args: &[&str]) -> PythonResult<ScriptOutput> {
+        let mut cmd = self.script_command(script_path, args);
+        let _permit = self.acquire_slot();
+
+        if let Some(timeout) = self.config.timeout {
+            return self.output_with_deadline(cmd, timeout);
+        }
+
+        let output = cmd
+            .output()
+            .map_err(|e| PythonError::spawn_failed(format!("Failed to spawn Python: {}", e)))?;
+
+        self.process_output(output)
+    }
+
+    /// Run a Python script with extra environment variables for this run only.
+    /// Use this for secrets: unlike arguments, a process's environment isn't
+    /// visible to other users.
+    pub fn run_script_with_env(
+        &self,
+        script_name: &str,
+        args: &[&str],
+        env: &[(&str, &str)],
+    ) -> PythonResult<ScriptOutput> {
+        let script_path = self.script_path(script_name)?;
+        let mut cmd = self.script_command(&script_path, args);
+        cmd.envs(env.iter().copied());
+        let _permit = self.acquire_slot();
+
+        if let Some(timeout) = self.config.timeout {
+            return self.output_with_deadline(cmd, timeout);
+        }
+
+        let output = cmd
+            .output()
+            .map_err(|e| PythonError::spawn_failed(format!("Failed to spawn Python: {}", e)))?;
+
+        self.process_output(output)
+    }
+
+    /// Resolve a script name inside the scripts directory
+    fn script_path(&self, script_name: &str) -> PythonResult<PathBuf> {
         let script_path = self.scripts_dir.join(script_name);
 
         if !script_path.exists() {
@@ -253,11 +639,11 @@ impl PythonBridge {
             )));
         }
 
-        self.run_script_path(&script_path, args)
+        Ok(script_path)
     }
 
-    /// Run a Python script from a specific path
-    pub fn run_script_path(&self, script_path: &PathBuf, args: &[&str]) -> PythonResult<ScriptOutput> {
+    /// Build the interpreter command for a script with env vars and working dir applied
+    fn script_command(&self, script_path: &PathBuf, args: &[&str]) -> Command {
         let mut cmd = Command::new(&self.python_path);
         cmd.arg(script_path);
         cmd.args(args);
@@ -272,11 +658,72 @@ impl PythonBridge {
             cmd.current_dir(wd);
         }
 
-        let output = cmd
-            .output()
-            .map_err(|e| PythonError::spawn_failed(format!("Failed to spawn Python: {}", e)))?;
+        cmd
+    }
 
-        self.process_output(output)
+    /// Run a Python script, forwarding its `PROGRESS {json}` stdout lines to the
+    /// frontend as `python-progress` events. Other lines form the returned stdout.
+    /// Uses the configured timeout (if any).
+    pub fn run_script_streamed(
+        &self,
+        app: &AppHandle,
+        script_name: &str,
+        args: &[&str],
+    ) -> PythonResult<ScriptOutput> {
+        let script_path = self.script_path(script_name)?;
+        let _permit = self.acquire_slot();
+        let mut child = spawn_piped(self.script_command(&script_path, args))?;
+
+        let stdout = capture_progress(child.stdout.take(), app.clone(), script_name.to_string());
+        let stderr = capture_pipe(child.stderr.take());
+
+        self.wait_for_child(child, stdout, stderr, self.config.timeout)
+    }
+
+    /// Spawn `cmd` and wait until it exits or `timeout` elapses
+    fn output_with_deadline(&self, cmd: Command, timeout: Duration) -> PythonResult<ScriptOutput> {
+        let mut child = spawn_piped(cmd)?;
+
+        let stdout = capture_pipe(child.stdout.take());
+        let stderr = capture_pipe(child.stderr.take());
+
+        self.wait_for_child(child, stdout, stderr, Some(timeout))
+    }
+
+    /// Wait for a spawned script. On timeout the process is killed and whatever
+    /// output it produced so far is attached to the error.
+    fn wait_for_child(
+        &self,
+        mut child: Child,
+        stdout: CapturedPipe,
+        stderr: CapturedPipe,
+        timeout: Option<Duration>,
+    ) -> PythonResult<ScriptOutput> {
+        let deadline = timeout.map(|t| Instant::now() + t);
+        let status = loop {
+            match child.try_wait() {
+                Ok(Some(status)) => break status,
+                Ok(None) if deadline.is_some_and(|d| Instant::now() >= d) => {
+                    kill_child(&mut child);
+                    return Err(PythonError::timeout(
+                        format!("Script timed out after {}s", timeout.unwrap_or_default().as_secs_f32()),
+                        Some(stdout.snapshot()),
+                        Some(stderr.snapshot()),
+                    ));
+                }
+                Ok(None) => thread::sleep(Duration::from_millis(50)),
+                Err(e) => {
+                    kill_child(&mut child);
+                    return Err(PythonError::spawn_failed(format!("Failed to wait for Python: {}", e)));
+                }
+            }
+        };
+
+        self.process_output(Output {
+            status,
+            stdout: stdout.finish(),
+            stderr: stderr.finish(),
+        })
     }
 
     /// Run a Python command (like -m module)
@@ -320,28 +767,309 @@ impl PythonBridge {
 
     /// Process command output into structured result
     fn process_output(&self, output: Output) -> PythonResult<ScriptOutput> {
-        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-        let exit_code = output.status.code();
-
-        if output.status.success() {
-            Ok(ScriptOutput {
-                stdout,
-                stderr,
-                exit_code,
-                success: true,
-            })
-        } else {
-            Err(PythonError::execution_failed(
-                "Script execution failed",
-                Some(stdout),
-                Some(stderr),
-                exit_code,
-            ))
+        script_result(
+            String::from_utf8_lossy(&output.stdout).to_string(),
+            String::from_utf8_lossy(&output.stderr).to_string(),
+            output.status.code(),
+        )
+    }
+}
+
+/// Turn a finished script's output into a result, recognizing error envelopes
+fn script_result(stdout: String, stderr: String, exit_code: Option<i32>) -> PythonResult<ScriptOutput> {
+    if exit_code == Some(0) {
+        Ok(ScriptOutput {
+            stdout,
+            stderr,
+            exit_code,
+            success: true,
+        })
+    } else if let Some(envelope) = parse_error_envelope(&stdout) {
+        Err(PythonError::script_error(
+            envelope.code,
+            envelope.message,
+            Some(stdout),
+            Some(stderr),
+            exit_code,
+        ))
+    } else {
+        Err(PythonError::execution_failed(
+            "Script execution failed",
+            Some(stdout),
+            Some(stderr),
+            exit_code,
+        ))
+    }
+}
+
+/// Long-lived `worker.py` process answering one JSON request per line
+struct PythonWorker {
+    child: Child,
+    stdin: ChildStdin,
+    /// Reply lines from the worker's stdout; disconnects when it exits
+    replies: Receiver<std::io::Result<String>>,
+    next_id: u64,
+}
+
+/// Why a worker request got no usable reply
+enum WorkerFailure {
+    /// No reply within the timeout; the worker may still be running it
+    TimedOut,
+    /// I/O or protocol error: the worker is unusable and should be replaced
+    Broken(String),
+}
+
+#[derive(Serialize)]
+struct WorkerRequest<'a> {
+    id: u64,
+    method: &'a str,
+    params: &'a [&'a str],
+}
+
+/// Worker reply: the script's captured output, or a protocol-level error
+#[derive(Debug, Deserialize)]
+struct WorkerResponse {
+    id: Option<u64>,
+    #[serde(default)]
+    stdout: String,
+    #[serde(default)]
+    stderr: String,
+    #[serde(default)]
+    exit_code: Option<i32>,
+    #[serde(default)]
+    error: Option<ErrorEnvelope>,
+}
+
+impl PythonWorker {
+    fn spawn(mut cmd: Command) -> PythonResult<Self> {
+        let mut child = cmd
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .map_err(|e| PythonError::spawn_failed(format!("Failed to spawn Python worker: {}", e)))?;
+
+        let (Some(stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+            kill_child(&mut child);
+            return Err(PythonError::spawn_failed("Python worker pipes unavailable"));
+        };
+
+        // Replies are read on their own thread so a request can time out
+        let (sender, replies) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(Self {
+            child,
+            stdin,
+            replies,
+            next_id: 0,
+        })
+    }
+
+    fn is_alive(&mut self) -> bool {
+        matches!(self.child.try_wait(), Ok(None))
+    }
+
+    /// Send one request and wait up to `timeout` for its reply
+    fn request(
+        &mut self,
+        method: &str,
+        params: &[&str],
+        timeout: Duration,
+    ) -> Result<WorkerResponse, WorkerFailure> {
+        self.next_id += 1;
+        let request = WorkerRequest {
+            id: self.next_id,
+            method,
+            params,
+        };
+        let line = serde_json::to_string(&request).map_err(|e| WorkerFailure::Broken(e.to_string()))?;
+        writeln!(self.stdin, "{}", line)
+            .and_then(|_| self.stdin.flush())
+            .map_err(|e| WorkerFailure::Broken(format!("write failed: {}", e)))?;
+
+        let reply = match self.replies.recv_timeout(timeout) {
+            Ok(Ok(reply)) => reply,
+            Ok(Err(e)) => return Err(WorkerFailure::Broken(format!("read failed: {}", e))),
+            Err(RecvTimeoutError::Timeout) => return Err(WorkerFailure::TimedOut),
+            Err(RecvTimeoutError::Disconnected) => {
+                return Err(WorkerFailure::Broken("worker exited".to_string()))
+            }
+        };
+
+        let response: WorkerResponse = serde_json::from_str(reply.trim())
+            .map_err(|e| WorkerFailure::Broken(format!("bad reply: {}", e)))?;
+        if response.id != Some(self.next_id) {
+            return Err(WorkerFailure::Broken(format!(
+                "reply for request {:?}, expected {}",
+                response.id, self.next_id
+            )));
+        }
+        Ok(response)
+    }
+}
+
+impl Drop for PythonWorker {
+    fn drop(&mut self) {
+        kill_child(&mut self.child);
+    }
+}
+
+/// Map a worker reply onto the same result a spawned script would give
+fn response_output(response: WorkerResponse) -> PythonResult<ScriptOutput> {
+    if let Some(error) = response.error {
+        return Err(PythonError::script_error(error.code, error.message, None, None, None));
+    }
+    script_result(response.stdout, response.stderr, response.exit_code)
+}
+
+/// Error a script reported via `utils.report_error`
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct ErrorEnvelope {
+    pub code: String,
+    pub message: String,
+    #[serde(default)]
+    pub details: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ErrorEnvelopeLine {
+    error: ErrorEnvelope,
+}
+
+/// Find an error envelope (`{"error": {"code": ..., "message": ..., "details": ...}}`)
+/// on the last non-empty stdout line. The message includes the details, if any.
+/// Plain `{"error": "text"}` results are not envelopes.
+pub fn parse_error_envelope(stdout: &str) -> Option<ErrorEnvelope> {
+    let line = stdout.lines().rev().find(|l| !l.trim().is_empty())?;
+    let mut envelope = serde_json::from_str::<ErrorEnvelopeLine>(line.trim()).ok()?.error;
+    if let Some(details) = envelope.details.as_deref().filter(|d| !d.is_empty()) {
+        envelope.message = format!("{}: {}", envelope.message, details);
+    }
+    Some(envelope)
+}
+
+/// Pipe contents collected by a background reader thread
+struct CapturedPipe {
+    buffer: Arc<Mutex<Vec<u8>>>,
+    reader: Option<thread::JoinHandle<()>>,
+}
+
+impl CapturedPipe {
+    /// Output read so far, without waiting for the pipe to close
+    fn snapshot(&self) -> String {
+        let buffer = self.buffer.lock().map(|b| b.clone()).unwrap_or_default();
+        String::from_utf8_lossy(&buffer).to_string()
+    }
+
+    /// Wait for the pipe to close and return everything written to it
+    fn finish(mut self) -> Vec<u8> {
+        if let Some(reader) = self.reader.take() {
+            let _ = reader.join();
         }
+        self.buffer.lock().map(|b| b.clone()).unwrap_or_default()
     }
 }
 
+fn capture_pipe<R: Read + Send + 'static>(pipe: Option<R>) -> CapturedPipe {
+    let buffer = Arc::new(Mutex::new(Vec::new()));
+    let reader = pipe.map(|mut pipe| {
+        let buffer = Arc::clone(&buffer);
+        thread::spawn(move || {
+            let mut chunk = [0u8; 8192];
+            while let Ok(n) = pipe.read(&mut chunk) {
+                if n == 0 {
+                    break;
+                }
+                if let Ok(mut b) = buffer.lock() {
+                    b.extend_from_slice(&chunk[..n]);
+                }
+            }
+        })
+    });
+    CapturedPipe { buffer, reader }
+}
+
+/// Progress reported by a script via a `PROGRESS {json}` line
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProgressLine {
+    #[serde(default)]
+    pub phase: String,
+    pub current: u64,
+    pub total: u64,
+}
+
+/// Payload of the `python-progress` event
+#[derive(Debug, Clone, Serialize)]
+pub struct PythonProgress {
+    pub script: String,
+    pub phase: String,
+    pub current: u64,
+    pub total: u64,
+}
+
+/// Parse a stdout line as a progress report, if it is one
+pub fn parse_progress_line(line: &str) -> Option<ProgressLine> {
+    let payload = line.trim_end().strip_prefix(PROGRESS_PREFIX)?;
+    serde_json::from_str(payload).ok()
+}
+
+/// Read stdout line by line, emitting progress events and collecting all other lines
+fn capture_progress<R: Read + Send + 'static>(pipe: Option<R>, app: AppHandle, script: String) -> CapturedPipe {
+    let buffer = Arc::new(Mutex::new(Vec::new()));
+    let reader = pipe.map(|pipe| {
+        let buffer = Arc::clone(&buffer);
+        thread::spawn(move || {
+            let mut reader = BufReader::new(pipe);
+            let mut line = Vec::new();
+            while let Ok(n) = reader.read_until(b'\n', &mut line) {
+                if n == 0 {
+                    break;
+                }
+                match parse_progress_line(&String::from_utf8_lossy(&line)) {
+                    Some(progress) => {
+                        let _ = app.emit(
+                            PROGRESS_EVENT,
+                            PythonProgress {
+                                script: script.clone(),
+                                phase: progress.phase,
+                                current: progress.current,
+                                total: progress.total,
+                            },
+                        );
+                    }
+                    None => {
+                        if let Ok(mut b) = buffer.lock() {
+                            b.extend_from_slice(&line);
+                        }
+                    }
+                }
+                line.clear();
+            }
+        })
+    });
+    CapturedPipe { buffer, reader }
+}
+
+fn spawn_piped(mut cmd: Command) -> PythonResult<Child> {
+    cmd.stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| PythonError::spawn_failed(format!("Failed to spawn Python: {}", e)))
+}
+
+fn kill_child(child: &mut Child) {
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
 /// Output from a successful script execution
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScriptOutput {
@@ -363,13 +1091,88 @@ impl ScriptOutput {
     }
 }
 
+/// Relative path of the interpreter inside a virtual environment
+#[cfg(windows)]
+const VENV_PYTHON: &str = "Scripts/python.exe";
+#[cfg(not(windows))]
+const VENV_PYTHON: &str = "bin/python3";
+
+/// System interpreters probed when no venv is found, most preferred first.
+/// Windows installs expose `python.exe` rather than versioned `python3.x` names.
+#[cfg(windows)]
+const SYSTEM_PYTHONS: &[&str] = &["python", "python3"];
+#[cfg(not(windows))]
+const SYSTEM_PYTHONS: &[&str] = &["python3.12", "python3.11", "python3.10", "python3"];
+
+/// The interpreter for `path`: the path itself, or the interpreter inside it when
+/// it names a venv or conda environment directory
+fn interpreter_path(path: &Path) -> PathBuf {
+    if !path.is_dir() {
+        return path.to_path_buf();
+    }
+
+    // venvs use bin/ (Scripts\ on Windows); conda on Windows keeps python.exe at the root
+    [VENV_PYTHON, "bin/python", "python.exe"]
+        .iter()
+        .map(|candidate| path.join(candidate))
+        .find(|candidate| candidate.is_file())
+        .unwrap_or_else(|| path.join(VENV_PYTHON))
+}
+
+/// Run `python --version` and return the reported version (e.g. "3.12.1")
+fn probe_interpreter(python_bin: &Path) -> PythonResult<String> {
+    let output = Command::new(python_bin)
+        .arg("--version")
+        .output()
+        .map_err(|e| {
+            PythonError::python_not_found(format!("Cannot run {}: {}", python_bin.display(), e))
+        })?;
+
+    // Python 2 and some 3.x builds print the version to stderr
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let reported = if stdout.trim().is_empty() { stderr.trim() } else { stdout.trim() };
+
+    match parse_python_version(reported) {
+        Some(version) if output.status.success() => Ok(version),
+        _ => Err(PythonError::python_not_found(format!(
+            "{} is not a Python interpreter (reported: {:?})",
+            python_bin.display(),
+            reported
+        ))),
+    }
+}
+
+/// Extract "3.12.1" from `python --version` output like "Python 3.12.1"
+fn parse_python_version(reported: &str) -> Option<String> {
+    let version = reported.strip_prefix("Python ")?.trim();
+    version
+        .starts_with(|c: char| c.is_ascii_digit())
+        .then(|| version.to_string())
+}
+
+/// The interpreter saved with `PythonBridge::set_interpreter`, if it still exists
+fn stored_python_bin(app: &AppHandle) -> Option<PathBuf> {
+    let store = app.store(SETTINGS_STORE).ok()?;
+    let path = PathBuf::from(store.get(PYTHON_INTERPRETER_KEY)?.as_str()?);
+    if path.exists() {
+        Some(path)
+    } else {
+        log::warn!("Stored Python interpreter {:?} no longer exists; auto-detecting", path);
+        None
+    }
+}
+
 /// Determine which Python interpreter to use.
 /// Priority:
 /// 1) APP_PYTHON_BIN env var
-/// 2) backend/venv/bin/python3 relative to workspace root
-/// 3) python3.12
+/// 2) backend/venv/bin/python3 (backend\venv\Scripts\python.exe on Windows)
+///    relative to workspace root
+/// 3) python3.12, python3.11, python3.10 (python on Windows)
 /// 4) python3
-fn resolve_python_bin() -> String {
+///
+/// Shared with the OCR module, which runs its script outside the bridge.
+pub(crate) fn resolve_python_bin() -> String {
     if let Ok(p) = std::env::var("APP_PYTHON_BIN") {
         return p;
     }
@@ -380,13 +1183,13 @@ fn resolve_python_bin() -> String {
     }
 
     // Check venv first
-    let venv = root.join("backend/venv/bin/python3");
+    let venv = root.join("backend/venv").join(VENV_PYTHON);
     if venv.exists() {
         return venv.to_string_lossy().to_string();
     }
 
     // Try common Python versions
-    for bin in &["python3.12", "python3.11", "python3.10", "python3"] {
+    for bin in SYSTEM_PYTHONS {
         if Command::new(bin)
             .arg("--version")
             .stdout(Stdio::null())
@@ -512,6 +1315,47 @@ mod tests {
         assert!(err.to_string().contains("PythonNotFound"));
     }
 
+    #[test]
+    fn test_parse_error_envelope() {
+        let stdout = "partial output\n{\"error\": {\"code\": \"conversion_failed\", \"message\": \"Conversion failed\", \"details\": \"pdf -> png\"}}\n";
+        let envelope = parse_error_envelope(stdout).unwrap();
+        assert_eq!(envelope.code, "conversion_failed");
+        assert_eq!(envelope.message, "Conversion failed: pdf -> png");
+
+        let bare = parse_error_envelope("{\"error\": {\"code\": \"internal\", \"message\": \"boom\", \"details\": null}}").unwrap();
+        assert_eq!(bare.message, "boom");
+
+        assert!(parse_error_envelope("{\"error\": \"plain text\"}").is_none());
+        assert!(parse_error_envelope("Created 3 pages").is_none());
+        assert!(parse_error_envelope("").is_none());
+
+        let err = PythonError::script_error("timeout", "Operation timed out", None, Some("Traceback".into()), Some(1));
+        assert_eq!(err.to_string(), "ExecutionFailed [timeout]: Operation timed out");
+    }
+
+    #[test]
+    fn test_script_limiter_queues() {
+        let limiter = ScriptLimiter::new(1);
+        let first = limiter.acquire();
+        assert_eq!(limiter.status().running, 1);
+
+        let waiter = {
+            let limiter = limiter.clone();
+            thread::spawn(move || drop(limiter.acquire()))
+        };
+        while limiter.status().waiting == 0 {
+            thread::sleep(Duration::from_millis(5));
+        }
+        assert_eq!(limiter.status(), QueueStatus { running: 1, waiting: 1, max_concurrent: 1 });
+
+        drop(first);
+        waiter.join().unwrap();
+        assert_eq!(limiter.status(), QueueStatus { running: 0, waiting: 0, max_concurrent: 1 });
+
+        limiter.set_max_concurrent(0);
+        assert_eq!(limiter.status().max_concurrent, 1);
+    }
+
     #[test]
     fn test_script_output_lines() {
         let output = ScriptOutput {
@@ -522,4 +1366,86 @@ mod tests {
         };
         assert_eq!(output.lines().len(), 3);
     }
+
+    #[test]
+    fn test_parse_python_version() {
+        assert_eq!(parse_python_version("Python 3.12.1"), Some("3.12.1".to_string()));
+        assert_eq!(parse_python_version("Python 3.13.0rc1"), Some("3.13.0rc1".to_string()));
+        assert_eq!(parse_python_version("bash: python: command not found"), None);
+        assert_eq!(parse_python_version("Python"), None);
+    }
+
+    #[test]
+    fn test_parse_progress_line() {
+        let progress = parse_progress_line("PROGRESS {\"phase\": \"ocr\", \"current\": 3, \"total\": 10}\n").unwrap();
+        assert_eq!(progress.phase, "ocr");
+        assert_eq!((progress.current, progress.total), (3, 10));
+
+        assert!(parse_progress_line("Created 3 images:").is_none());
+        assert!(parse_progress_line("PROGRESS not-json").is_none());
+    }
+
+    #[test]
+    fn test_worker_response_maps_like_spawned_script() {
+        let ok: WorkerResponse =
+            serde_json::from_str(r#"{"id": 1, "stdout": "{}", "stderr": "", "exit_code": 0}"#).unwrap();
+        assert_eq!(response_output(ok).unwrap().stdout, "{}");
+
+        let failed: WorkerResponse = serde_json::from_str(
+            r#"{"id": 2, "stdout": "{\"error\": {\"code\": \"FILE_NOT_FOUND\", \"message\": \"missing\"}}", "exit_code": 1}"#,
+        )
+        .unwrap();
+        let err = response_output(failed).unwrap_err();
+        assert_eq!(err.code.as_deref(), Some("FILE_NOT_FOUND"));
+        assert_eq!(err.message, "missing");
+
+        let rejected: WorkerResponse = serde_json::from_str(
+            r#"{"id": 3, "error": {"code": "WORKER_ERROR", "message": "Unknown method: x"}}"#,
+        )
+        .unwrap();
+        assert_eq!(response_output(rejected).unwrap_err().code.as_deref(), Some("WORKER_ERROR"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_worker_request_times_out() {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "sleep 5"]);
+        let mut worker = PythonWorker::spawn(cmd).unwrap();
+        let started = Instant::now();
+        let outcome = worker.request("pdf_edit.py", &[], Duration::from_millis(200));
+        assert!(matches!(outcome, Err(WorkerFailure::TimedOut)));
+        assert!(started.elapsed() < Duration::from_secs(4));
+
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "exit 0"]);
+        let mut exited = PythonWorker::spawn(cmd).unwrap();
+        let outcome = exited.request("pdf_edit.py", &[], Duration::from_secs(5));
+        assert!(matches!(outcome, Err(WorkerFailure::Broken(_))));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_output_with_deadline_kills_slow_process() {
+        let bridge = PythonBridge {
+            config: PythonConfig::default(),
+            python_path: PathBuf::from("sh"),
+            scripts_dir: PathBuf::from("."),
+            limiter: None,
+            worker: Mutex::new(None),
+        };
+
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "echo partial; sleep 5"]);
+        let err = bridge
+            .output_with_deadline(cmd, Duration::from_millis(500))
+            .unwrap_err();
+        assert_eq!(err.kind, PythonErrorKind::Timeout);
+        assert_eq!(err.stdout.as_deref().map(str::trim), Some("partial"));
+
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "echo done"]);
+        let output = bridge.output_with_deadline(cmd, Duration::from_secs(5)).unwrap();
+        assert_eq!(output.stdout.trim(), "done");
+    }
 }
@@ -0,0 +1,204 @@
+//! On-disk thumbnail cache so reopening a document doesn't re-render every page.
+//!
+//! Thumbnails are stored as PNG files under `<app cache>/thumbnails/<path hash>/`,
+//! named after the file's modification time and size, the page and the
+//! requested size. Encrypted documents are never written here.
+//! A total-size cap is enforced by evicting the least recently used files
+//! (a cache hit bumps the file's modification time).
+
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+
+/// Upper bound for the whole thumbnail cache on disk
+pub const THUMBNAIL_CACHE_MAX_BYTES: u64 = 256 * 1024 * 1024;
+
+/// Stable (FNV-1a) hash of a document path, usable as a file or directory name
+pub(crate) fn path_hash(path: &str) -> String {
+    let hash = path.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+    format!("{:016x}", hash)
+}
+
+/// Width and height from a PNG's IHDR chunk
+pub(crate) fn png_dimensions(data: &[u8]) -> Option<(u32, u32)> {
+    const SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+    if data.len() < 24 || !data.starts_with(SIGNATURE) || &data[12..16] != b"IHDR" {
+        return None;
+    }
+    let width = u32::from_be_bytes(data[16..20].try_into().ok()?);
+    let height = u32::from_be_bytes(data[20..24].try_into().ok()?);
+    Some((width, height))
+}
+
+/// Thumbnail cache rooted at one directory
+pub struct ThumbnailCache {
+    root: PathBuf,
+    max_bytes: u64,
+}
+
+impl ThumbnailCache {
+    pub fn new(root: PathBuf, max_bytes: u64) -> Self {
+        Self { root, max_bytes }
+    }
+
+    /// Cache in the app cache dir with the default size cap
+    pub fn for_app(app: &AppHandle) -> Self {
+        let cache_dir = app
+            .path()
+            .app_cache_dir()
+            .unwrap_or_else(|_| std::env::temp_dir());
+        Self::new(cache_dir.join("thumbnails"), THUMBNAIL_CACHE_MAX_BYTES)
+    }
+
+    fn document_dir(&self, path: &str) -> PathBuf {
+        self.root.join(path_hash(path))
+    }
+
+    /// File name prefix shared by all thumbnails of one version of the document.
+    /// Nanosecond mtime plus length, so two saves within a second still differ.
+    fn stamp_prefix(path: &str) -> Option<String> {
+        let meta = fs::metadata(path).ok()?;
+        let nanos = meta.modified().ok()?.duration_since(UNIX_EPOCH).ok()?.as_nanos();
+        Some(format!("{}-{}-", nanos, meta.len()))
+    }
+
+    fn entry_path(&self, path: &str, page: u32, max_size: u32) -> Option<PathBuf> {
+        let prefix = Self::stamp_prefix(path)?;
+        Some(
+            self.document_dir(path)
+                .join(format!("{}p{}-{}.png", prefix, page, max_size)),
+        )
+    }
+
+    /// Cached PNG for `page`, if the document hasn't changed since it was stored
+    pub fn get(&self, path: &str, page: u32, max_size: u32) -> Option<Vec<u8>> {
+        let entry = self.entry_path(path, page, max_size)?;
+        let data = fs::read(&entry).ok()?;
+        // Mark as recently used for eviction
+        if let Ok(file) = fs::File::options().write(true).open(&entry) {
+            let _ = file.set_modified(SystemTime::now());
+        }
+        Some(data)
+    }
+
+    /// Store a rendered PNG, dropping thumbnails of older versions of the document
+    pub fn put(&self, path: &str, page: u32, max_size: u32, png: &[u8]) -> Result<(), String> {
+        let entry = self
+            .entry_path(path, page, max_size)
+            .ok_or_else(|| format!("Failed to read modification time of {}", path))?;
+        let dir = self.document_dir(path);
+        fs::create_dir_all(&dir).map_err(|e| format!("Failed to create thumbnail cache: {}", e))?;
+
+        let prefix = Self::stamp_prefix(path).unwrap_or_default();
+        if let Ok(entries) = fs::read_dir(&dir) {
+            for stale in entries.flatten() {
+                if !stale.file_name().to_string_lossy().starts_with(&prefix) {
+                    let _ = fs::remove_file(stale.path());
+                }
+            }
+        }
+
+        fs::write(&entry, png).map_err(|e| format!("Failed to write thumbnail: {}", e))
+    }
+
+    /// Remove every cached thumbnail of `path`
+    pub fn clear(&self, path: &str) -> Result<(), String> {
+        let dir = self.document_dir(path);
+        if dir.exists() {
+            fs::remove_dir_all(&dir).map_err(|e| format!("Failed to clear thumbnail cache: {}", e))?;
+        }
+        Ok(())
+    }
+
+    /// Delete least recently used thumbnails until the cache fits the size cap
+    pub fn evict(&self) {
+        let mut files: Vec<(SystemTime, u64, PathBuf)> = Vec::new();
+        let dirs = match fs::read_dir(&self.root) {
+            Ok(dirs) => dirs,
+            Err(_) => return,
+        };
+        for dir in dirs.flatten() {
+            let Ok(entries) = fs::read_dir(dir.path()) else { continue };
+            for entry in entries.flatten() {
+                if let Ok(meta) = entry.metadata() {
+                    let used = meta.modified().unwrap_or(UNIX_EPOCH);
+                    files.push((used, meta.len(), entry.path()));
+                }
+            }
+        }
+
+        let mut total: u64 = files.iter().map(|(_, size, _)| size).sum();
+        if total <= self.max_bytes {
+            return;
+        }
+
+        files.sort_by_key(|(used, _, _)| *used);
+        for (_, size, file) in files {
+            if total <= self.max_bytes {
+                break;
+            }
+            if fs::remove_file(&file).is_ok() {
+                total -= size;
+                if let Some(parent) = file.parent() {
+                    // Only succeeds once the document directory is empty
+                    let _ = fs::remove_dir(parent);
+                }
+            }
+        }
+    }
+}
+
+/// Drop the cached thumbnails of a document
+#[tauri::command]
+pub fn thumbnails_clear_cache(app: AppHandle, path: String) -> Result<(), String> {
+    ThumbnailCache::for_app(&app).clear(&path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_png_dimensions() {
+        let mut png = b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR".to_vec();
+        png.extend_from_slice(&120u32.to_be_bytes());
+        png.extend_from_slice(&200u32.to_be_bytes());
+        assert_eq!(png_dimensions(&png), Some((120, 200)));
+        assert_eq!(png_dimensions(&png[..20]), None);
+        assert_eq!(png_dimensions(b"not a png at all, definitely"), None);
+    }
+
+    #[test]
+    fn test_put_get_evict() {
+        let root = std::env::temp_dir().join(format!("thumb-cache-{}", uuid::Uuid::new_v4()));
+        let doc = root.join("doc.pdf");
+        fs::create_dir_all(&root).unwrap();
+        fs::write(&doc, b"%PDF").unwrap();
+        let doc = doc.to_string_lossy().to_string();
+
+        let cache = ThumbnailCache::new(root.join("thumbnails"), 10);
+        assert!(cache.get(&doc, 1, 200).is_none());
+        cache.put(&doc, 1, 200, b"12345678").unwrap();
+        assert_eq!(cache.get(&doc, 1, 200).as_deref(), Some(&b"12345678"[..]));
+        assert!(cache.get(&doc, 1, 100).is_none());
+
+        // A save that changes the size invalidates the entry even within the same second
+        fs::write(&doc, b"%PDF-1.7").unwrap();
+        assert!(cache.get(&doc, 1, 200).is_none());
+        cache.put(&doc, 1, 200, b"12345678").unwrap();
+
+        // Second entry pushes the total over the cap; the older one goes
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        cache.put(&doc, 2, 200, b"12345678").unwrap();
+        cache.evict();
+        assert!(cache.get(&doc, 1, 200).is_none());
+        assert!(cache.get(&doc, 2, 200).is_some());
+
+        cache.clear(&doc).unwrap();
+        assert!(cache.get(&doc, 2, 200).is_none());
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -79,6 +79,8 @@ export interface Annotation {
   opacity: number;
   text?: string;                // For comments and freetext
   fontsize?: number;            // For freetext (in PDF points)
+  fontFamily?: 'sans' | 'serif' | 'mono'; // For freetext
+  borderColor?: string;         // For freetext text boxes (border width is strokeWidth)
   createdAt: Date;
   modifiedAt: Date;
   author?: string;              // Author attribution
@@ -161,6 +161,8 @@ export interface AppSettings {
   palette: PaletteId;
   language: Language;
   author: AuthorSettings;
+  annotationsDir: string; // Where annotation sidecars are saved ('' = next to the PDF)
+  backupOnSave: boolean; // Keep the previous version as {name}.bak when saving in place
 }
 
 // Default author settings
@@ -183,6 +185,8 @@ const DEFAULT_SETTINGS: AppSettings = {
   palette: 'nord',
   language: 'en',
   author: { ...DEFAULT_AUTHOR },
+  annotationsDir: '',
+  backupOnSave: false,
 };
 
 // Complete tool catalog
@@ -252,6 +256,8 @@ async function initStore(): Promise<void> {
     const palette = await store.get<PaletteId>('palette');
     const language = await store.get<Language>('language');
     const author = await store.get<AuthorSettings>('author');
+    const annotationsDir = await store.get<string>('annotationsDir');
+    const backupOnSave = await store.get<boolean>('backupOnSave');
 
     settings = {
       favorites: favorites ?? DEFAULT_SETTINGS.favorites,
@@ -263,6 +269,8 @@ async function initStore(): Promise<void> {
       palette: palette ?? DEFAULT_SETTINGS.palette,
       language: language ?? DEFAULT_SETTINGS.language,
       author: author ?? { ...DEFAULT_AUTHOR },
+      annotationsDir: annotationsDir ?? DEFAULT_SETTINGS.annotationsDir,
+      backupOnSave: backupOnSave ?? DEFAULT_SETTINGS.backupOnSave,
     };
 
     isLoaded = true;
@@ -343,6 +351,14 @@ export async function setAuthor(author: AuthorSettings): Promise<void> {
   await saveSetting('author', author);
 }
 
+export async function setAnnotationsDir(dir: string): Promise<void> {
+  await saveSetting('annotationsDir', dir);
+}
+
+export async function setBackupOnSave(value: boolean): Promise<void> {
+  await saveSetting('backupOnSave', value);
+}
+
 export async function updateAuthorField<K extends keyof AuthorSettings>(
   field: K,
   value: AuthorSettings[K]
@@ -389,6 +405,7 @@ export function getSettings() {
     get palette() { return settings.palette; },
     get language() { return settings.language; },
     get author() { return settings.author; },
+    get backupOnSave() { return settings.backupOnSave; },
     get isLoaded() { return isLoaded; },
   };
 }
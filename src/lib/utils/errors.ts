@@ -0,0 +1,36 @@
+/**
+ * Error helpers for Tauri command failures.
+ * Converted commands reject with a structured `CommandError`; older ones still
+ * reject with a plain string.
+ */
+
+/** Structured error returned by backend commands (see `command_error.rs`) */
+export interface CommandError {
+  /** Stable code, e.g. `file_not_found`, `password_required`, `timeout` */
+  code: string;
+  /** Human-readable message */
+  message: string;
+  /** Extra diagnostics such as script stderr */
+  detail?: string;
+}
+
+export function isCommandError(err: unknown): err is CommandError {
+  return (
+    typeof err === 'object' &&
+    err !== null &&
+    typeof (err as CommandError).code === 'string' &&
+    typeof (err as CommandError).message === 'string'
+  );
+}
+
+/** Error code of a failed command, or undefined for plain string errors */
+export function errorCode(err: unknown): string | undefined {
+  return isCommandError(err) ? err.code : undefined;
+}
+
+/** Message to show for anything a command (or other code) threw */
+export function errorMessage(err: unknown): string {
+  if (isCommandError(err)) return err.message;
+  if (err instanceof Error) return err.message;
+  return String(err);
+}